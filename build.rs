@@ -0,0 +1,32 @@
+// File: ./build.rs
+// Records the git commit for `--version`; builds outside a checkout simply go without.
+use std::path::Path;
+use std::process::Command;
+
+fn main() {
+    // A missing path would make cargo rerun this on every build
+    if let Ok(head) = std::fs::read_to_string(".git/HEAD") {
+        println!("cargo:rerun-if-changed=.git/HEAD");
+        // On a branch, HEAD stays the same when committing: the ref it names moves
+        if let Some(branch) = head.trim().strip_prefix("ref: ") {
+            for path in [format!(".git/{}", branch), ".git/packed-refs".to_string()] {
+                if Path::new(&path).exists() {
+                    println!("cargo:rerun-if-changed={}", path);
+                }
+            }
+        }
+    }
+    println!("cargo:rerun-if-env-changed=CFAIT_GIT_HASH");
+    if std::env::var_os("CFAIT_GIT_HASH").is_some() {
+        return;
+    }
+    let hash = Command::new("git")
+        .args(["rev-parse", "--short", "HEAD"])
+        .output()
+        .ok()
+        .filter(|out| out.status.success())
+        .and_then(|out| String::from_utf8(out.stdout).ok());
+    if let Some(hash) = hash.map(|h| h.trim().to_string()).filter(|h| !h.is_empty()) {
+        println!("cargo:rustc-env=CFAIT_GIT_HASH={}", hash);
+    }
+}
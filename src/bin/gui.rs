@@ -1,3 +1,10 @@
 fn main() -> iced::Result {
+    if std::env::args()
+        .skip(1)
+        .any(|a| a == "-V" || a == "--version")
+    {
+        println!("{}", cfait::build_info());
+        return Ok(());
+    }
     cfait::gui::run()
 }
@@ -3,6 +3,7 @@ use crate::model::{CalendarListEntry, Task};
 use crate::paths::AppPaths;
 use crate::storage::LocalStorage;
 use anyhow::Result;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::hash_map::DefaultHasher;
 use std::fs;
@@ -13,8 +14,14 @@ use std::path::PathBuf;
 struct CalendarCache {
     sync_token: Option<String>,
     tasks: Vec<Task>,
+    /// Last successful fetch from the server; missing in older cache files.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    synced_at: Option<DateTime<Utc>>,
 }
 
+/// Data older than this is flagged as stale in the UI.
+pub const STALE_AFTER: chrono::Duration = chrono::Duration::hours(1);
+
 pub struct Cache;
 
 impl Cache {
@@ -33,12 +40,34 @@ impl Cache {
         })
     }
 
+    /// Saves local changes, keeping the time of the last server sync.
     pub fn save(key: &str, tasks: &[Task], sync_token: Option<String>) -> Result<()> {
+        Self::write(key, tasks, sync_token, None)
+    }
+
+    /// Saves what was just fetched from the server and records the sync time.
+    pub fn save_synced(key: &str, tasks: &[Task], sync_token: Option<String>) -> Result<()> {
+        Self::write(key, tasks, sync_token, Some(Utc::now()))
+    }
+
+    fn write(
+        key: &str,
+        tasks: &[Task],
+        sync_token: Option<String>,
+        synced_at: Option<DateTime<Utc>>,
+    ) -> Result<()> {
         if let Some(path) = Self::get_path(key) {
             LocalStorage::with_lock(&path, || {
+                let synced_at = synced_at.or_else(|| {
+                    fs::read_to_string(&path)
+                        .ok()
+                        .and_then(|json| serde_json::from_str::<CalendarCache>(&json).ok())
+                        .and_then(|cache| cache.synced_at)
+                });
                 let data = CalendarCache {
                     sync_token: sync_token.clone(),
                     tasks: tasks.to_vec(),
+                    synced_at,
                 };
                 let json = serde_json::to_string_pretty(&data)?;
                 LocalStorage::atomic_write(&path, json)?;
@@ -67,6 +96,27 @@ impl Cache {
         Ok((vec![], None))
     }
 
+    /// When `key` was last fetched from the server, if known.
+    pub fn synced_at(key: &str) -> Option<DateTime<Utc>> {
+        let path = Self::get_path(key)?;
+        if !path.exists() {
+            return None;
+        }
+        LocalStorage::with_lock(&path, || {
+            let json = fs::read_to_string(&path)?;
+            Ok(serde_json::from_str::<CalendarCache>(&json)
+                .ok()
+                .and_then(|cache| cache.synced_at))
+        })
+        .ok()
+        .flatten()
+    }
+
+    /// Time since `key` was last fetched from the server; `None` if unknown.
+    pub fn age(key: &str) -> Option<std::time::Duration> {
+        Self::synced_at(key).and_then(|t| (Utc::now() - t).to_std().ok())
+    }
+
     pub fn save_calendars(cals: &[CalendarListEntry]) -> Result<()> {
         if let Some(path) = Self::get_calendars_path() {
             LocalStorage::with_lock(&path, || {
@@ -91,3 +141,17 @@ impl Cache {
         Ok(vec![])
     }
 }
+
+/// Short "synced 5m ago" style readout for the header.
+pub fn format_age(age: chrono::Duration) -> String {
+    let mins = age.num_minutes();
+    if mins < 1 {
+        "synced just now".to_string()
+    } else if mins < 60 {
+        format!("synced {}m ago", mins)
+    } else if mins < 24 * 60 {
+        format!("synced {}h ago", mins / 60)
+    } else {
+        format!("synced {}d ago", mins / (24 * 60))
+    }
+}
@@ -0,0 +1,117 @@
+// File: ./src/client/capabilities.rs
+// What the CalDAV server supports, probed once per client
+
+/// Server features the sync code adapts to. Unknown features count as
+/// supported, so a failed probe keeps the previous behavior.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServerCapabilities {
+    /// WebDAV MOVE is allowed; otherwise moving a task copies it and deletes the original
+    pub move_resource: bool,
+    /// Calendars report a CalendarServer getctag
+    pub ctag: bool,
+    /// Calendars report a DAV:sync-token
+    pub sync_token: bool,
+    /// Compliance classes from the DAV header, e.g. "1", "3", "calendar-access"
+    pub dav_classes: Vec<String>,
+}
+
+impl Default for ServerCapabilities {
+    fn default() -> Self {
+        Self {
+            move_resource: true,
+            ctag: true,
+            sync_token: true,
+            dav_classes: Vec::new(),
+        }
+    }
+}
+
+impl ServerCapabilities {
+    /// Builds the capabilities from the `Allow` and `DAV` headers of an OPTIONS
+    /// response and the body of a PROPFIND for getctag and sync-token on a
+    /// calendar. Missing parts leave the matching features assumed supported.
+    pub fn from_probe(allow: Option<&str>, dav: Option<&str>, propfind: Option<&str>) -> Self {
+        let mut caps = Self::default();
+        if let Some(allow) = allow.filter(|a| !a.trim().is_empty()) {
+            caps.move_resource = allow
+                .split(',')
+                .any(|method| method.trim().eq_ignore_ascii_case("MOVE"));
+        }
+        if let Some(dav) = dav {
+            caps.dav_classes = dav
+                .split(',')
+                .map(|class| class.trim().to_string())
+                .filter(|class| !class.is_empty())
+                .collect();
+        }
+        if let Some(body) = propfind {
+            caps.ctag = has_value(body, "getctag");
+            caps.sync_token = has_value(body, "sync-token");
+        }
+        caps
+    }
+
+    /// One line for the debug views, e.g. "MOVE: yes, ctag: no, sync-token: yes".
+    pub fn summary(&self) -> String {
+        let yes_no = |b: bool| if b { "yes" } else { "no" };
+        let mut line = format!(
+            "MOVE: {}, ctag: {}, sync-token: {}",
+            yes_no(self.move_resource),
+            yes_no(self.ctag),
+            yes_no(self.sync_token)
+        );
+        if !self.dav_classes.is_empty() {
+            line.push_str(&format!(", DAV: {}", self.dav_classes.join(" ")));
+        }
+        line
+    }
+}
+
+/// Whether the XML has a `name` element (any namespace prefix) with text in it.
+/// Properties the server doesn't know come back empty (`<cs:getctag/>`).
+fn has_value(xml: &str, name: &str) -> bool {
+    xml.split('<').any(|tag| {
+        let Some((head, content)) = tag.split_once('>') else {
+            return false;
+        };
+        let element = head.split_whitespace().next().unwrap_or("");
+        let local = element.rsplit(':').next().unwrap_or(element);
+        local == name && !head.ends_with('/') && !content.trim().is_empty()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_capabilities_from_probe() {
+        let propfind = r#"<d:multistatus xmlns:d="DAV:" xmlns:cs="http://calendarserver.org/ns/">
+            <d:response><d:propstat><d:prop><cs:getctag>"42"</cs:getctag></d:prop>
+            <d:status>HTTP/1.1 200 OK</d:status></d:propstat>
+            <d:propstat><d:prop><d:sync-token/></d:prop>
+            <d:status>HTTP/1.1 404 Not Found</d:status></d:propstat></d:response>
+            </d:multistatus>"#;
+        let caps = ServerCapabilities::from_probe(
+            Some("OPTIONS, GET, PUT, DELETE, PROPFIND, REPORT"),
+            Some("1, 3, calendar-access"),
+            Some(propfind),
+        );
+        assert!(!caps.move_resource);
+        assert!(caps.ctag);
+        assert!(!caps.sync_token);
+        assert_eq!(caps.dav_classes, vec!["1", "3", "calendar-access"]);
+        assert_eq!(
+            caps.summary(),
+            "MOVE: no, ctag: yes, sync-token: no, DAV: 1 3 calendar-access"
+        );
+
+        // Nothing learned: keep trying everything
+        assert_eq!(
+            ServerCapabilities::from_probe(None, None, None),
+            ServerCapabilities::default()
+        );
+        let caps = ServerCapabilities::from_probe(Some("GET, MOVE, PUT"), None, None);
+        assert!(caps.move_resource);
+    }
+}
@@ -1,25 +1,39 @@
 // File: src/client/core.rs
 
 use crate::cache::Cache;
+use crate::client::capabilities::ServerCapabilities;
 use crate::client::cert::NoVerifier;
-use crate::config::Config;
-use crate::journal::{Action, Journal};
-use crate::model::{CalendarListEntry, Task, TaskStatus};
+use crate::client::demo::{DEMO_SCHEME, DemoBackend};
+use crate::client::proxy::ProxyConnector;
+use crate::client::redirect::{FollowRedirects, REDIRECT_MESSAGE};
+use crate::client::sync_collection::{self, SyncChanges};
+use crate::client::timeout::{DEFAULT_TIMEOUT, TIMEOUT_MESSAGE, Timeout, describe_error};
+use crate::config::{AuthType, Config, ConflictStrategy};
+use crate::journal::{Action, FieldDiff, Journal, MergeConflict};
+use crate::model::item::RawProperty;
+use crate::model::parser::format_mins;
+use crate::model::{CONFLICT_COPY_SUFFIX, CalendarListEntry, Task, TaskStatus};
 use crate::storage::{LOCAL_CALENDAR_HREF, LocalStorage};
 
 // Libdav imports
-use libdav::caldav::{FindCalendarHomeSet, FindCalendars, GetCalendarResources};
-use libdav::dav::{Delete, GetProperty, ListResources, PutResource};
+use libdav::caldav::{
+    CalendarComponent, CreateCalendar, FindCalendarHomeSet, FindCalendars, GetCalendarResources,
+};
+use libdav::dav::{Delete, GetProperty, ListResources, PutResource, SetProperty};
 use libdav::dav::{WebDavClient, WebDavError};
 use libdav::{CalDavClient, PropertyName, names};
 
+use chrono::{DateTime, Local, Utc};
 use futures::stream::{self, StreamExt};
-use http::{Request, StatusCode, Uri};
+use http::{HeaderValue, Request, StatusCode, Uri};
 use hyper_rustls::HttpsConnectorBuilder;
 use hyper_util::client::legacy::Client;
+use hyper_util::client::legacy::connect::HttpConnector;
 use hyper_util::rt::TokioExecutor;
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
 use tower_http::auth::AddAuthorization;
 use uuid::Uuid;
 
@@ -33,11 +47,8 @@ pub const GET_CTAG: PropertyName = PropertyName::new("http://calendarserver.org/
 pub const APPLE_COLOR: PropertyName =
     PropertyName::new("http://apple.com/ns/ical/", "calendar-color");
 
-type HttpsClient = AddAuthorization<
-    Client<
-        hyper_rustls::HttpsConnector<hyper_util::client::legacy::connect::HttpConnector>,
-        String,
-    >,
+type HttpsClient = FollowRedirects<
+    Timeout<AddAuthorization<Client<hyper_rustls::HttpsConnector<ProxyConnector>, String>>>,
 >;
 
 fn strip_host(href: &str) -> String {
@@ -52,15 +63,159 @@ fn strip_host(href: &str) -> String {
     href.to_string()
 }
 
+/// Retries for transient failures (network errors, 5xx) before a sync gives up.
+const MAX_SYNC_RETRIES: u32 = 3;
+const DEFAULT_RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// Errors worth retrying: the request may succeed if sent again unchanged.
+fn is_transient<E>(err: &WebDavError<E>) -> bool {
+    match err {
+        WebDavError::Request(_) => true,
+        WebDavError::BadStatusCode(status) => {
+            status.is_server_error() || *status == StatusCode::TOO_MANY_REQUESTS
+        }
+        _ => false,
+    }
+}
+
+/// How requests authenticate against the server.
+#[derive(Clone, Copy, Debug)]
+pub enum Auth<'a> {
+    Basic {
+        user: &'a str,
+        pass: &'a str,
+    },
+    /// Static OAuth access token, sent as `Authorization: Bearer <token>`
+    Bearer(&'a str),
+}
+
+/// What `get_all_tasks_with_progress` fetched.
+#[derive(Clone, Debug, Default)]
+pub struct FetchedTasks {
+    /// Tasks of each calendar that could be fetched, by href
+    pub calendars: Vec<(String, Vec<Task>)>,
+    /// Server resources left out because they couldn't be parsed ("href: error")
+    pub parse_failures: Vec<String>,
+}
+
+impl FetchedTasks {
+    /// Non-fatal warning about the resources that failed to parse, naming the
+    /// first one, e.g. "3 items failed to parse (/cal/x.ics: Parse: ...)".
+    pub fn parse_warning(&self) -> Option<String> {
+        let count = self.parse_failures.len();
+        let first = self.parse_failures.first()?;
+        Some(format!(
+            "{} item{} failed to parse ({})",
+            count,
+            if count == 1 { "" } else { "s" },
+            first
+        ))
+    }
+}
+
+/// What sending the journal did.
+#[derive(Clone, Debug, Default)]
+pub struct JournalSync {
+    pub warnings: Vec<String>,
+    /// ETag the server gave each task it stored, by UID
+    pub etags: HashMap<String, String>,
+}
+
 #[derive(Clone, Debug)]
 pub struct RustyClient {
     pub client: Option<CalDavClient<HttpsClient>>,
+    retry_delay: Duration,
+    conflict_strategy: ConflictStrategy,
+    read_only_calendars: Vec<String>,
+    /// Roll stale recurring tasks forward when loading (`auto_promote_recurring`)
+    promote_recurring: bool,
+    capabilities: Arc<tokio::sync::OnceCell<ServerCapabilities>>,
+    /// The server refused the sync-collection REPORT, so calendars are listed in full
+    no_sync_collection: Arc<AtomicBool>,
+    demo: Option<Arc<DemoBackend>>,
 }
 
 impl RustyClient {
     pub fn new(url: &str, user: &str, pass: &str, insecure: bool) -> Result<Self, String> {
+        Self::new_with_timeout(url, user, pass, insecure, DEFAULT_TIMEOUT)
+    }
+
+    /// Like `new`, but requests (including connecting) fail with a
+    /// "Connection timed out" error after `timeout`.
+    pub fn new_with_timeout(
+        url: &str,
+        user: &str,
+        pass: &str,
+        insecure: bool,
+        timeout: Duration,
+    ) -> Result<Self, String> {
+        Self::new_with_auth(url, Auth::Basic { user, pass }, insecure, timeout)
+    }
+
+    /// Builds the client with the authentication and timeout set in the config.
+    pub fn from_config(config: &Config) -> Result<Self, String> {
+        let auth = match config.auth_type {
+            AuthType::Basic => Auth::Basic {
+                user: &config.username,
+                pass: &config.password,
+            },
+            AuthType::Bearer => Auth::Bearer(&config.token),
+        };
+        Self::new_with_proxy(
+            &config.url,
+            auth,
+            config.allow_insecure_certs,
+            config.timeout(),
+            config.proxy.as_deref(),
+        )
+        .map(|c| {
+            c.with_conflict_strategy(config.conflict_strategy)
+                .with_read_only_calendars(config.read_only_calendars.clone())
+                .with_promote_recurring(config.auto_promote_recurring)
+        })
+    }
+
+    pub fn new_with_auth(
+        url: &str,
+        auth: Auth<'_>,
+        insecure: bool,
+        timeout: Duration,
+    ) -> Result<Self, String> {
+        Self::new_with_proxy(url, auth, insecure, timeout, None)
+    }
+
+    /// Like `new_with_auth`, with `proxy` overriding the HTTP(S)_PROXY environment
+    /// variables: a proxy url, or "none" to connect directly.
+    pub fn new_with_proxy(
+        url: &str,
+        auth: Auth<'_>,
+        insecure: bool,
+        timeout: Duration,
+        proxy: Option<&str>,
+    ) -> Result<Self, String> {
         if url.is_empty() {
-            return Ok(Self { client: None });
+            return Ok(Self {
+                client: None,
+                retry_delay: DEFAULT_RETRY_DELAY,
+                conflict_strategy: ConflictStrategy::default(),
+                read_only_calendars: Vec::new(),
+                promote_recurring: false,
+                capabilities: Arc::default(),
+                no_sync_collection: Arc::default(),
+                demo: None,
+            });
+        }
+        if url.starts_with(DEMO_SCHEME) {
+            return Ok(Self {
+                client: None,
+                retry_delay: DEFAULT_RETRY_DELAY,
+                conflict_strategy: ConflictStrategy::default(),
+                read_only_calendars: Vec::new(),
+                promote_recurring: false,
+                capabilities: Arc::default(),
+                no_sync_collection: Arc::default(),
+                demo: Some(Arc::new(DemoBackend::seeded())),
+            });
         }
         let uri: Uri = url
             .parse()
@@ -96,24 +251,81 @@ impl RustyClient {
             }
         };
 
+        let mut http_connector = HttpConnector::new();
+        http_connector.enforce_http(false);
+        http_connector.set_connect_timeout(Some(timeout));
         let https_connector = HttpsConnectorBuilder::new()
             .with_tls_config(tls_config)
             .https_or_http()
             .enable_http1()
-            .build();
+            .wrap_connector(ProxyConnector::new(http_connector, proxy)?);
 
         let http_client = Client::builder(TokioExecutor::new()).build(https_connector);
-        let auth_client = AddAuthorization::basic(http_client.clone(), user, pass);
-        let webdav = WebDavClient::new(uri, auth_client.clone());
+        let auth_client = match auth {
+            Auth::Basic { user, pass } => AddAuthorization::basic(http_client, user, pass),
+            Auth::Bearer(token) => {
+                // tower-http panics on a token that isn't a valid header value
+                HeaderValue::from_str(&format!("Bearer {}", token)).map_err(|_| {
+                    "Invalid token: remove line breaks and control characters".to_string()
+                })?;
+                AddAuthorization::bearer(http_client, token)
+            }
+        };
+        let webdav = WebDavClient::new(
+            uri,
+            FollowRedirects::new(Timeout::new(auth_client, timeout)),
+        );
         let caldav = CalDavClient::new(webdav);
         Ok(Self {
             client: Some(caldav),
+            retry_delay: DEFAULT_RETRY_DELAY,
+            conflict_strategy: ConflictStrategy::default(),
+            read_only_calendars: Vec::new(),
+            promote_recurring: false,
+            capabilities: Arc::default(),
+            no_sync_collection: Arc::default(),
+            demo: None,
         })
     }
 
+    /// Sets the first backoff delay for transient sync errors; it doubles on each retry.
+    pub fn with_retry_delay(mut self, delay: Duration) -> Self {
+        self.retry_delay = delay;
+        self
+    }
+
+    pub fn with_conflict_strategy(mut self, strategy: ConflictStrategy) -> Self {
+        self.conflict_strategy = strategy;
+        self
+    }
+
+    /// Calendars (href or name) reported as read-only whatever the server says.
+    pub fn with_read_only_calendars(mut self, calendars: Vec<String>) -> Self {
+        self.read_only_calendars = calendars;
+        self
+    }
+
+    /// Roll recurring tasks overdue by more than one interval forward in `get_all_tasks`.
+    pub fn with_promote_recurring(mut self, promote: bool) -> Self {
+        self.promote_recurring = promote;
+        self
+    }
+
+    /// True when backed by the in-memory sample data of a `demo://` url.
+    pub fn is_demo(&self) -> bool {
+        self.demo.is_some()
+    }
+
     // --- DISCOVERY & CONNECTION ---
 
     pub async fn discover_calendar(&self) -> Result<String, String> {
+        if let Some(demo) = &self.demo {
+            return demo
+                .calendars
+                .first()
+                .map(|c| c.href.clone())
+                .ok_or_else(|| "No calendars".to_string());
+        }
         if let Some(client) = &self.client {
             let base_path = client.base_url().path().to_string();
             if let Ok(response) = client.request(ListResources::new(&base_path)).await
@@ -135,8 +347,12 @@ impl RustyClient {
         }
     }
 
-    pub async fn connect_with_fallback(
+    /// Connects and loads the calendars, falling back to the cache when offline.
+    /// The journal left from the last session is sent first, calling
+    /// `on_sync_progress(current, total)` as in `sync_journal_with_progress`.
+    pub async fn connect_with_fallback<S>(
         config: Config,
+        on_sync_progress: S,
     ) -> Result<
         (
             Self,
@@ -146,26 +362,29 @@ impl RustyClient {
             Option<String>,
         ),
         String,
-    > {
-        let client = Self::new(
-            &config.url,
-            &config.username,
-            &config.password,
-            config.allow_insecure_certs,
-        )
-        .map_err(|e| e.to_string())?;
+    >
+    where
+        S: FnMut(usize, usize) + Send,
+    {
+        let client = Self::from_config(&config).map_err(|e| e.to_string())?;
 
-        let _ = client.sync_journal().await;
+        let _ = client.sync_journal_with_progress(on_sync_progress).await;
 
         let (calendars, warning) = match client.get_calendars().await {
             Ok(c) => {
-                let _ = Cache::save_calendars(&c);
+                if !client.is_demo() {
+                    let _ = Cache::save_calendars(&c);
+                }
                 (c, None)
             }
             Err(e) => {
                 if e.contains("InvalidCertificate") {
                     return Err(format!("Connection failed: {}", e));
                 }
+                // Not an outage: the url needs updating
+                if e.starts_with(REDIRECT_MESSAGE) {
+                    return Err(e);
+                }
                 (
                     Cache::load_calendars().unwrap_or_default(),
                     Some("Offline Mode".to_string()),
@@ -205,24 +424,16 @@ impl RustyClient {
     }
 
     pub async fn get_calendars(&self) -> Result<Vec<CalendarListEntry>, String> {
+        if let Some(demo) = &self.demo {
+            return Ok(demo.calendars.clone());
+        }
         if let Some(client) = &self.client {
-            let principal = client
-                .find_current_user_principal()
-                .await
-                .map_err(|e| format!("{:?}", e))?
-                .ok_or("No principal")?;
-
-            let home_set_resp = client
-                .request(FindCalendarHomeSet::new(&principal))
-                .await
-                .map_err(|e| format!("{:?}", e))?;
-
-            let home_url = home_set_resp.home_sets.first().ok_or("No home set")?;
+            let home_url = Self::calendar_home(client).await?;
 
             let cals_resp = client
-                .request(FindCalendars::new(home_url))
+                .request(FindCalendars::new(&home_url))
                 .await
-                .map_err(|e| format!("{:?}", e))?;
+                .map_err(|e| describe_error("", &e))?;
 
             let mut calendars = Vec::new();
             for col in cals_resp.calendars {
@@ -240,10 +451,15 @@ impl RustyClient {
                     .ok()
                     .and_then(|r| r.value);
 
+                let read_only = self.read_only_calendars.contains(&name)
+                    || self.read_only_calendars.contains(&col.href)
+                    || Self::read_only_by_privileges(client, &col.href).await;
+
                 calendars.push(CalendarListEntry {
                     name,
                     href: col.href,
                     color, // Store it
+                    read_only,
                 });
             }
             Ok(calendars)
@@ -252,14 +468,258 @@ impl RustyClient {
         }
     }
 
+    /// Asks for the DAV:current-user-privilege-set of a calendar (RFC 3744).
+    /// Servers that don't report it are assumed to allow writes.
+    async fn read_only_by_privileges(client: &CalDavClient<HttpsClient>, href: &str) -> bool {
+        let Ok(uri) = client.webdav_client.relative_uri(href) else {
+            return false;
+        };
+        let body = concat!(
+            r#"<?xml version="1.0" encoding="utf-8"?>"#,
+            r#"<D:propfind xmlns:D="DAV:"><D:prop><D:current-user-privilege-set/>"#,
+            r#"</D:prop></D:propfind>"#,
+        );
+        let Ok(req) = Request::builder()
+            .method("PROPFIND")
+            .uri(uri)
+            .header("Depth", "0")
+            .header("Content-Type", "application/xml; charset=utf-8")
+            .body(body.to_string())
+        else {
+            return false;
+        };
+        match client.webdav_client.request_raw(req).await {
+            Ok((parts, bytes)) if parts.status.is_success() => {
+                privileges_allow_write(&String::from_utf8_lossy(&bytes)) == Some(false)
+            }
+            _ => false,
+        }
+    }
+
+    /// Server features, probed once against `calendar_href`: OPTIONS for the
+    /// allowed methods, PROPFIND for the sync properties. A probe that fails
+    /// to connect is tried again on the next call.
+    pub async fn capabilities(&self, calendar_href: &str) -> Result<ServerCapabilities, String> {
+        let Some(client) = &self.client else {
+            return Ok(ServerCapabilities::default());
+        };
+        self.capabilities
+            .get_or_try_init(|| Self::probe_capabilities(client, calendar_href))
+            .await
+            .cloned()
+    }
+
+    /// What the last probe found, if one ran (for the debug views).
+    pub fn known_capabilities(&self) -> Option<ServerCapabilities> {
+        self.capabilities.get().cloned()
+    }
+
+    async fn probe_capabilities(
+        client: &CalDavClient<HttpsClient>,
+        calendar_href: &str,
+    ) -> Result<ServerCapabilities, String> {
+        let path = strip_host(calendar_href);
+        let uri = client
+            .webdav_client
+            .relative_uri(&path)
+            .map_err(|e| format!("Invalid calendar URI: {}", e))?;
+
+        let options = Request::builder()
+            .method("OPTIONS")
+            .uri(uri.clone())
+            .body(String::new())
+            .map_err(|e| e.to_string())?;
+        let (parts, _) = client
+            .webdav_client
+            .request_raw(options)
+            .await
+            .map_err(|e| describe_error("OPTIONS", &e))?;
+        let header = |name: &str| {
+            parts
+                .headers
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .filter(|_| parts.status.is_success())
+        };
+
+        let body = concat!(
+            r#"<?xml version="1.0" encoding="utf-8"?>"#,
+            r#"<D:propfind xmlns:D="DAV:" xmlns:CS="http://calendarserver.org/ns/">"#,
+            r#"<D:prop><CS:getctag/><D:sync-token/></D:prop></D:propfind>"#,
+        );
+        let propfind = Request::builder()
+            .method("PROPFIND")
+            .uri(uri)
+            .header("Depth", "0")
+            .header("Content-Type", "application/xml; charset=utf-8")
+            .body(body.to_string())
+            .map_err(|e| e.to_string())?;
+        let props = match client.webdav_client.request_raw(propfind).await {
+            Ok((props, bytes)) if props.status.is_success() => {
+                Some(String::from_utf8_lossy(&bytes).into_owned())
+            }
+            _ => None,
+        };
+
+        Ok(ServerCapabilities::from_probe(
+            header("Allow"),
+            header("DAV"),
+            props.as_deref(),
+        ))
+    }
+
+    async fn calendar_home(client: &CalDavClient<HttpsClient>) -> Result<Uri, String> {
+        let principal = client
+            .find_current_user_principal()
+            .await
+            .map_err(|e| describe_error("", &e))?
+            .ok_or("No principal")?;
+
+        let home_set_resp = client
+            .request(FindCalendarHomeSet::new(&principal))
+            .await
+            .map_err(|e| describe_error("", &e))?;
+
+        home_set_resp
+            .home_sets
+            .into_iter()
+            .next()
+            .ok_or_else(|| "No home set".to_string())
+    }
+
+    /// Creates a task calendar named `name` in the calendar home of the user.
+    /// Tries MKCALENDAR first and falls back to an Extended MKCOL for servers
+    /// that only implement RFC 5689.
+    pub async fn create_calendar(&self, name: &str) -> Result<CalendarListEntry, String> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err("Calendar name is empty".to_string());
+        }
+        if self.demo.is_some() {
+            return Err("Cannot create calendars in demo mode".to_string());
+        }
+        // Without a server the calendar is kept on disk
+        let Some(client) = self.client.as_ref() else {
+            return LocalStorage::create_calendar(name).map_err(|e| e.to_string());
+        };
+
+        let home_url = Self::calendar_home(client).await?;
+        let home_path = home_url.path().trim_end_matches('/');
+        let href = format!("{}/{}/", home_path, Uuid::new_v4());
+
+        let uri = client
+            .webdav_client
+            .relative_uri(&href)
+            .map_err(|e| format!("Invalid calendar URI: {}", e))?;
+        let body = format!(
+            concat!(
+                r#"<?xml version="1.0" encoding="utf-8"?>"#,
+                r#"<C:mkcalendar xmlns:D="DAV:" xmlns:C="urn:ietf:params:xml:ns:caldav">"#,
+                r#"<D:set><D:prop><D:displayname>{}</D:displayname>"#,
+                r#"<C:supported-calendar-component-set><C:comp name="VTODO"/>"#,
+                r#"</C:supported-calendar-component-set></D:prop></D:set></C:mkcalendar>"#,
+            ),
+            escape_xml(name)
+        );
+        let req = Request::builder()
+            .method("MKCALENDAR")
+            .uri(uri)
+            .header("Content-Type", "application/xml; charset=utf-8")
+            .body(body)
+            .map_err(|e| e.to_string())?;
+        let (parts, _) = client
+            .webdav_client
+            .request_raw(req)
+            .await
+            .map_err(|e| describe_error("MKCALENDAR", &e))?;
+
+        let status = parts.status;
+        if !status.is_success() {
+            if status != StatusCode::METHOD_NOT_ALLOWED && status != StatusCode::NOT_IMPLEMENTED {
+                return Err(format!(
+                    "Server refused to create the calendar ({})",
+                    status
+                ));
+            }
+            let components = [CalendarComponent::VTodo];
+            client
+                .request(
+                    CreateCalendar::new(&href)
+                        .with_display_name(name)
+                        .with_components(&components),
+                )
+                .await
+                .map_err(|_| {
+                    format!(
+                        "Server does not support creating calendars (MKCALENDAR: {})",
+                        status
+                    )
+                })?;
+        }
+
+        Ok(CalendarListEntry {
+            name: name.to_string(),
+            href,
+            color: None,
+            read_only: false,
+        })
+    }
+
+    /// Sets the display name of a calendar and updates the cached calendar list.
+    pub async fn rename_calendar(&self, href: &str, new_name: &str) -> Result<(), String> {
+        let new_name = new_name.trim();
+        if new_name.is_empty() {
+            return Err("Calendar name is empty".to_string());
+        }
+        if href == LOCAL_CALENDAR_HREF {
+            return Err("The local calendar cannot be renamed".to_string());
+        }
+        if LocalStorage::is_local(href) {
+            return LocalStorage::rename_calendar(href, new_name).map_err(|e| e.to_string());
+        }
+        if self.demo.is_some() {
+            return Err("Cannot rename calendars in demo mode".to_string());
+        }
+        let client = self.client.as_ref().ok_or("Offline")?;
+
+        client
+            .request(SetProperty::new(href, &names::DISPLAY_NAME, Some(new_name)))
+            .await
+            .map_err(|e| {
+                let msg = describe_error("", &e);
+                if msg.contains("403") || msg.contains("401") {
+                    "Permission denied: this calendar cannot be renamed (shared or read-only?)"
+                        .to_string()
+                } else {
+                    format!("Rename failed: {}", msg)
+                }
+            })?;
+
+        if let Ok(mut cals) = Cache::load_calendars()
+            && let Some(cal) = cals.iter_mut().find(|c| c.href == href)
+        {
+            cal.name = new_name.to_string();
+            let _ = Cache::save_calendars(&cals);
+        }
+        Ok(())
+    }
+
     // --- TASK FETCHING ---
 
+    /// The tasks of a calendar, with the resources that failed to parse
+    /// ("href: error"). While some do, the sync token isn't cached, so the
+    /// next fetch lists the calendar in full and tries them again.
     async fn fetch_calendar_tasks_internal(
         &self,
         calendar_href: &str,
-    ) -> Result<Vec<Task>, String> {
-        if calendar_href == LOCAL_CALENDAR_HREF {
-            return LocalStorage::load().map_err(|e| e.to_string());
+    ) -> Result<(Vec<Task>, Vec<String>), String> {
+        if LocalStorage::is_local(calendar_href) {
+            return LocalStorage::load(calendar_href)
+                .map(|tasks| (tasks, vec![]))
+                .map_err(|e| e.to_string());
+        }
+        if let Some(demo) = &self.demo {
+            return Ok((demo.tasks(calendar_href), vec![]));
         }
 
         let (cached_tasks, cached_token) = Cache::load(calendar_href).unwrap_or((vec![], None));
@@ -267,33 +727,60 @@ impl RustyClient {
         if let Some(client) = &self.client {
             let path_href = strip_host(calendar_href);
 
-            let remote_token = if let Ok(resp) = client
-                .request(GetProperty::new(&path_href, &GET_CTAG))
-                .await
-                && let Some(val) = resp.value
-            {
-                Some(val)
-            } else if let Ok(resp) = client
-                .request(GetProperty::new(&path_href, &names::SYNC_TOKEN))
-                .await
-                && let Some(val) = resp.value
-            {
-                Some(val)
-            } else {
-                None
+            // Don't wait out the other requests if the server is unreachable
+            let caps = match self.capabilities(calendar_href).await {
+                Err(e) if e == TIMEOUT_MESSAGE => return Err(e),
+                Err(_) => ServerCapabilities::default(),
+                Ok(caps) => caps,
             };
+            // The sync-token also drives the incremental REPORT, so it is preferred
+            // to the ctag. Without either token every sync compares the ETags of all tasks
+            let mut remote_token = None;
+            let tokens = [
+                (caps.sync_token, &names::SYNC_TOKEN),
+                (caps.ctag, &GET_CTAG),
+            ];
+            for (supported, name) in tokens {
+                if !supported || remote_token.is_some() {
+                    continue;
+                }
+                remote_token = match client.request(GetProperty::new(&path_href, name)).await {
+                    Err(e) if describe_error("", &e) == TIMEOUT_MESSAGE => {
+                        return Err(TIMEOUT_MESSAGE.to_string());
+                    }
+                    resp => resp.ok().and_then(|r| r.value),
+                };
+            }
 
             if let Some(r_tok) = &remote_token
                 && let Some(c_tok) = &cached_token
                 && r_tok == c_tok
             {
-                return Ok(cached_tasks);
+                // Unchanged on the server, which still counts as synced
+                let _ = Cache::save_synced(calendar_href, &cached_tasks, remote_token);
+                return Ok((cached_tasks, vec![]));
+            }
+
+            if caps.sync_token
+                && !self.no_sync_collection.load(Ordering::Relaxed)
+                && let Some(c_tok) = &cached_token
+                && let Some(changes) = self.sync_collection(client, &path_href, c_tok).await?
+            {
+                let (final_tasks, failures) = self
+                    .apply_sync_changes(client, &path_href, calendar_href, cached_tasks, &changes)
+                    .await?;
+                let token = changes
+                    .token
+                    .or(remote_token)
+                    .filter(|_| failures.is_empty());
+                let _ = Cache::save_synced(calendar_href, &final_tasks, token);
+                return Ok((final_tasks, failures));
             }
 
             let list_resp = client
                 .request(ListResources::new(&path_href))
                 .await
-                .map_err(|e| format!("PROPFIND: {:?}", e))?;
+                .map_err(|e| describe_error("PROPFIND", &e))?;
 
             let mut cache_map: HashMap<String, Task> = HashMap::new();
             for t in cached_tasks {
@@ -331,45 +818,169 @@ impl RustyClient {
                 }
             }
 
-            if !to_fetch.is_empty() {
-                let fetched_resp = client
-                    .request(GetCalendarResources::new(&path_href).with_hrefs(to_fetch))
-                    .await
-                    .map_err(|e| format!("MULTIGET: {:?}", e))?;
-
-                for item in fetched_resp.resources {
-                    if let Ok(content) = item.content
-                        && let Ok(task) = Task::from_ics(
-                            &content.data,
-                            content.etag,
-                            item.href,
-                            calendar_href.to_string(),
-                        )
-                    {
-                        final_tasks.push(task);
-                    }
-                }
-            }
+            let (fetched, failures) =
+                Self::fetch_resources(client, &path_href, calendar_href, to_fetch).await?;
+            final_tasks.extend(fetched);
 
-            let _ = Cache::save(calendar_href, &final_tasks, remote_token);
-            Ok(final_tasks)
+            let token = remote_token.filter(|_| failures.is_empty());
+            let _ = Cache::save_synced(calendar_href, &final_tasks, token);
+            Ok((final_tasks, failures))
         } else {
             Err("Offline".to_string())
         }
     }
 
+    /// Downloads `hrefs` with a calendar-multiget, returning the tasks and the
+    /// resources that failed to parse ("href: error").
+    async fn fetch_resources(
+        client: &CalDavClient<HttpsClient>,
+        path_href: &str,
+        calendar_href: &str,
+        hrefs: Vec<String>,
+    ) -> Result<(Vec<Task>, Vec<String>), String> {
+        let mut tasks = Vec::new();
+        let mut failures = Vec::new();
+        if hrefs.is_empty() {
+            return Ok((tasks, failures));
+        }
+        let fetched_resp = client
+            .request(GetCalendarResources::new(path_href).with_hrefs(hrefs))
+            .await
+            .map_err(|e| describe_error("MULTIGET", &e))?;
+
+        for item in fetched_resp.resources {
+            let Ok(content) = item.content else {
+                continue;
+            };
+            match Task::from_ics(
+                &content.data,
+                content.etag,
+                item.href.clone(),
+                calendar_href.to_string(),
+            ) {
+                Ok(task) => tasks.push(task),
+                Err(e) => failures.push(format!("{}: {}", item.href, e)),
+            }
+        }
+        Ok((tasks, failures))
+    }
+
+    /// Asks for the changes since `token` with a sync-collection REPORT.
+    /// `Ok(None)` means the calendar has to be listed in full: the server
+    /// rejected the token (expired, or a ctag kept by an older cache), doesn't
+    /// support the REPORT, or truncated its answer.
+    async fn sync_collection(
+        &self,
+        client: &CalDavClient<HttpsClient>,
+        path_href: &str,
+        token: &str,
+    ) -> Result<Option<SyncChanges>, String> {
+        let uri = client
+            .webdav_client
+            .relative_uri(path_href)
+            .map_err(|e| format!("Invalid calendar URI: {}", e))?;
+        let report = Request::builder()
+            .method("REPORT")
+            .uri(uri)
+            .header("Depth", "0")
+            .header("Content-Type", "application/xml; charset=utf-8")
+            .body(sync_collection::report_body(token))
+            .map_err(|e| e.to_string())?;
+        match client.webdav_client.request_raw(report).await {
+            Err(e) if describe_error("", &e) == TIMEOUT_MESSAGE => Err(TIMEOUT_MESSAGE.to_string()),
+            Err(_) => Ok(None),
+            Ok((parts, body)) if parts.status.is_success() => Ok(sync_collection::parse_changes(
+                &String::from_utf8_lossy(&body),
+            )
+            .ok()
+            .filter(|changes| !changes.truncated)),
+            Ok((parts, _)) => {
+                if matches!(
+                    parts.status,
+                    StatusCode::METHOD_NOT_ALLOWED | StatusCode::NOT_IMPLEMENTED
+                ) {
+                    self.no_sync_collection.store(true, Ordering::Relaxed);
+                }
+                Ok(None)
+            }
+        }
+    }
+
+    /// The cached tasks of a calendar updated with the changes of a
+    /// sync-collection REPORT, downloading only the added and modified ones,
+    /// with the resources that failed to parse.
+    async fn apply_sync_changes(
+        &self,
+        client: &CalDavClient<HttpsClient>,
+        path_href: &str,
+        calendar_href: &str,
+        cached_tasks: Vec<Task>,
+        changes: &SyncChanges,
+    ) -> Result<(Vec<Task>, Vec<String>), String> {
+        // Tasks never uploaded have no href yet and are kept as they are
+        let (mut final_tasks, synced): (Vec<Task>, Vec<Task>) = cached_tasks
+            .into_iter()
+            .partition(|t| t.etag.is_empty() || t.href.is_empty());
+        let mut by_href: HashMap<String, Task> =
+            synced.into_iter().map(|t| (t.href.clone(), t)).collect();
+        for href in &changes.removed {
+            by_href.remove(href);
+        }
+        let to_fetch: Vec<String> = changes
+            .changed
+            .iter()
+            .filter(|(href, etag)| {
+                // Unknown ETags are fetched, as the full listing does
+                href.ends_with(".ics")
+                    && etag
+                        .as_ref()
+                        .is_none_or(|e| by_href.get(href).is_none_or(|t| t.etag != *e))
+            })
+            .map(|(href, _)| strip_host(href))
+            .collect();
+
+        let (fetched, failures) =
+            Self::fetch_resources(client, path_href, calendar_href, to_fetch).await?;
+        for task in fetched {
+            by_href.insert(task.href.clone(), task);
+        }
+        final_tasks.extend(by_href.into_values());
+        Ok((final_tasks, failures))
+    }
+
     pub async fn get_tasks(&self, calendar_href: &str) -> Result<Vec<Task>, String> {
         let _ = self.sync_journal().await;
-        self.fetch_calendar_tasks_internal(calendar_href).await
+        self.fetch_calendar_tasks_internal(calendar_href)
+            .await
+            .map(|(tasks, _)| tasks)
     }
 
     pub async fn get_all_tasks(
         &self,
         calendars: &[CalendarListEntry],
     ) -> Result<Vec<(String, Vec<Task>)>, String> {
-        let _ = self.sync_journal().await;
+        self.get_all_tasks_with_progress(calendars, |_, _| {}, |_, _| {})
+            .await
+            .map(|fetched| fetched.calendars)
+    }
+
+    /// Same as `get_all_tasks`, but calls `on_sync_progress(current, total)` while
+    /// queued changes are sent, then `on_progress(done, total)` each time the
+    /// fetch of a calendar finishes (or fails), in the order they land.
+    pub async fn get_all_tasks_with_progress<S, F>(
+        &self,
+        calendars: &[CalendarListEntry],
+        on_sync_progress: S,
+        mut on_progress: F,
+    ) -> Result<FetchedTasks, String>
+    where
+        S: FnMut(usize, usize) + Send,
+        F: FnMut(usize, usize) + Send,
+    {
+        let _ = self.sync_journal_with_progress(on_sync_progress).await;
 
         let hrefs: Vec<String> = calendars.iter().map(|c| c.href.clone()).collect();
+        let total = hrefs.len();
         let futures = hrefs.into_iter().map(|href| {
             let client = self.clone();
             async move {
@@ -382,58 +993,134 @@ impl RustyClient {
 
         let mut stream = stream::iter(futures).buffer_unordered(4);
         let mut final_results = Vec::new();
+        let mut parse_failures = Vec::new();
+        let mut done = 0;
 
         while let Some((href, res)) = stream.next().await {
-            if let Ok(tasks) = res {
+            done += 1;
+            on_progress(done, total);
+            if let Ok((tasks, failures)) = res {
                 final_results.push((href, tasks));
+                parse_failures.extend(failures);
             }
         }
 
-        Ok(final_results)
+        if self.promote_recurring {
+            self.promote_stale_recurring(calendars, &mut final_results)
+                .await;
+        }
+
+        Ok(FetchedTasks {
+            calendars: final_results,
+            parse_failures,
+        })
+    }
+
+    /// Rolls the stale recurring tasks of the writable calendars forward
+    /// (`auto_promote_recurring`), uploading them all in one journal sync.
+    async fn promote_stale_recurring(
+        &self,
+        calendars: &[CalendarListEntry],
+        results: &mut [(String, Vec<Task>)],
+    ) {
+        let today = chrono::Local::now().date_naive();
+        let now = chrono::Utc::now();
+        let mut queued = Vec::new();
+        for (href, tasks) in results.iter_mut() {
+            if calendars.iter().any(|c| c.href == *href && c.read_only) {
+                continue;
+            }
+            let mut promoted = Vec::new();
+            for task in tasks.iter_mut() {
+                if task.catch_up(today) {
+                    task.last_modified = Some(now);
+                    promoted.push(task.clone());
+                }
+            }
+            if promoted.is_empty() {
+                continue;
+            }
+            if LocalStorage::is_local(href) {
+                let _ = LocalStorage::save(href, tasks);
+            } else if let Some(demo) = &self.demo {
+                promoted.iter().for_each(|task| demo.upsert(task));
+            } else {
+                queued.extend(promoted.into_iter().map(Action::Update));
+            }
+        }
+        if queued.is_empty() || Journal::modify(|queue| queue.extend(queued)).is_err() {
+            return;
+        }
+        // Left in the journal for the next sync if the upload fails
+        let Ok(sync) = self.sync_journal_with_progress(|_, _| {}).await else {
+            return;
+        };
+        // The ETags of the uploaded tasks stand in for fetching them again
+        for (href, tasks) in results.iter_mut() {
+            let mut uploaded = false;
+            for task in tasks.iter_mut() {
+                if let Some(etag) = sync.etags.get(&task.uid) {
+                    task.etag = etag.clone();
+                    uploaded = true;
+                }
+            }
+            if uploaded {
+                let token = Cache::load(href).ok().and_then(|(_, token)| token);
+                let _ = Cache::save(href, tasks, token);
+            }
+        }
     }
 
     // --- TASK OPERATIONS ---
 
     pub async fn create_task(&self, task: &mut Task) -> Result<Vec<String>, String> {
-        if task.calendar_href == LOCAL_CALENDAR_HREF {
-            let mut all = LocalStorage::load().map_err(|e| e.to_string())?;
+        if LocalStorage::is_local(&task.calendar_href) {
+            let mut all = LocalStorage::load(&task.calendar_href).map_err(|e| e.to_string())?;
             all.push(task.clone());
-            LocalStorage::save(&all).map_err(|e| e.to_string())?;
+            LocalStorage::save(&task.calendar_href, &all).map_err(|e| e.to_string())?;
             return Ok(vec![]);
         }
 
-        let cal_path = task.calendar_href.clone();
-        let filename = format!("{}.ics", task.uid);
-        let full_href = if cal_path.ends_with('/') {
-            format!("{}{}", cal_path, filename)
-        } else {
-            format!("{}/{}", cal_path, filename)
-        };
-        task.href = full_href;
+        task.href = resource_href(&task.calendar_href, &task.uid);
+        task.last_modified = Some(chrono::Utc::now());
+
+        if let Some(demo) = &self.demo {
+            demo.upsert(task);
+            return Ok(vec![]);
+        }
 
         Journal::push(Action::Create(task.clone())).map_err(|e| e.to_string())?;
         self.sync_journal().await
     }
 
     pub async fn update_task(&self, task: &mut Task) -> Result<Vec<String>, String> {
-        if task.calendar_href == LOCAL_CALENDAR_HREF {
-            let mut all = LocalStorage::load().map_err(|e| e.to_string())?;
+        task.last_modified = Some(chrono::Utc::now());
+        if LocalStorage::is_local(&task.calendar_href) {
+            let mut all = LocalStorage::load(&task.calendar_href).map_err(|e| e.to_string())?;
             if let Some(idx) = all.iter().position(|t| t.uid == task.uid) {
                 all[idx] = task.clone();
-                LocalStorage::save(&all).map_err(|e| e.to_string())?;
+                LocalStorage::save(&task.calendar_href, &all).map_err(|e| e.to_string())?;
             }
             return Ok(vec![]);
         }
+        if let Some(demo) = &self.demo {
+            demo.upsert(task);
+            return Ok(vec![]);
+        }
 
         Journal::push(Action::Update(task.clone())).map_err(|e| e.to_string())?;
         self.sync_journal().await
     }
 
     pub async fn delete_task(&self, task: &Task) -> Result<Vec<String>, String> {
-        if task.calendar_href == LOCAL_CALENDAR_HREF {
-            let mut all = LocalStorage::load().map_err(|e| e.to_string())?;
+        if LocalStorage::is_local(&task.calendar_href) {
+            let mut all = LocalStorage::load(&task.calendar_href).map_err(|e| e.to_string())?;
             all.retain(|t| t.uid != task.uid);
-            LocalStorage::save(&all).map_err(|e| e.to_string())?;
+            LocalStorage::save(&task.calendar_href, &all).map_err(|e| e.to_string())?;
+            return Ok(vec![]);
+        }
+        if let Some(demo) = &self.demo {
+            demo.remove(task);
             return Ok(vec![]);
         }
 
@@ -441,6 +1128,35 @@ impl RustyClient {
         self.sync_journal().await
     }
 
+    /// Settles a conflict left in the journal by `sync_journal`. `keep_local` lists
+    /// the diverged fields where the local value wins (the rest take the server's);
+    /// `None` keeps both versions by uploading the local one as a conflict copy.
+    pub async fn resolve_conflict(
+        &self,
+        uid: &str,
+        keep_local: Option<&[String]>,
+    ) -> Result<Vec<String>, String> {
+        let mut found = false;
+        Journal::modify_all(|journal| {
+            if let Some(pos) = journal.conflicts.iter().position(|c| c.local.uid == uid) {
+                let conflict = journal.conflicts.remove(pos);
+                let action = match keep_local {
+                    Some(fields) => Action::Update(resolve_merge(&conflict, fields)),
+                    None => Action::Create(conflict_copy(&conflict.local)),
+                };
+                // Ahead of anything queued later for this task, so the new ETag propagates
+                journal.queue.insert(0, action);
+                found = true;
+            }
+        })
+        .map_err(|e| e.to_string())?;
+
+        if !found {
+            return Err(format!("No pending conflict for task {}", uid));
+        }
+        self.sync_journal().await
+    }
+
     pub async fn toggle_task(
         &self,
         task: &mut Task,
@@ -451,15 +1167,15 @@ impl RustyClient {
             None
         };
 
-        if task.calendar_href == LOCAL_CALENDAR_HREF {
-            let mut all = LocalStorage::load().map_err(|e| e.to_string())?;
+        if LocalStorage::is_local(&task.calendar_href) {
+            let mut all = LocalStorage::load(&task.calendar_href).map_err(|e| e.to_string())?;
             if let Some(idx) = all.iter().position(|t| t.uid == task.uid) {
                 all[idx] = task.clone();
             }
             if let Some(new_t) = &next_task {
                 all.push(new_t.clone());
             }
-            LocalStorage::save(&all).map_err(|e| e.to_string())?;
+            LocalStorage::save(&task.calendar_href, &all).map_err(|e| e.to_string())?;
             return Ok((task.clone(), next_task, vec![]));
         }
 
@@ -479,7 +1195,10 @@ impl RustyClient {
         task: &Task,
         new_calendar_href: &str,
     ) -> Result<(Task, Vec<String>), String> {
-        if task.calendar_href == LOCAL_CALENDAR_HREF {
+        if LocalStorage::is_local(&task.calendar_href)
+            || LocalStorage::is_local(new_calendar_href)
+            || self.demo.is_some()
+        {
             let mut new_task = task.clone();
             new_task.calendar_href = new_calendar_href.to_string();
             new_task.href = String::new();
@@ -534,32 +1253,52 @@ impl RustyClient {
     }
 
     pub async fn sync_journal(&self) -> Result<Vec<String>, String> {
+        self.sync_journal_with_progress(|_, _| {})
+            .await
+            .map(|sync| sync.warnings)
+    }
+
+    /// Same as `sync_journal`, but calls `on_progress(current, total)` before each
+    /// queued action is sent. `total` can grow while syncing, e.g. when a conflict
+    /// queues a copy of the task.
+    pub async fn sync_journal_with_progress<F>(
+        &self,
+        mut on_progress: F,
+    ) -> Result<JournalSync, String>
+    where
+        F: FnMut(usize, usize) + Send,
+    {
+        // The demo backend applies changes immediately, nothing is ever queued
+        if self.demo.is_some() {
+            return Ok(JournalSync::default());
+        }
         let client = self.client.as_ref().ok_or("Offline")?;
         let mut warnings = Vec::new();
+        let mut etags = HashMap::new();
+        let mut done = 0;
+        let mut attempt = 0;
 
         loop {
-            let next_action = {
+            let (next_action, remaining) = {
                 let j = Journal::load();
                 if j.queue.is_empty() {
-                    return Ok(warnings);
+                    return Ok(JournalSync { warnings, etags });
                 }
-                j.queue[0].clone()
+                (j.queue[0].clone(), j.queue.len())
             };
+            on_progress(done + 1, done + remaining);
 
             let mut conflict_resolved_action = None;
             let mut new_etag_to_propagate: Option<String> = None;
             let mut new_href_to_propagate: Option<(String, String)> = None;
             let mut path_for_refresh: Option<String> = None;
+            let mut transient = false;
+            let mut pending_conflict: Option<MergeConflict> = None;
+            let mut renamed_uid: Option<(String, Task)> = None;
 
             let result = match &next_action {
                 Action::Create(task) => {
-                    let filename = format!("{}.ics", task.uid);
-                    let full_href = if task.calendar_href.ends_with('/') {
-                        format!("{}{}", task.calendar_href, filename)
-                    } else {
-                        format!("{}/{}", task.calendar_href, filename)
-                    };
-                    let path = strip_host(&full_href);
+                    let path = strip_host(&resource_href(&task.calendar_href, &task.uid));
                     let ics_string = task.to_ics();
                     match client
                         .request(PutResource::new(&path).create(ics_string, "text/calendar"))
@@ -573,7 +1312,49 @@ impl RustyClient {
                             }
                             Ok(())
                         }
-                        Err(e) => Err(format!("{:?}", e)),
+                        // If-None-Match failed: something already lives at <uid>.ics
+                        Err(WebDavError::BadStatusCode(StatusCode::PRECONDITION_FAILED))
+                        | Err(WebDavError::PreconditionFailed(_)) => {
+                            let existing = self.fetch_remote_task(&task.calendar_href, &path).await;
+                            if existing.is_some_and(|e| is_same_upload(&e, task)) {
+                                // An earlier attempt got through, its answer was lost
+                                path_for_refresh = Some(path.clone());
+                                Ok(())
+                            } else {
+                                let mut fresh = task.clone();
+                                fresh.uid = Uuid::new_v4().to_string();
+                                fresh.href = resource_href(&fresh.calendar_href, &fresh.uid);
+                                let new_path = strip_host(&fresh.href);
+                                match client
+                                    .request(
+                                        PutResource::new(&new_path)
+                                            .create(fresh.to_ics(), "text/calendar"),
+                                    )
+                                    .await
+                                {
+                                    Ok(resp) => {
+                                        warnings.push(format!(
+                                            "UID {} of '{}' was already taken on the server, created it as {}.",
+                                            task.uid, task.summary, fresh.uid
+                                        ));
+                                        match resp.etag {
+                                            Some(etag) => new_etag_to_propagate = Some(etag),
+                                            None => path_for_refresh = Some(new_path),
+                                        }
+                                        renamed_uid = Some((task.uid.clone(), fresh));
+                                        Ok(())
+                                    }
+                                    Err(e) => {
+                                        transient = is_transient(&e);
+                                        Err(describe_error("", &e))
+                                    }
+                                }
+                            }
+                        }
+                        Err(e) => {
+                            transient = is_transient(&e);
+                            Err(describe_error("", &e))
+                        }
                     }
                 }
                 Action::Update(task) => {
@@ -597,26 +1378,35 @@ impl RustyClient {
                         }
                         Err(WebDavError::BadStatusCode(StatusCode::PRECONDITION_FAILED))
                         | Err(WebDavError::PreconditionFailed(_)) => {
-                            if let Some((resolution, msg)) =
-                                self.attempt_conflict_resolution(task).await
-                            {
-                                warnings.push(msg);
-                                conflict_resolved_action = Some(resolution);
-                                Ok(())
-                            } else {
-                                let msg = format!(
-                                    "Conflict (412) on task '{}'. Merge failed. Creating copy.",
-                                    task.summary
-                                );
-                                warnings.push(msg);
-
-                                let mut conflict_copy = task.clone();
-                                conflict_copy.uid = Uuid::new_v4().to_string();
-                                conflict_copy.summary = format!("{} (Conflict Copy)", task.summary);
-                                conflict_copy.href = String::new();
-                                conflict_copy.etag = String::new();
-                                conflict_resolved_action = Some(Action::Create(conflict_copy));
-                                Ok(())
+                            match self.attempt_conflict_resolution(task).await {
+                                Some(Ok(merged)) => {
+                                    warnings.push(format!(
+                                        "Conflict (412) on '{}' resolved via 3-way merge.",
+                                        task.summary
+                                    ));
+                                    conflict_resolved_action = Some(Action::Update(merged));
+                                    Ok(())
+                                }
+                                Some(Err(conflict)) => {
+                                    warnings.push(format!(
+                                        "Conflict (412) on '{}': {} field(s) changed on both sides. Waiting for you to pick.",
+                                        task.summary,
+                                        conflict.fields.len()
+                                    ));
+                                    pending_conflict = Some(conflict);
+                                    Ok(())
+                                }
+                                None => {
+                                    let msg = format!(
+                                        "Conflict (412) on task '{}'. Merge failed. Creating copy.",
+                                        task.summary
+                                    );
+                                    warnings.push(msg);
+
+                                    conflict_resolved_action =
+                                        Some(Action::Create(conflict_copy(task)));
+                                    Ok(())
+                                }
                             }
                         }
                         Err(WebDavError::BadStatusCode(StatusCode::NOT_FOUND)) => {
@@ -632,14 +1422,11 @@ impl RustyClient {
                                 );
                                 warnings.push(w);
 
-                                let mut conflict_copy = task.clone();
-                                conflict_copy.uid = Uuid::new_v4().to_string();
-                                conflict_copy.summary = format!("{} (Conflict Copy)", task.summary);
-                                conflict_copy.href = String::new();
-                                conflict_copy.etag = String::new();
-                                conflict_resolved_action = Some(Action::Create(conflict_copy));
+                                conflict_resolved_action =
+                                    Some(Action::Create(conflict_copy(task)));
                                 Ok(())
                             } else {
+                                transient = is_transient(&e);
                                 Err(msg)
                             }
                         }
@@ -660,7 +1447,10 @@ impl RustyClient {
                             ));
                             Ok(())
                         }
-                        Err(e) => Err(format!("{:?}", e)),
+                        Err(e) => {
+                            transient = is_transient(&e);
+                            Err(describe_error("", &e))
+                        }
                     }
                 }
                 Action::Move(task, new_cal) => match self.execute_move(task, new_cal).await {
@@ -686,11 +1476,20 @@ impl RustyClient {
                     // --- FIX: Fetch ETag if needed ---
                     if new_etag_to_propagate.is_none()
                         && let Some(path) = path_for_refresh
-                            && let Some(fetched) = self.fetch_etag(&path).await {
-                                new_etag_to_propagate = Some(fetched);
-                            }
+                        && let Some(fetched) = self.fetch_etag(&path).await
+                    {
+                        new_etag_to_propagate = Some(fetched);
+                    }
 
-                    let commit_res = Journal::modify(|queue| {
+                    let commit_res = Journal::modify_all(|journal| {
+                        if let Some(conflict) = pending_conflict {
+                            journal
+                                .conflicts
+                                .retain(|c| c.local.uid != conflict.local.uid);
+                            journal.conflicts.push(conflict);
+                        }
+
+                        let queue = &mut journal.queue;
                         if !queue.is_empty() {
                             queue.remove(0);
                         }
@@ -699,13 +1498,35 @@ impl RustyClient {
                             queue.insert(0, act);
                         }
 
+                        // Later changes to a task created under a new UID follow it there
+                        if let Some((old_uid, fresh)) = &renamed_uid {
+                            for item in queue.iter_mut() {
+                                let (Action::Create(t)
+                                | Action::Update(t)
+                                | Action::Delete(t)
+                                | Action::Move(t, _)) = item;
+                                if t.uid == *old_uid {
+                                    t.uid = fresh.uid.clone();
+                                    t.href = fresh.href.clone();
+                                }
+                                if t.parent_uid.as_ref() == Some(old_uid) {
+                                    t.parent_uid = Some(fresh.uid.clone());
+                                }
+                                for dep in t.dependencies.iter_mut().filter(|d| *d == old_uid) {
+                                    *dep = fresh.uid.clone();
+                                }
+                            }
+                        }
+
                         if let Some(etag) = new_etag_to_propagate {
-                            let target_uid = match &next_action {
-                                Action::Create(t) | Action::Update(t) => t.uid.clone(),
-                                Action::Move(t, _) => t.uid.clone(),
+                            let target_uid = match (&renamed_uid, &next_action) {
+                                (Some((_, fresh)), _) => fresh.uid.clone(),
+                                (None, Action::Create(t) | Action::Update(t)) => t.uid.clone(),
+                                (None, Action::Move(t, _)) => t.uid.clone(),
                                 _ => String::new(),
                             };
                             if !target_uid.is_empty() {
+                                etags.insert(target_uid.clone(), etag.clone());
                                 for item in queue.iter_mut() {
                                     match item {
                                         Action::Update(t) | Action::Delete(t) => {
@@ -754,33 +1575,89 @@ impl RustyClient {
                     if let Err(e) = commit_res {
                         return Err(e.to_string());
                     }
+                    done += 1;
+                    attempt = 0;
+                }
+                Err(_) if transient && attempt < MAX_SYNC_RETRIES => {
+                    // Leave the action at the head of the queue and try it again
+                    tokio::time::sleep(self.retry_delay * 2u32.pow(attempt)).await;
+                    attempt += 1;
                 }
                 Err(e) => {
+                    if transient {
+                        return Err(format!(
+                            "{} (gave up after {} retries)",
+                            e, MAX_SYNC_RETRIES
+                        ));
+                    }
                     return Err(e);
                 }
             }
         }
     }
 
-    async fn attempt_conflict_resolution(&self, local_task: &Task) -> Option<(Action, String)> {
-        let (cached_tasks, _) = Cache::load(&local_task.calendar_href).ok()?;
-        let base_task = cached_tasks.iter().find(|t| t.uid == local_task.uid)?;
+    /// Downloads the single resource at `path`, if it exists and parses.
+    async fn fetch_remote_task(&self, calendar_href: &str, path: &str) -> Option<Task> {
+        let client = self.client.as_ref()?;
+        let resp = client
+            .request(
+                GetCalendarResources::new(&strip_host(calendar_href))
+                    .with_hrefs(vec![path.to_string()]),
+            )
+            .await
+            .ok()?;
+        resp.resources.into_iter().find_map(|item| {
+            let content = item.content.ok()?;
+            Task::from_ics(
+                &content.data,
+                content.etag,
+                item.href,
+                calendar_href.to_string(),
+            )
+            .ok()
+        })
+    }
 
-        let server_tasks = self
+    /// Tries a 3-way merge against the cached base and the current server copy.
+    /// With the "newest" strategy, what cannot be merged goes to the side with the
+    /// later LAST-MODIFIED. `None` means the base or server version could not be loaded.
+    async fn attempt_conflict_resolution(
+        &self,
+        local_task: &Task,
+    ) -> Option<Result<Task, MergeConflict>> {
+        // Read the base before fetching, which refreshes the cache
+        let base_task = Cache::load(&local_task.calendar_href)
+            .ok()
+            .and_then(|(cached, _)| cached.into_iter().find(|t| t.uid == local_task.uid));
+
+        let (server_tasks, _) = self
             .fetch_calendar_tasks_internal(&local_task.calendar_href)
             .await
             .ok()?;
         let server_task = server_tasks.iter().find(|t| t.uid == local_task.uid)?;
+        let newest = self.conflict_strategy == ConflictStrategy::Newest;
 
-        if let Some(merged) = three_way_merge(base_task, local_task, server_task) {
-            let msg = format!(
-                "Conflict (412) on '{}' resolved via 3-way merge.",
-                local_task.summary
-            );
-            return Some((Action::Update(merged), msg));
+        let Some(base_task) = base_task else {
+            if !newest {
+                return None;
+            }
+            return Some(Ok(newest_wins(None, local_task, server_task, &[])));
+        };
+        match three_way_merge(&base_task, local_task, server_task) {
+            Ok(merged) => Some(Ok(merged)),
+            Err(fields) if newest => Some(Ok(newest_wins(
+                Some(&base_task),
+                local_task,
+                server_task,
+                &fields,
+            ))),
+            Err(fields) => Some(Err(MergeConflict {
+                base: base_task,
+                local: local_task.clone(),
+                server: server_task.clone(),
+                fields,
+            })),
         }
-
-        None
     }
 
     async fn execute_move(&self, task: &Task, new_calendar_href: &str) -> Result<(), String> {
@@ -790,6 +1667,13 @@ impl RustyClient {
         } else {
             format!("{}/{}.ics", new_calendar_href, task.uid)
         };
+        let supports_move = self
+            .capabilities(&task.calendar_href)
+            .await
+            .map_or(true, |caps| caps.move_resource);
+        if !supports_move {
+            return Self::copy_and_delete(client, task, new_calendar_href, &destination).await;
+        }
         let source_path = strip_host(&task.href);
         let source_uri = client
             .webdav_client
@@ -811,43 +1695,396 @@ impl RustyClient {
             .webdav_client
             .request_raw(req)
             .await
-            .map_err(|e| format!("{:?}", e))?;
+            .map_err(|e| describe_error("MOVE", &e))?;
         if parts.status.is_success() {
             Ok(())
+        } else if parts.status == StatusCode::METHOD_NOT_ALLOWED
+            || parts.status == StatusCode::NOT_IMPLEMENTED
+        {
+            Self::copy_and_delete(client, task, new_calendar_href, &destination).await
         } else {
             Err(format!("MOVE failed: {}", parts.status))
         }
     }
+
+    /// Moves a task on servers without MOVE: creates it in the new calendar,
+    /// then deletes the original. A copy that already exists is left from an
+    /// earlier attempt whose DELETE failed, so the retry goes on to the DELETE.
+    async fn copy_and_delete(
+        client: &CalDavClient<HttpsClient>,
+        task: &Task,
+        new_calendar_href: &str,
+        destination: &str,
+    ) -> Result<(), String> {
+        let mut moved = task.clone();
+        moved.calendar_href = new_calendar_href.to_string();
+        match client
+            .request(
+                PutResource::new(&strip_host(destination)).create(moved.to_ics(), "text/calendar"),
+            )
+            .await
+        {
+            Ok(_) | Err(WebDavError::BadStatusCode(StatusCode::PRECONDITION_FAILED)) => {}
+            Err(e) => return Err(describe_error("PUT", &e)),
+        }
+        match client
+            .request(Delete::new(&strip_host(&task.href)).with_etag(&task.etag))
+            .await
+        {
+            Ok(_) | Err(WebDavError::BadStatusCode(StatusCode::NOT_FOUND)) => Ok(()),
+            Err(e) => Err(describe_error("DELETE", &e)),
+        }
+    }
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
 }
 
-fn three_way_merge(base: &Task, local: &Task, server: &Task) -> Option<Task> {
+fn resource_href(calendar_href: &str, uid: &str) -> String {
+    if calendar_href.ends_with('/') {
+        format!("{}{}.ics", calendar_href, uid)
+    } else {
+        format!("{}/{}.ics", calendar_href, uid)
+    }
+}
+
+/// Whether the server copy is this very task, uploaded by an earlier attempt.
+fn is_same_upload(server: &Task, local: &Task) -> bool {
+    let secs = |t: &Task| t.last_modified.map(|d| d.timestamp());
+    server.uid == local.uid && server.summary == local.summary && secs(server) == secs(local)
+}
+
+fn conflict_copy(task: &Task) -> Task {
+    let mut copy = task.clone();
+    copy.uid = Uuid::new_v4().to_string();
+    copy.summary = format!("{}{}", task.summary, CONFLICT_COPY_SUFFIX);
+    copy.href = String::new();
+    copy.etag = String::new();
+    copy
+}
+
+/// Merges on top of the server copy: fields changed on one side only take that
+/// side's value. Fields changed on both sides are listed in the returned diff and
+/// keep the server value unless named in `keep_local`.
+fn merge_tasks(
+    base: &Task,
+    local: &Task,
+    server: &Task,
+    keep_local: &[String],
+) -> (Task, Vec<FieldDiff>) {
     let mut merged = server.clone();
+    let mut diffs = Vec::new();
 
+    // `$show` renders the field's value for the conflict dialog
     macro_rules! merge_field {
-        ($field:ident) => {
+        ($field:ident, $show:expr) => {
             if local.$field != base.$field {
                 if server.$field == base.$field {
                     merged.$field = local.$field.clone();
                 } else if local.$field != server.$field {
-                    return None;
+                    let name = stringify!($field);
+                    let show = $show;
+                    diffs.push(FieldDiff {
+                        field: name.to_string(),
+                        base: show(&base.$field),
+                        local: show(&local.$field),
+                        server: show(&server.$field),
+                    });
+                    if keep_local.iter().any(|f| f == name) {
+                        merged.$field = local.$field.clone();
+                    }
                 }
             }
         };
     }
 
-    merge_field!(summary);
-    merge_field!(description);
-    merge_field!(status);
-    merge_field!(priority);
-    merge_field!(due);
-    merge_field!(dtstart);
-    merge_field!(estimated_duration);
-    merge_field!(rrule);
-    merge_field!(categories);
-    merge_field!(dependencies);
-    merge_field!(parent_uid);
-    merge_field!(unmapped_properties);
-    merge_field!(raw_components);
-
-    Some(merged)
+    merge_field!(summary, show_text);
+    merge_field!(description, show_text);
+    merge_field!(status, |s: &TaskStatus| s.label().to_string());
+    merge_field!(priority, |p: &u8| match p {
+        0 => NO_VALUE.to_string(),
+        p => format!("!{}", p),
+    });
+    merge_field!(due, show_date);
+    merge_field!(due_is_date_only, |all_day: &bool| {
+        if *all_day { "All day" } else { "At a time" }.to_string()
+    });
+    merge_field!(dtstart, show_date);
+    merge_field!(estimated_duration, |d: &Option<u32>| d
+        .map_or(NO_VALUE.to_string(), format_mins));
+    merge_field!(rrule, show_opt_text);
+    merge_field!(categories, show_list);
+    merge_field!(dependencies, show_list);
+    merge_field!(parent_uid, show_opt_text);
+    merge_field!(unmapped_properties, |props: &Vec<RawProperty>| {
+        show_list(
+            &props
+                .iter()
+                .map(|p| format!("{}:{}", p.key, p.value))
+                .collect::<Vec<_>>(),
+        )
+    });
+    merge_field!(raw_components, |c: &Vec<String>| match c.len() {
+        0 => NO_VALUE.to_string(),
+        n => format!("{} components", n),
+    });
+    merge_field!(completed_instances, |dates: &Vec<DateTime<Utc>>| {
+        show_list(
+            &dates
+                .iter()
+                .map(|d| show_date(&Some(*d)))
+                .collect::<Vec<_>>(),
+        )
+    });
+    merge_field!(reminders, |r: &Vec<u32>| {
+        show_list(
+            &r.iter()
+                .map(|m| format!("{} before", format_mins(*m)))
+                .collect::<Vec<_>>(),
+        )
+    });
+    merge_field!(completed_at, show_date);
+    merge_field!(percent_complete, |p: &u8| format!("{}%", p));
+    merge_field!(url, show_opt_text);
+    merge_field!(attendees, show_list);
+    merge_field!(color, show_opt_text);
+    merge_field!(sort_order, |o: &Option<i64>| o
+        .map_or(NO_VALUE.to_string(), |o| o.to_string()));
+    merge_field!(deferred, |d: &bool| if *d { "Yes" } else { "No" }
+        .to_string());
+    merge_field!(time_spent, |m: &u32| format_mins(*m));
+    merge_field!(geo, |g: &Option<(f64, f64)>| g.map_or(
+        NO_VALUE.to_string(),
+        |(lat, lon)| format!("{}, {}", lat, lon)
+    ));
+
+    (merged, diffs)
+}
+
+/// Shown in the conflict dialog for a field that isn't set.
+const NO_VALUE: &str = "—";
+
+fn show_text(text: &str) -> String {
+    if text.is_empty() {
+        NO_VALUE.to_string()
+    } else {
+        text.to_string()
+    }
+}
+
+fn show_opt_text(text: &Option<String>) -> String {
+    text.as_deref().map_or(NO_VALUE.to_string(), show_text)
+}
+
+fn show_list(items: &[String]) -> String {
+    show_text(&items.join(", "))
+}
+
+fn show_date(date: &Option<DateTime<Utc>>) -> String {
+    match date {
+        Some(d) => d.with_timezone(&Local).format("%Y-%m-%d %H:%M").to_string(),
+        None => NO_VALUE.to_string(),
+    }
+}
+
+/// Automatic merge; on failure returns the fields that diverged on both sides.
+fn three_way_merge(base: &Task, local: &Task, server: &Task) -> Result<Task, Vec<FieldDiff>> {
+    let (merged, diffs) = merge_tasks(base, local, server, &[]);
+    if diffs.is_empty() {
+        Ok(merged)
+    } else {
+        Err(diffs)
+    }
+}
+
+/// Settles a conflict in favour of the most recently modified side: the diverged
+/// `fields` when the base is known, the whole task otherwise. Ties go to the server.
+fn newest_wins(base: Option<&Task>, local: &Task, server: &Task, fields: &[FieldDiff]) -> Task {
+    let local_newer = local.last_modified > server.last_modified;
+    match base {
+        Some(base) => {
+            let keep_local: Vec<String> = if local_newer {
+                fields.iter().map(|f| f.field.clone()).collect()
+            } else {
+                Vec::new()
+            };
+            merge_tasks(base, local, server, &keep_local).0
+        }
+        None if local_newer => {
+            let mut task = local.clone();
+            task.etag = server.etag.clone();
+            task
+        }
+        None => server.clone(),
+    }
+}
+
+/// Builds the task to upload once the user has picked a side for each diverged field.
+pub fn resolve_merge(conflict: &MergeConflict, keep_local: &[String]) -> Task {
+    merge_tasks(
+        &conflict.base,
+        &conflict.local,
+        &conflict.server,
+        keep_local,
+    )
+    .0
+}
+
+/// Whether a PROPFIND response grants write access in its
+/// current-user-privilege-set, or `None` when the set is missing or empty
+/// (the property was not found).
+fn privileges_allow_write(xml: &str) -> Option<bool> {
+    const SET: &str = "current-user-privilege-set";
+    let open = xml.find(SET)?;
+    let start = open + xml[open..].find('>')? + 1;
+    let end = xml.rfind(SET).filter(|end| *end > start)?;
+    let set = &xml[start..end];
+    let mut privileges = set
+        .split('<')
+        .skip(1)
+        .filter(|tag| !tag.starts_with('/'))
+        .filter_map(|tag| {
+            let name = tag.split(['>', '/', ' ']).next()?;
+            Some(name.rsplit(':').next().unwrap_or(name))
+        })
+        .filter(|name| *name != "privilege")
+        .peekable();
+    privileges.peek()?;
+    Some(privileges.any(|name| matches!(name, "all" | "write" | "write-content" | "bind")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_privileges_allow_write() {
+        let read_only = r#"<d:multistatus xmlns:d="DAV:"><d:response><d:propstat><d:prop>
+            <d:current-user-privilege-set><d:privilege><d:read/></d:privilege>
+            <d:privilege><d:read-current-user-privilege-set/></d:privilege>
+            </d:current-user-privilege-set></d:prop></d:propstat></d:response></d:multistatus>"#;
+        assert_eq!(privileges_allow_write(read_only), Some(false));
+
+        let writable = read_only.replace(
+            "<d:read/>",
+            "<d:read/></d:privilege><d:privilege><d:write/>",
+        );
+        assert_eq!(privileges_allow_write(&writable), Some(true));
+        let all = r#"<current-user-privilege-set xmlns="DAV:"><privilege><all/></privilege>
+            </current-user-privilege-set>"#;
+        assert_eq!(privileges_allow_write(all), Some(true));
+
+        // 404 propstat: the property is listed but empty
+        let missing = r#"<d:prop><d:current-user-privilege-set/></d:prop>"#;
+        assert_eq!(privileges_allow_write(missing), None);
+        assert_eq!(privileges_allow_write("<d:multistatus/>"), None);
+    }
+
+    #[test]
+    fn test_merge_conflict_diff_and_resolution() {
+        let mut base = Task::new("Base", &HashMap::new());
+        base.priority = 5;
+        let mut local = base.clone();
+        local.summary = "Local".to_string();
+        local.priority = 1;
+        let mut server = base.clone();
+        server.summary = "Server".to_string();
+        server.description = "from server".to_string();
+        server.etag = "server-etag".to_string();
+
+        let fields = three_way_merge(&base, &local, &server).unwrap_err();
+        assert_eq!(fields.len(), 1);
+        assert_eq!(fields[0].field, "summary");
+        assert_eq!(fields[0].local, "Local");
+        assert_eq!(fields[0].server, "Server");
+
+        let conflict = MergeConflict {
+            base,
+            local,
+            server,
+            fields,
+        };
+        let server_wins = resolve_merge(&conflict, &[]);
+        assert_eq!(server_wins.summary, "Server");
+        assert_eq!(server_wins.priority, 1);
+        assert_eq!(server_wins.description, "from server");
+        assert_eq!(server_wins.etag, "server-etag");
+
+        let local_wins = resolve_merge(&conflict, &["summary".to_string()]);
+        assert_eq!(local_wins.summary, "Local");
+    }
+
+    #[test]
+    fn test_conflict_fields_are_shown_readably() {
+        use chrono::TimeZone;
+        let base = Task::new("Base", &HashMap::new());
+        let mut local = base.clone();
+        local.status = TaskStatus::InProcess;
+        local.priority = 2;
+        local.due = Some(Utc.with_ymd_and_hms(2026, 3, 2, 9, 30, 0).unwrap());
+        let mut server = base.clone();
+        server.status = TaskStatus::Cancelled;
+        server.priority = 7;
+        server.url = Some("https://example.com".to_string());
+        local.url = Some(String::new());
+
+        let fields = three_way_merge(&base, &local, &server).unwrap_err();
+        let shown = |name: &str| {
+            let diff = fields.iter().find(|f| f.field == name).unwrap();
+            (
+                diff.base.as_str(),
+                diff.local.as_str(),
+                diff.server.as_str(),
+            )
+        };
+        assert_eq!(shown("status"), ("Needs action", "In process", "Cancelled"));
+        assert_eq!(shown("priority"), ("—", "!2", "!7"));
+        assert_eq!(shown("url"), ("—", "—", "https://example.com"));
+        // Only changed locally so far
+        assert!(fields.iter().all(|f| f.field != "due"));
+        server.due = Some(Utc.with_ymd_and_hms(2026, 3, 3, 9, 30, 0).unwrap());
+        let fields = three_way_merge(&base, &local, &server).unwrap_err();
+        let diff = fields.iter().find(|f| f.field == "due").unwrap();
+        assert_eq!(diff.base, "—");
+        let local_due = local.due.unwrap().with_timezone(&Local);
+        assert_eq!(diff.local, local_due.format("%Y-%m-%d %H:%M").to_string());
+    }
+
+    #[test]
+    fn test_newest_wins_by_last_modified() {
+        use chrono::TimeZone;
+        let base = Task::new("Base", &HashMap::new());
+        let mut local = base.clone();
+        local.summary = "Local".to_string();
+        local.priority = 2;
+        let mut server = base.clone();
+        server.summary = "Server".to_string();
+        server.etag = "server-etag".to_string();
+        let fields = three_way_merge(&base, &local, &server).unwrap_err();
+
+        local.last_modified = Some(chrono::Utc.with_ymd_and_hms(2025, 3, 2, 9, 0, 0).unwrap());
+        server.last_modified = Some(chrono::Utc.with_ymd_and_hms(2025, 3, 1, 9, 0, 0).unwrap());
+        let merged = newest_wins(Some(&base), &local, &server, &fields);
+        assert_eq!(merged.summary, "Local");
+        assert_eq!(merged.priority, 2);
+        assert_eq!(merged.etag, "server-etag");
+
+        // Server edited later: its summary wins, the local-only change is kept
+        server.last_modified = Some(chrono::Utc.with_ymd_and_hms(2025, 3, 3, 9, 0, 0).unwrap());
+        let merged = newest_wins(Some(&base), &local, &server, &fields);
+        assert_eq!(merged.summary, "Server");
+        assert_eq!(merged.priority, 2);
+
+        // Without a base the newer version is taken whole
+        let whole = newest_wins(None, &local, &server, &[]);
+        assert_eq!(whole.summary, "Server");
+        server.last_modified = None;
+        let whole = newest_wins(None, &local, &server, &[]);
+        assert_eq!(whole.summary, "Local");
+        assert_eq!(whole.etag, "server-etag");
+    }
 }
@@ -0,0 +1,98 @@
+// File: src/client/demo.rs
+// In-memory backend behind the "demo://" url: try the app without a server
+use crate::model::{CalendarListEntry, Task};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+pub const DEMO_SCHEME: &str = "demo://";
+
+/// Calendars and tasks kept in memory only; everything is lost on exit.
+/// Unlike the Local calendar, nothing is written to disk or queued in the journal.
+#[derive(Debug)]
+pub struct DemoBackend {
+    pub calendars: Vec<CalendarListEntry>,
+    tasks: Mutex<HashMap<String, Vec<Task>>>,
+}
+
+impl DemoBackend {
+    pub fn seeded() -> Self {
+        let calendars = vec![
+            CalendarListEntry {
+                name: "Personal".to_string(),
+                href: "demo://personal/".to_string(),
+                color: Some("#4A90D9".to_string()),
+                read_only: false,
+            },
+            CalendarListEntry {
+                name: "Work".to_string(),
+                href: "demo://work/".to_string(),
+                color: Some("#E67E22".to_string()),
+                read_only: false,
+            },
+        ];
+
+        let aliases = HashMap::new();
+        let seed: [(&str, &[&str]); 2] = [
+            (
+                "demo://personal/",
+                &[
+                    "Buy groceries !3 @tomorrow #shopping",
+                    "Call the dentist !2 @today",
+                    "Water the plants @every 3 days #home",
+                    "Read a book ~1h #leisure",
+                ],
+            ),
+            (
+                "demo://work/",
+                &[
+                    "Prepare the quarterly report !1 @2d ~3h #reports",
+                    "Review pull requests ~30m #dev",
+                    "Plan the team offsite ^1w #planning",
+                ],
+            ),
+        ];
+
+        let mut tasks = HashMap::new();
+        for (href, inputs) in seed {
+            let list: Vec<Task> = inputs
+                .iter()
+                .map(|input| {
+                    let mut task = Task::new(input, &aliases);
+                    task.calendar_href = href.to_string();
+                    task.href = format!("{}{}.ics", href, task.uid);
+                    task
+                })
+                .collect();
+            tasks.insert(href.to_string(), list);
+        }
+
+        Self {
+            calendars,
+            tasks: Mutex::new(tasks),
+        }
+    }
+
+    pub fn tasks(&self, calendar_href: &str) -> Vec<Task> {
+        self.tasks
+            .lock()
+            .unwrap()
+            .get(calendar_href)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    pub fn upsert(&self, task: &Task) {
+        let mut map = self.tasks.lock().unwrap();
+        let list = map.entry(task.calendar_href.clone()).or_default();
+        match list.iter_mut().find(|t| t.uid == task.uid) {
+            Some(existing) => *existing = task.clone(),
+            None => list.push(task.clone()),
+        }
+    }
+
+    pub fn remove(&self, task: &Task) {
+        if let Some(list) = self.tasks.lock().unwrap().get_mut(&task.calendar_href) {
+            list.retain(|t| t.uid != task.uid);
+        }
+    }
+}
@@ -1,6 +1,13 @@
 // File: ./src/client/mod.rs
 // re-exports the cleaned up client modules
+pub mod capabilities;
 pub mod cert;
 pub mod core;
+pub mod demo;
+pub mod proxy;
+pub mod redirect;
+pub mod sync_collection;
+pub mod timeout;
 
-pub use self::core::{GET_CTAG, RustyClient};
+pub use self::capabilities::ServerCapabilities;
+pub use self::core::{FetchedTasks, GET_CTAG, JournalSync, RustyClient};
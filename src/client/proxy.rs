@@ -0,0 +1,146 @@
+// File: src/client/proxy.rs
+// Connector going through the proxy of HTTP(S)_PROXY, or the one set in the config
+use http::Uri;
+use hyper_util::client::legacy::connect::HttpConnector;
+use hyper_util::client::legacy::connect::proxy::Tunnel;
+use hyper_util::client::proxy::matcher::Matcher;
+use std::error::Error;
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tower_service::Service;
+
+/// Shown instead of the raw client error when the proxy can't be reached or refuses
+/// the connection, so it isn't mistaken for a server error.
+pub const PROXY_MESSAGE: &str = "Could not connect through the proxy";
+
+type BoxError = Box<dyn Error + Send + Sync>;
+
+/// Picks the proxy for each destination, or none for hosts listed in NO_PROXY.
+/// Both HTTPS and plain HTTP requests are tunnelled with CONNECT.
+#[derive(Clone)]
+pub struct ProxyConnector {
+    http: HttpConnector,
+    matcher: Option<Arc<Matcher>>,
+}
+
+impl ProxyConnector {
+    /// `setting` overrides the environment: a proxy url, or "none" to always
+    /// connect directly. Without it ALL_PROXY, HTTPS_PROXY and HTTP_PROXY apply.
+    pub fn new(http: HttpConnector, setting: Option<&str>) -> Result<Self, String> {
+        let matcher = match setting.map(str::trim) {
+            None => Some(Matcher::from_env()),
+            Some("" | "none") => None,
+            Some(url) => {
+                let uri: Uri = url
+                    .parse()
+                    .map_err(|e| format!("Invalid proxy '{}': {}", url, e))?;
+                if uri.host().is_none() {
+                    return Err(format!("Invalid proxy '{}': missing host", url));
+                }
+                let no_proxy = ["NO_PROXY", "no_proxy"]
+                    .iter()
+                    .find_map(|k| std::env::var(k).ok())
+                    .unwrap_or_default();
+                Some(Matcher::builder().all(url).no(no_proxy).build())
+            }
+        };
+        Ok(Self {
+            http,
+            matcher: matcher.map(Arc::new),
+        })
+    }
+}
+
+pub enum ProxyError {
+    /// The tunnel through the proxy (named by host:port) could not be set up.
+    Proxy(String, BoxError),
+    Direct(BoxError),
+}
+
+// `ProxyFailed[...]` is what `describe_error` looks for in the formatted error chain.
+impl fmt::Debug for ProxyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Proxy(proxy, e) => {
+                write!(f, "ProxyFailed[{}: {}", proxy, e)?;
+                let mut source = e.source();
+                while let Some(cause) = source {
+                    write!(f, ": {}", cause)?;
+                    source = cause.source();
+                }
+                f.write_str("]")
+            }
+            Self::Direct(e) => e.fmt(f),
+        }
+    }
+}
+
+impl fmt::Display for ProxyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Proxy(proxy, e) => write!(f, "{} {}: {}", PROXY_MESSAGE, proxy, e),
+            Self::Direct(e) => e.fmt(f),
+        }
+    }
+}
+
+impl Error for ProxyError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Proxy(_, e) | Self::Direct(e) => Some(e.as_ref()),
+        }
+    }
+}
+
+impl Service<Uri> for ProxyConnector {
+    type Response = <HttpConnector as Service<Uri>>::Response;
+    type Error = ProxyError;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.http
+            .poll_ready(cx)
+            .map_err(|e| ProxyError::Direct(Box::new(e)))
+    }
+
+    fn call(&mut self, dst: Uri) -> Self::Future {
+        let Some(intercept) = self.matcher.as_ref().and_then(|m| m.intercept(&dst)) else {
+            let connecting = self.http.call(dst);
+            return Box::pin(async move {
+                connecting
+                    .await
+                    .map_err(|e| ProxyError::Direct(Box::new(e)))
+            });
+        };
+
+        // Never show the proxy credentials, only where it is
+        let proxy = intercept.uri();
+        let name = proxy
+            .authority()
+            .map_or("", |a| a.as_str())
+            .rsplit('@')
+            .next()
+            .unwrap_or_default()
+            .to_string();
+        let mut tunnel = Tunnel::new(proxy.clone(), self.http.clone());
+        if let Some(auth) = intercept.basic_auth() {
+            tunnel = tunnel.with_auth(auth.clone());
+        }
+        // The tunnel assumes port 443 when the url has none
+        let target = match (dst.host(), dst.port_u16(), dst.scheme_str()) {
+            (Some(host), None, Some("http")) => {
+                format!("http://{}:80", host).parse().unwrap_or(dst)
+            }
+            _ => dst,
+        };
+        Box::pin(async move {
+            tunnel
+                .call(target)
+                .await
+                .map_err(|e| ProxyError::Proxy(name, Box::new(e)))
+        })
+    }
+}
@@ -0,0 +1,167 @@
+// File: src/client/redirect.rs
+// Redirect handling around the HTTP client used by libdav
+use http::{HeaderValue, Request, Response, StatusCode, Uri, header};
+use std::fmt;
+use std::future::{Future, poll_fn};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tower_service::Service;
+
+/// Redirects followed for one request before giving up.
+pub const MAX_REDIRECTS: usize = 5;
+
+/// Start of the error shown when a redirect can't be followed.
+pub const REDIRECT_MESSAGE: &str = "Server redirected to";
+
+pub fn redirect_message(location: &str) -> String {
+    format!("{} {}, update your URL?", REDIRECT_MESSAGE, location)
+}
+
+/// Follows 301/302/307/308 answers on the same origin, keeping the method and body.
+/// Redirects to another host, port or scheme are not followed, so the
+/// credentials never leave the configured server.
+#[derive(Clone, Debug)]
+pub struct FollowRedirects<S> {
+    inner: S,
+}
+
+impl<S> FollowRedirects<S> {
+    pub fn new(inner: S) -> Self {
+        Self { inner }
+    }
+}
+
+pub enum RedirectError<E> {
+    /// The server redirected somewhere we don't follow (or too many times).
+    Unfollowed(String),
+    Inner(E),
+}
+
+// `Redirected[...]` is what `describe_error` looks for in the formatted error chain.
+impl<E: fmt::Debug> fmt::Debug for RedirectError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unfollowed(location) => write!(f, "Redirected[{}]", location),
+            Self::Inner(e) => e.fmt(f),
+        }
+    }
+}
+
+impl<E: fmt::Display> fmt::Display for RedirectError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unfollowed(location) => f.write_str(&redirect_message(location)),
+            Self::Inner(e) => e.fmt(f),
+        }
+    }
+}
+
+impl<E: std::error::Error + 'static> std::error::Error for RedirectError<E> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Unfollowed(_) => None,
+            Self::Inner(e) => Some(e),
+        }
+    }
+}
+
+/// Where a redirect from `from` to `location` leads, if it may be followed.
+fn follow_target(from: &Uri, location: &HeaderValue) -> Result<Uri, String> {
+    let location = location.to_str().map_err(|e| e.to_string())?;
+    let target: Uri = location.parse().map_err(|_| location.to_string())?;
+    let target = if target.authority().is_some() {
+        target
+    } else if location.starts_with('/') {
+        let mut parts = from.clone().into_parts();
+        parts.path_and_query = target.path_and_query().cloned();
+        Uri::from_parts(parts).map_err(|_| location.to_string())?
+    } else {
+        return Err(location.to_string());
+    };
+    // Same scheme, host and port: another port may be another service
+    if target.scheme() == from.scheme() && target.authority() == from.authority() {
+        Ok(target)
+    } else {
+        Err(target.to_string())
+    }
+}
+
+impl<S, Rx> Service<Request<String>> for FollowRedirects<S>
+where
+    S: Service<Request<String>, Response = Response<Rx>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    S::Error: Send,
+    Rx: Send + 'static,
+{
+    type Response = Response<Rx>;
+    type Error = RedirectError<S::Error>;
+    type Future = Pin<Box<dyn Future<Output = Result<Response<Rx>, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx).map_err(RedirectError::Inner)
+    }
+
+    fn call(&mut self, req: Request<String>) -> Self::Future {
+        // The ready clone sends the first request, the fresh one the redirected ones
+        let fresh = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, fresh);
+        Box::pin(async move {
+            let (parts, body) = req.into_parts();
+            let mut uri = parts.uri.clone();
+            let mut hops = 0;
+            loop {
+                let mut request = Request::new(body.clone());
+                *request.method_mut() = parts.method.clone();
+                *request.uri_mut() = uri.clone();
+                *request.headers_mut() = parts.headers.clone();
+                let response = inner.call(request).await.map_err(RedirectError::Inner)?;
+                let status = response.status();
+                let followable = matches!(
+                    status,
+                    StatusCode::MOVED_PERMANENTLY
+                        | StatusCode::FOUND
+                        | StatusCode::TEMPORARY_REDIRECT
+                        | StatusCode::PERMANENT_REDIRECT
+                );
+                let location = response.headers().get(header::LOCATION);
+                let (true, Some(location)) = (followable, location) else {
+                    return Ok(response);
+                };
+                match follow_target(&uri, location) {
+                    Ok(target) if hops < MAX_REDIRECTS => uri = target,
+                    Ok(target) => return Err(RedirectError::Unfollowed(target.to_string())),
+                    Err(target) => return Err(RedirectError::Unfollowed(target)),
+                }
+                hops += 1;
+                poll_fn(|cx| inner.poll_ready(cx))
+                    .await
+                    .map_err(RedirectError::Inner)?;
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_follow_target() {
+        let from: Uri = "https://dav.example.com/".parse().unwrap();
+        let target = |loc: &str| follow_target(&from, &HeaderValue::from_str(loc).unwrap());
+        assert_eq!(
+            target("/dav/").unwrap().to_string(),
+            "https://dav.example.com/dav/"
+        );
+        assert_eq!(
+            target("https://dav.example.com:8443/dav/").unwrap_err(),
+            "https://dav.example.com:8443/dav/"
+        );
+        assert_eq!(
+            target("https://other.example.com/dav/").unwrap_err(),
+            "https://other.example.com/dav/"
+        );
+        assert!(target("http://dav.example.com/dav/").is_err());
+        assert!(target("dav/").is_err());
+    }
+}
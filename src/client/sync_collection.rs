@@ -0,0 +1,111 @@
+// File: ./src/client/sync_collection.rs
+// Incremental listing with the WebDAV sync-collection REPORT (RFC 6578)
+
+const DAV: &str = "DAV:";
+
+/// Body of a REPORT asking for the resources changed since `token`, with their ETags.
+pub fn report_body(token: &str) -> String {
+    let token = token
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;");
+    format!(
+        concat!(
+            r#"<?xml version="1.0" encoding="utf-8"?>"#,
+            r#"<D:sync-collection xmlns:D="DAV:">"#,
+            r#"<D:sync-token>{}</D:sync-token><D:sync-level>1</D:sync-level>"#,
+            r#"<D:prop><D:getetag/></D:prop></D:sync-collection>"#,
+        ),
+        token
+    )
+}
+
+/// What changed in a calendar since the token sent with the REPORT.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct SyncChanges {
+    /// Added or modified resources, with their ETag when the server sent one
+    pub changed: Vec<(String, Option<String>)>,
+    /// Resources deleted on the server
+    pub removed: Vec<String>,
+    /// Token for the next REPORT
+    pub token: Option<String>,
+    /// The server sent only part of the changes (507 on the collection)
+    pub truncated: bool,
+}
+
+/// Reads the multistatus answer of a sync-collection REPORT.
+pub fn parse_changes(xml: &str) -> Result<SyncChanges, String> {
+    let doc = roxmltree::Document::parse(xml).map_err(|e| e.to_string())?;
+    let is = |node: &roxmltree::Node, name: &str| {
+        node.is_element()
+            && node.tag_name().name() == name
+            && node.tag_name().namespace() == Some(DAV)
+    };
+    let child_text = |node: &roxmltree::Node, name: &str| {
+        node.children()
+            .find(|c| is(c, name))
+            .and_then(|c| c.text())
+            .map(|t| t.trim().to_string())
+    };
+
+    let root = doc.root_element();
+    if !is(&root, "multistatus") {
+        return Err("Not a multistatus response".to_string());
+    }
+    let mut changes = SyncChanges {
+        token: child_text(&root, "sync-token").filter(|t| !t.is_empty()),
+        ..Default::default()
+    };
+    for response in root.children().filter(|c| is(c, "response")) {
+        let Some(href) = child_text(&response, "href") else {
+            continue;
+        };
+        let status = child_text(&response, "status").unwrap_or_default();
+        if status.contains(" 404") {
+            changes.removed.push(href);
+        } else if status.contains(" 507") {
+            changes.truncated = true;
+        } else {
+            let etag = response
+                .children()
+                .filter(|c| is(c, "propstat"))
+                .filter(|p| child_text(p, "status").is_none_or(|s| s.contains(" 200")))
+                .filter_map(|p| p.children().find(|c| is(c, "prop")))
+                .find_map(|prop| child_text(&prop, "getetag"))
+                .filter(|e| !e.is_empty());
+            changes.changed.push((href, etag));
+        }
+    }
+    Ok(changes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_sync_changes() {
+        let xml = r#"<d:multistatus xmlns:d="DAV:">
+            <d:response><d:href>/cal/new.ics</d:href><d:propstat><d:prop>
+            <d:getetag>"7"</d:getetag></d:prop><d:status>HTTP/1.1 200 OK</d:status>
+            </d:propstat></d:response>
+            <d:response><d:href>/cal/gone.ics</d:href>
+            <d:status>HTTP/1.1 404 Not Found</d:status></d:response>
+            <d:sync-token>http://example.com/sync/12</d:sync-token>
+            </d:multistatus>"#;
+        let changes = parse_changes(xml).unwrap();
+        assert_eq!(
+            changes.changed,
+            vec![("/cal/new.ics".to_string(), Some("\"7\"".to_string()))]
+        );
+        assert_eq!(changes.removed, vec!["/cal/gone.ics"]);
+        assert_eq!(changes.token.as_deref(), Some("http://example.com/sync/12"));
+        assert!(!changes.truncated);
+
+        let truncated = r#"<multistatus xmlns="DAV:"><response><href>/cal/</href>
+            <status>HTTP/1.1 507 Insufficient Storage</status></response></multistatus>"#;
+        assert!(parse_changes(truncated).unwrap().truncated);
+        assert!(parse_changes("<html/>").is_err());
+        assert!(report_body("a&b").contains("<D:sync-token>a&amp;b</D:sync-token>"));
+    }
+}
@@ -0,0 +1,106 @@
+// File: src/client/timeout.rs
+// Request timeout around the HTTP client used by libdav
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tower_service::Service;
+
+use crate::client::proxy::PROXY_MESSAGE;
+use crate::client::redirect::redirect_message;
+
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Shown instead of the raw client error when a request times out.
+pub const TIMEOUT_MESSAGE: &str = "Connection timed out";
+
+#[derive(Clone, Debug)]
+pub struct Timeout<S> {
+    inner: S,
+    timeout: Duration,
+}
+
+impl<S> Timeout<S> {
+    pub fn new(inner: S, timeout: Duration) -> Self {
+        Self { inner, timeout }
+    }
+}
+
+pub enum TimeoutError<E> {
+    TimedOut(Duration),
+    Inner(E),
+}
+
+// `TimedOut` is what `describe_error` looks for in the formatted error chain;
+// refused or unreachable connections timing out in the OS show up the same way.
+impl<E: fmt::Debug> fmt::Debug for TimeoutError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TimedOut(d) => write!(f, "TimedOut({}s)", d.as_secs()),
+            Self::Inner(e) => e.fmt(f),
+        }
+    }
+}
+
+impl<E: fmt::Display> fmt::Display for TimeoutError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TimedOut(d) => write!(f, "{} after {}s", TIMEOUT_MESSAGE, d.as_secs()),
+            Self::Inner(e) => e.fmt(f),
+        }
+    }
+}
+
+impl<E: std::error::Error + 'static> std::error::Error for TimeoutError<E> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::TimedOut(_) => None,
+            Self::Inner(e) => Some(e),
+        }
+    }
+}
+
+impl<S, Req> Service<Req> for Timeout<S>
+where
+    S: Service<Req>,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = TimeoutError<S::Error>;
+    type Future = Pin<Box<dyn Future<Output = Result<S::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx).map_err(TimeoutError::Inner)
+    }
+
+    fn call(&mut self, req: Req) -> Self::Future {
+        let timeout = self.timeout;
+        let response = self.inner.call(req);
+        Box::pin(async move {
+            match tokio::time::timeout(timeout, response).await {
+                Ok(res) => res.map_err(TimeoutError::Inner),
+                Err(_) => Err(TimeoutError::TimedOut(timeout)),
+            }
+        })
+    }
+}
+
+/// Debug-formats a request error prefixed with `context`, except that proxy
+/// failures, redirects we don't follow and timeouts get a plain message so they
+/// can't be mistaken for TLS or server errors.
+pub fn describe_error<E: fmt::Debug>(context: &str, err: &E) -> String {
+    let msg = format!("{:?}", err);
+    if let Some((_, rest)) = msg.split_once("ProxyFailed[") {
+        let detail = rest.split_once(']').map_or(rest, |(d, _)| d);
+        format!("{} ({})", PROXY_MESSAGE, detail)
+    } else if let Some((_, rest)) = msg.split_once("Redirected[") {
+        redirect_message(rest.split_once(']').map_or(rest, |(l, _)| l))
+    } else if msg.contains("TimedOut") {
+        TIMEOUT_MESSAGE.to_string()
+    } else if context.is_empty() {
+        msg
+    } else {
+        format!("{}: {}", context, msg)
+    }
+}
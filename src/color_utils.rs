@@ -1,6 +1,25 @@
 // File: src/color_utils.rs
+use std::collections::HashMap;
 use std::hash::{Hash, Hasher};
 
+/// Color of a tag: the configured one for the tag (or its closest parent, so
+/// `#work:meeting` follows `work`), otherwise the stable color from `generate_color`.
+pub fn tag_color(tag: &str, overrides: &HashMap<String, String>) -> (f32, f32, f32) {
+    let mut search = tag;
+    loop {
+        if let Some(rgb) = overrides
+            .get(search)
+            .and_then(|hex| parse_hex_to_floats(hex))
+        {
+            return rgb;
+        }
+        match search.rfind(':') {
+            Some(idx) => search = &search[..idx],
+            None => return generate_color(tag),
+        }
+    }
+}
+
 /// Generates a deterministic color tuple (r, g, b) in [0.0, 1.0] range based on the input string.
 /// Ranges updated to S: 40-90, L: 65-90 per user request.
 pub fn generate_color(tag: &str) -> (f32, f32, f32) {
@@ -68,6 +87,43 @@ pub fn parse_hex_to_floats(hex: &str) -> Option<(f32, f32, f32)> {
     Some((r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0))
 }
 
+/// Flag colors offered by the pickers, by name. The names are CSS3 color names,
+/// what RFC 7986 expects in COLOR.
+pub const FLAG_COLORS: [(&str, &str); 9] = [
+    ("red", "#e5484d"),
+    ("orange", "#f76b15"),
+    ("yellow", "#ffc53d"),
+    ("green", "#46a758"),
+    ("blue", "#0090ff"),
+    ("purple", "#8e4ec6"),
+    ("pink", "#d6409f"),
+    ("brown", "#ad7f58"),
+    ("gray", "#8b8d98"),
+];
+
+/// The `FLAG_COLORS` name matching `color` in any case.
+pub fn flag_color_name(color: &str) -> Option<&'static str> {
+    FLAG_COLORS
+        .iter()
+        .map(|(name, _)| *name)
+        .find(|name| name.eq_ignore_ascii_case(color))
+}
+
+/// RGB of a task flag color: one of `FLAG_COLORS` by name (any case) or `#rrggbb`.
+pub fn flag_color(color: &str) -> Option<(f32, f32, f32)> {
+    if let Some((_, hex)) = FLAG_COLORS
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(color))
+    {
+        return parse_hex_to_floats(hex);
+    }
+    let hex = color.strip_prefix('#')?;
+    if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    parse_hex_to_floats(hex)
+}
+
 pub fn parse_hex_to_u8(hex: &str) -> Option<(u8, u8, u8)> {
     let hex = hex.trim_start_matches('#');
     if hex.len() < 6 {
@@ -79,3 +135,33 @@ pub fn parse_hex_to_u8(hex: &str) -> Option<(u8, u8, u8)> {
 
     Some((r, g, b))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tag_color_override_and_inheritance() {
+        let mut overrides = HashMap::new();
+        overrides.insert("work".to_string(), "#ff0000".to_string());
+
+        assert_eq!(tag_color("work", &overrides), (1.0, 0.0, 0.0));
+        assert_eq!(tag_color("work:meeting", &overrides), (1.0, 0.0, 0.0));
+        assert_eq!(tag_color("home", &overrides), generate_color("home"));
+        assert_eq!(
+            tag_color("home", &overrides),
+            tag_color("home", &HashMap::new())
+        );
+    }
+
+    #[test]
+    fn test_flag_color() {
+        assert_eq!(flag_color("#FF0000"), Some((1.0, 0.0, 0.0)));
+        assert_eq!(flag_color("Blue"), parse_hex_to_floats("#0090ff"));
+        assert_eq!(flag_color("#ff00"), None);
+        assert_eq!(flag_color("#ff00é0"), None);
+        assert_eq!(flag_color("teal"), None);
+        assert_eq!(flag_color_name("Blue"), Some("blue"));
+        assert_eq!(flag_color_name("#0090ff"), None);
+    }
+}
@@ -1,4 +1,5 @@
 // File: src/config.rs
+use crate::model::{DEFAULT_DATE_FORMAT, Task, is_valid_date_format};
 use crate::paths::AppPaths;
 use crate::storage::LocalStorage;
 use anyhow::Result;
@@ -13,11 +14,181 @@ fn default_cutoff() -> Option<u32> {
     Some(6)
 }
 
+fn default_date_format() -> String {
+    DEFAULT_DATE_FORMAT.to_string()
+}
+
+fn default_snooze_presets() -> Vec<String> {
+    ["tomorrow", "3d", "next week"].map(String::from).to_vec()
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum AuthType {
+    /// HTTP Basic with `username` and `password`
+    #[default]
+    Basic,
+    /// `Authorization: Bearer` with `token`
+    Bearer,
+}
+
+/// What to do with fields changed both here and on the server since the last sync.
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ConflictStrategy {
+    /// Ask, or upload a conflict copy when the versions cannot be compared
+    #[default]
+    Copy,
+    /// Keep the version with the most recent LAST-MODIFIED
+    Newest,
+}
+
+/// First day of the week for the "This Week" agenda section.
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum WeekStart {
+    #[default]
+    #[serde(alias = "mon")]
+    Monday,
+    #[serde(alias = "sun")]
+    Sunday,
+}
+
+impl WeekStart {
+    pub fn weekday(self) -> chrono::Weekday {
+        match self {
+            Self::Monday => chrono::Weekday::Mon,
+            Self::Sunday => chrono::Weekday::Sun,
+        }
+    }
+}
+
+/// Colors of the GUI.
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ThemeMode {
+    #[default]
+    Dark,
+    Light,
+    /// Follow the desktop's light/dark preference, dark when it has none
+    System,
+}
+
+impl ThemeMode {
+    pub const ALL: [ThemeMode; 3] = [ThemeMode::Dark, ThemeMode::Light, ThemeMode::System];
+}
+
+impl std::fmt::Display for ThemeMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Dark => "Dark",
+            Self::Light => "Light",
+            Self::System => "System",
+        })
+    }
+}
+
+/// Applied to tasks created in a calendar (`[calendar_defaults."Work"]`).
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct CalendarDefaults {
+    /// Tags added to every new task (without '#')
+    #[serde(default)]
+    pub categories: Vec<String>,
+    /// Priority used unless the input sets one
+    #[serde(default)]
+    pub priority: Option<u8>,
+}
+
+impl CalendarDefaults {
+    /// The defaults of a calendar, keyed by its name or href.
+    pub fn lookup<'a>(
+        defaults: &'a HashMap<String, CalendarDefaults>,
+        href: &str,
+        name: Option<&str>,
+    ) -> Option<&'a CalendarDefaults> {
+        defaults
+            .get(href)
+            .or_else(|| name.and_then(|n| defaults.get(n)))
+    }
+
+    /// Merges the defaults into a freshly parsed task; what the input set wins.
+    pub fn apply(&self, task: &mut Task) {
+        if task.priority == 0
+            && let Some(p) = self.priority
+            && (1..=9).contains(&p)
+        {
+            task.priority = p;
+        }
+        for cat in &self.categories {
+            let cat = cat.trim_start_matches('#');
+            if !cat.is_empty() && !task.categories.iter().any(|c| c == cat) {
+                task.categories.push(cat.to_string());
+            }
+        }
+    }
+}
+
+/// A task and its subtasks, created in one go from the template picker
+/// (`[templates."Deploy"]`).
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct TaskTemplate {
+    /// Smart input of the parent task
+    pub task: String,
+    /// Smart input of each subtask, in order
+    #[serde(default)]
+    pub children: Vec<String>,
+}
+
+impl TaskTemplate {
+    /// Parses the template into new tasks, the parent first and then its
+    /// children. `{date}` is replaced by `today` (YYYY-MM-DD) in every line.
+    /// Nothing is created when the parent has no title.
+    pub fn instantiate(
+        &self,
+        aliases: &HashMap<String, Vec<String>>,
+        today: chrono::NaiveDate,
+    ) -> Vec<Task> {
+        let date = today.format("%Y-%m-%d").to_string();
+        let parent = Task::new(&self.task.replace("{date}", &date), aliases);
+        if parent.summary.trim().is_empty() {
+            return vec![];
+        }
+        let children: Vec<Task> = self
+            .children
+            .iter()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| {
+                let mut child = Task::new(&line.replace("{date}", &date), aliases);
+                child.parent_uid = Some(parent.uid.clone());
+                child
+            })
+            .collect();
+        std::iter::once(parent).chain(children).collect()
+    }
+}
+
 #[derive(Deserialize, Serialize, Clone, Debug)]
 pub struct Config {
     pub url: String,
     pub username: String,
+    /// Left empty in the file when `use_keyring` stored it in the OS keyring
+    #[serde(default)]
     pub password: String,
+    /// Keep the password in the OS secret store, keyed by url and username
+    #[serde(default)]
+    pub use_keyring: bool,
+    /// Problems `load` worked around (keyring unreadable, invalid date format)
+    #[serde(skip)]
+    pub load_warning: Option<String>,
+    /// Account and password `load` read from the keyring, so `save` only writes
+    /// when either changed
+    #[serde(skip)]
+    pub keyring_entry: Option<(String, String)>,
+    #[serde(default)]
+    pub auth_type: AuthType,
+    /// OAuth access token, used when `auth_type = "bearer"`
+    #[serde(default)]
+    pub token: String,
     pub default_calendar: Option<String>,
     #[serde(default)]
     pub allow_insecure_certs: bool,
@@ -25,14 +196,111 @@ pub struct Config {
     pub hidden_calendars: Vec<String>,
     #[serde(default)]
     pub disabled_calendars: Vec<String>,
+    /// Calendars (href or name) to treat as read-only regardless of the server
+    #[serde(default)]
+    pub read_only_calendars: Vec<String>,
     #[serde(default)]
     pub hide_completed: bool,
+    #[serde(default)]
+    pub hide_cancelled: bool,
+    /// Hide tasks blocked by an unfinished dependency instead of badging them
+    #[serde(default)]
+    pub hide_blocked: bool,
     #[serde(default = "default_true")]
     pub hide_fully_completed_tags: bool,
+    /// Order the tags sidebar by number of active tasks instead of by name
+    #[serde(default)]
+    pub sort_tags_by_count: bool,
     #[serde(default = "default_cutoff")]
     pub sort_cutoff_months: Option<u32>,
     #[serde(default)]
     pub tag_aliases: HashMap<String, Vec<String>>,
+    #[serde(default)]
+    pub auto_complete_parent: bool,
+    #[serde(default)]
+    pub refresh_interval_secs: Option<u64>,
+    /// Tag name (without '#') to hex color, e.g. "work" = "#3366ff"
+    #[serde(default)]
+    pub tag_colors: HashMap<String, String>,
+    /// Seconds before a server request gives up (default 15)
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+    /// Proxy url overriding HTTPS_PROXY / HTTP_PROXY, or "none" to ignore them
+    #[serde(default)]
+    pub proxy: Option<String>,
+    /// Ask before deleting tasks
+    #[serde(default = "default_true")]
+    pub confirm_delete: bool,
+    /// Offer to sync the offline queue when quitting the TUI with changes left in it
+    #[serde(default = "default_true")]
+    pub confirm_quit_unsynced: bool,
+    /// Sort completed and cancelled tasks below the open ones
+    #[serde(default = "default_true")]
+    pub completed_last: bool,
+    /// Sort tasks by their manual order instead of dates and priority
+    #[serde(default)]
+    pub manual_order: bool,
+    /// Show due dates relative to today in the GUI list ("tomorrow", "in 3d")
+    #[serde(default)]
+    pub relative_dates: bool,
+    /// chrono format of the dates shown in both UIs ("%d/%m/%Y", "%b %e")
+    #[serde(default = "default_date_format")]
+    pub date_format: String,
+    /// Hide someday/maybe tasks from the lists
+    #[serde(default)]
+    pub hide_deferred: bool,
+    /// Clock and next-due countdown in the TUI status bar
+    #[serde(default = "default_true")]
+    pub show_clock: bool,
+    /// Calendar href that "Archive Completed" moves finished tasks to
+    #[serde(default)]
+    pub archive_calendar: Option<String>,
+    /// Name or href of the calendar that TUI quick-adds (`a`) go to, whatever the view
+    #[serde(default)]
+    pub inbox_calendar: Option<String>,
+    /// Fuzzy (subsequence) search, best matches first
+    #[serde(default)]
+    pub fuzzy_search: bool,
+    /// `"monday"` or `"sunday"`
+    #[serde(default)]
+    pub week_start: WeekStart,
+    /// Append the ISO week number to shown dates ("2025-06-02 W23")
+    #[serde(default)]
+    pub show_week_numbers: bool,
+    /// Hide the sidebar so the task list takes the full width (F in the TUI, Shift+F in the GUI)
+    #[serde(default)]
+    pub focus_mode: bool,
+    /// On load, move recurring tasks overdue by more than one interval to their
+    /// next occurrence due today or later
+    #[serde(default)]
+    pub auto_promote_recurring: bool,
+    /// Start a focus timer when a task is started; its minutes are logged on stop
+    #[serde(default)]
+    pub focus_timer: bool,
+    /// Choices of the snooze menu, in smart input date syntax ("tomorrow", "3d", ...)
+    #[serde(default = "default_snooze_presets")]
+    pub snooze_presets: Vec<String>,
+    /// Smaller GUI rows, with the task actions behind a "⋯" button
+    #[serde(default)]
+    pub compact_list: bool,
+    /// Widest the TUI task list and details get, centered on wider terminals
+    #[serde(default)]
+    pub tui_max_width: Option<u16>,
+    /// GUI colors: `"dark"`, `"light"` or `"system"`
+    #[serde(default)]
+    pub theme: ThemeMode,
+    /// `"newest"` settles sync conflicts by LAST-MODIFIED instead of asking
+    #[serde(default)]
+    pub conflict_strategy: ConflictStrategy,
+    /// Calendar name or href to the defaults of tasks created there
+    #[serde(default)]
+    pub calendar_defaults: HashMap<String, CalendarDefaults>,
+    /// Template name to the task and subtasks it creates
+    #[serde(default)]
+    pub templates: HashMap<String, TaskTemplate>,
+    /// TUI action name to key, e.g. "delete" = "D"
+    #[serde(default)]
+    pub keybindings: HashMap<String, String>,
 }
 
 // --- ADDED THIS IMPLEMENTATION ---
@@ -42,15 +310,53 @@ impl Default for Config {
             url: String::new(),
             username: String::new(),
             password: String::new(),
+            use_keyring: false,
+            load_warning: None,
+            keyring_entry: None,
+            auth_type: AuthType::Basic,
+            token: String::new(),
             default_calendar: None,
             allow_insecure_certs: false,
             hidden_calendars: Vec::new(),
             disabled_calendars: Vec::new(),
+            read_only_calendars: Vec::new(),
             hide_completed: false,
+            hide_cancelled: false,
+            hide_blocked: false,
             // Match the serde defaults
             hide_fully_completed_tags: true,
+            sort_tags_by_count: false,
             sort_cutoff_months: Some(6),
             tag_aliases: HashMap::new(),
+            auto_complete_parent: false,
+            refresh_interval_secs: None,
+            tag_colors: HashMap::new(),
+            timeout_secs: None,
+            proxy: None,
+            confirm_delete: true,
+            confirm_quit_unsynced: true,
+            completed_last: true,
+            manual_order: false,
+            relative_dates: false,
+            date_format: default_date_format(),
+            hide_deferred: false,
+            show_clock: true,
+            archive_calendar: None,
+            inbox_calendar: None,
+            fuzzy_search: false,
+            week_start: WeekStart::default(),
+            show_week_numbers: false,
+            focus_mode: false,
+            auto_promote_recurring: false,
+            focus_timer: false,
+            snooze_presets: default_snooze_presets(),
+            compact_list: false,
+            tui_max_width: None,
+            theme: ThemeMode::Dark,
+            conflict_strategy: ConflictStrategy::Copy,
+            calendar_defaults: HashMap::new(),
+            templates: HashMap::new(),
+            keybindings: HashMap::new(),
         }
     }
 }
@@ -62,20 +368,91 @@ impl Config {
         let path = AppPaths::get_config_file_path()?;
         if path.exists() {
             let contents = fs::read_to_string(path)?;
-            let config: Config = toml::from_str(&contents)?;
+            let mut config: Config = toml::from_str(&contents)?;
+            if config.use_keyring && config.password.is_empty() {
+                match secret_store::read(&config) {
+                    Ok(pass) => {
+                        config.password = pass.clone().unwrap_or_default();
+                        config.keyring_entry = pass.map(|p| (config.keyring_account(), p));
+                    }
+                    Err(e) => {
+                        config.load_warning = Some(format!(
+                            "Could not read the password from the keyring: {}",
+                            e
+                        ))
+                    }
+                }
+            }
+            if !is_valid_date_format(&config.date_format) {
+                let warning = format!(
+                    "Invalid date_format \"{}\", using {}.",
+                    config.date_format, DEFAULT_DATE_FORMAT
+                );
+                config.load_warning = Some(match config.load_warning.take() {
+                    Some(previous) => format!("{} {}", previous, warning),
+                    None => warning,
+                });
+                config.date_format = default_date_format();
+            }
             return Ok(config);
         }
         Err(anyhow::anyhow!("Config file not found"))
     }
 
     pub fn save(&self) -> Result<()> {
+        self.save_with_warning().map(|_| ())
+    }
+
+    /// Saves the config, moving the password to the OS keyring when `use_keyring`
+    /// is set. If the keyring is unavailable the password is written to the file
+    /// instead, and the returned warning says why.
+    pub fn save_with_warning(&self) -> Result<Option<String>> {
+        let mut to_write = self.clone();
+        let mut warning = None;
+        if self.use_keyring && !self.password.is_empty() {
+            let entry = (self.keyring_account(), self.password.clone());
+            if self.keyring_entry.as_ref() == Some(&entry) {
+                // Already in the keyring, don't ask the OS to write it again
+                to_write.password.clear();
+            } else {
+                match secret_store::store(self) {
+                    Ok(()) => to_write.password.clear(),
+                    Err(e) => {
+                        warning = Some(format!(
+                            "Keyring unavailable ({}), the password was saved in plain text.",
+                            e
+                        ))
+                    }
+                }
+            }
+        }
         let path = AppPaths::get_config_file_path()?;
         LocalStorage::with_lock(&path, || {
-            let toml_str = toml::to_string_pretty(self)?;
+            let toml_str = toml::to_string_pretty(&to_write)?;
             LocalStorage::atomic_write(&path, toml_str)?;
             Ok(())
         })?;
-        Ok(())
+        Ok(warning)
+    }
+
+    /// The auto-refresh period, or `None` when disabled (unset or 0).
+    pub fn refresh_interval(&self) -> Option<std::time::Duration> {
+        self.refresh_interval_secs
+            .filter(|secs| *secs > 0)
+            .map(std::time::Duration::from_secs)
+    }
+
+    /// The server request timeout, falling back to the default when unset or 0.
+    pub fn timeout(&self) -> std::time::Duration {
+        self.timeout_secs.filter(|secs| *secs > 0).map_or(
+            crate::client::timeout::DEFAULT_TIMEOUT,
+            std::time::Duration::from_secs,
+        )
+    }
+
+    /// Keyring entry name of the password: one per url and username.
+    fn keyring_account(&self) -> String {
+        format!("{}@{}", self.username, self.url)
     }
 
     pub fn get_path_string() -> Result<String> {
@@ -83,3 +460,129 @@ impl Config {
         Ok(path.to_string_lossy().to_string())
     }
 }
+
+/// The OS secret store, one entry per url and username.
+#[cfg(all(feature = "keyring", not(target_os = "android")))]
+mod secret_store {
+    use super::Config;
+
+    /// Service name of the OS keyring entries holding `use_keyring` passwords.
+    const KEYRING_SERVICE: &str = "cfait";
+
+    fn entry(config: &Config) -> keyring::Result<keyring::Entry> {
+        keyring::Entry::new(KEYRING_SERVICE, &config.keyring_account())
+    }
+
+    /// The stored password, `None` when there is no entry yet.
+    pub fn read(config: &Config) -> Result<Option<String>, String> {
+        match entry(config).and_then(|e| e.get_password()) {
+            Ok(pass) => Ok(Some(pass)),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+
+    pub fn store(config: &Config) -> Result<(), String> {
+        entry(config)
+            .and_then(|e| e.set_password(&config.password))
+            .map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(not(all(feature = "keyring", not(target_os = "android"))))]
+mod secret_store {
+    use super::Config;
+
+    const UNSUPPORTED: &str = if cfg!(target_os = "android") {
+        "not supported on Android"
+    } else {
+        "built without the keyring feature"
+    };
+
+    pub fn read(_config: &Config) -> Result<Option<String>, String> {
+        Err(UNSUPPORTED.to_string())
+    }
+
+    pub fn store(_config: &Config) -> Result<(), String> {
+        Err(UNSUPPORTED.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_calendar_defaults_merge() {
+        let cfg: Config = toml::from_str(
+            r##"
+            url = ""
+            username = ""
+            password = ""
+
+            [calendar_defaults."Work"]
+            categories = ["work"]
+            priority = 3
+            "##,
+        )
+        .unwrap();
+        let defaults =
+            CalendarDefaults::lookup(&cfg.calendar_defaults, "/cal/work/", Some("Work")).unwrap();
+
+        let mut task = Task::new("Report #urgent", &HashMap::new());
+        defaults.apply(&mut task);
+        assert_eq!(task.priority, 3);
+        assert_eq!(task.categories, vec!["urgent", "work"]);
+
+        // Inline tokens win over the defaults
+        let mut task = Task::new("Report !1 #work", &HashMap::new());
+        defaults.apply(&mut task);
+        assert_eq!(task.priority, 1);
+        assert_eq!(task.categories, vec!["work"]);
+
+        assert!(CalendarDefaults::lookup(&cfg.calendar_defaults, "/cal/home/", None).is_none());
+    }
+
+    #[test]
+    fn test_template_instantiate() {
+        let cfg: Config = toml::from_str(
+            r##"
+            url = ""
+            username = ""
+
+            [templates."Deploy"]
+            task = "Deploy release-{date} @{date} #ops"
+            children = ["Run tests", "Tag {date}", ""]
+            "##,
+        )
+        .unwrap();
+        let today = chrono::NaiveDate::from_ymd_opt(2025, 6, 2).unwrap();
+        let tasks = cfg.templates["Deploy"].instantiate(&HashMap::new(), today);
+
+        let summaries: Vec<_> = tasks.iter().map(|t| t.summary.as_str()).collect();
+        assert_eq!(
+            summaries,
+            ["Deploy release-2025-06-02", "Run tests", "Tag 2025-06-02"]
+        );
+        assert_eq!(tasks[0].categories, vec!["ops"]);
+        assert!(tasks[0].due.is_some());
+        assert!(tasks[0].parent_uid.is_none());
+        assert!(
+            tasks[1..]
+                .iter()
+                .all(|t| t.parent_uid.as_ref() == Some(&tasks[0].uid))
+        );
+    }
+
+    #[test]
+    fn test_template_without_title_creates_nothing() {
+        let today = chrono::NaiveDate::from_ymd_opt(2025, 6, 2).unwrap();
+        for task in ["", "  ", "@{date} #ops"] {
+            let template = TaskTemplate {
+                task: task.to_string(),
+                children: vec!["Run tests".to_string()],
+            };
+            assert!(template.instantiate(&HashMap::new(), today).is_empty());
+        }
+    }
+}
@@ -1,7 +1,10 @@
 // File: ./src/gui/async_ops.rs
 use crate::client::RustyClient;
 use crate::config::Config;
+use crate::gui::message::Message;
 use crate::model::{CalendarListEntry, Task as TodoTask};
+use iced::futures::SinkExt;
+use iced::futures::channel::mpsc::Sender;
 use std::sync::OnceLock;
 use tokio::runtime::Runtime;
 
@@ -23,22 +26,47 @@ pub fn get_runtime() -> &'static Runtime {
 
 // --- WRAPPERS ---
 
-pub async fn connect_and_fetch_wrapper(
+/// Connects, as `JournalSyncProgress` messages while the journal left from the
+/// last session is sent, followed by `Loaded`.
+pub fn connect_stream(
     config: Config,
-) -> Result<
-    (
-        RustyClient,
-        Vec<CalendarListEntry>,
-        Vec<TodoTask>,
-        Option<String>,
-        Option<String>,
-    ),
-    String,
-> {
-    let rt = get_runtime();
-    rt.spawn(async { RustyClient::connect_with_fallback(config).await })
-        .await
-        .map_err(|e| e.to_string())?
+) -> impl iced::futures::Stream<Item = Message> + Send + 'static {
+    iced::stream::channel(16, async move |mut output: Sender<Message>| {
+        let mut progress = output.clone();
+        let result = get_runtime()
+            .spawn(async move {
+                RustyClient::connect_with_fallback(config, move |current, total| {
+                    let _ = progress.try_send(Message::JournalSyncProgress(current, total));
+                })
+                .await
+            })
+            .await
+            .map_err(|e| e.to_string())
+            .and_then(|r| r);
+        let _ = output.send(Message::Loaded(result)).await;
+    })
+}
+
+/// Sends the journal, as `JournalSyncProgress` messages for each queued action
+/// followed by `JournalSynced`.
+pub fn sync_journal_stream(
+    client: RustyClient,
+) -> impl iced::futures::Stream<Item = Message> + Send + 'static {
+    iced::stream::channel(16, async move |mut output: Sender<Message>| {
+        let mut progress = output.clone();
+        let result = get_runtime()
+            .spawn(async move {
+                client
+                    .sync_journal_with_progress(move |current, total| {
+                        let _ = progress.try_send(Message::JournalSyncProgress(current, total));
+                    })
+                    .await
+            })
+            .await
+            .map_err(|e| e.to_string())
+            .and_then(|r| r);
+        let _ = output.send(Message::JournalSynced(result)).await;
+    })
 }
 
 pub async fn async_fetch_wrapper(
@@ -54,16 +82,62 @@ pub async fn async_fetch_wrapper(
     .map_err(|e| e.to_string())?
 }
 
-pub async fn async_fetch_all_wrapper(
+/// Fetches all calendars, as `JournalSyncProgress` messages while queued changes
+/// are sent, then `FetchProgress` messages for each calendar that lands, followed
+/// by `RefreshedAll`.
+pub fn fetch_all_stream(
     client: RustyClient,
     cals: Vec<CalendarListEntry>,
-) -> Result<Vec<(String, Vec<TodoTask>)>, String> {
+) -> impl iced::futures::Stream<Item = Message> + Send + 'static {
+    iced::stream::channel(16, async move |mut output: Sender<Message>| {
+        let mut progress = output.clone();
+        let mut sync_progress = output.clone();
+        let result = get_runtime()
+            .spawn(async move {
+                client
+                    .get_all_tasks_with_progress(
+                        &cals,
+                        move |current, total| {
+                            let _ = sync_progress
+                                .try_send(Message::JournalSyncProgress(current, total));
+                        },
+                        move |done, total| {
+                            let _ = progress.try_send(Message::FetchProgress(done, total));
+                        },
+                    )
+                    .await
+            })
+            .await
+            .map_err(|e| e.to_string())
+            .and_then(|r| r);
+        let _ = output.send(Message::RefreshedAll(result)).await;
+    })
+}
+
+pub async fn async_create_calendar_wrapper(
+    client: RustyClient,
+    name: String,
+) -> Result<CalendarListEntry, String> {
     let rt = get_runtime();
-    rt.spawn(async move { client.get_all_tasks(&cals).await })
+    rt.spawn(async move { client.create_calendar(&name).await })
         .await
         .map_err(|e| e.to_string())?
 }
 
+pub async fn async_rename_calendar_wrapper(
+    client: RustyClient,
+    href: String,
+    name: String,
+) -> Result<(String, String), String> {
+    let rt = get_runtime();
+    rt.spawn(async move {
+        client.rename_calendar(&href, &name).await?;
+        Ok((href, name.trim().to_string()))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
 pub async fn async_create_wrapper(
     client: RustyClient,
     mut task: TodoTask,
@@ -93,6 +167,14 @@ pub async fn async_toggle_wrapper(
     Ok((task, next))
 }
 
+pub async fn async_resolve_conflict_wrapper(
+    client: RustyClient,
+    uid: String,
+    keep_local: Option<Vec<String>>,
+) -> Result<Vec<String>, String> {
+    client.resolve_conflict(&uid, keep_local.as_deref()).await
+}
+
 pub async fn async_move_wrapper(
     client: RustyClient,
     task: TodoTask,
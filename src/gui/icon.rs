@@ -44,6 +44,12 @@ pub const CHILD: char = '\u{f0a89}'; // nf-md-account_child
 pub const CREATE_CHILD: char = '\u{f0014}'; // nf-md-account_plus
 pub const CLEAR_ALL: char = '\u{eabf}'; // nf-cod-clear_all
 pub const ELEVATOR_UP: char = '\u{f12c1}'; // nf-md-elevator_up
+pub const DRAG_HANDLE: char = '\u{f0c9}'; // nf-fa-bars
+pub const SOMEDAY: char = '\u{f017}'; // nf-fa-clock_o
+pub const DUPLICATE: char = '\u{f0191}'; // nf-md-content_duplicate
+pub const SKIP: char = '\u{f04ad}'; // nf-md-skip_next
+pub const SIDEBAR_HIDE: char = '\u{f100}'; // nf-fa-angle_double_left
+pub const SIDEBAR_SHOW: char = '\u{f101}'; // nf-fa-angle_double_right
 
 // New Icons
 pub const SETTINGS_GEAR: char = '\u{e690}'; // nf-seti-settings
@@ -1,7 +1,8 @@
 // File: src/gui/message.rs
-use crate::client::RustyClient;
-use crate::config::Config;
+use crate::client::{FetchedTasks, JournalSync, RustyClient};
+use crate::config::{Config, ThemeMode};
 use crate::gui::state::{ResizeDirection, SidebarMode};
+use crate::model::recurrence::Frequency;
 use crate::model::{CalendarListEntry, Task as TodoTask};
 use iced::widget::text_editor;
 
@@ -22,6 +23,7 @@ pub enum Message {
     ObUserChanged(String),
     ObPassChanged(String),
     ObInsecureToggled(bool),
+    ObKeyringToggled(bool),
     ToggleCalendarVisibility(String, bool),
     ToggleCalendarDisabled(String, bool),
     ObDefaultCalChanged(String),
@@ -31,26 +33,82 @@ pub enum Message {
     OpenHelp,
     CloseHelp,
     InputChanged(String),
-
+    /// Pasted into the quick-add, the input as the paste left it
+    InputPasted(String),
+    /// Clipboard read after a paste: the input from before it and the pasted text
+    OutlinePasted(String, Option<String>),
+    PickPriority(u8),
+    /// Recurrence builder of the edit panel
+    RecurrenceFreqPicked(Frequency),
+    RecurrenceIntervalChanged(u32),
+    RecurrenceWeekdayToggled(chrono::Weekday),
+    ClearRecurrence,
+    /// Picked a `#tag` suggestion under the input
+    CompleteTag(String),
+
+    ToggleChecklistItem(String, usize),
     DescriptionChanged(text_editor::Action),
 
     SearchChanged(String),
     SubmitTask,
     ToggleTask(usize, bool),
     DeleteTask(usize),
+    /// Answer to the delete confirmation dialog (`true` deletes)
+    ConfirmDelete(bool),
     EditTaskStart(usize),
     CancelEdit,
     ChangePriority(usize, i8),
     SetTaskStatus(usize, crate::model::TaskStatus),
+    ToggleDeferred(String),
+    SnoozeTask(String, String),
+    CreateFromTemplate(String),
+    SkipOccurrence(String),
+    DuplicateTask(String),
+    ToggleHideDeferred(bool),
+    ToggleFocusMode,
+    DragStart(String),
+    DragOver(String),
+    DragEnd,
     SetMinDuration(Option<u32>),
     SetMaxDuration(Option<u32>),
     ToggleIncludeUnsetDuration(bool),
     ToggleDetails(String),
+    OpenUrl(String),
+    /// `false` asks for confirmation, `true` deletes the completed tasks of the calendar
+    ClearCompleted(bool),
+    FindDuplicates,
+    ToggleDuplicateDelete(String),
+    DeleteDuplicates,
+    OpenJournal,
+    /// Send the queued offline changes now
+    SyncJournal,
+    JournalSyncProgress(usize, usize),
+    JournalSynced(Result<JournalSync, String>),
+    CloseJournal,
+    DropJournalEntry(usize),
+    /// `false` asks for confirmation, `true` clears the whole sync queue
+    ClearJournal(bool),
+    OpenNewCalendar,
+    CloseNewCalendar,
+    NewCalendarNameChanged(String),
+    SubmitNewCalendar,
+    OpenCsvExport,
+    CloseCsvExport,
+    CsvExportPathChanged(String),
+    SubmitCsvExport,
+    CalendarCreated(Result<CalendarListEntry, String>),
+    StartRenameCalendar(String),
+    RenameCalendarInput(String),
+    CancelRenameCalendar,
+    SubmitRenameCalendar,
+    /// href and new name of the renamed calendar
+    CalendarRenamed(Result<(String, String), String>),
     ConfigLoaded(Result<Config, String>),
     ObSortMonthsChanged(String),
 
     Loaded(LoadedResult),
     Refresh,
+    AutoRefresh,
 
     SyncSaved(Result<TodoTask, String>),
     SyncToggleComplete(Box<Result<(TodoTask, Option<TodoTask>), String>>),
@@ -64,12 +122,37 @@ pub enum Message {
     CategoryToggled(String),
     ClearAllTags,
     CategoryMatchModeChanged(bool),
-    RefreshedAll(Result<Vec<(String, Vec<TodoTask>)>, String>),
+    /// Calendars fetched so far (done, total) while loading all of them
+    FetchProgress(usize, usize),
+    RefreshedAll(Result<FetchedTasks, String>),
 
     ToggleHideCompleted(bool),
+    ToggleHideCancelled(bool),
+    ToggleHideBlocked(bool),
+    ToggleConfirmDelete(bool),
+    ToggleCompletedLast(bool),
+    ToggleManualOrder(bool),
+    ToggleRelativeDates(bool),
+    ToggleCompactList(bool),
+    ToggleFuzzySearch(bool),
+    SetTheme(ThemeMode),
+    SystemThemeChanged(iced::theme::Mode),
+    HoverTask(String),
+    ToggleRowMenu(String),
+    ToggleAgenda(bool),
+    ToggleReview(bool),
+    ToggleToday(bool),
+    /// Fold or unfold the "No Date" section of the agenda and today views
+    ToggleNoDate,
+    SetPriorityFilter(Option<u8>),
+    TimerTick,
+    ToggleDebugIds,
+    StopTimer,
     ToggleHideFullyCompletedTags(bool),
+    ToggleSortTagsByCount(bool),
 
     YankTask(String),
+    CopySelectedTask,
     ClearYank,
     StartCreateChild(String),
     AddDependency(String),
@@ -81,17 +164,31 @@ pub enum Message {
     AliasValueInput(String),
     AddAlias,
     RemoveAlias(String),
+    TagColorTagSelected(String),
+    TagColorHexInput(String),
+    AddTagColor,
+    RemoveTagColor(String),
     MoveTask(String, String),
+    SetTaskColor(String, Option<String>),
 
     JumpToTag(String),
 
     TaskMoved(Result<TodoTask, String>),
     ObSubmitOffline,
     MigrateLocalTo(String),
+    ArchiveCompleted,
+    ArchiveCompletedTo(String),
+    PickArchiveCalendar,
 
     MigrationComplete(Result<usize, String>),
+    ArchiveComplete(Result<usize, String>),
     FontLoaded(Result<(), String>),
     DismissError,
+    Undo,
+    DismissUndo,
+    ToggleConflictField(String),
+    ResolveConflict(bool), // true: apply the picked sides, false: keep both (conflict copy)
+    ConflictResolved(Result<Vec<String>, String>),
     ToggleAllCalendars(bool),
 
     TabPressed(bool),
@@ -101,6 +198,9 @@ pub enum Message {
     MinimizeWindow,
     CloseWindow,
     WindowResized(iced::Size),
+    WindowMoved(iced::Point),
+    WindowOpened(iced::window::Id),
+    WindowClosed,
 
     // Resize
     ResizeStart(ResizeDirection),
@@ -5,22 +5,32 @@ pub mod state;
 pub mod subscription;
 pub mod update;
 pub mod view;
+pub mod window_state;
 
 use crate::config::Config;
 use crate::gui::message::Message;
 use crate::gui::state::GuiApp;
+use crate::gui::window_state::{DEFAULT_SIZE, WindowGeometry};
 use iced::{Element, Subscription, Task, Theme, font, window};
 
 pub fn run() -> iced::Result {
     // Initialize the Tokio runtime managed in async_ops
     async_ops::init_runtime();
 
+    let geometry = WindowGeometry::load();
+    let size = geometry.map(|g| g.size()).unwrap_or(DEFAULT_SIZE);
+    let position = geometry
+        .map(|g| g.startup_position())
+        .unwrap_or(window::Position::Centered);
+
     iced::application(GuiApp::new, GuiApp::update, GuiApp::view)
         .title(GuiApp::title)
         .subscription(GuiApp::subscription)
         .theme(GuiApp::theme)
         .window(window::Settings {
             decorations: false, // <--- Disable OS Top Bar
+            size,
+            position,
             platform_specific: window::settings::PlatformSpecific {
                 #[cfg(target_os = "linux")]
                 application_id: String::from("cfait"),
@@ -34,8 +44,15 @@ pub fn run() -> iced::Result {
 
 impl GuiApp {
     fn new() -> (Self, Task<Message>) {
+        let mut app = Self::default();
+        if let Some(geo) = WindowGeometry::load() {
+            app.current_window_size = geo.size();
+            app.current_window_position = geo.position();
+            app.window_position_restored =
+                matches!(geo.startup_position(), window::Position::Specific(_));
+        }
         (
-            Self::default(),
+            app,
             Task::batch(vec![
                 // Load config
                 Task::perform(
@@ -44,6 +61,7 @@ impl GuiApp {
                 ),
                 // Load Font Bytes
                 font::load(icon::FONT_BYTES).map(|_| Message::FontLoaded(Ok(()))),
+                iced::system::theme().map(Message::SystemThemeChanged),
             ]),
         )
     }
@@ -57,7 +75,11 @@ impl GuiApp {
     }
 
     fn theme(&self) -> Theme {
-        Theme::Dark
+        if self.is_dark_theme() {
+            Theme::Dark
+        } else {
+            Theme::Light
+        }
     }
 
     fn subscription(&self) -> Subscription<Message> {
@@ -1,7 +1,9 @@
 // File: src/gui/state.rs
 use crate::client::RustyClient;
-use crate::model::{CalendarListEntry, Task as TodoTask};
-use crate::store::TaskStore;
+use crate::config::{CalendarDefaults, TaskTemplate, ThemeMode};
+use crate::journal::MergeConflict;
+use crate::model::{CalendarListEntry, DEFAULT_DATE_FORMAT, Task as TodoTask};
+use crate::store::{DueBucket, TaskStore, UndoEntry};
 use iced::widget::text_editor;
 use std::collections::{HashMap, HashSet};
 
@@ -38,9 +40,14 @@ pub struct GuiApp {
     pub state: AppState,
     pub store: TaskStore,
     pub tasks: Vec<TodoTask>,
+    /// Subtask progress of each parent, taken by `refresh_filtered_tasks`
+    pub child_progress: HashMap<String, (usize, usize)>,
     pub calendars: Vec<CalendarListEntry>,
     pub client: Option<RustyClient>,
     pub tag_aliases: HashMap<String, Vec<String>>,
+    pub calendar_defaults: HashMap<String, CalendarDefaults>,
+    pub templates: HashMap<String, TaskTemplate>,
+    pub tag_colors: HashMap<String, String>,
 
     // UI State
     pub sidebar_mode: SidebarMode,
@@ -56,8 +63,77 @@ pub struct GuiApp {
 
     // Preferences
     pub hide_completed: bool,
+    pub hide_cancelled: bool,
+    pub hide_blocked: bool,
     pub hide_fully_completed_tags: bool,
+    pub sort_tags_by_count: bool,
     pub sort_cutoff_months: Option<u32>,
+    pub auto_complete_parent: bool,
+    pub refresh_interval_secs: Option<u64>,
+    pub agenda_view: bool,
+    pub today_view: bool,
+    pub agenda_sections: Vec<(usize, DueBucket)>,
+    /// Open tasks without a due date, listed last in the agenda and today views
+    pub undated_count: usize,
+    pub no_date_collapsed: bool,
+    /// Read-only list of what was completed lately, grouped by day.
+    pub review_view: bool,
+    pub review_sections: Vec<(usize, chrono::NaiveDate)>,
+    pub last_action: Option<UndoEntry>,
+    /// Sync conflicts waiting for the user; the first one is shown as a modal.
+    pub conflicts: Vec<MergeConflict>,
+    pub conflict_keep_local: HashSet<String>,
+    pub confirm_delete: bool,
+    pub completed_last: bool,
+    pub manual_order: bool,
+    pub hide_deferred: bool,
+    pub relative_dates: bool,
+    /// chrono format of the shown dates (config `date_format`)
+    pub date_format: String,
+    pub compact_list: bool,
+    pub theme_mode: ThemeMode,
+    /// Light/dark preference reported by the desktop, for `ThemeMode::System`
+    pub system_theme: iced::theme::Mode,
+    pub fuzzy_search: bool,
+    pub week_start: chrono::Weekday,
+    pub show_week_numbers: bool,
+    /// Sidebar hidden behind a thin strip (`focus_mode`)
+    pub focus_mode: bool,
+    /// Only show tasks with a priority from 1 up to this one
+    pub priority_filter: Option<u8>,
+    /// Start the focus timer with a task (config `focus_timer`), and the running one
+    pub focus_timer: bool,
+    pub snooze_presets: Vec<String>,
+    pub timer: Option<crate::model::TimerState>,
+    /// Show UIDs and hrefs in the task details (Shift+D, not saved)
+    pub show_debug_ids: bool,
+    /// Compact list: row under the mouse, and the row whose action menu is open
+    pub hovered_uid: Option<String>,
+    pub row_menu: Option<String>,
+    /// Task being dragged in manual order, and the row it would be dropped on
+    pub dragging: Option<String>,
+    pub drag_target: Option<String>,
+    /// Task waiting for the delete confirmation dialog
+    pub pending_delete: Option<String>,
+    /// Pending sync actions, while the sync queue dialog is open
+    pub journal_entries: Option<Vec<String>>,
+    pub journal_confirm_clear: bool,
+    /// Name typed into the new calendar dialog, while it is open
+    pub new_calendar_name: Option<String>,
+    /// Destination typed into the CSV export dialog, while it is open
+    pub csv_export_path: Option<String>,
+    pub tag_completion: Option<crate::model::completion::TagCompletion>,
+    /// Calendar being renamed in the settings (href, name typed so far)
+    pub renaming_calendar: Option<(String, String)>,
+    /// Where "Archive Completed" moves finished tasks, and whether the picker is shown
+    pub archive_calendar: Option<String>,
+    pub picking_archive: bool,
+    /// "Clear completed" was clicked once; the next click deletes
+    pub confirm_clear_completed: bool,
+    /// Groups found by "Find duplicates" in the settings, the kept task first
+    pub duplicates: Option<Vec<Vec<TodoTask>>>,
+    /// Duplicates ticked for deletion
+    pub duplicate_delete: HashSet<String>,
 
     // Filter State
     pub filter_min_duration: Option<u32>,
@@ -71,12 +147,21 @@ pub struct GuiApp {
     pub editing_uid: Option<String>,
     pub creating_child_of: Option<String>,
     pub expanded_tasks: HashSet<String>,
-    pub unsynced_changes: bool,
+    /// Offline changes waiting in the journal
+    pub unsynced_count: usize,
+    /// Progress (current, total) while the journal is sent with "Sync now"
+    pub journal_sync_progress: Option<(usize, usize)>,
+    /// Calendars fetched (done, total) while all of them load
+    pub fetch_progress: Option<(usize, usize)>,
 
     // Inputs - Settings (Aliases)
     pub alias_input_key: String,
     pub alias_input_values: String,
 
+    // Inputs - Settings (Tag colors)
+    pub tag_color_input_tag: Option<String>,
+    pub tag_color_input_hex: String,
+
     // System
     pub loading: bool,
     pub error_msg: Option<String>,
@@ -88,12 +173,16 @@ pub struct GuiApp {
     pub ob_default_cal: Option<String>,
     pub ob_sort_months_input: String,
     pub ob_insecure: bool,
+    pub ob_use_keyring: bool,
     pub scrollable_id: iced::widget::Id,
     pub sidebar_scrollable_id: iced::widget::Id, // Added for tag scrolling
 
     // Window Resizing State
     pub resize_direction: Option<ResizeDirection>,
     pub current_window_size: iced::Size,
+    pub current_window_position: Option<iced::Point>,
+    /// The window was placed at the saved position rather than centered
+    pub window_position_restored: bool,
 }
 
 impl Default for GuiApp {
@@ -102,9 +191,13 @@ impl Default for GuiApp {
             state: AppState::Loading,
             store: TaskStore::new(),
             tasks: vec![],
+            child_progress: HashMap::new(),
             calendars: vec![],
             client: None,
             tag_aliases: HashMap::new(),
+            calendar_defaults: HashMap::new(),
+            templates: HashMap::new(),
+            tag_colors: HashMap::new(),
 
             sidebar_mode: SidebarMode::Calendars,
             active_cal_href: None,
@@ -116,8 +209,57 @@ impl Default for GuiApp {
             selected_uid: None,
 
             hide_completed: false,
+            hide_cancelled: false,
+            hide_blocked: false,
             hide_fully_completed_tags: true,
+            sort_tags_by_count: false,
             sort_cutoff_months: Some(6),
+            auto_complete_parent: false,
+            refresh_interval_secs: None,
+            agenda_view: false,
+            today_view: false,
+            agenda_sections: Vec::new(),
+            undated_count: 0,
+            no_date_collapsed: false,
+            review_view: false,
+            review_sections: Vec::new(),
+            last_action: None,
+            conflicts: Vec::new(),
+            conflict_keep_local: HashSet::new(),
+            confirm_delete: true,
+            completed_last: true,
+            manual_order: false,
+            hide_deferred: false,
+            relative_dates: false,
+            date_format: DEFAULT_DATE_FORMAT.to_string(),
+            compact_list: false,
+            theme_mode: ThemeMode::Dark,
+            system_theme: iced::theme::Mode::None,
+            fuzzy_search: false,
+            week_start: chrono::Weekday::Mon,
+            show_week_numbers: false,
+            focus_mode: false,
+            priority_filter: None,
+            focus_timer: false,
+            snooze_presets: Vec::new(),
+            timer: None,
+            show_debug_ids: false,
+            hovered_uid: None,
+            row_menu: None,
+            dragging: None,
+            drag_target: None,
+            pending_delete: None,
+            journal_entries: None,
+            journal_confirm_clear: false,
+            new_calendar_name: None,
+            csv_export_path: None,
+            tag_completion: None,
+            renaming_calendar: None,
+            archive_calendar: None,
+            picking_archive: false,
+            confirm_clear_completed: false,
+            duplicates: None,
+            duplicate_delete: HashSet::new(),
             ob_sort_months_input: "6".to_string(),
 
             filter_min_duration: None,
@@ -130,10 +272,14 @@ impl Default for GuiApp {
             editing_uid: None,
             creating_child_of: None,
             expanded_tasks: HashSet::new(),
-            unsynced_changes: false,
+            unsynced_count: 0,
+            journal_sync_progress: None,
+            fetch_progress: None,
 
             alias_input_key: String::new(),
             alias_input_values: String::new(),
+            tag_color_input_tag: None,
+            tag_color_input_hex: String::new(),
 
             loading: true,
             error_msg: None,
@@ -142,11 +288,24 @@ impl Default for GuiApp {
             ob_pass: String::new(),
             ob_default_cal: None,
             ob_insecure: false,
+            ob_use_keyring: false,
             scrollable_id: iced::widget::Id::unique(),
             sidebar_scrollable_id: iced::widget::Id::unique(), // Initialize unique ID
 
             resize_direction: None,
             current_window_size: iced::Size::new(800.0, 600.0),
+            current_window_position: None,
+            window_position_restored: false,
+        }
+    }
+}
+
+impl GuiApp {
+    pub fn is_dark_theme(&self) -> bool {
+        match self.theme_mode {
+            ThemeMode::Dark => true,
+            ThemeMode::Light => false,
+            ThemeMode::System => self.system_theme != iced::theme::Mode::Light,
         }
     }
 }
@@ -2,6 +2,7 @@
 use crate::gui::message::Message;
 use crate::gui::state::{AppState, GuiApp};
 use iced::{Subscription, event, keyboard, window};
+use std::time::Duration;
 
 pub fn subscription(app: &GuiApp) -> Subscription<Message> {
     use iced::keyboard::key;
@@ -19,11 +20,44 @@ pub fn subscription(app: &GuiApp) -> Subscription<Message> {
         }));
     }
 
-    // Track window metrics (Size)
-    subs.push(event::listen_with(|evt, _status, _window_id| match evt {
+    if matches!(app.state, AppState::Active) {
+        // Shift+Y copies the selected task's title, Shift+D shows the UIDs and
+        // Shift+F hides the sidebar, unless a text field took the key
+        subs.push(event::listen_with(|evt, status, _| {
+            if let iced::Event::Keyboard(keyboard::Event::KeyPressed { key, .. }) = evt
+                && status == event::Status::Ignored
+            {
+                match key.as_ref() {
+                    key::Key::Character("Y") => return Some(Message::CopySelectedTask),
+                    key::Key::Character("D") => return Some(Message::ToggleDebugIds),
+                    key::Key::Character("F") => return Some(Message::ToggleFocusMode),
+                    _ => {}
+                }
+            }
+            None
+        }));
+    }
+
+    subs.push(iced::system::theme_changes().map(Message::SystemThemeChanged));
+
+    // Track window metrics (Size, Position)
+    subs.push(event::listen_with(|evt, _status, window_id| match evt {
         iced::Event::Window(window::Event::Resized(size)) => Some(Message::WindowResized(size)),
+        iced::Event::Window(window::Event::Moved(point)) => Some(Message::WindowMoved(point)),
+        iced::Event::Window(window::Event::Opened { .. }) => Some(Message::WindowOpened(window_id)),
         _ => None,
     }));
+    // Closing through the window manager (e.g. Alt+F4) skips `CloseWindow`
+    subs.push(window::close_events().map(|_| Message::WindowClosed));
+
+    if let Some(secs) = app.refresh_interval_secs.filter(|s| *s > 0) {
+        subs.push(iced::time::every(Duration::from_secs(secs)).map(|_| Message::AutoRefresh));
+    }
+
+    // Redraw the focus timer's clock
+    if app.timer.is_some() {
+        subs.push(iced::time::every(Duration::from_secs(1)).map(|_| Message::TimerTick));
+    }
 
     Subscription::batch(subs)
 }
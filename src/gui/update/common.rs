@@ -3,12 +3,18 @@ use crate::config::Config;
 use crate::gui::async_ops::*;
 use crate::gui::message::Message;
 use crate::gui::state::GuiApp;
-use crate::store::FilterOptions;
+use crate::model::completion;
+use crate::store::{FilterOptions, UNCATEGORIZED_ID};
 use chrono::{Duration, Utc};
 use iced::Task;
+use std::collections::HashSet;
 
 pub fn refresh_filtered_tasks(app: &mut GuiApp) {
     let cal_filter = None;
+    let selected_pos = app
+        .selected_uid
+        .as_ref()
+        .and_then(|uid| app.tasks.iter().position(|t| &t.uid == uid));
 
     let cutoff_date = if let Some(months) = app.sort_cutoff_months {
         let now = Utc::now();
@@ -18,35 +24,106 @@ pub fn refresh_filtered_tasks(app: &mut GuiApp) {
         None
     };
 
-    app.tasks = app.store.filter(FilterOptions {
+    let options = FilterOptions {
         active_cal_href: cal_filter,
         hidden_calendars: &app.hidden_calendars,
         selected_categories: &app.selected_categories,
         match_all_categories: app.match_all_categories,
         search_term: &app.search_value,
         hide_completed_global: app.hide_completed,
+        hide_cancelled: app.hide_cancelled,
+        hide_blocked: app.hide_blocked,
         cutoff_date,
         min_duration: app.filter_min_duration,
         max_duration: app.filter_max_duration,
         include_unset_duration: app.filter_include_unset_duration,
-    });
+        due_today_only: app.today_view,
+        completed_last: app.completed_last,
+        manual_order: app.manual_order,
+        hide_deferred: app.hide_deferred,
+        fuzzy_search: app.fuzzy_search,
+        week_start: app.week_start,
+        priority_filter: app.priority_filter,
+    };
+
+    app.agenda_sections.clear();
+    app.review_sections.clear();
+    app.child_progress = app.store.child_progress_map();
+    if app.review_view {
+        (app.tasks, app.review_sections) = app.store.recently_completed(&app.hidden_calendars);
+    } else if app.agenda_view {
+        (app.tasks, app.agenda_sections) = app.store.agenda(options);
+    } else {
+        app.tasks = app.store.filter(options);
+    }
+    app.undated_count = if !app.review_view && (app.agenda_view || app.today_view) {
+        app.store.add_no_date_section(
+            options,
+            &mut app.tasks,
+            &mut app.agenda_sections,
+            app.no_date_collapsed,
+        )
+    } else {
+        0
+    };
+
+    // Keep the selection on the same task, or on the one now at its position
+    // when it was removed or filtered out
+    if let Some(uid) = &app.selected_uid
+        && !app.tasks.iter().any(|t| &t.uid == uid)
+        && let Some(pos) = selected_pos
+    {
+        app.selected_uid = app
+            .tasks
+            .get(pos.min(app.tasks.len().saturating_sub(1)))
+            .map(|t| t.uid.clone());
+    }
+}
+
+/// Existing tags and aliases offered when completing a `#tag` in the input.
+pub fn tag_candidates(app: &GuiApp) -> Vec<String> {
+    let categories = app
+        .store
+        .get_all_categories(false, false, &HashSet::new(), &HashSet::new(), false)
+        .into_iter()
+        .map(|(name, _)| name)
+        .filter(|name| name != UNCATEGORIZED_ID);
+    completion::tag_candidates(categories, &app.tag_aliases)
 }
 
-pub fn save_config(app: &GuiApp) {
-    let _ = Config {
-        url: app.ob_url.clone(),
-        username: app.ob_user.clone(),
-        password: app.ob_pass.clone(),
-        default_calendar: app.ob_default_cal.clone(),
-        hide_completed: app.hide_completed,
-        hide_fully_completed_tags: app.hide_fully_completed_tags,
-        allow_insecure_certs: app.ob_insecure,
-        hidden_calendars: app.hidden_calendars.iter().cloned().collect(),
-        disabled_calendars: app.disabled_calendars.iter().cloned().collect(),
-        tag_aliases: app.tag_aliases.clone(),
-        sort_cutoff_months: app.sort_cutoff_months,
+pub fn save_config(app: &mut GuiApp) {
+    // Start from the file on disk so options the GUI doesn't expose are preserved.
+    let mut config = Config::load().unwrap_or_default();
+    config.url = app.ob_url.clone();
+    config.username = app.ob_user.clone();
+    config.password = app.ob_pass.clone();
+    config.default_calendar = app.ob_default_cal.clone();
+    config.hide_completed = app.hide_completed;
+    config.hide_cancelled = app.hide_cancelled;
+    config.hide_blocked = app.hide_blocked;
+    config.hide_fully_completed_tags = app.hide_fully_completed_tags;
+    config.sort_tags_by_count = app.sort_tags_by_count;
+    config.allow_insecure_certs = app.ob_insecure;
+    config.use_keyring = app.ob_use_keyring;
+    config.hidden_calendars = app.hidden_calendars.iter().cloned().collect();
+    config.disabled_calendars = app.disabled_calendars.iter().cloned().collect();
+    config.tag_aliases = app.tag_aliases.clone();
+    config.tag_colors = app.tag_colors.clone();
+    config.sort_cutoff_months = app.sort_cutoff_months;
+    config.auto_complete_parent = app.auto_complete_parent;
+    config.confirm_delete = app.confirm_delete;
+    config.completed_last = app.completed_last;
+    config.manual_order = app.manual_order;
+    config.relative_dates = app.relative_dates;
+    config.compact_list = app.compact_list;
+    config.theme = app.theme_mode;
+    config.fuzzy_search = app.fuzzy_search;
+    config.archive_calendar = app.archive_calendar.clone();
+    config.hide_deferred = app.hide_deferred;
+    config.focus_mode = app.focus_mode;
+    if let Ok(Some(warning)) = config.save_with_warning() {
+        app.error_msg = Some(warning);
     }
-    .save();
 }
 
 /// Helper: Find all tasks with a specific alias tag and ensure they have all target tags.
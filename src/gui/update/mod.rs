@@ -10,6 +10,16 @@ use crate::gui::state::GuiApp;
 use iced::Task;
 
 pub fn update(app: &mut GuiApp, message: Message) -> Task<Message> {
+    let (calendar, state) = (app.active_cal_href.clone(), app.state);
+    let task = dispatch(app, message);
+    // Clearing is confirmed for the calendar and screen it was asked on
+    if app.active_cal_href != calendar || app.state != state {
+        app.confirm_clear_completed = false;
+    }
+    task
+}
+
+fn dispatch(app: &mut GuiApp, message: Message) -> Task<Message> {
     match message {
         Message::FontLoaded(_) => Task::none(),
         Message::DeleteComplete(_) => Task::none(),
@@ -20,6 +30,7 @@ pub fn update(app: &mut GuiApp, message: Message) -> Task<Message> {
         | Message::ObPassChanged(_)
         | Message::ObDefaultCalChanged(_)
         | Message::ObInsecureToggled(_)
+        | Message::ObKeyringToggled(_)
         | Message::ObSubmit
         | Message::OpenSettings
         | Message::CancelSettings
@@ -28,26 +39,62 @@ pub fn update(app: &mut GuiApp, message: Message) -> Task<Message> {
         | Message::AliasValueInput(_)
         | Message::AddAlias
         | Message::RemoveAlias(_)
+        | Message::TagColorTagSelected(_)
+        | Message::TagColorHexInput(_)
+        | Message::AddTagColor
+        | Message::RemoveTagColor(_)
         | Message::ObSortMonthsChanged(_) => settings::handle(app, message),
 
         Message::InputChanged(_)
+        | Message::InputPasted(_)
+        | Message::OutlinePasted(_, _)
+        | Message::CompleteTag(_)
         | Message::DescriptionChanged(_)
+        | Message::ToggleChecklistItem(_, _)
         | Message::StartCreateChild(_)
         | Message::SubmitTask
         | Message::ToggleTask(_, _)
         | Message::EditTaskStart(_)
         | Message::CancelEdit
         | Message::DeleteTask(_)
+        | Message::ConfirmDelete(_)
         | Message::ChangePriority(_, _)
         | Message::SetTaskStatus(_, _)
+        | Message::StopTimer
+        | Message::ToggleDeferred(_)
+        | Message::SnoozeTask(_, _)
+        | Message::CreateFromTemplate(_)
+        | Message::SkipOccurrence(_)
+        | Message::DuplicateTask(_)
+        | Message::DragStart(_)
+        | Message::DragOver(_)
+        | Message::DragEnd
         | Message::YankTask(_)
+        | Message::CopySelectedTask
         | Message::ClearYank
         | Message::MakeChild(_)
         | Message::RemoveParent(_)
         | Message::RemoveDependency(_, _)
         | Message::AddDependency(_)
+        | Message::PickPriority(_)
+        | Message::RecurrenceFreqPicked(_)
+        | Message::RecurrenceIntervalChanged(_)
+        | Message::RecurrenceWeekdayToggled(_)
+        | Message::ClearRecurrence
         | Message::MoveTask(_, _)
-        | Message::MigrateLocalTo(_) => tasks::handle(app, message),
+        | Message::SetTaskColor(_, _)
+        | Message::MigrateLocalTo(_)
+        | Message::ArchiveCompleted
+        | Message::ArchiveCompletedTo(_)
+        | Message::PickArchiveCalendar
+        | Message::ClearCompleted(_)
+        | Message::FindDuplicates
+        | Message::ToggleDuplicateDelete(_)
+        | Message::DeleteDuplicates
+        | Message::Undo
+        | Message::DismissUndo
+        | Message::ToggleConflictField(_)
+        | Message::ResolveConflict(_) => tasks::handle(app, message),
 
         Message::TabPressed(_)
         | Message::DismissError
@@ -59,7 +106,29 @@ pub fn update(app: &mut GuiApp, message: Message) -> Task<Message> {
         | Message::ClearAllTags
         | Message::CategoryMatchModeChanged(_)
         | Message::ToggleHideCompleted(_)
+        | Message::ToggleHideCancelled(_)
+        | Message::ToggleHideBlocked(_)
+        | Message::ToggleConfirmDelete(_)
+        | Message::ToggleCompletedLast(_)
+        | Message::ToggleManualOrder(_)
+        | Message::ToggleRelativeDates(_)
+        | Message::ToggleCompactList(_)
+        | Message::ToggleFuzzySearch(_)
+        | Message::SetTheme(_)
+        | Message::SystemThemeChanged(_)
+        | Message::HoverTask(_)
+        | Message::ToggleRowMenu(_)
+        | Message::ToggleHideDeferred(_)
+        | Message::ToggleFocusMode
+        | Message::ToggleAgenda(_)
+        | Message::ToggleReview(_)
+        | Message::ToggleToday(_)
+        | Message::ToggleNoDate
+        | Message::SetPriorityFilter(_)
+        | Message::TimerTick
+        | Message::ToggleDebugIds
         | Message::ToggleHideFullyCompletedTags(_)
+        | Message::ToggleSortTagsByCount(_)
         | Message::SelectCalendar(_)
         | Message::ToggleCalendarDisabled(_, _)
         | Message::SearchChanged(_)
@@ -67,6 +136,21 @@ pub fn update(app: &mut GuiApp, message: Message) -> Task<Message> {
         | Message::SetMaxDuration(_)
         | Message::ToggleIncludeUnsetDuration(_)
         | Message::ToggleDetails(_)
+        | Message::OpenUrl(_)
+        | Message::OpenJournal
+        | Message::CloseJournal
+        | Message::DropJournalEntry(_)
+        | Message::ClearJournal(_)
+        | Message::OpenNewCalendar
+        | Message::CloseNewCalendar
+        | Message::NewCalendarNameChanged(_)
+        | Message::OpenCsvExport
+        | Message::CloseCsvExport
+        | Message::CsvExportPathChanged(_)
+        | Message::SubmitCsvExport
+        | Message::StartRenameCalendar(_)
+        | Message::RenameCalendarInput(_)
+        | Message::CancelRenameCalendar
         | Message::OpenHelp
         | Message::CloseHelp
         | Message::WindowDragged
@@ -74,15 +158,29 @@ pub fn update(app: &mut GuiApp, message: Message) -> Task<Message> {
         | Message::CloseWindow
         | Message::ResizeStart(_)
         | Message::WindowResized(_)
+        | Message::WindowMoved(_)
+        | Message::WindowOpened(_)
+        | Message::WindowClosed
         | Message::JumpToTag(_) => view::handle(app, message),
 
         Message::Refresh
+        | Message::AutoRefresh
         | Message::Loaded(_)
+        | Message::FetchProgress(_, _)
         | Message::RefreshedAll(_)
         | Message::TasksRefreshed(_)
         | Message::SyncSaved(_)
         | Message::SyncToggleComplete(_)
         | Message::TaskMoved(_)
-        | Message::MigrationComplete(_) => network::handle(app, message),
+        | Message::ConflictResolved(_)
+        | Message::SyncJournal
+        | Message::JournalSyncProgress(_, _)
+        | Message::JournalSynced(_)
+        | Message::SubmitNewCalendar
+        | Message::CalendarCreated(_)
+        | Message::SubmitRenameCalendar
+        | Message::CalendarRenamed(_)
+        | Message::MigrationComplete(_)
+        | Message::ArchiveComplete(_) => network::handle(app, message),
     }
 }
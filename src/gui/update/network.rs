@@ -6,8 +6,7 @@ use crate::gui::message::Message;
 use crate::gui::state::{AppState, GuiApp};
 use crate::gui::update::common::{refresh_filtered_tasks, save_config};
 use crate::journal::Journal;
-use crate::model::CalendarListEntry;
-use crate::storage::{LOCAL_CALENDAR_HREF, LOCAL_CALENDAR_NAME};
+use crate::storage::{LOCAL_CALENDAR_HREF, LocalStorage};
 use iced::Task;
 
 pub fn handle(app: &mut GuiApp, message: Message) -> Task<Message> {
@@ -19,12 +18,25 @@ pub fn handle(app: &mut GuiApp, message: Message) -> Task<Message> {
             if app.client.is_some()
                 && let Ok(cfg) = Config::load()
             {
-                return Task::perform(connect_and_fetch_wrapper(cfg), Message::Loaded);
+                return Task::run(connect_stream(cfg), |msg| msg);
             }
             Task::none()
         }
+        Message::AutoRefresh => {
+            // Don't yank the list from under the user while they type or edit
+            let busy = app.loading
+                || app.editing_uid.is_some()
+                || app.creating_child_of.is_some()
+                || !app.input_value.is_empty()
+                || !matches!(app.state, AppState::Active);
+            if busy {
+                return Task::none();
+            }
+            handle(app, Message::Refresh)
+        }
         Message::Loaded(Ok((client, mut cals, tasks, mut active, warning))) => {
             app.client = Some(client.clone());
+            app.journal_sync_progress = None;
 
             if let Some(w) = warning {
                 app.error_msg = Some(w);
@@ -32,32 +44,21 @@ pub fn handle(app: &mut GuiApp, message: Message) -> Task<Message> {
                 app.error_msg = None;
             }
 
-            app.unsynced_changes = !Journal::load().is_empty();
+            let journal = Journal::load();
+            app.unsynced_count = journal.queue.len();
+            app.conflicts = journal.conflicts;
 
-            let local_entry = CalendarListEntry {
-                name: LOCAL_CALENDAR_NAME.to_string(),
-                href: LOCAL_CALENDAR_HREF.to_string(),
-                color: None,
-            };
-
-            if !cals.iter().any(|c| c.href == LOCAL_CALENDAR_HREF) {
-                cals.push(local_entry);
-            }
+            LocalStorage::inject_calendars(&mut cals);
 
             app.calendars = cals.clone();
             app.store.clear();
 
-            if let Ok(local_t) = crate::gui::async_ops::get_runtime()
-                .block_on(async { client.get_tasks(LOCAL_CALENDAR_HREF).await })
-            {
-                app.store.insert(LOCAL_CALENDAR_HREF.to_string(), local_t);
-            }
-
             for cal in &app.calendars {
-                if cal.href == LOCAL_CALENDAR_HREF {
-                    continue;
-                }
-                if let Ok((cached_tasks, _)) = Cache::load(&cal.href) {
+                if LocalStorage::is_local(&cal.href) {
+                    if let Ok(local_t) = LocalStorage::load(&cal.href) {
+                        app.store.insert(cal.href.clone(), local_t);
+                    }
+                } else if let Ok((cached_tasks, _)) = Cache::load(&cal.href) {
                     app.store.insert(cal.href.clone(), cached_tasks);
                 }
             }
@@ -85,7 +86,7 @@ pub fn handle(app: &mut GuiApp, message: Message) -> Task<Message> {
             app.active_cal_href = active.clone();
 
             if let Some(href) = &active
-                && href != LOCAL_CALENDAR_HREF
+                && !LocalStorage::is_local(href)
                 && app.error_msg.is_none()
             {
                 app.store.insert(href.clone(), tasks);
@@ -93,7 +94,10 @@ pub fn handle(app: &mut GuiApp, message: Message) -> Task<Message> {
 
             if let Ok(cfg) = Config::load() {
                 app.hide_completed = cfg.hide_completed;
+                app.hide_cancelled = cfg.hide_cancelled;
+                app.hide_blocked = cfg.hide_blocked;
                 app.hide_fully_completed_tags = cfg.hide_fully_completed_tags;
+                app.sort_tags_by_count = cfg.sort_tags_by_count;
                 app.tag_aliases = cfg.tag_aliases;
                 app.disabled_calendars = cfg.disabled_calendars.into_iter().collect();
             }
@@ -108,26 +112,77 @@ pub fn handle(app: &mut GuiApp, message: Message) -> Task<Message> {
 
             if app.error_msg.is_none() {
                 app.loading = true;
-                Task::perform(async_fetch_all_wrapper(client, cals), Message::RefreshedAll)
+                Task::run(fetch_all_stream(client, cals), |msg| msg)
             } else {
                 Task::none()
             }
         }
         Message::Loaded(Err(e)) => {
             app.error_msg = Some(format!("Connection Failed: {}", e));
+            app.journal_sync_progress = None;
             app.state = AppState::Onboarding;
             app.loading = false;
             Task::none()
         }
-        Message::RefreshedAll(Ok(results)) => {
-            for (href, tasks) in results {
+        Message::SyncJournal => match &app.client {
+            Some(client) if app.journal_sync_progress.is_none() => {
+                app.journal_sync_progress = Some((0, app.unsynced_count));
+                Task::run(sync_journal_stream(client.clone()), |msg| msg)
+            }
+            _ => Task::none(),
+        },
+        Message::JournalSyncProgress(current, total) => {
+            app.journal_sync_progress = Some((current, total));
+            Task::none()
+        }
+        Message::JournalSynced(result) => {
+            app.journal_sync_progress = None;
+            let journal = Journal::load();
+            app.unsynced_count = journal.queue.len();
+            app.conflicts = journal.conflicts;
+            if app.journal_entries.is_some() {
+                app.journal_entries = Some(Journal::queue_summary());
+            }
+            if let Ok(sync) = &result {
+                app.store.set_etags(&sync.etags);
+            }
+            app.error_msg = match result.map(|sync| sync.warnings) {
+                Err(e) => Some(format!("Sync: {}", e)),
+                Ok(warnings) if !warnings.is_empty() => Some(warnings.join("; ")),
+                Ok(_) if app.unsynced_count > 0 => Some(format!(
+                    "{} changes still queued, the server may be unreachable.",
+                    app.unsynced_count
+                )),
+                Ok(_) => None,
+            };
+            Task::none()
+        }
+        Message::FetchProgress(done, total) => {
+            app.fetch_progress = Some((done, total));
+            Task::none()
+        }
+        Message::RefreshedAll(Ok(fetched)) => {
+            app.fetch_progress = None;
+            app.journal_sync_progress = None;
+            let journal = Journal::load();
+            app.unsynced_count = journal.queue.len();
+            app.conflicts = journal.conflicts;
+            for (href, tasks) in &fetched.calendars {
                 app.store.insert(href.clone(), tasks.clone());
             }
+            if let Some(warning) = fetched.parse_warning() {
+                app.error_msg = Some(format!("Sync warning: {}", warning));
+            }
             refresh_filtered_tasks(app);
             app.loading = false;
             Task::none()
         }
         Message::RefreshedAll(Err(e)) => {
+            app.fetch_progress = None;
+            app.journal_sync_progress = None;
+            let journal = Journal::load();
+            app.unsynced_count = journal.queue.len();
+            app.conflicts = journal.conflicts;
             app.error_msg = Some(format!("Sync warning: {}", e));
             app.loading = false;
             Task::none()
@@ -151,23 +206,34 @@ pub fn handle(app: &mut GuiApp, message: Message) -> Task<Message> {
             // Fix: Use update_or_add_task to ensure index is updated
             app.store.update_or_add_task(updated);
 
-            app.unsynced_changes = !Journal::load().is_empty();
-            if app.unsynced_changes {
+            let journal = Journal::load();
+            app.unsynced_count = journal.queue.len();
+            app.conflicts = journal.conflicts;
+            if app.unsynced_count > 0 {
                 app.error_msg = Some("Offline: Changes queued.".to_string());
             }
             refresh_filtered_tasks(app);
             Task::none()
         }
+        Message::ConflictResolved(Ok(_)) => handle(app, Message::Refresh),
+        Message::ConflictResolved(Err(e)) => {
+            app.error_msg = Some(format!("Resolve: {}", e));
+            handle(app, Message::Refresh)
+        }
         Message::SyncSaved(Err(e)) => {
             app.error_msg = Some(format!("Sync Error: {}", e));
             Task::none()
         }
         Message::SyncToggleComplete(boxed_res) => match *boxed_res {
             Ok((updated, created_opt)) => {
+                let updated_uid = updated.uid.clone();
                 // Fix: Use update_or_add_task
                 app.store.update_or_add_task(updated);
 
                 if let Some(created) = created_opt {
+                    if let Some(undo) = &mut app.last_action {
+                        undo.record_spawned(&updated_uid, &created.uid);
+                    }
                     app.store.update_or_add_task(created);
                 }
                 refresh_filtered_tasks(app);
@@ -195,14 +261,89 @@ pub fn handle(app: &mut GuiApp, message: Message) -> Task<Message> {
             app.error_msg = Some(format!("Move failed: {}", e));
             Task::none()
         }
+        Message::SubmitNewCalendar => {
+            let name = app.new_calendar_name.take().unwrap_or_default();
+            if name.trim().is_empty() {
+                return Task::none();
+            }
+            if let Some(client) = &app.client {
+                app.loading = true;
+                return Task::perform(
+                    async_create_calendar_wrapper(client.clone(), name),
+                    Message::CalendarCreated,
+                );
+            }
+            // Not connected yet: keep the new calendar on disk
+            let created = LocalStorage::create_calendar(&name).map_err(|e| e.to_string());
+            Task::done(Message::CalendarCreated(created))
+        }
+        Message::CalendarCreated(Ok(cal)) => {
+            app.loading = false;
+            app.error_msg = None;
+            if !app.calendars.iter().any(|c| c.href == cal.href) {
+                app.calendars.push(cal.clone());
+                let remote: Vec<_> = app
+                    .calendars
+                    .iter()
+                    .filter(|c| !LocalStorage::is_local(&c.href))
+                    .cloned()
+                    .collect();
+                let _ = Cache::save_calendars(&remote);
+            }
+            app.store.insert(cal.href.clone(), vec![]);
+            app.active_cal_href = Some(cal.href);
+            refresh_filtered_tasks(app);
+            Task::none()
+        }
+        Message::CalendarCreated(Err(e)) => {
+            app.loading = false;
+            app.error_msg = Some(format!("Could not create calendar: {}", e));
+            Task::none()
+        }
+        Message::SubmitRenameCalendar => {
+            let Some((href, name)) = app.renaming_calendar.take() else {
+                return Task::none();
+            };
+            if name.trim().is_empty() {
+                return Task::none();
+            }
+            if LocalStorage::is_local(&href) {
+                let name = name.trim().to_string();
+                let renamed = LocalStorage::rename_calendar(&href, &name)
+                    .map(|_| (href, name))
+                    .map_err(|e| e.to_string());
+                return Task::done(Message::CalendarRenamed(renamed));
+            }
+            if let Some(client) = &app.client {
+                app.loading = true;
+                return Task::perform(
+                    async_rename_calendar_wrapper(client.clone(), href, name),
+                    Message::CalendarRenamed,
+                );
+            }
+            Task::none()
+        }
+        Message::CalendarRenamed(Ok((href, name))) => {
+            app.loading = false;
+            app.error_msg = None;
+            if let Some(cal) = app.calendars.iter_mut().find(|c| c.href == href) {
+                cal.name = name;
+            }
+            Task::none()
+        }
+        Message::CalendarRenamed(Err(e)) => {
+            app.loading = false;
+            app.error_msg = Some(e);
+            Task::none()
+        }
         Message::MigrationComplete(Ok(count)) => {
             app.loading = false;
             app.error_msg = Some(format!("Exported {} tasks successfully.", count));
             if let Some(client) = &app.client {
                 app.loading = true;
-                return Task::perform(
-                    async_fetch_all_wrapper(client.clone(), app.calendars.clone()),
-                    Message::RefreshedAll,
+                return Task::run(
+                    fetch_all_stream(client.clone(), app.calendars.clone()),
+                    |msg| msg,
                 );
             }
             Task::none()
@@ -212,6 +353,23 @@ pub fn handle(app: &mut GuiApp, message: Message) -> Task<Message> {
             app.error_msg = Some(format!("Export failed: {}", e));
             Task::none()
         }
+        Message::ArchiveComplete(Ok(count)) => {
+            app.loading = false;
+            app.error_msg = Some(format!("Archived {} tasks.", count));
+            if let Some(client) = &app.client {
+                app.loading = true;
+                return Task::run(
+                    fetch_all_stream(client.clone(), app.calendars.clone()),
+                    |msg| msg,
+                );
+            }
+            Task::none()
+        }
+        Message::ArchiveComplete(Err(e)) => {
+            app.loading = false;
+            app.error_msg = Some(format!("Archive failed: {}", e));
+            Task::none()
+        }
         _ => Task::none(),
     }
 }
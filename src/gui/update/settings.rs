@@ -1,11 +1,12 @@
 // File: src/gui/update/settings.rs
 use crate::cache::Cache;
+use crate::color_utils;
 use crate::config::Config;
 use crate::gui::async_ops::*;
 use crate::gui::message::Message;
 use crate::gui::state::{AppState, GuiApp};
 use crate::gui::update::common::{apply_alias_retroactively, refresh_filtered_tasks, save_config};
-use crate::storage::{LOCAL_CALENDAR_HREF, LOCAL_CALENDAR_NAME, LocalStorage};
+use crate::storage::{LOCAL_CALENDAR_HREF, LocalStorage};
 use iced::Task;
 
 pub fn handle(app: &mut GuiApp, message: Message) -> Task<Message> {
@@ -19,37 +20,55 @@ pub fn handle(app: &mut GuiApp, message: Message) -> Task<Message> {
                 None => "".to_string(),
             };
             app.ob_insecure = config.allow_insecure_certs;
+            app.ob_use_keyring = config.use_keyring;
             app.tag_aliases = config.tag_aliases.clone();
+            app.calendar_defaults = config.calendar_defaults.clone();
+            app.templates = config.templates.clone();
+            app.tag_colors = config.tag_colors.clone();
             app.hide_completed = config.hide_completed;
+            app.hide_cancelled = config.hide_cancelled;
+            app.hide_blocked = config.hide_blocked;
             app.hide_fully_completed_tags = config.hide_fully_completed_tags;
+            app.sort_tags_by_count = config.sort_tags_by_count;
+            app.auto_complete_parent = config.auto_complete_parent;
+            app.confirm_delete = config.confirm_delete;
+            app.completed_last = config.completed_last;
+            app.manual_order = config.manual_order;
+            app.relative_dates = config.relative_dates;
+            app.compact_list = config.compact_list;
+            app.theme_mode = config.theme;
+            app.fuzzy_search = config.fuzzy_search;
+            app.week_start = config.week_start.weekday();
+            app.show_week_numbers = config.show_week_numbers;
+            app.focus_mode = config.focus_mode;
+            app.date_format = config.date_format.clone();
+            app.focus_timer = config.focus_timer;
+            app.snooze_presets = config.snooze_presets.clone();
+            app.archive_calendar = config.archive_calendar.clone();
+            app.hide_deferred = config.hide_deferred;
+            app.refresh_interval_secs = config.refresh_interval_secs;
 
             app.ob_url = config.url.clone();
             app.ob_user = config.username.clone();
             app.ob_pass = config.password.clone();
             app.ob_default_cal = config.default_calendar.clone();
+            if let Some(warning) = &config.load_warning {
+                app.error_msg = Some(warning.clone());
+            }
 
             let mut cached_cals = Cache::load_calendars().unwrap_or_default();
 
-            if !cached_cals.iter().any(|c| c.href == LOCAL_CALENDAR_HREF) {
-                cached_cals.push(crate::model::CalendarListEntry {
-                    name: LOCAL_CALENDAR_NAME.to_string(),
-                    href: LOCAL_CALENDAR_HREF.to_string(),
-                    color: None,
-                });
-            }
+            LocalStorage::inject_calendars(&mut cached_cals);
             app.calendars = cached_cals;
 
             app.store.clear();
 
-            if let Ok(local_tasks) = LocalStorage::load() {
-                app.store
-                    .insert(LOCAL_CALENDAR_HREF.to_string(), local_tasks);
-            }
-
             for cal in &app.calendars {
-                if cal.href != LOCAL_CALENDAR_HREF
-                    && let Ok((tasks, _)) = Cache::load(&cal.href)
-                {
+                if LocalStorage::is_local(&cal.href) {
+                    if let Ok(local_tasks) = LocalStorage::load(&cal.href) {
+                        app.store.insert(cal.href.clone(), local_tasks);
+                    }
+                } else if let Ok((tasks, _)) = Cache::load(&cal.href) {
                     app.store.insert(cal.href.clone(), tasks);
                 }
             }
@@ -77,7 +96,7 @@ pub fn handle(app: &mut GuiApp, message: Message) -> Task<Message> {
             refresh_filtered_tasks(app);
             app.state = AppState::Active;
             app.loading = true;
-            Task::perform(connect_and_fetch_wrapper(config), Message::Loaded)
+            Task::run(connect_stream(config), |msg| msg)
         }
         Message::ConfigLoaded(Err(_)) => {
             app.state = AppState::Onboarding;
@@ -103,6 +122,10 @@ pub fn handle(app: &mut GuiApp, message: Message) -> Task<Message> {
             app.ob_insecure = val;
             Task::none()
         }
+        Message::ObKeyringToggled(val) => {
+            app.ob_use_keyring = val;
+            Task::none()
+        }
         Message::ObSubmit => {
             if app.ob_sort_months_input.trim().is_empty() {
                 app.sort_cutoff_months = None;
@@ -110,38 +133,34 @@ pub fn handle(app: &mut GuiApp, message: Message) -> Task<Message> {
                 app.sort_cutoff_months = Some(n);
             }
 
-            let mut config_to_save = Config::load().unwrap_or_else(|_| Config {
-                url: String::new(),
-                username: String::new(),
-                password: String::new(),
-                default_calendar: None,
-                allow_insecure_certs: false,
-                hidden_calendars: Vec::new(),
-                disabled_calendars: Vec::new(),
-                hide_completed: app.hide_completed,
-                hide_fully_completed_tags: app.hide_fully_completed_tags,
-                tag_aliases: app.tag_aliases.clone(),
-                sort_cutoff_months: Some(6),
-            });
+            let mut config_to_save = Config::load().unwrap_or_default();
 
             config_to_save.url = app.ob_url.clone();
             config_to_save.username = app.ob_user.clone();
             config_to_save.password = app.ob_pass.clone();
             config_to_save.default_calendar = app.ob_default_cal.clone();
             config_to_save.allow_insecure_certs = app.ob_insecure;
+            config_to_save.use_keyring = app.ob_use_keyring;
             config_to_save.hidden_calendars = app.hidden_calendars.iter().cloned().collect();
             config_to_save.disabled_calendars = app.disabled_calendars.iter().cloned().collect();
             config_to_save.hide_completed = app.hide_completed;
+            config_to_save.hide_cancelled = app.hide_cancelled;
+            config_to_save.hide_blocked = app.hide_blocked;
             config_to_save.hide_fully_completed_tags = app.hide_fully_completed_tags;
+            config_to_save.sort_tags_by_count = app.sort_tags_by_count;
             config_to_save.tag_aliases = app.tag_aliases.clone();
+            config_to_save.tag_colors = app.tag_colors.clone();
             config_to_save.sort_cutoff_months = app.sort_cutoff_months;
 
-            let _ = config_to_save.save();
+            let warning = config_to_save.save_with_warning().ok().flatten();
 
             app.state = AppState::Loading;
-            app.error_msg = Some("Connecting...".to_string());
+            app.error_msg = Some(match warning {
+                Some(w) => format!("{} Connecting...", w),
+                None => "Connecting...".to_string(),
+            });
 
-            Task::perform(connect_and_fetch_wrapper(config_to_save), Message::Loaded)
+            Task::run(connect_stream(config_to_save), |msg| msg)
         }
         Message::OpenSettings => {
             if let Ok(cfg) = Config::load() {
@@ -150,10 +169,27 @@ pub fn handle(app: &mut GuiApp, message: Message) -> Task<Message> {
                 app.ob_pass = cfg.password;
                 app.ob_default_cal = cfg.default_calendar;
                 app.hide_completed = cfg.hide_completed;
+                app.hide_cancelled = cfg.hide_cancelled;
+                app.hide_blocked = cfg.hide_blocked;
                 app.hide_fully_completed_tags = cfg.hide_fully_completed_tags;
+                app.sort_tags_by_count = cfg.sort_tags_by_count;
+                app.confirm_delete = cfg.confirm_delete;
+                app.completed_last = cfg.completed_last;
+                app.relative_dates = cfg.relative_dates;
+                app.compact_list = cfg.compact_list;
+                app.theme_mode = cfg.theme;
+                app.fuzzy_search = cfg.fuzzy_search;
+                app.week_start = cfg.week_start.weekday();
+                app.show_week_numbers = cfg.show_week_numbers;
+                app.date_format = cfg.date_format.clone();
+                app.focus_timer = cfg.focus_timer;
+                app.snooze_presets = cfg.snooze_presets.clone();
+                app.archive_calendar = cfg.archive_calendar;
                 app.ob_insecure = cfg.allow_insecure_certs;
+                app.ob_use_keyring = cfg.use_keyring;
                 app.hidden_calendars = cfg.hidden_calendars.into_iter().collect();
                 app.tag_aliases = cfg.tag_aliases;
+                app.tag_colors = cfg.tag_colors;
                 app.sort_cutoff_months = cfg.sort_cutoff_months;
                 app.ob_sort_months_input = match cfg.sort_cutoff_months {
                     Some(m) => m.to_string(),
@@ -181,15 +217,34 @@ pub fn handle(app: &mut GuiApp, message: Message) -> Task<Message> {
                 hidden_calendars: Vec::new(),
                 disabled_calendars: Vec::new(),
                 hide_completed: app.hide_completed,
+                hide_cancelled: app.hide_cancelled,
+                hide_blocked: app.hide_blocked,
                 hide_fully_completed_tags: app.hide_fully_completed_tags,
+                sort_tags_by_count: app.sort_tags_by_count,
                 tag_aliases: app.tag_aliases.clone(),
+                tag_colors: app.tag_colors.clone(),
                 sort_cutoff_months: app.sort_cutoff_months,
+                auto_complete_parent: app.auto_complete_parent,
+                confirm_delete: app.confirm_delete,
+                completed_last: app.completed_last,
+                manual_order: app.manual_order,
+                relative_dates: app.relative_dates,
+                compact_list: app.compact_list,
+                theme: app.theme_mode,
+                fuzzy_search: app.fuzzy_search,
+                archive_calendar: app.archive_calendar.clone(),
+                hide_deferred: app.hide_deferred,
+                focus_mode: app.focus_mode,
+                refresh_interval_secs: app.refresh_interval_secs,
+                // Keep options the GUI doesn't expose
+                ..Config::load().unwrap_or_default()
             };
 
-            let _ = config_to_save.save();
+            let warning = config_to_save.save_with_warning().ok().flatten();
 
             app.state = AppState::Loading;
-            Task::perform(connect_and_fetch_wrapper(config_to_save), Message::Loaded)
+            app.error_msg = warning;
+            Task::run(connect_stream(config_to_save), |msg| msg)
         }
         Message::AliasKeyInput(v) => {
             app.alias_input_key = v;
@@ -232,6 +287,38 @@ pub fn handle(app: &mut GuiApp, message: Message) -> Task<Message> {
             save_config(app);
             Task::none()
         }
+        Message::TagColorTagSelected(tag) => {
+            app.tag_color_input_tag = Some(tag);
+            Task::none()
+        }
+        Message::TagColorHexInput(v) => {
+            app.tag_color_input_hex = v;
+            Task::none()
+        }
+        Message::AddTagColor => {
+            let hex = app.tag_color_input_hex.trim();
+            let hex = if hex.starts_with('#') {
+                hex.to_string()
+            } else {
+                format!("#{}", hex)
+            };
+            if color_utils::parse_hex_to_floats(&hex).is_none() {
+                app.error_msg = Some(format!("Invalid color '{}' (use #rrggbb)", hex));
+                return Task::none();
+            }
+            if let Some(tag) = app.tag_color_input_tag.take() {
+                app.tag_colors.insert(tag, hex);
+                app.tag_color_input_hex.clear();
+                app.error_msg = None;
+                save_config(app);
+            }
+            Task::none()
+        }
+        Message::RemoveTagColor(tag) => {
+            app.tag_colors.remove(&tag);
+            save_config(app);
+            Task::none()
+        }
         Message::ObSortMonthsChanged(val) => {
             if val.is_empty() || val.chars().all(|c| c.is_numeric()) {
                 app.ob_sort_months_input = val;
@@ -1,9 +1,14 @@
 // File: src/gui/update/tasks.rs
+use crate::config::CalendarDefaults;
 use crate::gui::async_ops::*;
 use crate::gui::message::Message;
 use crate::gui::state::{GuiApp, SidebarMode};
 use crate::gui::update::common::{apply_alias_retroactively, refresh_filtered_tasks, save_config};
+use crate::model::recurrence::{Recurrence, validate_rrule};
 use crate::model::{Task as TodoTask, extract_inline_aliases};
+use crate::model::{completion, parser};
+use crate::storage::LocalStorage;
+use crate::store::UndoEntry;
 use iced::Task;
 use iced::widget::operation;
 use iced::widget::scrollable::RelativeOffset;
@@ -12,6 +17,59 @@ pub fn handle(app: &mut GuiApp, message: Message) -> Task<Message> {
     match message {
         Message::InputChanged(value) => {
             app.input_value = value;
+            app.tag_completion = None;
+            Task::none()
+        }
+        // The input drops the line breaks of a paste, the clipboard still has them
+        Message::InputPasted(value) => {
+            let before = std::mem::replace(&mut app.input_value, value);
+            app.tag_completion = None;
+            iced::clipboard::read().map(move |text| Message::OutlinePasted(before.clone(), text))
+        }
+        Message::OutlinePasted(before, Some(text))
+            if text.lines().filter(|l| !l.trim().is_empty()).count() > 1 =>
+        {
+            app.input_value = before;
+            create_from_outline(app, &text)
+        }
+        Message::OutlinePasted(_, _) => Task::none(),
+        // The edit panel's priority picker rewrites the `!n` token of the title
+        Message::PickPriority(priority) => {
+            app.input_value = parser::with_priority(&app.input_value, priority);
+            Task::none()
+        }
+        // ...and the recurrence builder its recurrence token
+        Message::RecurrenceFreqPicked(freq) => {
+            let mut rec = current_recurrence(app).unwrap_or_else(|| Recurrence::new(freq));
+            rec.freq = freq;
+            set_recurrence(app, &rec);
+            Task::none()
+        }
+        Message::RecurrenceIntervalChanged(interval) => {
+            if let Some(mut rec) = current_recurrence(app) {
+                rec.interval = interval.max(1);
+                set_recurrence(app, &rec);
+            }
+            Task::none()
+        }
+        Message::RecurrenceWeekdayToggled(day) => {
+            if let Some(mut rec) = current_recurrence(app) {
+                rec.toggle_weekday(day);
+                set_recurrence(app, &rec);
+            }
+            Task::none()
+        }
+        Message::ClearRecurrence => {
+            app.input_value = parser::with_recurrence(&app.input_value, None);
+            Task::none()
+        }
+        Message::CompleteTag(tag) => {
+            let cursor = app.input_value.chars().count();
+            if let Some((start, _)) = completion::tag_token_at(&app.input_value, cursor) {
+                let head: String = app.input_value.chars().take(start).collect();
+                app.input_value = format!("{}#{} ", head, tag);
+            }
+            app.tag_completion = None;
             Task::none()
         }
         Message::DescriptionChanged(action) => {
@@ -58,33 +116,77 @@ pub fn handle(app: &mut GuiApp, message: Message) -> Task<Message> {
         Message::ToggleTask(index, _) => {
             if let Some(view_task) = app.tasks.get(index) {
                 let uid = view_task.uid.clone();
+                let undo = UndoEntry::status_of(view_task);
                 app.selected_uid = Some(uid.clone());
                 if let Some(updated) = app.store.toggle_task(&uid) {
+                    app.last_action = Some(undo);
+                    let parent = if app.auto_complete_parent
+                        && updated.status == crate::model::TaskStatus::Completed
+                    {
+                        app.store.complete_parent_if_done(&uid)
+                    } else {
+                        None
+                    };
+                    let (updated, logged) = follow_timer(app, updated);
                     refresh_filtered_tasks(app);
                     if let Some(client) = &app.client {
-                        return Task::perform(
+                        let mut commands = vec![Task::perform(
                             async_toggle_wrapper(client.clone(), updated),
                             |res| Message::SyncToggleComplete(Box::new(res)),
-                        );
+                        )];
+                        commands.extend(logged.into_iter().map(|t| {
+                            Task::perform(
+                                async_update_wrapper(client.clone(), t),
+                                Message::SyncSaved,
+                            )
+                        }));
+                        if let Some(p) = parent {
+                            commands.push(Task::perform(
+                                async_toggle_wrapper(client.clone(), p),
+                                |res| Message::SyncToggleComplete(Box::new(res)),
+                            ));
+                        }
+                        return Task::batch(commands);
                     }
                 }
             }
             Task::none()
         }
-        Message::DeleteTask(index) => {
-            if let Some(view_task) = app.tasks.get(index)
-                && let Some(deleted) = app.store.delete_task(&view_task.uid)
+        Message::ToggleChecklistItem(uid, line_idx) => {
+            let href = app.store.index.get(&uid);
+            if let Some(cal) = app
+                .calendars
+                .iter()
+                .find(|c| Some(&c.href) == href && c.read_only)
             {
+                app.error_msg = Some(format!("Calendar '{}' is read-only", cal.name));
+                return Task::none();
+            }
+            if let Some(updated) = app.store.toggle_checklist_item(&uid, line_idx) {
                 refresh_filtered_tasks(app);
                 if let Some(client) = &app.client {
                     return Task::perform(
-                        async_delete_wrapper(client.clone(), deleted),
-                        Message::DeleteComplete,
+                        async_update_wrapper(client.clone(), updated),
+                        Message::SyncSaved,
                     );
                 }
             }
             Task::none()
         }
+        Message::DeleteTask(index) => {
+            if let Some(uid) = app.tasks.get(index).map(|t| t.uid.clone()) {
+                if app.confirm_delete {
+                    app.pending_delete = Some(uid);
+                } else {
+                    return delete_task(app, &uid);
+                }
+            }
+            Task::none()
+        }
+        Message::ConfirmDelete(confirmed) => match app.pending_delete.take() {
+            Some(uid) if confirmed => delete_task(app, &uid),
+            _ => Task::none(),
+        },
         Message::ChangePriority(index, delta) => {
             if let Some(view_task) = app.tasks.get(index) {
                 app.selected_uid = Some(view_task.uid.clone());
@@ -100,10 +202,37 @@ pub fn handle(app: &mut GuiApp, message: Message) -> Task<Message> {
             }
             Task::none()
         }
-        Message::SetTaskStatus(index, new_status) => {
-            if let Some(view_task) = app.tasks.get(index) {
-                app.selected_uid = Some(view_task.uid.clone());
-                if let Some(updated) = app.store.set_status(&view_task.uid, new_status) {
+        Message::ToggleDeferred(uid) => {
+            app.selected_uid = Some(uid.clone());
+            if let Some(updated) = app.store.toggle_deferred(&uid) {
+                refresh_filtered_tasks(app);
+                if let Some(client) = &app.client {
+                    return Task::perform(
+                        async_update_wrapper(client.clone(), updated),
+                        Message::SyncSaved,
+                    );
+                }
+            }
+            Task::none()
+        }
+        Message::SetTaskColor(uid, color) => {
+            app.selected_uid = Some(uid.clone());
+            if let Some(updated) = app.store.set_color(&uid, color) {
+                refresh_filtered_tasks(app);
+                if let Some(client) = &app.client {
+                    return Task::perform(
+                        async_update_wrapper(client.clone(), updated),
+                        Message::SyncSaved,
+                    );
+                }
+            }
+            Task::none()
+        }
+        Message::CreateFromTemplate(name) => create_from_template(app, &name),
+        Message::SnoozeTask(uid, preset) => {
+            app.selected_uid = Some(uid.clone());
+            match app.store.snooze(&uid, &preset, app.week_start) {
+                Some(updated) => {
                     refresh_filtered_tasks(app);
                     if let Some(client) = &app.client {
                         return Task::perform(
@@ -112,6 +241,166 @@ pub fn handle(app: &mut GuiApp, message: Message) -> Task<Message> {
                         );
                     }
                 }
+                None => app.error_msg = Some(format!("Can't read snooze preset '{}'", preset)),
+            }
+            Task::none()
+        }
+        Message::DuplicateTask(uid) => {
+            let Some(copy) = app
+                .tasks
+                .iter()
+                .find(|t| t.uid == uid)
+                .map(|t| t.duplicate())
+            else {
+                return Task::none();
+            };
+            app.store.add_task(copy.clone());
+            app.selected_uid = Some(copy.uid.clone());
+            refresh_filtered_tasks(app);
+            if let Some(client) = &app.client {
+                return Task::perform(
+                    async_create_wrapper(client.clone(), copy),
+                    Message::SyncSaved,
+                );
+            }
+            Task::none()
+        }
+        Message::SkipOccurrence(uid) => {
+            app.selected_uid = Some(uid.clone());
+            if let Some(updated) = app.store.skip_occurrence(&uid) {
+                refresh_filtered_tasks(app);
+                if let Some(client) = &app.client {
+                    return Task::perform(
+                        async_update_wrapper(client.clone(), updated),
+                        Message::SyncSaved,
+                    );
+                }
+            }
+            Task::none()
+        }
+        Message::DragStart(uid) => {
+            app.drag_target = Some(uid.clone());
+            app.dragging = Some(uid);
+            Task::none()
+        }
+        Message::DragOver(uid) => {
+            if app.dragging.is_some() {
+                app.drag_target = Some(uid);
+            }
+            Task::none()
+        }
+        Message::DragEnd => {
+            let (Some(uid), Some(target)) = (app.dragging.take(), app.drag_target.take()) else {
+                return Task::none();
+            };
+            let updated = app.store.reorder_task(&uid, &target, &app.tasks);
+            refresh_filtered_tasks(app);
+            if let Some(client) = &app.client {
+                return Task::batch(updated.into_iter().map(|t| {
+                    Task::perform(async_update_wrapper(client.clone(), t), Message::SyncSaved)
+                }));
+            }
+            Task::none()
+        }
+        Message::SetTaskStatus(index, new_status) => {
+            if let Some(view_task) = app.tasks.get(index) {
+                app.selected_uid = Some(view_task.uid.clone());
+                let undo = UndoEntry::status_of(view_task);
+                if let Some(updated) = app.store.set_status(&view_task.uid, new_status) {
+                    app.last_action = Some(undo);
+                    let (updated, logged) = follow_timer(app, updated);
+                    refresh_filtered_tasks(app);
+                    if let Some(client) = &app.client {
+                        return Task::batch(std::iter::once(updated).chain(logged).map(|t| {
+                            Task::perform(
+                                async_update_wrapper(client.clone(), t),
+                                Message::SyncSaved,
+                            )
+                        }));
+                    }
+                }
+            }
+            Task::none()
+        }
+        Message::StopTimer => {
+            if let Some(updated) = app.store.stop_timer(&mut app.timer, chrono::Utc::now()) {
+                refresh_filtered_tasks(app);
+                if let Some(client) = &app.client {
+                    return Task::perform(
+                        async_update_wrapper(client.clone(), updated),
+                        Message::SyncSaved,
+                    );
+                }
+            }
+            Task::none()
+        }
+        Message::Undo => {
+            if let Some(entry) = app.last_action.take() {
+                let is_delete = matches!(entry, UndoEntry::Deleted(_));
+                if let Some((task, spawned)) = app.store.undo(entry) {
+                    app.selected_uid = Some(task.uid.clone());
+                    refresh_filtered_tasks(app);
+                    // Compensating action, queued through the journal like any other edit
+                    if let Some(client) = &app.client {
+                        let restore = if is_delete {
+                            Task::perform(
+                                async_create_wrapper(client.clone(), task),
+                                Message::SyncSaved,
+                            )
+                        } else {
+                            Task::perform(
+                                async_update_wrapper(client.clone(), task),
+                                Message::SyncSaved,
+                            )
+                        };
+                        return match spawned {
+                            Some(next) => Task::batch([
+                                restore,
+                                Task::perform(
+                                    async_delete_wrapper(client.clone(), next),
+                                    Message::DeleteComplete,
+                                ),
+                            ]),
+                            None => restore,
+                        };
+                    }
+                }
+            }
+            Task::none()
+        }
+        Message::DismissUndo => {
+            app.last_action = None;
+            Task::none()
+        }
+        Message::ToggleConflictField(field) => {
+            if !app.conflict_keep_local.remove(&field) {
+                app.conflict_keep_local.insert(field);
+            }
+            Task::none()
+        }
+        Message::ResolveConflict(apply) => {
+            if app.conflicts.is_empty() {
+                return Task::none();
+            }
+            let conflict = app.conflicts.remove(0);
+            let keep_local: Vec<String> = app.conflict_keep_local.drain().collect();
+            if let Some(client) = &app.client {
+                return Task::perform(
+                    async_resolve_conflict_wrapper(
+                        client.clone(),
+                        conflict.local.uid,
+                        apply.then_some(keep_local),
+                    ),
+                    Message::ConflictResolved,
+                );
+            }
+            Task::none()
+        }
+        Message::CopySelectedTask => {
+            if let Some(uid) = &app.selected_uid
+                && let Some(summary) = app.store.get_summary(uid)
+            {
+                return iced::clipboard::write(summary);
             }
             Task::none()
         }
@@ -201,8 +490,80 @@ pub fn handle(app: &mut GuiApp, message: Message) -> Task<Message> {
             }
             Task::none()
         }
+        Message::ArchiveCompleted => match app.archive_calendar.clone() {
+            Some(target) if app.active_cal_href.as_ref() != Some(&target) => {
+                archive_completed(app, target)
+            }
+            _ => {
+                app.picking_archive = true;
+                Task::none()
+            }
+        },
+        Message::ArchiveCompletedTo(target_href) => {
+            app.archive_calendar = Some(target_href.clone());
+            app.picking_archive = false;
+            save_config(app);
+            archive_completed(app, target_href)
+        }
+        Message::ClearCompleted(false) => {
+            app.confirm_clear_completed = true;
+            Task::none()
+        }
+        Message::ClearCompleted(true) => {
+            app.confirm_clear_completed = false;
+            let Some(href) = app.active_cal_href.clone() else {
+                return Task::none();
+            };
+            let deleted: Vec<TodoTask> = app
+                .store
+                .clearable_tasks(&href)
+                .into_iter()
+                .filter_map(|t| app.store.delete_task(&t.uid))
+                .collect();
+            refresh_filtered_tasks(app);
+            delete_all(app, deleted)
+        }
+        Message::FindDuplicates => {
+            let mut groups = app.store.find_duplicates();
+            groups.retain(|group| {
+                !app.calendars
+                    .iter()
+                    .any(|c| c.href == group[0].calendar_href && c.read_only)
+            });
+            app.duplicate_delete = groups
+                .iter()
+                .flat_map(|group| group.iter().skip(1))
+                .filter(|t| !t.status.is_done())
+                .map(|t| t.uid.clone())
+                .collect();
+            app.duplicates = Some(groups);
+            Task::none()
+        }
+        Message::ToggleDuplicateDelete(uid) => {
+            if !app.duplicate_delete.remove(&uid) {
+                app.duplicate_delete.insert(uid);
+            }
+            Task::none()
+        }
+        Message::DeleteDuplicates => {
+            app.duplicates = None;
+            let mut deleted = Vec::new();
+            for uid in std::mem::take(&mut app.duplicate_delete) {
+                deleted.extend(app.store.delete_task(&uid));
+            }
+            refresh_filtered_tasks(app);
+            delete_all(app, deleted)
+        }
+        Message::PickArchiveCalendar => {
+            app.picking_archive = !app.picking_archive;
+            Task::none()
+        }
         Message::MigrateLocalTo(target_href) => {
-            if let Some(local_tasks) = app.store.calendars.get(crate::storage::LOCAL_CALENDAR_HREF)
+            if let Some(source) = app
+                .active_cal_href
+                .as_deref()
+                .filter(|href| LocalStorage::is_local(href))
+                && let Some(local_tasks) = app.store.calendars.get(source)
             {
                 let tasks_to_move = local_tasks.clone();
                 if tasks_to_move.is_empty() {
@@ -222,6 +583,33 @@ pub fn handle(app: &mut GuiApp, message: Message) -> Task<Message> {
     }
 }
 
+fn delete_task(app: &mut GuiApp, uid: &str) -> Task<Message> {
+    if let Some(deleted) = app.store.delete_task(uid) {
+        app.last_action = Some(UndoEntry::Deleted(Box::new(deleted.clone())));
+        refresh_filtered_tasks(app);
+        if let Some(client) = &app.client {
+            return Task::perform(
+                async_delete_wrapper(client.clone(), deleted),
+                Message::DeleteComplete,
+            );
+        }
+    }
+    Task::none()
+}
+
+/// Sends the deletion of tasks already removed from the store.
+fn delete_all(app: &GuiApp, deleted: Vec<TodoTask>) -> Task<Message> {
+    match &app.client {
+        Some(client) => Task::batch(deleted.into_iter().map(|task| {
+            Task::perform(
+                async_delete_wrapper(client.clone(), task),
+                Message::DeleteComplete,
+            )
+        })),
+        None => Task::none(),
+    }
+}
+
 fn handle_submit(app: &mut GuiApp) -> Task<Message> {
     if app.input_value.is_empty() {
         return Task::none();
@@ -312,7 +700,26 @@ fn handle_submit(app: &mut GuiApp) -> Task<Message> {
             .or_else(|| app.calendars.first().map(|c| c.href.clone()))
             .unwrap_or_default();
 
+        if let Some(cal) = app
+            .calendars
+            .iter()
+            .find(|c| c.href == target_href && c.read_only)
+        {
+            app.error_msg = Some(format!("Calendar '{}' is read-only", cal.name));
+            return Task::none();
+        }
+
         if !target_href.is_empty() {
+            let name = app
+                .calendars
+                .iter()
+                .find(|c| c.href == target_href)
+                .map(|c| c.name.as_str());
+            if let Some(defaults) =
+                CalendarDefaults::lookup(&app.calendar_defaults, &target_href, name)
+            {
+                defaults.apply(&mut new_task);
+            }
             new_task.calendar_href = target_href.clone();
 
             // Fix: Use add_task to maintain index
@@ -355,3 +762,141 @@ fn handle_submit(app: &mut GuiApp) -> Task<Message> {
     }
     Task::none()
 }
+
+/// Creates the tasks of the template `name` in the active calendar.
+fn create_from_template(app: &mut GuiApp, name: &str) -> Task<Message> {
+    let Some(template) = app.templates.get(name) else {
+        return Task::none();
+    };
+    let Some(href) = app
+        .active_cal_href
+        .clone()
+        .or_else(|| app.calendars.first().map(|c| c.href.clone()))
+    else {
+        return Task::none();
+    };
+    let cal = app.calendars.iter().find(|c| c.href == href);
+    if let Some(cal) = cal.filter(|c| c.read_only) {
+        app.error_msg = Some(format!("Calendar '{}' is read-only", cal.name));
+        return Task::none();
+    }
+    let defaults =
+        CalendarDefaults::lookup(&app.calendar_defaults, &href, cal.map(|c| c.name.as_str()))
+            .cloned();
+
+    let mut tasks = template.instantiate(&app.tag_aliases, chrono::Local::now().date_naive());
+    if tasks.is_empty() {
+        app.error_msg = Some(format!(
+            "Template '{}' has no task, set its `task` in the config.",
+            name
+        ));
+        return Task::none();
+    }
+    for task in &mut tasks {
+        if let Some(defaults) = &defaults {
+            defaults.apply(task);
+        }
+        task.calendar_href = href.clone();
+        app.store.add_task(task.clone());
+    }
+    app.selected_uid = Some(tasks[0].uid.clone());
+    refresh_filtered_tasks(app);
+
+    let Some(client) = &app.client else {
+        return Task::none();
+    };
+    Task::batch(tasks.into_iter().map(|task| {
+        Task::perform(
+            async_create_wrapper(client.clone(), task),
+            Message::SyncSaved,
+        )
+    }))
+}
+
+/// Creates the tasks of an outline pasted into the quick-add.
+fn create_from_outline(app: &mut GuiApp, text: &str) -> Task<Message> {
+    let Some(href) = app
+        .active_cal_href
+        .clone()
+        .or_else(|| app.calendars.first().map(|c| c.href.clone()))
+    else {
+        return Task::none();
+    };
+    let cal = app.calendars.iter().find(|c| c.href == href);
+    if let Some(cal) = cal.filter(|c| c.read_only) {
+        app.error_msg = Some(format!("Calendar '{}' is read-only", cal.name));
+        return Task::none();
+    }
+    let defaults =
+        CalendarDefaults::lookup(&app.calendar_defaults, &href, cal.map(|c| c.name.as_str()))
+            .cloned();
+
+    let mut tasks = TodoTask::tree_from_outline(text, &app.tag_aliases);
+    if tasks.is_empty() {
+        return Task::none();
+    }
+    for task in &mut tasks {
+        if let Some(defaults) = &defaults {
+            defaults.apply(task);
+        }
+        task.calendar_href = href.clone();
+        if task.parent_uid.is_none() {
+            task.parent_uid = app.creating_child_of.clone();
+        }
+        app.store.add_task(task.clone());
+    }
+    app.creating_child_of = None;
+    app.input_value.clear();
+    app.selected_uid = Some(tasks[0].uid.clone());
+    refresh_filtered_tasks(app);
+
+    let Some(client) = &app.client else {
+        return Task::none();
+    };
+    Task::batch(tasks.into_iter().map(|task| {
+        Task::perform(
+            async_create_wrapper(client.clone(), task),
+            Message::SyncSaved,
+        )
+    }))
+}
+
+/// Moves the completed and cancelled tasks of the active calendar to `target`.
+fn archive_completed(app: &mut GuiApp, target: String) -> Task<Message> {
+    let Some(from) = &app.active_cal_href else {
+        return Task::none();
+    };
+    let tasks = app.store.archivable_tasks(from);
+    if tasks.is_empty() {
+        return Task::none();
+    }
+    if let Some(client) = &app.client {
+        app.loading = true;
+        return Task::perform(
+            async_migrate_wrapper(client.clone(), tasks, target),
+            Message::ArchiveComplete,
+        );
+    }
+    Task::none()
+}
+
+/// Starts or stops the focus timer after `updated` changed status.
+fn follow_timer(app: &mut GuiApp, updated: TodoTask) -> (TodoTask, Vec<TodoTask>) {
+    let now = chrono::Utc::now();
+    app.store
+        .follow_timer(&mut app.timer, updated, app.focus_timer, now)
+}
+
+/// Recurrence of the title being edited, when the builder can show it.
+fn current_recurrence(app: &GuiApp) -> Option<Recurrence> {
+    parser::input_recurrence(&app.input_value).and_then(|r| Recurrence::from_rrule(&r))
+}
+
+/// Writes the builder's rule into the title, if the recurrence engine accepts it.
+fn set_recurrence(app: &mut GuiApp, rec: &Recurrence) {
+    let rule = rec.to_rrule();
+    match validate_rrule(&rule) {
+        Ok(()) => app.input_value = parser::with_recurrence(&app.input_value, Some(&rule)),
+        Err(e) => app.error_msg = Some(e),
+    }
+}
@@ -2,13 +2,31 @@
 use crate::gui::async_ops::*;
 use crate::gui::message::Message;
 use crate::gui::state::{AppState, GuiApp, ResizeDirection, SidebarMode};
-use crate::gui::update::common::{refresh_filtered_tasks, save_config};
+use crate::gui::update::common::{refresh_filtered_tasks, save_config, tag_candidates};
+use crate::gui::window_state::WindowGeometry;
+use crate::journal::Journal;
+use crate::model::{completion, csv};
 use iced::widget::operation;
 use iced::{Task, window};
+use std::path::Path;
 
 pub fn handle(app: &mut GuiApp, message: Message) -> Task<Message> {
     match message {
         Message::TabPressed(shift_held) => {
+            // Tab completes a `#tag` being typed at the end of the input first
+            if !shift_held && matches!(app.state, AppState::Active) {
+                let candidates = tag_candidates(app);
+                let cursor = app.input_value.chars().count();
+                if let Some((input, _)) = completion::complete_tag(
+                    &app.input_value,
+                    cursor,
+                    &candidates,
+                    &mut app.tag_completion,
+                ) {
+                    app.input_value = input;
+                    return Task::none();
+                }
+            }
             if shift_held {
                 operation::focus_previous()
             } else {
@@ -91,12 +109,128 @@ pub fn handle(app: &mut GuiApp, message: Message) -> Task<Message> {
             refresh_filtered_tasks(app);
             Task::none()
         }
+        Message::ToggleHideCancelled(val) => {
+            app.hide_cancelled = val;
+            save_config(app);
+            refresh_filtered_tasks(app);
+            Task::none()
+        }
+        Message::ToggleHideBlocked(val) => {
+            app.hide_blocked = val;
+            save_config(app);
+            refresh_filtered_tasks(app);
+            Task::none()
+        }
+        Message::ToggleConfirmDelete(val) => {
+            app.confirm_delete = val;
+            save_config(app);
+            Task::none()
+        }
+        Message::ToggleCompletedLast(val) => {
+            app.completed_last = val;
+            save_config(app);
+            refresh_filtered_tasks(app);
+            Task::none()
+        }
+        Message::ToggleHideDeferred(val) => {
+            app.hide_deferred = val;
+            save_config(app);
+            refresh_filtered_tasks(app);
+            Task::none()
+        }
+        Message::ToggleFocusMode => {
+            app.focus_mode = !app.focus_mode;
+            save_config(app);
+            Task::none()
+        }
+        Message::ToggleRelativeDates(val) => {
+            app.relative_dates = val;
+            save_config(app);
+            Task::none()
+        }
+        Message::ToggleCompactList(val) => {
+            app.compact_list = val;
+            app.row_menu = None;
+            save_config(app);
+            Task::none()
+        }
+        Message::SetTheme(mode) => {
+            app.theme_mode = mode;
+            save_config(app);
+            Task::none()
+        }
+        Message::SystemThemeChanged(mode) => {
+            app.system_theme = mode;
+            Task::none()
+        }
+        Message::ToggleFuzzySearch(val) => {
+            app.fuzzy_search = val;
+            save_config(app);
+            refresh_filtered_tasks(app);
+            Task::none()
+        }
+        Message::HoverTask(uid) => {
+            if app.hovered_uid.as_ref() != Some(&uid) {
+                app.hovered_uid = Some(uid);
+            }
+            Task::none()
+        }
+        Message::ToggleRowMenu(uid) => {
+            if app.row_menu.as_ref() == Some(&uid) {
+                app.row_menu = None;
+            } else {
+                app.row_menu = Some(uid);
+            }
+            Task::none()
+        }
+        Message::ToggleManualOrder(val) => {
+            app.manual_order = val;
+            save_config(app);
+            refresh_filtered_tasks(app);
+            Task::none()
+        }
+        Message::ToggleAgenda(val) => {
+            app.agenda_view = val;
+            refresh_filtered_tasks(app);
+            Task::none()
+        }
+        Message::ToggleReview(val) => {
+            app.review_view = val;
+            refresh_filtered_tasks(app);
+            Task::none()
+        }
+        Message::ToggleToday(val) => {
+            app.today_view = val;
+            refresh_filtered_tasks(app);
+            Task::none()
+        }
+        Message::ToggleNoDate => {
+            app.no_date_collapsed = !app.no_date_collapsed;
+            refresh_filtered_tasks(app);
+            Task::none()
+        }
+        // Only redraws the timer's clock
+        Message::TimerTick => Task::none(),
+        Message::ToggleDebugIds => {
+            app.show_debug_ids = !app.show_debug_ids;
+            Task::none()
+        }
+        Message::SetPriorityFilter(val) => {
+            app.priority_filter = val;
+            refresh_filtered_tasks(app);
+            Task::none()
+        }
         Message::ToggleHideFullyCompletedTags(val) => {
             app.hide_fully_completed_tags = val;
             save_config(app);
             refresh_filtered_tasks(app);
             Task::none()
         }
+        Message::ToggleSortTagsByCount(val) => {
+            app.sort_tags_by_count = val;
+            save_config(app);
+            Task::none()
+        }
         Message::SelectCalendar(href) => {
             if app.sidebar_mode == SidebarMode::Categories {
                 app.sidebar_mode = SidebarMode::Calendars;
@@ -173,6 +307,109 @@ pub fn handle(app: &mut GuiApp, message: Message) -> Task<Message> {
             app.selected_uid = Some(uid);
             Task::none()
         }
+        Message::OpenUrl(url) => {
+            if let Err(e) = open_url(&url) {
+                app.error_msg = Some(e);
+            }
+            Task::none()
+        }
+        Message::OpenJournal => {
+            app.journal_entries = Some(Journal::queue_summary());
+            app.journal_confirm_clear = false;
+            Task::none()
+        }
+        Message::CloseJournal => {
+            app.journal_entries = None;
+            app.journal_confirm_clear = false;
+            Task::none()
+        }
+        Message::DropJournalEntry(index) => {
+            let result = Journal::remove_at(index);
+            reload_journal(app);
+            match result {
+                // Refetch so the dropped change disappears locally too
+                Ok(Some(_)) => Task::perform(async { Ok::<(), String>(()) }, |_| Message::Refresh),
+                Ok(None) => Task::none(),
+                Err(e) => {
+                    app.error_msg = Some(e.to_string());
+                    Task::none()
+                }
+            }
+        }
+        Message::OpenNewCalendar => {
+            app.new_calendar_name = Some(String::new());
+            Task::none()
+        }
+        Message::CloseNewCalendar => {
+            app.new_calendar_name = None;
+            Task::none()
+        }
+        Message::NewCalendarNameChanged(name) => {
+            app.new_calendar_name = Some(name);
+            Task::none()
+        }
+        Message::OpenCsvExport => {
+            let dir = directories::UserDirs::new().and_then(|d| {
+                d.download_dir()
+                    .map(|p| p.to_path_buf())
+                    .or_else(|| Some(d.home_dir().to_path_buf()))
+            });
+            let path = dir.unwrap_or_default().join("cfait-tasks.csv");
+            app.csv_export_path = Some(path.to_string_lossy().to_string());
+            Task::none()
+        }
+        Message::CloseCsvExport => {
+            app.csv_export_path = None;
+            Task::none()
+        }
+        Message::CsvExportPathChanged(path) => {
+            app.csv_export_path = Some(path);
+            Task::none()
+        }
+        Message::SubmitCsvExport => {
+            if let Some(path) = app.csv_export_path.take()
+                && let Err(e) = csv::export_csv(&app.tasks, &app.calendars, Path::new(&path))
+            {
+                app.error_msg = Some(format!("Couldn't export to {}: {}", path, e));
+            }
+            Task::none()
+        }
+        Message::StartRenameCalendar(href) => {
+            let name = app
+                .calendars
+                .iter()
+                .find(|c| c.href == href)
+                .map(|c| c.name.clone())
+                .unwrap_or_default();
+            app.renaming_calendar = Some((href, name));
+            Task::none()
+        }
+        Message::RenameCalendarInput(value) => {
+            if let Some((_, name)) = &mut app.renaming_calendar {
+                *name = value;
+            }
+            Task::none()
+        }
+        Message::CancelRenameCalendar => {
+            app.renaming_calendar = None;
+            Task::none()
+        }
+        Message::ClearJournal(false) => {
+            app.journal_confirm_clear = true;
+            Task::none()
+        }
+        Message::ClearJournal(true) => {
+            app.journal_confirm_clear = false;
+            let result = Journal::clear_queue();
+            reload_journal(app);
+            match result {
+                Ok(()) => Task::perform(async { Ok::<(), String>(()) }, |_| Message::Refresh),
+                Err(e) => {
+                    app.error_msg = Some(e.to_string());
+                    Task::none()
+                }
+            }
+        }
         Message::OpenHelp => {
             app.state = AppState::Help;
             Task::none()
@@ -195,13 +432,16 @@ pub fn handle(app: &mut GuiApp, message: Message) -> Task<Message> {
                 Task::none()
             }
         }),
-        Message::CloseWindow => window::latest().then(|id| {
-            if let Some(id) = id {
-                window::close(id)
-            } else {
-                Task::none()
-            }
-        }),
+        Message::CloseWindow => {
+            save_geometry(app);
+            window::latest().then(|id| {
+                if let Some(id) = id {
+                    window::close(id)
+                } else {
+                    Task::none()
+                }
+            })
+        }
         Message::ResizeStart(direction) => {
             let dir = match direction {
                 ResizeDirection::North => window::Direction::North,
@@ -225,6 +465,40 @@ pub fn handle(app: &mut GuiApp, message: Message) -> Task<Message> {
             app.current_window_size = size;
             Task::none()
         }
+        Message::WindowMoved(point) => {
+            app.current_window_position = Some(point);
+            Task::none()
+        }
+        Message::WindowClosed => {
+            save_geometry(app);
+            Task::none()
+        }
+        Message::WindowOpened(id) => {
+            // The saved position may belong to a monitor that is no longer
+            // connected, in which case the window is not on any monitor: bring
+            // it to the one at the origin and center it there.
+            if !app.window_position_restored {
+                return Task::none();
+            }
+            let size = app.current_window_size;
+            window::monitor_size(id).then(move |monitor| {
+                if monitor.is_some() {
+                    return Task::none();
+                }
+                window::move_to(id, iced::Point::ORIGIN).chain(window::monitor_size(id).then(
+                    move |monitor| match monitor {
+                        Some(screen) => window::move_to(
+                            id,
+                            iced::Point::new(
+                                ((screen.width - size.width) / 2.0).max(0.0),
+                                ((screen.height - size.height) / 2.0).max(0.0),
+                            ),
+                        ),
+                        None => Task::none(),
+                    },
+                ))
+            })
+        }
         Message::JumpToTag(tag) => {
             app.sidebar_mode = SidebarMode::Categories;
             app.selected_categories.clear();
@@ -238,6 +512,7 @@ pub fn handle(app: &mut GuiApp, message: Message) -> Task<Message> {
                 app.hide_fully_completed_tags,
                 &app.selected_categories,
                 &app.hidden_calendars,
+                app.sort_tags_by_count,
             );
 
             if let Some(index) = all_cats.iter().position(|(t, _)| t == &tag) {
@@ -260,3 +535,53 @@ pub fn handle(app: &mut GuiApp, message: Message) -> Task<Message> {
         _ => Task::none(),
     }
 }
+
+/// Saved once when the window closes rather than on every resize or move event.
+fn save_geometry(app: &GuiApp) {
+    let geometry = WindowGeometry {
+        width: app.current_window_size.width,
+        height: app.current_window_size.height,
+        x: app.current_window_position.map(|p| p.x),
+        y: app.current_window_position.map(|p| p.y),
+    };
+    let _ = geometry.save();
+}
+
+fn reload_journal(app: &mut GuiApp) {
+    let entries = Journal::queue_summary();
+    app.unsynced_count = entries.len();
+    app.journal_entries = Some(entries);
+}
+
+/// Hands the link to the system's default handler (browser, dialer, mail client).
+fn open_url(url: &str) -> Result<(), String> {
+    let valid_scheme = url.split_once(':').is_some_and(|(scheme, rest)| {
+        !rest.is_empty()
+            && scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+            && scheme
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+    });
+    if !valid_scheme || url.chars().any(|c| c.is_whitespace() || c.is_control()) {
+        return Err(format!("Not a valid link: {}", url));
+    }
+
+    #[cfg(target_os = "windows")]
+    let mut cmd = {
+        let mut c = std::process::Command::new("rundll32");
+        c.arg("url.dll,FileProtocolHandler");
+        c
+    };
+    #[cfg(target_os = "macos")]
+    let mut cmd = std::process::Command::new("open");
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    let mut cmd = std::process::Command::new("xdg-open");
+
+    let mut child = cmd
+        .arg(url)
+        .spawn()
+        .map_err(|e| format!("Could not open {}: {}", url, e))?;
+    // Reap the opener so it doesn't linger as a zombie
+    std::thread::spawn(move || child.wait());
+    Ok(())
+}
@@ -12,7 +12,7 @@ const COL_CARD_BG: Color = Color::from_rgb(0.15, 0.15, 0.17); // Slightly lighte
 pub fn view_help() -> Element<'static, Message> {
     let title = row![
         crate::gui::icon::icon(crate::gui::icon::HELP_RHOMBUS).size(28).style(|_: &Theme| text::Style { color: Some(COL_ACCENT) }),
-        text("Syntax guide").size(28).style(|_: &Theme| text::Style { color: Some(Color::WHITE) })
+        text("Syntax guide").size(28).style(|theme: &Theme| text::Style { color: Some(theme.palette().text) })
     ]
     .spacing(15)
     .align_y(iced::Alignment::Center);
@@ -28,7 +28,13 @@ pub fn view_help() -> Element<'static, Message> {
                 entry("!1", "Priority High (1) to Low (9)", "!1, !5, !9"),
                 entry("#tag", "Add category. Use ':' for sub-tags.", "#work, #dev:backend"),
                 entry("#a=#b,#c", "Define/update alias inline.", "#groceries=#home,#shopping"),
-                entry("~30m", "Estimated Duration (m/h/d/w).", "~30m, ~1.5h, ~2d"),
+                entry("Tab", "Complete the #tag being typed from existing tags.", "#gro + Tab -> #groceries"),
+                entry("~30m", "Estimated Duration (m/h/d/w), units can be combined.", "~30m, ~1h30m, ~2d4h, ~90"),
+                entry("~%50", "Progress in percent (0-100).", "~%25, ~%80"),
+                entry("url:link", "Attach a link, clickable in the details.", "url:https://example.com"),
+                entry("color:name", "Flag with a color (or pick one in the details).", "color:red, color:green"),
+                entry("@@person", "Waiting on someone (saved as an attendee).", "@@alice, @@bob@example.com"),
+                entry("Outline", "Paste several lines: indented ones become subtasks.", "Trip, then \"  Pack\" below it"),
             ]
         ),
 
@@ -37,8 +43,9 @@ pub fn view_help() -> Element<'static, Message> {
             "Timeline & Scheduling", 
             crate::gui::icon::CALENDAR,
             vec![
-                entry("@date", "Due Date. Deadline for the task.", "@tomorrow, @2025-12-31"),
+                entry("@date", "Due Date. Deadline for the task, with an optional time.", "@tomorrow, @2025-12-31, @tomorrow 14:30"),
                 entry("^date", "Start Date. Hides/sorts lower until date.", "^next week, ^2025-01-01"),
+                entry("*15m", "Reminder before the due date (repeatable).", "*15m, *1h, *1d"),
                 entry("Offsets", "Add time from today.", "1d (1 day), 2w (2 weeks), 3mo (3 months), 4y (4 years)"),
                 entry("Keywords", "Relative dates supported.", "today, tomorrow, next week, next year"),
 
@@ -53,6 +60,7 @@ pub fn view_help() -> Element<'static, Message> {
             vec![
                 entry("@daily", "Quick presets.", "@daily, @weekly, @monthly, @yearly"),
                 entry("@every X", "Custom intervals.", "@every 3 days, @every 2 weeks"),
+                entry("rec:RULE", "Any iCalendar RRULE (or use the Repeat builder when editing).", "rec:FREQ=WEEKLY;BYDAY=MO,WE"),
                 entry("Note", "Recurrence calculates next date based on Start Date if present, else Due Date.", ""),
             ]
         ),
@@ -65,6 +73,7 @@ pub fn view_help() -> Element<'static, Message> {
                 entry("text", "Matches summary or description.", "buy cat food"),
                 entry("#tag", "Filter by specific tag.", "#gardening"),
                 entry("is:status", "Filter by state.", "is:done, is:ongoing, is:active"),
+                entry("@@person", "Tasks waiting on someone.", "@@alice"),
                 entry("Operators", "Compare values (<, >, <=, >=).", "~<20m (less than 20 minutes), <!4 (urgent tasks)"),
                 entry("  Dates", "Filter by timeframe.", "@<today (Overdue), ^>tomorrow"),
                 entry("  Priority", "Filter by priority range.", "!<3 (High prio), !>=5"),
@@ -1,25 +1,42 @@
 // File: src/gui/view/mod.rs
+use chrono::Weekday;
 use std::time::Duration;
 pub mod help;
 pub mod settings;
 pub mod sidebar;
 pub mod task_row;
 
+use crate::cache::{STALE_AFTER, format_age};
 use crate::gui::icon;
 use crate::gui::message::Message;
 use crate::gui::state::{AppState, GuiApp, ResizeDirection, SidebarMode};
+use crate::gui::update::common::tag_candidates;
 use crate::gui::view::help::view_help;
 use crate::gui::view::settings::view_settings;
 use crate::gui::view::sidebar::{view_sidebar_calendars, view_sidebar_categories};
 use crate::gui::view::task_row::view_task_row;
-use crate::storage::LOCAL_CALENDAR_HREF;
+use crate::journal::MergeConflict;
+use crate::model::completion;
+use crate::model::parser::{self, format_mins};
+use crate::model::recurrence::{Frequency, Recurrence};
+use crate::storage::LocalStorage;
+use crate::store::{DueBucket, REVIEW_DAYS, UndoEntry, day_label, sum_estimated_duration};
 
 use iced::widget::scrollable::{Direction, Scrollbar};
 use iced::widget::{
-    MouseArea, Space, column, container, row, scrollable, stack, svg, text, tooltip,
+    MouseArea, Space, column, container, opaque, row, scrollable, stack, svg, text, tooltip,
 };
 use iced::{Color, Element, Length, Theme, mouse};
 
+/// Grey for secondary text, darker on light themes so it stays readable.
+pub fn muted_color(app: &GuiApp) -> Color {
+    if app.is_dark_theme() {
+        Color::from_rgb(0.6, 0.6, 0.6)
+    } else {
+        Color::from_rgb(0.4, 0.4, 0.4)
+    }
+}
+
 /// Shared style for tooltips with slight transparency
 pub fn tooltip_style(theme: &Theme) -> container::Style {
     let palette = theme.extended_palette();
@@ -44,12 +61,18 @@ pub fn tooltip_style(theme: &Theme) -> container::Style {
 
 pub fn root_view(app: &GuiApp) -> Element<'_, Message> {
     match app.state {
-        AppState::Loading => container(text("Loading...").size(30))
-            .width(Length::Fill)
-            .height(Length::Fill)
-            .center_x(Length::Fill)
-            .center_y(Length::Fill)
-            .into(),
+        AppState::Loading => container(
+            text(match app.journal_sync_progress {
+                Some((current, total)) => format!("Syncing {}/{}...", current, total),
+                None => "Loading...".to_string(),
+            })
+            .size(30),
+        )
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .center_x(Length::Fill)
+        .center_y(Length::Fill)
+        .into(),
         AppState::Onboarding | AppState::Settings => view_settings(app),
         AppState::Help => view_help(),
         AppState::Active => {
@@ -74,6 +97,7 @@ pub fn root_view(app: &GuiApp) -> Element<'_, Message> {
                             app.hide_fully_completed_tags,
                             &app.selected_categories,
                             &app.hidden_calendars,
+                            app.sort_tags_by_count,
                         )
                         .len() as f32
                         * ITEM_HEIGHT_TAG
@@ -83,13 +107,23 @@ pub fn root_view(app: &GuiApp) -> Element<'_, Message> {
             let available_height = app.current_window_size.height - SIDEBAR_CHROME;
             let show_logo_in_sidebar = (available_height - content_height) > LOGO_SPACE_REQUIRED;
 
-            let content_layout = row![
-                view_sidebar(app, show_logo_in_sidebar),
-                iced::widget::rule::vertical(1),
-                container(view_main_content(app, !show_logo_in_sidebar))
-                    .width(Length::Fill)
-                    .center_x(Length::Fill)
-            ];
+            let content_layout = if app.focus_mode {
+                row![
+                    view_sidebar_strip(),
+                    iced::widget::rule::vertical(1),
+                    container(view_main_content(app, true))
+                        .width(Length::Fill)
+                        .center_x(Length::Fill)
+                ]
+            } else {
+                row![
+                    view_sidebar(app, show_logo_in_sidebar),
+                    iced::widget::rule::vertical(1),
+                    container(view_main_content(app, !show_logo_in_sidebar))
+                        .width(Length::Fill)
+                        .center_x(Length::Fill)
+                ]
+            };
 
             // ... [Resize Grips and Stack: No Change] ...
             let main_container = container(content_layout)
@@ -163,7 +197,7 @@ pub fn root_view(app: &GuiApp) -> Element<'_, Message> {
             .on_press(Message::ResizeStart(ResizeDirection::SouthEast))
             .interaction(mouse::Interaction::ResizingDiagonallyDown);
 
-            stack![
+            let mut layers = stack![
                 main_container,
                 container(n_grip)
                     .width(Length::Fill)
@@ -201,12 +235,242 @@ pub fn root_view(app: &GuiApp) -> Element<'_, Message> {
                     .height(Length::Fill)
                     .align_x(iced::alignment::Horizontal::Right)
                     .align_y(iced::alignment::Vertical::Bottom),
-            ]
-            .into()
+            ];
+            if let Some(conflict) = app.conflicts.first() {
+                layers = layers.push(view_conflict(app, conflict));
+            } else if let Some(uid) = &app.pending_delete {
+                layers = layers.push(view_confirm_delete(app, uid));
+            } else if let Some(entries) = &app.journal_entries {
+                layers = layers.push(view_journal(app, entries));
+            } else if let Some(name) = &app.new_calendar_name {
+                layers = layers.push(view_new_calendar(name));
+            } else if let Some(path) = &app.csv_export_path {
+                layers = layers.push(view_csv_export(path, app.tasks.len()));
+            }
+            layers.into()
         }
     }
 }
 
+/// Modal asking which version wins for each field of an update the server rejected.
+fn view_conflict<'a>(app: &'a GuiApp, conflict: &'a MergeConflict) -> Element<'a, Message> {
+    let mut content = column![
+        text(format!("Sync conflict: {}", conflict.local.summary)).size(20),
+        text("These fields were changed both here and on the server. Pick the version to keep.")
+            .size(14),
+    ]
+    .spacing(12);
+
+    for diff in &conflict.fields {
+        let keep_local = app.conflict_keep_local.contains(&diff.field);
+        content = content.push(
+            column![
+                text(&diff.field).size(14),
+                row![
+                    conflict_side("Mine", &diff.local, keep_local, &diff.field),
+                    conflict_side("Server", &diff.server, !keep_local, &diff.field),
+                ]
+                .spacing(10),
+            ]
+            .spacing(4),
+        );
+    }
+
+    content = content.push(
+        row![
+            Space::new().width(Length::Fill),
+            iced::widget::button(text("Keep both").size(14))
+                .style(iced::widget::button::secondary)
+                .on_press(Message::ResolveConflict(false)),
+            iced::widget::button(text("Apply").size(14))
+                .style(iced::widget::button::primary)
+                .on_press(Message::ResolveConflict(true)),
+        ]
+        .spacing(10),
+    );
+
+    modal(scrollable(content))
+}
+
+fn view_confirm_delete<'a>(app: &'a GuiApp, uid: &str) -> Element<'a, Message> {
+    let summary = app
+        .tasks
+        .iter()
+        .find(|t| t.uid == uid)
+        .map(|t| t.summary.as_str())
+        .unwrap_or_default();
+    let content = column![
+        text(format!("Delete '{}'?", summary)).size(20),
+        row![
+            Space::new().width(Length::Fill),
+            iced::widget::button(text("Cancel").size(14))
+                .style(iced::widget::button::secondary)
+                .on_press(Message::ConfirmDelete(false)),
+            iced::widget::button(text("Delete").size(14))
+                .style(iced::widget::button::danger)
+                .on_press(Message::ConfirmDelete(true)),
+        ]
+        .spacing(10),
+    ]
+    .spacing(20);
+
+    modal(content)
+}
+
+/// The offline sync queue, with a way out when the server keeps rejecting an action.
+fn view_journal<'a>(app: &'a GuiApp, entries: &'a [String]) -> Element<'a, Message> {
+    let mut list = column![].spacing(6);
+    if entries.is_empty() {
+        list = list.push(text("Nothing waiting to be synced.").size(14));
+    }
+    for (i, entry) in entries.iter().enumerate() {
+        list = list.push(
+            row![
+                text(entry).size(14).width(Length::Fill),
+                iced::widget::button(text("Drop").size(12))
+                    .style(iced::widget::button::secondary)
+                    .on_press(Message::DropJournalEntry(i)),
+            ]
+            .spacing(10)
+            .align_y(iced::Alignment::Center),
+        );
+    }
+
+    let clear_btn = if app.journal_confirm_clear {
+        iced::widget::button(text("Really drop all unsynced changes?").size(14))
+            .style(iced::widget::button::danger)
+            .on_press(Message::ClearJournal(true))
+    } else {
+        iced::widget::button(text("Clear all").size(14))
+            .style(iced::widget::button::secondary)
+            .on_press_maybe((!entries.is_empty()).then_some(Message::ClearJournal(false)))
+    };
+
+    let content = column![
+        text("Sync queue").size(20),
+        scrollable(list).height(Length::Shrink),
+        row![
+            clear_btn,
+            Space::new().width(Length::Fill),
+            iced::widget::button(text("Sync now").size(14))
+                .style(iced::widget::button::secondary)
+                .on_press_maybe(
+                    (!entries.is_empty() && app.journal_sync_progress.is_none())
+                        .then_some(Message::SyncJournal)
+                ),
+            iced::widget::button(text("Close").size(14))
+                .style(iced::widget::button::primary)
+                .on_press(Message::CloseJournal),
+        ]
+        .spacing(10),
+    ]
+    .spacing(15);
+
+    modal(content)
+}
+
+fn view_new_calendar(name: &str) -> Element<'_, Message> {
+    let can_submit = !name.trim().is_empty();
+    let content = column![
+        text("New calendar").size(20),
+        iced::widget::text_input("Calendar name", name)
+            .on_input(Message::NewCalendarNameChanged)
+            .on_submit_maybe(can_submit.then_some(Message::SubmitNewCalendar))
+            .padding(8),
+        row![
+            Space::new().width(Length::Fill),
+            iced::widget::button(text("Cancel").size(14))
+                .style(iced::widget::button::secondary)
+                .on_press(Message::CloseNewCalendar),
+            iced::widget::button(text("Create").size(14))
+                .style(iced::widget::button::primary)
+                .on_press_maybe(can_submit.then_some(Message::SubmitNewCalendar)),
+        ]
+        .spacing(10),
+    ]
+    .spacing(15);
+
+    modal(content)
+}
+
+fn view_csv_export(path: &str, count: usize) -> Element<'_, Message> {
+    let can_submit = !path.trim().is_empty();
+    let content = column![
+        text("Export to CSV").size(20),
+        text(format!(
+            "Saves the {} tasks in the current view (uid, summary, status, priority, due, \
+             categories, calendar).",
+            count
+        ))
+        .size(14),
+        iced::widget::text_input("File path", path)
+            .on_input(Message::CsvExportPathChanged)
+            .on_submit_maybe(can_submit.then_some(Message::SubmitCsvExport))
+            .padding(8),
+        row![
+            Space::new().width(Length::Fill),
+            iced::widget::button(text("Cancel").size(14))
+                .style(iced::widget::button::secondary)
+                .on_press(Message::CloseCsvExport),
+            iced::widget::button(text("Export").size(14))
+                .style(iced::widget::button::primary)
+                .on_press_maybe(can_submit.then_some(Message::SubmitCsvExport)),
+        ]
+        .spacing(10),
+    ]
+    .spacing(15);
+
+    modal(content)
+}
+
+/// Centers `content` in a card over a dimmed, click-blocking backdrop.
+fn modal<'a>(content: impl Into<Element<'a, Message>>) -> Element<'a, Message> {
+    let card = container(content)
+        .padding(20)
+        .max_width(600)
+        .style(|theme: &Theme| {
+            let palette = theme.extended_palette();
+            container::Style {
+                background: Some(palette.background.base.color.into()),
+                border: iced::Border {
+                    radius: 8.0.into(),
+                    width: 1.0,
+                    color: palette.background.strong.color,
+                },
+                ..Default::default()
+            }
+        });
+
+    opaque(
+        container(card)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .center_x(Length::Fill)
+            .center_y(Length::Fill)
+            .style(|_| container::Style {
+                background: Some(Color::from_rgba(0.0, 0.0, 0.0, 0.6).into()),
+                ..Default::default()
+            }),
+    )
+}
+
+/// One of the two versions of a conflicting field; pressing the unpicked one switches sides.
+fn conflict_side<'a>(
+    label: &'a str,
+    value: &'a str,
+    chosen: bool,
+    field: &'a str,
+) -> Element<'a, Message> {
+    let btn = iced::widget::button(column![text(label).size(12), text(value).size(14)].spacing(2))
+        .width(Length::Fill)
+        .on_press_maybe((!chosen).then(|| Message::ToggleConflictField(field.to_string())));
+    if chosen {
+        btn.style(iced::widget::button::primary).into()
+    } else {
+        btn.style(iced::widget::button::secondary).into()
+    }
+}
+
 fn view_sidebar(app: &GuiApp, show_logo: bool) -> Element<'_, Message> {
     let active_tab_style =
         |_theme: &Theme, status: iced::widget::button::Status| -> iced::widget::button::Style {
@@ -257,7 +521,23 @@ fn view_sidebar(app: &GuiApp, show_logo: bool) -> Element<'_, Message> {
     })
     .on_press(Message::SidebarModeChanged(SidebarMode::Categories));
 
-    let tabs = row![btn_cals, btn_tags].spacing(5);
+    let hide_btn = iced::widget::button(icon::icon(icon::SIDEBAR_HIDE).size(14))
+        .padding(5)
+        .style(iced::widget::button::text)
+        .on_press(Message::ToggleFocusMode);
+
+    let tabs = row![
+        btn_cals,
+        btn_tags,
+        tooltip(
+            hide_btn,
+            text("Hide the sidebar (Shift+F)").size(12),
+            tooltip::Position::Bottom
+        )
+        .style(tooltip_style)
+        .delay(Duration::from_millis(700))
+    ]
+    .spacing(5);
 
     let content = match app.sidebar_mode {
         SidebarMode::Calendars => view_sidebar_calendars(app),
@@ -287,6 +567,17 @@ fn view_sidebar(app: &GuiApp, show_logo: bool) -> Element<'_, Message> {
     .style(iced::widget::button::secondary)
     .on_press(Message::OpenHelp);
 
+    let csv_btn = iced::widget::button(
+        container(icon::icon(icon::EXPORT).size(20))
+            .center_x(Length::Fill)
+            .center_y(Length::Fill),
+    )
+    .padding(0)
+    .height(Length::Fixed(40.0))
+    .width(Length::Fixed(50.0))
+    .style(iced::widget::button::secondary)
+    .on_press(Message::OpenCsvExport);
+
     // Apply tooltip_style
     let footer = row![
         tooltip(
@@ -296,17 +587,31 @@ fn view_sidebar(app: &GuiApp, show_logo: bool) -> Element<'_, Message> {
         )
         .style(tooltip_style)
         .delay(Duration::from_millis(700)),
+        tooltip(
+            csv_btn,
+            text("Export the view to CSV").size(12),
+            tooltip::Position::Top
+        )
+        .style(tooltip_style)
+        .delay(Duration::from_millis(700)),
         tooltip(help_btn, text("Help").size(12), tooltip::Position::Top)
             .style(tooltip_style)
             .delay(Duration::from_millis(700))
     ]
     .spacing(5);
 
+    let someday_toggle = iced::widget::toggler(!app.hide_deferred)
+        .label("Show someday tasks")
+        .text_size(12)
+        .size(16)
+        .on_toggle(|show| Message::ToggleHideDeferred(!show));
+
     let mut sidebar_col = column![
         tabs,
         scrollable(content)
             .height(Length::Fill)
-            .id(app.sidebar_scrollable_id.clone())
+            .id(app.sidebar_scrollable_id.clone()),
+        someday_toggle
     ];
 
     if show_logo {
@@ -342,9 +647,37 @@ fn view_sidebar(app: &GuiApp, show_logo: bool) -> Element<'_, Message> {
         .into()
 }
 
+/// Thin strip standing in for the sidebar in focus mode; clicking it brings it back.
+fn view_sidebar_strip<'a>() -> Element<'a, Message> {
+    let strip = iced::widget::button(
+        container(icon::icon(icon::SIDEBAR_SHOW).size(12))
+            .center_x(Length::Fill)
+            .center_y(Length::Fill),
+    )
+    .padding(0)
+    .width(Length::Fixed(16.0))
+    .height(Length::Fill)
+    .style(iced::widget::button::text)
+    .on_press(Message::ToggleFocusMode);
+    tooltip(
+        strip,
+        text("Show the sidebar (Shift+F)").size(12),
+        tooltip::Position::Right,
+    )
+    .style(tooltip_style)
+    .delay(Duration::from_millis(700))
+    .into()
+}
+
 fn view_main_content(app: &GuiApp, show_logo: bool) -> Element<'_, Message> {
-    let title_text = if app.loading {
+    let title_text = if let Some((done, total)) = app.fetch_progress.filter(|_| app.loading) {
+        format!("Synced {}/{} calendars...", done, total)
+    } else if app.loading {
         "Loading...".to_string()
+    } else if app.review_view {
+        format!("Completed in the last {} days", REVIEW_DAYS)
+    } else if app.agenda_view {
+        "Agenda".to_string()
     } else if app.active_cal_href.is_none() {
         if app.selected_categories.is_empty() {
             "All Tasks".to_string()
@@ -361,6 +694,18 @@ fn view_main_content(app: &GuiApp, show_logo: bool) -> Element<'_, Message> {
 
     let task_count = app.tasks.len();
     let mut subtitle = format!("{} Tasks", task_count);
+    let estimated = sum_estimated_duration(&app.tasks);
+    if estimated > 0 {
+        subtitle.push_str(&format!(" | Est: {}", format_mins(estimated)));
+    }
+    if app.today_view {
+        subtitle.push_str(" | Today");
+    }
+    match app.priority_filter {
+        Some(1) => subtitle.push_str(" | Priority 1"),
+        Some(max) => subtitle.push_str(&format!(" | Priority 1-{}", max)),
+        None => {}
+    }
 
     if !app.search_value.is_empty() {
         subtitle.push_str(&format!(" | Search: '{}'", app.search_value));
@@ -392,21 +737,79 @@ fn view_main_content(app: &GuiApp, show_logo: bool) -> Element<'_, Message> {
         .spacing(10)
         .align_y(iced::Alignment::Center);
 
-    if app.unsynced_changes {
+    if app.unsynced_count > 0 || app.journal_sync_progress.is_some() {
+        let badge = match app.journal_sync_progress {
+            Some((current, total)) => format!("Syncing {}/{}", current, total),
+            None => format!("{} unsynced", app.unsynced_count),
+        };
         left_section = left_section.push(
-            container(text("Unsynced").size(10).color(Color::WHITE))
-                .style(|_| container::Style {
-                    background: Some(Color::from_rgb(0.8, 0.5, 0.0).into()),
-                    border: iced::Border {
-                        radius: 4.0.into(),
+            iced::widget::button(
+                container(text(badge).size(10).color(Color::WHITE))
+                    .style(|_| container::Style {
+                        background: Some(Color::from_rgb(0.8, 0.5, 0.0).into()),
+                        border: iced::Border {
+                            radius: 4.0.into(),
+                            ..Default::default()
+                        },
                         ..Default::default()
-                    },
-                    ..Default::default()
-                })
-                .padding(3),
+                    })
+                    .padding(3),
+            )
+            .style(iced::widget::button::text)
+            .padding(0)
+            .on_press_maybe(
+                app.journal_sync_progress
+                    .is_none()
+                    .then_some(Message::SyncJournal),
+            ),
+        );
+        left_section = left_section.push(
+            tooltip(
+                iced::widget::button(text("Queue").size(10))
+                    .style(iced::widget::button::text)
+                    .padding(0)
+                    .on_press(Message::OpenJournal),
+                text("Show the pending changes").size(12),
+                tooltip::Position::Bottom,
+            )
+            .style(tooltip_style),
         );
     }
 
+    if let Some(timer) = &app.timer {
+        let clock = timer.display(chrono::Utc::now());
+        left_section = left_section.push(
+            tooltip(
+                iced::widget::button(
+                    row![
+                        icon::icon(icon::PAUSE).size(12),
+                        text(format!("{} {}", timer.summary, clock)).size(12)
+                    ]
+                    .spacing(4)
+                    .align_y(iced::Alignment::Center),
+                )
+                .style(iced::widget::button::success)
+                .padding(4)
+                .on_press(Message::StopTimer),
+                text("Stop the timer and log the time spent").size(12),
+                tooltip::Position::Bottom,
+            )
+            .style(tooltip_style)
+            .delay(Duration::from_millis(700)),
+        );
+    }
+
+    if let Some(href) = &app.active_cal_href
+        && let Some(age) = app.store.sync_age(href)
+    {
+        let color = if age > STALE_AFTER {
+            Color::from_rgb(0.9, 0.5, 0.2)
+        } else {
+            muted_color(app)
+        };
+        left_section = left_section.push(text(format_age(age)).size(10).color(color));
+    }
+
     let refresh_btn = iced::widget::button(icon::icon(icon::REFRESH).size(16))
         .style(iced::widget::button::text)
         .padding(4)
@@ -423,9 +826,127 @@ fn view_main_content(app: &GuiApp, show_logo: bool) -> Element<'_, Message> {
         .delay(Duration::from_millis(700)),
     );
 
-    let subtitle_text = text(subtitle)
-        .size(14)
-        .color(Color::from_rgb(0.6, 0.6, 0.6));
+    let agenda_btn = iced::widget::button(icon::icon(icon::CALENDAR).size(16))
+        .style(if app.agenda_view {
+            iced::widget::button::primary
+        } else {
+            iced::widget::button::text
+        })
+        .padding(4)
+        .on_press(Message::ToggleAgenda(!app.agenda_view));
+
+    let review_btn = iced::widget::button(icon::icon(icon::CHECK).size(16))
+        .style(if app.review_view {
+            iced::widget::button::primary
+        } else {
+            iced::widget::button::text
+        })
+        .padding(4)
+        .on_press(Message::ToggleReview(!app.review_view));
+
+    let manual_btn = iced::widget::button(icon::icon(icon::DRAG_HANDLE).size(16))
+        .style(if app.manual_order {
+            iced::widget::button::primary
+        } else {
+            iced::widget::button::text
+        })
+        .padding(4)
+        .on_press(Message::ToggleManualOrder(!app.manual_order));
+
+    let today_btn = iced::widget::button(text("Today").size(12))
+        .style(if app.today_view {
+            iced::widget::button::primary
+        } else {
+            iced::widget::button::text
+        })
+        .padding(4)
+        .on_press(Message::ToggleToday(!app.today_view));
+
+    left_section = left_section.push(
+        tooltip(
+            today_btn,
+            text("Only tasks due today or overdue").size(12),
+            tooltip::Position::Bottom,
+        )
+        .style(tooltip_style)
+        .delay(Duration::from_millis(700)),
+    );
+
+    // Priority chips: clicking the active one clears the filter
+    let mut priority_chips = row![].spacing(2);
+    for max in 1..=3u8 {
+        let active = app.priority_filter == Some(max);
+        priority_chips = priority_chips.push(
+            iced::widget::button(text(format!("!{}", max)).size(12))
+                .style(if active {
+                    iced::widget::button::primary
+                } else {
+                    iced::widget::button::text
+                })
+                .padding(4)
+                .on_press(Message::SetPriorityFilter(if active {
+                    None
+                } else {
+                    Some(max)
+                })),
+        );
+    }
+    left_section = left_section.push(
+        tooltip(
+            priority_chips,
+            text("Only tasks with this priority or higher").size(12),
+            tooltip::Position::Bottom,
+        )
+        .style(tooltip_style)
+        .delay(Duration::from_millis(700)),
+    );
+
+    left_section = left_section.push(
+        tooltip(
+            manual_btn,
+            text(if app.manual_order {
+                "Back to automatic order"
+            } else {
+                "Manual order: drag tasks by their handle"
+            })
+            .size(12),
+            tooltip::Position::Bottom,
+        )
+        .style(tooltip_style)
+        .delay(Duration::from_millis(700)),
+    );
+
+    left_section = left_section.push(
+        tooltip(
+            agenda_btn,
+            text(if app.agenda_view {
+                "Back to list view"
+            } else {
+                "Agenda: all calendars by due date"
+            })
+            .size(12),
+            tooltip::Position::Bottom,
+        )
+        .style(tooltip_style)
+        .delay(Duration::from_millis(700)),
+    );
+
+    left_section = left_section.push(
+        tooltip(
+            review_btn,
+            text(if app.review_view {
+                "Back to list view"
+            } else {
+                "Review: what was completed this past week"
+            })
+            .size(12),
+            tooltip::Position::Bottom,
+        )
+        .style(tooltip_style)
+        .delay(Duration::from_millis(700)),
+    );
+
+    let subtitle_text = text(subtitle).size(14).color(muted_color(app));
     let middle_container = container(subtitle_text)
         .width(Length::Fill)
         .height(Length::Shrink)
@@ -467,11 +988,19 @@ fn view_main_content(app: &GuiApp, show_logo: bool) -> Element<'_, Message> {
     let header_drag_area = MouseArea::new(header_row).on_press(Message::WindowDragged);
 
     let mut export_ui: Element<'_, Message> = row![].into();
-    if app.active_cal_href.as_deref() == Some(LOCAL_CALENDAR_HREF) {
+    if app
+        .active_cal_href
+        .as_deref()
+        .is_some_and(LocalStorage::is_local)
+    {
         let targets: Vec<_> = app
             .calendars
             .iter()
-            .filter(|c| c.href != LOCAL_CALENDAR_HREF && !app.disabled_calendars.contains(&c.href))
+            .filter(|c| {
+                !LocalStorage::is_local(&c.href)
+                    && !c.read_only
+                    && !app.disabled_calendars.contains(&c.href)
+            })
             .collect();
         if !targets.is_empty() {
             let mut row = row![
@@ -550,22 +1079,98 @@ fn view_main_content(app: &GuiApp, show_logo: bool) -> Element<'_, Message> {
         );
     }
 
-    let tasks_view = column(
-        app.tasks
+    if let Some(entry) = &app.last_action {
+        let label = match entry {
+            UndoEntry::Deleted(t) => format!("Deleted '{}'", t.summary),
+            UndoEntry::StatusChanged { .. } => "Status changed".to_string(),
+        };
+        let undo_content = row![
+            text(label).size(14).width(Length::Fill),
+            iced::widget::button(text("Undo").size(14))
+                .style(iced::widget::button::text)
+                .padding(2)
+                .on_press(Message::Undo),
+            iced::widget::button(icon::icon(icon::CROSS).size(14))
+                .style(iced::widget::button::text)
+                .padding(2)
+                .on_press(Message::DismissUndo)
+        ]
+        .spacing(5)
+        .align_y(iced::Alignment::Center);
+        main_col = main_col.push(
+            container(undo_content)
+                .width(Length::Fill)
+                .padding(5)
+                .style(|theme: &Theme| container::Style {
+                    background: Some(theme.extended_palette().background.weak.color.into()),
+                    ..Default::default()
+                }),
+        );
+    }
+
+    let mut task_rows: Vec<Element<'_, Message>> = Vec::with_capacity(app.tasks.len());
+    if app.review_view && app.tasks.is_empty() && !app.loading {
+        task_rows.push(
+            container(
+                text("Nothing completed recently")
+                    .size(14)
+                    .color(muted_color(app)),
+            )
+            .padding(10)
+            .into(),
+        );
+    }
+    let today = chrono::Local::now().date_naive();
+    for (real_index, task) in app.tasks.iter().enumerate() {
+        let agenda_bucket = app
+            .agenda_sections
             .iter()
-            .enumerate()
-            .map(|(real_index, task)| view_task_row(app, real_index, task))
-            .collect::<Vec<_>>(),
-    )
-    .spacing(1);
-    main_col = main_col.push(
-        scrollable(tasks_view)
-            .height(Length::Fill)
-            .id(app.scrollable_id.clone())
-            .direction(Direction::Vertical(
-                Scrollbar::new().width(10).scroller_width(10).margin(0),
-            )),
-    );
+            .find(|(i, _)| *i == real_index)
+            .map(|(_, bucket)| *bucket);
+        if agenda_bucket == Some(DueBucket::NoDate) {
+            task_rows.push(no_date_header(app));
+        } else if let Some(bucket) = agenda_bucket {
+            task_rows.push(section_header(bucket.label().to_string()).into());
+        } else if let Some((_, day)) = app.review_sections.iter().find(|(i, _)| *i == real_index) {
+            task_rows.push(section_header(day_label(*day, today)).into());
+        }
+        let row = view_task_row(app, real_index, task);
+        if app.dragging.is_some() {
+            task_rows.push(
+                MouseArea::new(row)
+                    .on_enter(Message::DragOver(task.uid.clone()))
+                    .into(),
+            );
+        } else if app.compact_list {
+            task_rows.push(
+                MouseArea::new(row)
+                    .on_enter(Message::HoverTask(task.uid.clone()))
+                    .into(),
+            );
+        } else {
+            task_rows.push(row);
+        }
+    }
+    if app.no_date_collapsed && app.undated_count > 0 {
+        task_rows.push(no_date_header(app));
+    }
+    let tasks_view = column(task_rows).spacing(1);
+    let tasks_scroll = scrollable(tasks_view)
+        .height(Length::Fill)
+        .id(app.scrollable_id.clone())
+        .direction(Direction::Vertical(
+            Scrollbar::new().width(10).scroller_width(10).margin(0),
+        ));
+    // Dropping anywhere over the list ends the drag (on the last row hovered)
+    main_col = main_col.push(if app.dragging.is_some() {
+        Element::from(
+            MouseArea::new(tasks_scroll)
+                .interaction(iced::mouse::Interaction::Grabbing)
+                .on_release(Message::DragEnd),
+        )
+    } else {
+        tasks_scroll.into()
+    });
 
     container(main_col)
         .width(Length::Fill)
@@ -577,6 +1182,133 @@ fn view_main_content(app: &GuiApp, show_logo: bool) -> Element<'_, Message> {
         .into()
 }
 
+/// Title of a section of the agenda or review list.
+fn section_header<'a>(label: String) -> iced::widget::Container<'a, Message> {
+    container(text(label).size(14).color(Color::from_rgb(0.4, 0.7, 1.0))).padding(iced::Padding {
+        top: 8.0,
+        bottom: 2.0,
+        left: 4.0,
+        right: 0.0,
+    })
+}
+
+/// Header of the "No Date" section with its task count, folding it when clicked.
+fn no_date_header(app: &GuiApp) -> Element<'_, Message> {
+    let arrow = if app.no_date_collapsed { "▸" } else { "▾" };
+    let label = format!(
+        "{} {} ({})",
+        arrow,
+        DueBucket::NoDate.label(),
+        app.undated_count
+    );
+    iced::widget::button(section_header(label))
+        .padding(0)
+        .style(iced::widget::button::text)
+        .on_press(Message::ToggleNoDate)
+        .into()
+}
+
+/// Frequency, interval and weekdays of the title's recurrence, with a summary.
+fn recurrence_builder(app: &GuiApp) -> Element<'_, Message> {
+    let muted = muted_color(app);
+    let rule = parser::input_recurrence(&app.input_value);
+    let rec = rule.as_deref().and_then(Recurrence::from_rrule);
+
+    let mut controls = row![
+        text("Repeat:").size(12).color(muted),
+        iced::widget::pick_list(
+            Frequency::ALL,
+            rec.as_ref().map(|r| r.freq),
+            Message::RecurrenceFreqPicked
+        )
+        .placeholder("Never")
+        .text_size(12)
+        .padding(4)
+    ]
+    .spacing(5)
+    .align_y(iced::Alignment::Center);
+
+    if let Some(rec) = &rec {
+        controls = controls.push(text("every").size(12).color(muted));
+        controls = controls.push(
+            iced::widget::button(text("-").size(12))
+                .style(iced::widget::button::secondary)
+                .padding([2, 7])
+                .on_press_maybe(
+                    (rec.interval > 1)
+                        .then(|| Message::RecurrenceIntervalChanged(rec.interval - 1)),
+                ),
+        );
+        controls = controls.push(text(rec.interval.to_string()).size(12));
+        controls = controls.push(
+            iced::widget::button(text("+").size(12))
+                .style(iced::widget::button::secondary)
+                .padding([2, 7])
+                .on_press(Message::RecurrenceIntervalChanged(rec.interval + 1)),
+        );
+        if rec.freq == Frequency::Weekly {
+            for day in [
+                Weekday::Mon,
+                Weekday::Tue,
+                Weekday::Wed,
+                Weekday::Thu,
+                Weekday::Fri,
+                Weekday::Sat,
+                Weekday::Sun,
+            ] {
+                controls = controls.push(
+                    iced::widget::button(text(day.to_string()[..2].to_string()).size(12))
+                        .style(if rec.weekdays.contains(&day) {
+                            iced::widget::button::primary
+                        } else {
+                            iced::widget::button::secondary
+                        })
+                        .padding([2, 5])
+                        .on_press(Message::RecurrenceWeekdayToggled(day)),
+                );
+            }
+        }
+    }
+    if rule.is_some() {
+        controls = controls.push(
+            iced::widget::button(text("Clear").size(12))
+                .style(iced::widget::button::text)
+                .padding([2, 5])
+                .on_press(Message::ClearRecurrence),
+        );
+    }
+
+    let summary = match (&rec, &rule) {
+        (Some(rec), _) => rec.summary(),
+        (None, Some(rule)) => format!("Custom rule: {}", rule),
+        (None, None) => String::new(),
+    };
+    column![controls, text(summary).size(12).color(muted)]
+        .spacing(3)
+        .into()
+}
+
+/// Known tags matching the `#tag` being typed, shown under the input.
+fn tag_suggestions(app: &GuiApp) -> Element<'_, Message> {
+    let cursor = app.input_value.chars().count();
+    let suggestions = completion::suggestions(&app.input_value, cursor, &tag_candidates(app));
+    if suggestions.is_empty() {
+        return Space::new().height(0).into();
+    }
+    let mut list = row![text("Tab:").size(12).color(muted_color(app))]
+        .spacing(5)
+        .align_y(iced::Alignment::Center);
+    for tag in suggestions.into_iter().take(8) {
+        list = list.push(
+            iced::widget::button(text(format!("#{}", tag)).size(12))
+                .style(iced::widget::button::secondary)
+                .padding(4)
+                .on_press(Message::CompleteTag(tag)),
+        );
+    }
+    list.into()
+}
+
 fn view_input_area(app: &GuiApp) -> Element<'_, Message> {
     let input_placeholder = if app.editing_uid.is_some() {
         "Edit Title...".to_string()
@@ -601,6 +1333,7 @@ fn view_input_area(app: &GuiApp) -> Element<'_, Message> {
 
     let input_title = iced::widget::text_input(&input_placeholder, &app.input_value)
         .on_input(Message::InputChanged)
+        .on_paste_maybe(app.editing_uid.is_none().then_some(Message::InputPasted))
         .on_submit(Message::SubmitTask)
         .padding(10)
         .size(20);
@@ -610,7 +1343,11 @@ fn view_input_area(app: &GuiApp) -> Element<'_, Message> {
             .placeholder("Notes...")
             .on_action(Message::DescriptionChanged)
             .padding(10)
-            .height(Length::Fixed(100.0));
+            .wrapping(iced::widget::text::Wrapping::WordOrGlyph)
+            // Grows with long notes, scrolls past 300px
+            .height(Length::Shrink)
+            .min_height(100.0)
+            .max_height(300.0);
         let cancel_btn = iced::widget::button(text("Cancel").size(16))
             .style(iced::widget::button::secondary)
             .on_press(Message::CancelEdit);
@@ -627,6 +1364,27 @@ fn view_input_area(app: &GuiApp) -> Element<'_, Message> {
         ]
         .align_y(iced::Alignment::Center)
         .spacing(10);
+        let current = parser::input_priority(&app.input_value);
+        let mut priority_row = row![text("Priority:").size(12).color(muted_color(app))]
+            .spacing(3)
+            .align_y(iced::Alignment::Center);
+        for p in 0..=9u8 {
+            let label = if p == 0 {
+                "-".to_string()
+            } else {
+                p.to_string()
+            };
+            priority_row = priority_row.push(
+                iced::widget::button(text(label).size(12))
+                    .style(if p == current {
+                        iced::widget::button::primary
+                    } else {
+                        iced::widget::button::secondary
+                    })
+                    .padding([2, 7])
+                    .on_press(Message::PickPriority(p)),
+            );
+        }
         let mut move_element: Element<'_, Message> = row![].into();
 
         if let Some(edit_uid) = &app.editing_uid
@@ -636,13 +1394,13 @@ fn view_input_area(app: &GuiApp) -> Element<'_, Message> {
                 .calendars
                 .iter()
                 .filter(|c| {
-                    c.href != task.calendar_href && !app.disabled_calendars.contains(&c.href)
+                    c.href != task.calendar_href
+                        && !c.read_only
+                        && !app.disabled_calendars.contains(&c.href)
                 })
                 .collect();
             if !targets.is_empty() {
-                let label = text("Move to:")
-                    .size(12)
-                    .color(Color::from_rgb(0.6, 0.6, 0.6));
+                let label = text("Move to:").size(12).color(muted_color(app));
                 let mut btn_row = row![].spacing(5);
                 for cal in targets {
                     btn_row = btn_row.push(
@@ -658,11 +1416,35 @@ fn view_input_area(app: &GuiApp) -> Element<'_, Message> {
                     .into();
             }
         }
-        column![top_bar, input_title, input_desc, move_element]
-            .spacing(10)
-            .into()
+        column![
+            top_bar,
+            input_title,
+            tag_suggestions(app),
+            priority_row,
+            recurrence_builder(app),
+            input_desc,
+            move_element
+        ]
+        .spacing(10)
+        .into()
+    } else if app.templates.is_empty() {
+        column![input_title, tag_suggestions(app)].spacing(5).into()
     } else {
-        column![input_title].spacing(5).into()
+        let mut names: Vec<String> = app.templates.keys().cloned().collect();
+        names.sort();
+        let template_picker =
+            iced::widget::pick_list(names, None::<String>, Message::CreateFromTemplate)
+                .placeholder("Template")
+                .text_size(14)
+                .padding(10);
+        column![
+            row![input_title, template_picker]
+                .spacing(5)
+                .align_y(iced::Alignment::Center),
+            tag_suggestions(app)
+        ]
+        .spacing(5)
+        .into()
     };
 
     container(inner_content)
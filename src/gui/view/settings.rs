@@ -1,6 +1,12 @@
+use crate::color_utils;
+use crate::config::ThemeMode;
 use crate::gui::icon;
 use crate::gui::message::Message;
 use crate::gui::state::{AppState, GuiApp};
+use crate::gui::view::muted_color;
+use crate::storage::{LOCAL_CALENDAR_HREF, LocalStorage};
+use crate::store::UNCATEGORIZED_ID;
+use std::collections::HashSet;
 
 use iced::widget::{Space, button, checkbox, column, container, row, scrollable, text, text_input};
 use iced::{Color, Element, Length};
@@ -45,6 +51,41 @@ pub fn view_settings(app: &GuiApp) -> Element<'_, Message> {
                         .label("Hide Completed Tasks (Everywhere)")
                         .on_toggle(Message::ToggleHideCompleted),
                 ),
+                std::convert::Into::<Element<'_, Message>>::into(
+                    checkbox(app.hide_cancelled)
+                        .label("Hide Cancelled Tasks (Everywhere)")
+                        .on_toggle(Message::ToggleHideCancelled),
+                ),
+                std::convert::Into::<Element<'_, Message>>::into(
+                    checkbox(app.hide_blocked)
+                        .label("Hide Blocked Tasks (instead of a [Blocked] badge)")
+                        .on_toggle(Message::ToggleHideBlocked),
+                ),
+                std::convert::Into::<Element<'_, Message>>::into(
+                    checkbox(app.confirm_delete)
+                        .label("Ask before deleting tasks")
+                        .on_toggle(Message::ToggleConfirmDelete),
+                ),
+                std::convert::Into::<Element<'_, Message>>::into(
+                    checkbox(app.completed_last)
+                        .label("Sort completed tasks to the bottom")
+                        .on_toggle(Message::ToggleCompletedLast),
+                ),
+                std::convert::Into::<Element<'_, Message>>::into(
+                    checkbox(app.relative_dates)
+                        .label("Show due dates relative to today (tomorrow, in 3d)")
+                        .on_toggle(Message::ToggleRelativeDates),
+                ),
+                std::convert::Into::<Element<'_, Message>>::into(
+                    checkbox(app.compact_list)
+                        .label("Compact task list (actions behind a \u{22ef} button)")
+                        .on_toggle(Message::ToggleCompactList),
+                ),
+                std::convert::Into::<Element<'_, Message>>::into(
+                    checkbox(app.fuzzy_search)
+                        .label("Fuzzy search (best matches first)")
+                        .on_toggle(Message::ToggleFuzzySearch),
+                ),
                 // Conditional checkbox: only visible when 'Hide Completed Tasks (Everywhere)' is off
                 if !app.hide_completed {
                     std::convert::Into::<Element<'_, Message>>::into(
@@ -56,6 +97,11 @@ pub fn view_settings(app: &GuiApp) -> Element<'_, Message> {
                     // Placeholder to keep spacing
                     std::convert::Into::<Element<'_, Message>>::into(Space::new().width(0))
                 },
+                std::convert::Into::<Element<'_, Message>>::into(
+                    checkbox(app.sort_tags_by_count)
+                        .label("Sort tags by number of active tasks")
+                        .on_toggle(Message::ToggleSortTagsByCount),
+                ),
             ]
             .spacing(10),
         ))
@@ -63,12 +109,25 @@ pub fn view_settings(app: &GuiApp) -> Element<'_, Message> {
         std::convert::Into::<Element<'_, Message>>::into(Space::new().width(0))
     };
 
+    let theme_ui: Element<_> = if is_settings {
+        row![
+            text("Theme:"),
+            iced::widget::pick_list(ThemeMode::ALL, Some(app.theme_mode), Message::SetTheme)
+                .padding(5)
+        ]
+        .spacing(10)
+        .align_y(iced::Alignment::Center)
+        .into()
+    } else {
+        Space::new().width(0).into()
+    };
+
     let sorting_ui: Element<_> = if is_settings {
         column![
             text("Sorting priority cutoff (months):"),
             text("(Tasks due within this range are shown first. Blank = all timed first)")
                 .size(12)
-                .color(Color::from_rgb(0.6, 0.6, 0.6)),
+                .color(muted_color(app)),
             text_input("6", &app.ob_sort_months_input)
                 .on_input(Message::ObSortMonthsChanged)
                 .padding(10)
@@ -132,6 +191,69 @@ pub fn view_settings(app: &GuiApp) -> Element<'_, Message> {
         Space::new().width(0).into()
     };
 
+    // Tag Colors Section
+    let tag_colors_ui: Element<_> = if is_settings {
+        let mut list_col = column![text("Tag colors").size(20)].spacing(10);
+
+        let mut colored: Vec<(&String, &String)> = app.tag_colors.iter().collect();
+        colored.sort();
+        for (tag, hex) in colored {
+            let (r, g, b) = color_utils::tag_color(tag, &app.tag_colors);
+            let row_item = row![
+                text(format!("#{}", tag))
+                    .color(Color::from_rgb(r, g, b))
+                    .width(Length::FillPortion(1)),
+                text(hex).width(Length::FillPortion(1)),
+                button(icon::icon(icon::CROSS).size(12))
+                    .style(button::danger)
+                    .padding(5)
+                    .on_press(Message::RemoveTagColor(tag.clone()))
+            ]
+            .spacing(10)
+            .align_y(iced::Alignment::Center);
+            list_col = list_col.push(row_item);
+        }
+
+        let tags: Vec<String> = app
+            .store
+            .get_all_categories(false, false, &HashSet::new(), &HashSet::new(), false)
+            .into_iter()
+            .map(|(tag, _)| tag)
+            .filter(|tag| tag != UNCATEGORIZED_ID)
+            .collect();
+        let input_row = row![
+            iced::widget::pick_list(
+                tags,
+                app.tag_color_input_tag.clone(),
+                Message::TagColorTagSelected
+            )
+            .placeholder("Tag")
+            .padding(5)
+            .width(Length::FillPortion(1)),
+            text_input("#rrggbb", &app.tag_color_input_hex)
+                .on_input(Message::TagColorHexInput)
+                .on_submit(Message::AddTagColor)
+                .padding(5)
+                .width(Length::FillPortion(1)),
+            button("Set").padding(5).on_press(Message::AddTagColor)
+        ]
+        .spacing(10);
+
+        container(column![list_col, iced::widget::rule::horizontal(1), input_row].spacing(15))
+            .padding(10)
+            .style(|_| container::Style {
+                border: iced::Border {
+                    radius: 4.0.into(),
+                    width: 1.0,
+                    color: Color::from_rgb(0.3, 0.3, 0.3),
+                },
+                ..Default::default()
+            })
+            .into()
+    } else {
+        Space::new().width(0).into()
+    };
+
     let cal_mgmt_ui: Element<_> = if is_settings && !app.calendars.is_empty() {
         let mut col = column![text("Manage calendars").size(20)].spacing(10);
 
@@ -139,13 +261,41 @@ pub fn view_settings(app: &GuiApp) -> Element<'_, Message> {
             // Logic inverted: Checkbox checked = Enabled (!Disabled)
             let is_enabled = !app.disabled_calendars.contains(&cal.href);
 
-            let row_content = row![
-                checkbox(is_enabled)
-                    .label(&cal.name)
-                    // When toggled, we send !v because the msg is "ToggleDisabled"
-                    .on_toggle(move |v| Message::ToggleCalendarDisabled(cal.href.clone(), !v))
-                    .width(Length::Fill)
-            ];
+            let row_content = match &app.renaming_calendar {
+                Some((href, name)) if *href == cal.href => row![
+                    text_input("Calendar name", name)
+                        .on_input(Message::RenameCalendarInput)
+                        .on_submit(Message::SubmitRenameCalendar)
+                        .padding(5)
+                        .width(Length::Fill),
+                    button(text("Save").size(14))
+                        .style(button::primary)
+                        .on_press(Message::SubmitRenameCalendar),
+                    button(text("Cancel").size(14))
+                        .style(button::secondary)
+                        .on_press(Message::CancelRenameCalendar),
+                ],
+                _ => {
+                    let can_rename = cal.href != LOCAL_CALENDAR_HREF
+                        && (LocalStorage::is_local(&cal.href)
+                            || app.client.as_ref().is_some_and(|c| c.client.is_some()));
+                    row![
+                        checkbox(is_enabled)
+                            .label(&cal.name)
+                            // When toggled, we send !v because the msg is "ToggleDisabled"
+                            .on_toggle(move |v| Message::ToggleCalendarDisabled(
+                                cal.href.clone(),
+                                !v
+                            ))
+                            .width(Length::Fill),
+                        button(icon::icon(icon::EDIT).size(14))
+                            .style(button::text)
+                            .on_press_maybe(
+                                can_rename.then(|| Message::StartRenameCalendar(cal.href.clone()))
+                            ),
+                    ]
+                }
+            };
 
             col = col.push(row_content.spacing(10).align_y(iced::Alignment::Center));
         }
@@ -165,6 +315,216 @@ pub fn view_settings(app: &GuiApp) -> Element<'_, Message> {
         Space::new().width(0).into()
     };
 
+    // Archive: move the finished tasks of the active calendar
+    let archive_ui: Element<_> = if is_settings
+        && let Some(from) = &app.active_cal_href
+        && let Some(from_cal) = app.calendars.iter().find(|c| &c.href == from)
+    {
+        let count = app.store.archivable_tasks(from).len();
+        let target = app
+            .archive_calendar
+            .as_ref()
+            .and_then(|href| app.calendars.iter().find(|c| &c.href == href));
+        let target_row = row![
+            text(match target {
+                Some(cal) => format!("Archive calendar: {}", cal.name),
+                None => "No archive calendar chosen yet".to_string(),
+            })
+            .width(Length::Fill),
+            button(text("Change").size(14))
+                .style(button::secondary)
+                .on_press(Message::PickArchiveCalendar),
+        ]
+        .spacing(10)
+        .align_y(iced::Alignment::Center);
+
+        let mut col = column![
+            text("Archive").size(20),
+            text(format!(
+                "{} completed or cancelled tasks in {}",
+                count, from_cal.name
+            ))
+            .size(14)
+            .color(muted_color(app)),
+            target_row,
+        ]
+        .spacing(10);
+
+        if app.picking_archive {
+            let mut picker_row = row![text("Archive to:").size(14)]
+                .spacing(5)
+                .align_y(iced::Alignment::Center);
+            for cal in app.calendars.iter().filter(|c| {
+                &c.href != from && !c.read_only && !app.disabled_calendars.contains(&c.href)
+            }) {
+                picker_row = picker_row.push(
+                    button(text(&cal.name).size(12))
+                        .style(button::secondary)
+                        .padding(5)
+                        .on_press(Message::ArchiveCompletedTo(cal.href.clone())),
+                );
+            }
+            col = col.push(picker_row);
+        }
+
+        col = col.push(
+            button(text(format!("Archive {} tasks", count)).size(14))
+                .style(button::primary)
+                .on_press_maybe(
+                    (count > 0 && target.is_none_or(|c| &c.href != from))
+                        .then_some(Message::ArchiveCompleted),
+                ),
+        );
+
+        container(col)
+            .padding(10)
+            .style(|_| container::Style {
+                border: iced::Border {
+                    radius: 4.0.into(),
+                    width: 1.0,
+                    color: Color::from_rgb(0.3, 0.3, 0.3),
+                },
+                ..Default::default()
+            })
+            .into()
+    } else {
+        Space::new().width(0).into()
+    };
+
+    // Clear completed: delete the finished tasks of the active calendar for good
+    let clear_ui: Element<_> = if is_settings
+        && let Some(href) = &app.active_cal_href
+        && let Some(cal) = app.calendars.iter().find(|c| &c.href == href)
+        && !cal.read_only
+    {
+        let count = app.store.clearable_tasks(href).len();
+        let clear_btn = if app.confirm_clear_completed && count > 0 {
+            button(
+                text(format!(
+                    "Really delete {} tasks? This can't be undone",
+                    count
+                ))
+                .size(14),
+            )
+            .style(button::danger)
+            .on_press(Message::ClearCompleted(true))
+        } else {
+            button(text(format!("Delete {} completed tasks", count)).size(14))
+                .style(button::danger)
+                .on_press_maybe((count > 0).then_some(Message::ClearCompleted(false)))
+        };
+        container(
+            column![
+                text("Clear completed").size(20),
+                text(format!(
+                    "Deletes the completed tasks of {} for good. Recurring tasks \
+                     and parents of open subtasks are kept.",
+                    cal.name
+                ))
+                .size(14)
+                .color(muted_color(app)),
+                clear_btn,
+            ]
+            .spacing(10),
+        )
+        .padding(10)
+        .style(|_| container::Style {
+            border: iced::Border {
+                radius: 4.0.into(),
+                width: 1.0,
+                color: Color::from_rgb(0.3, 0.3, 0.3),
+            },
+            ..Default::default()
+        })
+        .into()
+    } else {
+        Space::new().width(0).into()
+    };
+
+    // Duplicates: same calendar, title and due date; the first of each group is kept
+    let duplicates_ui: Element<_> = if is_settings {
+        let mut col = column![text("Duplicates").size(20)].spacing(10);
+        match &app.duplicates {
+            None => {
+                col = col.push(
+                    text("Tasks sharing a calendar, a title and a due date, conflict copies included.")
+                        .size(14)
+                        .color(muted_color(app)),
+                );
+            }
+            Some(groups) if groups.is_empty() => {
+                col = col.push(
+                    text("No duplicate tasks found.")
+                        .size(14)
+                        .color(muted_color(app)),
+                );
+            }
+            Some(groups) => {
+                for group in groups {
+                    let cal = app
+                        .calendars
+                        .iter()
+                        .find(|c| c.href == group[0].calendar_href)
+                        .map_or(group[0].calendar_href.as_str(), |c| c.name.as_str());
+                    let mut group_col =
+                        column![text(format!("{} ({})", group[0].summary, cal)).size(14)]
+                            .spacing(4);
+                    for task in group {
+                        let mut label = match task.last_modified {
+                            Some(modified) => format!(
+                                "Delete: {}, modified {}",
+                                task.summary,
+                                modified
+                                    .with_timezone(&chrono::Local)
+                                    .format(&app.date_format)
+                            ),
+                            None => format!("Delete: {}", task.summary),
+                        };
+                        if !task.description.is_empty() {
+                            label.push_str(", has notes");
+                        }
+                        let uid = task.uid.clone();
+                        group_col = group_col.push(
+                            checkbox(app.duplicate_delete.contains(&task.uid))
+                                .label(label)
+                                .text_size(12)
+                                .on_toggle(move |_| Message::ToggleDuplicateDelete(uid.clone())),
+                        );
+                    }
+                    col = col.push(group_col);
+                }
+            }
+        }
+        col = col.push(
+            row![
+                button(text("Find duplicates").size(14))
+                    .style(button::secondary)
+                    .on_press(Message::FindDuplicates),
+                button(text(format!("Delete {} tasks", app.duplicate_delete.len())).size(14))
+                    .style(button::danger)
+                    .on_press_maybe(
+                        (app.duplicates.is_some() && !app.duplicate_delete.is_empty())
+                            .then_some(Message::DeleteDuplicates),
+                    ),
+            ]
+            .spacing(10),
+        );
+
+        container(col)
+            .padding(10)
+            .style(|_| container::Style {
+                border: iced::Border {
+                    radius: 4.0.into(),
+                    width: 1.0,
+                    color: Color::from_rgb(0.3, 0.3, 0.3),
+                },
+                ..Default::default()
+            })
+            .into()
+    } else {
+        Space::new().width(0).into()
+    };
+
     // Initialize the buttons row before using it
     let mut buttons = row![].spacing(10);
 
@@ -203,6 +563,21 @@ pub fn view_settings(app: &GuiApp) -> Element<'_, Message> {
         .on_toggle(Message::ObInsecureToggled)
         .size(16)
         .text_size(14);
+    let keyring_check = checkbox(app.ob_use_keyring)
+        .label("Store the password in the system keyring")
+        .on_toggle(Message::ObKeyringToggled)
+        .size(16)
+        .text_size(14);
+
+    // Debug info: what the sync code found the server to support
+    let server_info: Element<'_, Message> =
+        match app.client.as_ref().and_then(|c| c.known_capabilities()) {
+            Some(caps) => text(format!("Server: {}", caps.summary()))
+                .size(12)
+                .color(Color::from_rgb(0.5, 0.5, 0.5))
+                .into(),
+            None => Space::new().height(0).into(),
+        };
 
     let form = column![
         text("CalDAV server URL:"),
@@ -218,12 +593,19 @@ pub fn view_settings(app: &GuiApp) -> Element<'_, Message> {
             .on_input(Message::ObPassChanged)
             .secure(true)
             .padding(10),
+        keyring_check,
         insecure_check,
+        server_info,
         picker,
         prefs,
+        theme_ui,
         sorting_ui,
         aliases_ui,
+        tag_colors_ui,
         cal_mgmt_ui,
+        archive_ui,
+        clear_ui,
+        duplicates_ui,
         buttons
     ]
     .spacing(15)
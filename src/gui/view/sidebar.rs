@@ -1,9 +1,10 @@
 // File: src/gui/view/sidebar.rs
-use super::tooltip_style;
+use super::{muted_color, tooltip_style};
 use crate::color_utils;
 use crate::gui::icon;
 use crate::gui::message::Message;
 use crate::gui::state::GuiApp;
+use crate::model::parser::format_mins;
 use crate::store::UNCATEGORIZED_ID;
 use iced::never;
 use iced::widget::{Space, button, checkbox, column, container, row, text, toggler, tooltip};
@@ -92,7 +93,23 @@ pub fn view_sidebar_calendars(app: &GuiApp) -> Element<'_, Message> {
                 .style(tooltip_style)
                 .delay(Duration::from_millis(700));
 
-                let mut label = button(text(&cal.name).size(16))
+                let count = app.store.open_count(&cal.href, app.hide_completed);
+                let mut name_row = row![
+                    text(&cal.name).size(16),
+                    text(count.to_string())
+                        .size(12)
+                        .color(Color::from_rgb(0.5, 0.5, 0.5))
+                ]
+                .spacing(6)
+                .align_y(iced::Alignment::Center);
+                if cal.read_only {
+                    name_row = name_row.push(
+                        icon::icon(icon::LOCK)
+                            .size(12)
+                            .color(Color::from_rgb(0.5, 0.5, 0.5)),
+                    );
+                }
+                let mut label = button(name_row)
                     .width(Length::Fill)
                     .padding(10)
                     .on_press(Message::SelectCalendar(cal.href.clone()));
@@ -135,7 +152,16 @@ pub fn view_sidebar_calendars(app: &GuiApp) -> Element<'_, Message> {
     .spacing(2)
     .width(Length::Fill);
 
-    column![toggle_container, list].spacing(5).into()
+    let new_cal_btn = button(text("+ New Calendar").size(14))
+        .style(button::text)
+        .padding(10)
+        .width(Length::Fill)
+        // Without a server the new calendar is created locally
+        .on_press(Message::OpenNewCalendar);
+
+    column![toggle_container, list, new_cal_btn]
+        .spacing(5)
+        .into()
 }
 
 // ... DurationOpt (unchanged) ...
@@ -146,22 +172,6 @@ impl std::fmt::Display for DurationOpt {
         write!(f, "{}", self.1)
     }
 }
-fn format_mins(m: u32) -> String {
-    if m >= 525600 {
-        format!("{}y", m / 525600)
-    } else if m >= 43200 {
-        format!("{}mo", m / 43200)
-    } else if m >= 10080 {
-        format!("{}w", m / 10080)
-    } else if m >= 1440 {
-        format!("{}d", m / 1440)
-    } else if m >= 60 {
-        format!("{}h", m / 60)
-    } else {
-        format!("{}m", m)
-    }
-}
-
 pub fn view_sidebar_categories(app: &GuiApp) -> Element<'_, Message> {
     // ... [setup: No Change] ...
     let all_cats = app.store.get_all_categories(
@@ -169,6 +179,7 @@ pub fn view_sidebar_categories(app: &GuiApp) -> Element<'_, Message> {
         app.hide_fully_completed_tags,
         &app.selected_categories,
         &app.hidden_calendars,
+        app.sort_tags_by_count,
     );
     let has_selection = !app.selected_categories.is_empty();
 
@@ -249,7 +260,7 @@ pub fn view_sidebar_categories(app: &GuiApp) -> Element<'_, Message> {
                     let label_content: Element<'_, Message> = if cat == UNCATEGORIZED_ID {
                         text(format!("Uncategorized ({})", count)).size(16).into()
                     } else {
-                        let (r, g, b) = color_utils::generate_color(&cat);
+                        let (r, g, b) = color_utils::tag_color(&cat, &app.tag_colors);
                         let tag_color = Color::from_rgb(r, g, b);
                         crate::gui::view::task_row::rich_text![
                             crate::gui::view::task_row::span("#").color(tag_color),
@@ -302,9 +313,7 @@ pub fn view_sidebar_categories(app: &GuiApp) -> Element<'_, Message> {
 
     let dur_filters = column![
         iced::widget::rule::horizontal(1),
-        text("Filter duration")
-            .size(14)
-            .color(Color::from_rgb(0.7, 0.7, 0.7)),
+        text("Filter duration").size(14).color(muted_color(app)),
         row![
             text("Min:").size(12).width(30),
             iced::widget::pick_list(opts.clone(), Some(current_min), |o| {
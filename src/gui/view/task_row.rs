@@ -4,12 +4,14 @@ use crate::color_utils;
 use crate::gui::icon;
 use crate::gui::message::Message;
 use crate::gui::state::GuiApp;
-use crate::model::Task as TodoTask;
+use crate::model::checklist::{DescriptionLine, parse_description};
+use crate::model::parser::format_mins;
+use crate::model::{Task as TodoTask, humanize_date, week_label};
 use std::collections::HashSet;
 use std::time::Duration;
 
-use super::tooltip_style;
-use iced::widget::{Space, button, column, container, row, scrollable, text, tooltip};
+use super::{muted_color, tooltip_style};
+use iced::widget::{Space, button, checkbox, column, container, row, text, tooltip};
 pub use iced::widget::{rich_text, span};
 use iced::{Border, Color, Element, Length, Theme};
 
@@ -20,6 +22,13 @@ pub fn view_task_row<'a>(
 ) -> Element<'a, Message> {
     let is_blocked = app.store.is_blocked(task);
     let is_selected = app.selected_uid.as_ref() == Some(&task.uid);
+    let compact = app.compact_list;
+    let (title_size, status_size, date_size) = if compact {
+        (15, 18.0, 12)
+    } else {
+        (20, 24.0, 14)
+    };
+    let progress = app.child_progress.get(&task.uid).copied();
     let color = if is_blocked {
         Color::from_rgb(0.5, 0.5, 0.5)
     } else {
@@ -47,6 +56,14 @@ pub fn view_task_row<'a>(
             _ => Color::WHITE,
         }
     };
+    // The gradient is tuned for a dark background: darken it on light themes
+    let color = if app.is_dark_theme() {
+        color
+    } else if task.priority == 0 && !is_blocked {
+        Color::BLACK
+    } else {
+        Color::from_rgb(color.r * 0.6, color.g * 0.6, color.b * 0.6)
+    };
     let show_indent = app.active_cal_href.is_some() && app.search_value.is_empty();
     let indent_size = if show_indent { task.depth * 12 } else { 0 };
     let indent = Space::new().width(Length::Fixed(indent_size as f32));
@@ -179,7 +196,7 @@ pub fn view_task_row<'a>(
             }
             // --- END FIX ---
 
-            let (r, g, b) = color_utils::generate_color(cat);
+            let (r, g, b) = color_utils::tag_color(cat, &app.tag_colors);
             let bg_color = Color::from_rgb(r, g, b);
             let text_color = if color_utils::is_dark(r, g, b) {
                 Color::WHITE
@@ -214,6 +231,29 @@ pub fn view_task_row<'a>(
                     .on_press(Message::JumpToTag(cat.clone())),
             );
         }
+        if let Some((done, total)) = progress {
+            let bg = if done == total {
+                Color::from_rgb(0.0, 0.6, 0.0)
+            } else {
+                Color::from_rgb(0.3, 0.3, 0.3)
+            };
+            tags_row = tags_row.push(
+                container(
+                    text(format!("{}/{} done", done, total))
+                        .size(10)
+                        .color(Color::WHITE),
+                )
+                .style(move |_| container::Style {
+                    background: Some(bg.into()),
+                    border: iced::Border {
+                        radius: 4.0.into(),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                })
+                .padding(3),
+            );
+        }
         if let Some(mins) = task.estimated_duration {
             let label = if mins >= 525600 {
                 format!("{}y", mins / 525600)
@@ -247,24 +287,44 @@ pub fn view_task_row<'a>(
         tags_row.into()
     };
 
-    let date_text: Element<'a, Message> = match task.due {
-        Some(d) => container(
-            text(d.format("%Y-%m-%d").to_string())
-                .size(14)
-                .color(Color::from_rgb(0.5, 0.5, 0.5)),
-        )
-        .width(Length::Fixed(80.0))
-        .into(),
+    let date_text: Element<'a, Message> = match task.due_date() {
+        Some(d) => {
+            let mut label = if app.relative_dates {
+                humanize_date(d, &app.date_format)
+            } else {
+                d.format(&app.date_format).to_string()
+            };
+            let mut width = 80.0;
+            if let Some(time) = task.due_time() {
+                label = format!("{} {}", label, time.format("%H:%M"));
+                width += 40.0;
+            }
+            if app.show_week_numbers {
+                label = format!("{} {}", label, week_label(d));
+                width += 35.0;
+            }
+            container(
+                text(label)
+                    .size(date_size)
+                    .color(Color::from_rgb(0.5, 0.5, 0.5)),
+            )
+            .width(Length::Fixed(width))
+            .into()
+        }
         None => Space::new().width(Length::Fixed(0.0)).into(),
     };
 
     let has_desc = !task.description.is_empty();
     let has_deps = !task.dependencies.is_empty();
+    let has_history =
+        task.completion_count() > 0 || task.completed_at.is_some() || task.time_spent > 0;
+    // The list only shows the relative date, the details keep the exact one
+    let has_exact_due = app.relative_dates && task.due.is_some();
     let is_expanded = app.expanded_tasks.contains(&task.uid);
 
     let mut actions = row![].spacing(3);
 
-    if has_desc || has_deps {
+    if has_desc || has_deps || has_history || has_exact_due || app.show_debug_ids {
         let info_btn = button(icon::icon(icon::INFO).size(12))
             .style(if is_expanded {
                 button::primary
@@ -288,6 +348,15 @@ pub fn view_task_row<'a>(
         actions = actions.push(Space::new().width(Length::Fixed(25.0)));
     }
 
+    // Tasks of read-only calendars keep the details button and get a lock instead
+    // of the actions that modify them
+    let read_only = app
+        .calendars
+        .iter()
+        .any(|c| c.href == task.calendar_href && c.read_only);
+    let details_action = actions;
+    let mut actions = row![].spacing(3);
+
     if let Some(yanked) = &app.yanked_uid {
         if *yanked != task.uid {
             let block_btn = button(icon::icon(icon::BLOCKED).size(14))
@@ -373,6 +442,79 @@ pub fn view_task_row<'a>(
         );
     }
 
+    let duplicate_btn = button(icon::icon(icon::DUPLICATE).size(14))
+        .style(action_style)
+        .padding(4)
+        .on_press(Message::DuplicateTask(task.uid.clone()));
+    actions = actions.push(
+        tooltip(
+            duplicate_btn,
+            text("Duplicate").size(12),
+            tooltip::Position::Top,
+        )
+        .style(tooltip_style)
+        .delay(Duration::from_millis(700)),
+    );
+
+    if task.rrule.is_some() && !task.status.is_done() {
+        let skip_btn = button(icon::icon(icon::SKIP).size(14))
+            .style(action_style)
+            .padding(4)
+            .on_press(Message::SkipOccurrence(task.uid.clone()));
+        actions = actions.push(
+            tooltip(
+                skip_btn,
+                text("Skip this occurrence").size(12),
+                tooltip::Position::Top,
+            )
+            .style(tooltip_style)
+            .delay(Duration::from_millis(700)),
+        );
+    }
+
+    let someday_btn = button(icon::icon(icon::SOMEDAY).size(14))
+        .style(if task.deferred {
+            button::primary
+        } else {
+            action_style
+        })
+        .padding(4)
+        .on_press(Message::ToggleDeferred(task.uid.clone()));
+    actions = actions.push(
+        tooltip(
+            someday_btn,
+            text(if task.deferred {
+                "Make actionable again"
+            } else {
+                "Move to someday"
+            })
+            .size(12),
+            tooltip::Position::Top,
+        )
+        .style(tooltip_style)
+        .delay(Duration::from_millis(700)),
+    );
+
+    if !task.status.is_done() && !app.snooze_presets.is_empty() {
+        let uid = task.uid.clone();
+        let snooze_menu =
+            iced::widget::pick_list(&app.snooze_presets[..], None::<String>, move |preset| {
+                Message::SnoozeTask(uid.clone(), preset)
+            })
+            .placeholder("Snooze")
+            .text_size(12)
+            .padding([2, 4]);
+        actions = actions.push(
+            tooltip(
+                snooze_menu,
+                text("Push the due date").size(12),
+                tooltip::Position::Top,
+            )
+            .style(tooltip_style)
+            .delay(Duration::from_millis(700)),
+        );
+    }
+
     if task.status != crate::model::TaskStatus::Completed
         && task.status != crate::model::TaskStatus::Cancelled
     {
@@ -513,10 +655,10 @@ pub fn view_task_row<'a>(
         .align_x(iced::alignment::Horizontal::Center)
         .align_y(iced::alignment::Vertical::Center),
     )
-    .width(Length::Fixed(24.0))
-    .height(Length::Fixed(24.0))
+    .width(Length::Fixed(status_size))
+    .height(Length::Fixed(status_size))
     .padding(0)
-    .on_press(Message::ToggleTask(index, true))
+    .on_press_maybe((!read_only).then_some(Message::ToggleTask(index, true)))
     .style(move |_theme, status| {
         let base_active = button::Style {
             background: Some(bg_color.into()),
@@ -555,17 +697,19 @@ pub fn view_task_row<'a>(
             0
         }
         + if task.rrule.is_some() { 1 } else { 0 }
-        + if is_blocked { 9 } else { 0 };
+        + if is_blocked { 9 } else { 0 }
+        + if progress.is_some() { 8 } else { 0 };
     let place_inline = (title_chars + est_tags_len) <= 60;
     let has_metadata = !task.categories.is_empty()
         || task.rrule.is_some()
         || is_blocked
+        || progress.is_some()
         || task.estimated_duration.is_some();
 
     let title_row = if place_inline {
         row![
             text(&task.summary)
-                .size(20)
+                .size(title_size)
                 .color(color)
                 .width(Length::Fill),
             if has_metadata {
@@ -579,14 +723,14 @@ pub fn view_task_row<'a>(
     } else {
         row![
             text(&task.summary)
-                .size(20)
+                .size(title_size)
                 .color(color)
                 .width(Length::Fill)
         ]
         .spacing(6)
         .align_y(iced::Alignment::Center)
     };
-    let main_text_col = column![
+    let mut main_text_col = column![
         title_row,
         if !place_inline && has_metadata {
             row![Space::new().width(Length::Fill), build_tags()]
@@ -596,15 +740,109 @@ pub fn view_task_row<'a>(
     ]
     .width(Length::Fill)
     .spacing(1);
-    let row_main = row![indent, status_btn, main_text_col, date_text, actions]
-        .spacing(10)
-        .align_y(iced::Alignment::Center);
+    if task.status == crate::model::TaskStatus::InProcess && task.percent_complete > 0 {
+        main_text_col = main_text_col.push(
+            iced::widget::progress_bar(0.0..=100.0, task.percent_complete as f32)
+                .length(Length::Fill)
+                .girth(3),
+        );
+    }
+    if !app.search_value.is_empty()
+        && let Some(reason) = task.search_match_reason(&app.search_value)
+    {
+        main_text_col = main_text_col.push(
+            text(format!("Matched in {}", reason))
+                .size(11)
+                .color(Color::from_rgb(0.5, 0.5, 0.5)),
+        );
+    }
+    let drag_handle: Element<'a, Message> =
+        if app.manual_order && !app.agenda_view && !app.review_view && !read_only {
+            iced::widget::MouseArea::new(
+                icon::icon(icon::DRAG_HANDLE)
+                    .size(14)
+                    .color(Color::from_rgb(0.5, 0.5, 0.5)),
+            )
+            .interaction(iced::mouse::Interaction::Grab)
+            .on_press(Message::DragStart(task.uid.clone()))
+            .into()
+        } else {
+            Space::new().width(0).into()
+        };
 
+    // The review only lets tasks be reopened, through the status button
+    let actions = if app.review_view && !read_only {
+        details_action
+    } else if read_only {
+        details_action.push(
+            tooltip(
+                icon::icon(icon::LOCK)
+                    .size(14)
+                    .color(Color::from_rgb(0.5, 0.5, 0.5)),
+                text("Read-only calendar").size(12),
+                tooltip::Position::Top,
+            )
+            .style(tooltip_style)
+            .delay(Duration::from_millis(700)),
+        )
+    } else {
+        details_action.push(actions)
+    };
+
+    // Compact rows fold the actions into a menu shown on hover
+    let actions: Element<'a, Message> = if compact && app.row_menu.as_ref() != Some(&task.uid) {
+        let hovered = app.hovered_uid.as_ref() == Some(&task.uid);
+        if hovered || is_selected {
+            button(text("\u{22ef}").size(12))
+                .style(action_style)
+                .padding(iced::Padding {
+                    top: 0.0,
+                    right: 4.0,
+                    bottom: 0.0,
+                    left: 4.0,
+                })
+                .width(Length::Fixed(25.0))
+                .on_press(Message::ToggleRowMenu(task.uid.clone()))
+                .into()
+        } else {
+            Space::new().width(Length::Fixed(25.0)).into()
+        }
+    } else {
+        actions.into()
+    };
+
+    // Flag color bar, taken from the left padding so every title stays aligned
+    let flag = task.color.as_deref().and_then(color_utils::flag_color);
+    let flag_bar = container(Space::new())
+        .width(Length::Fixed(4.0))
+        .height(Length::Fixed(if compact { 18.0 } else { 24.0 }))
+        .style(move |_: &Theme| container::Style {
+            background: flag.map(|(r, g, b)| Color::from_rgb(r, g, b).into()),
+            border: iced::Border {
+                radius: 2.0.into(),
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+
+    let row_main = row![
+        flag_bar,
+        indent,
+        drag_handle,
+        status_btn,
+        main_text_col,
+        date_text,
+        actions
+    ]
+    .spacing(10)
+    .align_y(iced::Alignment::Center);
+
+    let vertical_padding = if compact { 0.0 } else { 2.0 };
     let mut padded_row = container(row_main).padding(iced::Padding {
-        top: 2.0,
+        top: vertical_padding,
         right: 16.0,
-        bottom: 2.0,
-        left: 6.0,
+        bottom: vertical_padding,
+        left: 2.0,
     });
     if is_selected {
         padded_row = padded_row.style(|theme: &Theme| {
@@ -630,15 +868,130 @@ pub fn view_task_row<'a>(
         });
     }
 
+    let is_drop_target = app.dragging.is_some()
+        && app.drag_target.as_ref() == Some(&task.uid)
+        && app.dragging.as_ref() != Some(&task.uid);
+    if is_drop_target {
+        padded_row = padded_row.style(|theme: &Theme| container::Style {
+            border: iced::Border {
+                color: theme.extended_palette().primary.base.color,
+                width: 1.0,
+                radius: 4.0.into(),
+            },
+            ..Default::default()
+        });
+    }
+
     let row_id = iced::widget::Id::from(task.uid.clone());
 
     if is_expanded {
         let mut details_col = column![].spacing(5);
         if !task.description.is_empty() {
+            for (line_idx, line) in parse_description(&task.description).into_iter().enumerate() {
+                let line_el: Element<'a, Message> = match line {
+                    DescriptionLine::Text(t) => text(t)
+                        .size(14)
+                        .color(muted_color(app))
+                        .width(Length::Fill)
+                        .wrapping(text::Wrapping::WordOrGlyph)
+                        .into(),
+                    DescriptionLine::Checkbox { checked, label } => {
+                        let uid = task.uid.clone();
+                        checkbox(checked)
+                            .label(label)
+                            .size(14)
+                            .text_size(14)
+                            .on_toggle_maybe((!read_only).then_some(move |_| {
+                                Message::ToggleChecklistItem(uid.clone(), line_idx)
+                            }))
+                            .into()
+                    }
+                };
+                details_col = details_col.push(line_el);
+            }
+        }
+        if let Some(url) = &task.url {
             details_col = details_col.push(
-                text(&task.description)
-                    .size(14)
-                    .color(Color::from_rgb(0.7, 0.7, 0.7)),
+                button(text(url).size(12).color(Color::from_rgb(0.4, 0.7, 1.0)))
+                    .style(button::text)
+                    .padding(0)
+                    .on_press(Message::OpenUrl(url.clone())),
+            );
+        }
+        if !task.attendees.is_empty() {
+            let mut people = row![text("Waiting on").size(12).color(muted_color(app))]
+                .spacing(4)
+                .align_y(iced::Alignment::Center);
+            for who in &task.attendees {
+                people = people.push(
+                    button(text(format!("@{}", who)).size(11).color(Color::WHITE))
+                        .style(|_theme, _status| button::Style {
+                            background: Some(Color::from_rgb(0.35, 0.3, 0.5).into()),
+                            border: iced::Border {
+                                radius: 8.0.into(),
+                                ..Default::default()
+                            },
+                            ..button::Style::default()
+                        })
+                        .padding([1, 6])
+                        .on_press(Message::SearchChanged(format!("@@{}", who))),
+                );
+            }
+            details_col = details_col.push(people.wrap());
+        }
+        if has_exact_due && let Some(due) = task.format_due_as(&app.date_format) {
+            details_col = details_col.push(
+                text(format!("Due {}", due))
+                    .size(12)
+                    .color(muted_color(app)),
+            );
+        }
+        if let Some(done_at) = task.completed_at {
+            details_col = details_col.push(
+                text({
+                    let done_at = done_at.with_timezone(&chrono::Local);
+                    format!(
+                        "Completed on {} {}",
+                        done_at.format(&app.date_format),
+                        done_at.format("%H:%M")
+                    )
+                })
+                .size(12)
+                .color(Color::from_rgb(0.4, 0.8, 0.4)),
+            );
+        }
+        if app.show_debug_ids {
+            details_col = details_col.push(
+                text(format!(
+                    "UID: {}\nHref: {}\nCalendar: {}",
+                    task.uid, task.href, task.calendar_href
+                ))
+                .size(11)
+                .font(iced::Font::MONOSPACE)
+                .color(Color::from_rgb(0.5, 0.5, 0.5)),
+            );
+        }
+        if task.time_spent > 0 {
+            details_col = details_col.push(
+                text(format!("Time spent: {}", format_mins(task.time_spent)))
+                    .size(12)
+                    .color(muted_color(app)),
+            );
+        }
+        if task.completion_count() > 0 {
+            let last = task
+                .completed_instances
+                .last()
+                .map(|d| d.format(&app.date_format).to_string())
+                .unwrap_or_default();
+            details_col = details_col.push(
+                text(format!(
+                    "Completed {} times (last: {})",
+                    task.completion_count(),
+                    last
+                ))
+                .size(12)
+                .color(Color::from_rgb(0.4, 0.8, 0.4)),
             );
         }
         if let Some(p_uid) = &task.parent_uid {
@@ -689,7 +1042,7 @@ pub fn view_task_row<'a>(
                 let dep_row = row![
                     text(format!("{} {}", check, name))
                         .size(12)
-                        .color(Color::from_rgb(0.6, 0.6, 0.6)),
+                        .color(muted_color(app)),
                     tooltip(
                         remove_dep_btn,
                         text("Remove dependency").size(12),
@@ -703,31 +1056,92 @@ pub fn view_task_row<'a>(
                 details_col = details_col.push(dep_row);
             }
         }
-        if app.calendars.len() > 1 {
-            let current_cal_href = task.calendar_href.clone();
-            let targets: Vec<_> = app
+        if app.calendars.len() > 1 && !read_only && !app.review_view {
+            // The current calendar first, then the ones the task can move to
+            let choices: Vec<CalendarChoice> = app
                 .calendars
                 .iter()
-                .filter(|c| c.href != current_cal_href && !app.disabled_calendars.contains(&c.href))
+                .filter(|c| {
+                    c.href == task.calendar_href
+                        || (!c.read_only && !app.disabled_calendars.contains(&c.href))
+                })
+                .map(|c| CalendarChoice {
+                    href: c.href.clone(),
+                    name: c.name.clone(),
+                })
                 .collect();
-            let move_label = text("Move to:")
-                .size(12)
-                .color(Color::from_rgb(0.5, 0.5, 0.5));
-            let mut move_row = row![].spacing(5).align_y(iced::Alignment::Center);
-            for cal in targets {
-                move_row = move_row.push(
-                    button(text(&cal.name).size(10))
-                        .style(button::secondary)
-                        .padding(3)
-                        .on_press(Message::MoveTask(task.uid.clone(), cal.href.clone())),
-                );
-            }
+            let current = choices
+                .iter()
+                .find(|c| c.href == task.calendar_href)
+                .cloned();
+            let uid = task.uid.clone();
+            let picker = iced::widget::pick_list(choices, current, move |choice| {
+                Message::MoveTask(uid.clone(), choice.href)
+            })
+            .text_size(12)
+            .padding([2, 6]);
             details_col = details_col.push(
-                row![move_label, scrollable(move_row).height(Length::Fixed(30.0))]
-                    .spacing(10)
-                    .align_y(iced::Alignment::Center),
+                row![
+                    text("Calendar:")
+                        .size(12)
+                        .color(Color::from_rgb(0.5, 0.5, 0.5)),
+                    picker
+                ]
+                .spacing(10)
+                .align_y(iced::Alignment::Center),
             );
         }
+        if !read_only && !app.review_view {
+            let mut swatches = row![
+                text("Color:")
+                    .size(12)
+                    .color(Color::from_rgb(0.5, 0.5, 0.5))
+            ]
+            .spacing(4)
+            .align_y(iced::Alignment::Center);
+            for (name, _) in color_utils::FLAG_COLORS {
+                let Some((r, g, b)) = color_utils::flag_color(name) else {
+                    continue;
+                };
+                let is_current = task.color.as_deref() == Some(name);
+                swatches = swatches.push(
+                    tooltip(
+                        button(
+                            Space::new()
+                                .width(Length::Fixed(12.0))
+                                .height(Length::Fixed(12.0)),
+                        )
+                        .style(move |_theme, _status| button::Style {
+                            background: Some(Color::from_rgb(r, g, b).into()),
+                            border: iced::Border {
+                                color: Color::WHITE,
+                                width: if is_current { 2.0 } else { 0.0 },
+                                radius: 7.0.into(),
+                            },
+                            ..button::Style::default()
+                        })
+                        .padding(1)
+                        .on_press(Message::SetTaskColor(
+                            task.uid.clone(),
+                            Some(name.to_string()),
+                        )),
+                        text(name).size(12),
+                        tooltip::Position::Top,
+                    )
+                    .style(tooltip_style)
+                    .delay(Duration::from_millis(700)),
+                );
+            }
+            if task.color.is_some() {
+                swatches = swatches.push(
+                    button(text("None").size(12))
+                        .style(button::text)
+                        .padding([0, 4])
+                        .on_press(Message::SetTaskColor(task.uid.clone(), None)),
+                );
+            }
+            details_col = details_col.push(swatches);
+        }
         let desc_row = row![
             Space::new().width(Length::Fixed(indent_size as f32 + 30.0)),
             details_col
@@ -740,3 +1154,16 @@ pub fn view_task_row<'a>(
         padded_row.id(row_id).into()
     }
 }
+
+/// Calendar in the details' picker, shown by name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct CalendarChoice {
+    href: String,
+    name: String,
+}
+
+impl std::fmt::Display for CalendarChoice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
@@ -0,0 +1,71 @@
+// File: src/gui/window_state.rs
+use crate::paths::AppPaths;
+use crate::storage::LocalStorage;
+use iced::{Point, Size, window};
+use serde::{Deserialize, Serialize};
+use std::fs;
+
+pub const DEFAULT_SIZE: Size = Size::new(1024.0, 768.0);
+const MIN_VISIBLE: f32 = 50.0;
+
+/// Last known window geometry, stored next to the journal so that writing
+/// it never races with the user editing config.toml.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct WindowGeometry {
+    pub width: f32,
+    pub height: f32,
+    #[serde(default)]
+    pub x: Option<f32>,
+    #[serde(default)]
+    pub y: Option<f32>,
+}
+
+impl WindowGeometry {
+    pub fn load() -> Option<Self> {
+        let path = AppPaths::get_window_state_path()?;
+        if !path.exists() {
+            return None;
+        }
+        let json = fs::read_to_string(path).ok()?;
+        let geo: Self = serde_json::from_str(&json).ok()?;
+        if geo.width < 100.0 || geo.height < 100.0 {
+            return None;
+        }
+        Some(geo)
+    }
+
+    pub fn save(&self) -> anyhow::Result<()> {
+        if let Some(path) = AppPaths::get_window_state_path() {
+            LocalStorage::with_lock(&path, || {
+                let json = serde_json::to_string_pretty(self)?;
+                LocalStorage::atomic_write(&path, json)?;
+                Ok(())
+            })?;
+        }
+        Ok(())
+    }
+
+    pub fn size(&self) -> Size {
+        Size::new(self.width, self.height)
+    }
+
+    pub fn position(&self) -> Option<Point> {
+        match (self.x, self.y) {
+            (Some(x), Some(y)) => Some(Point::new(x, y)),
+            _ => None,
+        }
+    }
+
+    /// Builds the startup position. Coordinates that are obviously bogus
+    /// (e.g. far into negative space) fall back to a centered window. A saved
+    /// position on a monitor that has since been unplugged is caught once the
+    /// window is open (see `Message::WindowOpened`).
+    pub fn startup_position(&self) -> window::Position {
+        match self.position() {
+            Some(p) if p.x > MIN_VISIBLE - self.width && p.y > -MIN_VISIBLE => {
+                window::Position::Specific(p)
+            }
+            _ => window::Position::Centered,
+        }
+    }
+}
@@ -1,8 +1,9 @@
 // File: src/journal.rs
+use crate::cache::Cache;
 use crate::model::Task;
 use crate::paths::AppPaths;
 use crate::storage::LocalStorage;
-use anyhow::Result;
+use anyhow::{Result, bail};
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
@@ -15,9 +16,52 @@ pub enum Action {
     Move(Task, String),
 }
 
+impl Action {
+    /// One-line description for the journal inspector, e.g. "Update: Buy milk".
+    pub fn summary(&self) -> String {
+        match self {
+            Action::Create(t) => format!("Create: {}", t.summary),
+            Action::Update(t) => format!("Update: {}", t.summary),
+            Action::Delete(t) => format!("Delete: {}", t.summary),
+            Action::Move(t, target) => format!("Move: {} -> {}", t.summary, target),
+        }
+    }
+
+    /// Hrefs of the calendars the action writes to.
+    fn calendar_hrefs(&self) -> Vec<&str> {
+        match self {
+            Action::Create(t) | Action::Update(t) | Action::Delete(t) => {
+                vec![t.calendar_href.as_str()]
+            }
+            Action::Move(t, target) => vec![t.calendar_href.as_str(), target.as_str()],
+        }
+    }
+}
+
+/// One field that was changed both locally and on the server, to different values.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct FieldDiff {
+    pub field: String,
+    pub base: String,
+    pub local: String,
+    pub server: String,
+}
+
+/// An update rejected by the server (412) that could not be merged automatically.
+/// It waits here until the user picks a side for each field, or dismisses it.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MergeConflict {
+    pub base: Task,
+    pub local: Task,
+    pub server: Task,
+    pub fields: Vec<FieldDiff>,
+}
+
 #[derive(Serialize, Deserialize, Debug, Default)]
 pub struct Journal {
     pub queue: Vec<Action>,
+    #[serde(default)]
+    pub conflicts: Vec<MergeConflict>,
 }
 
 impl Journal {
@@ -52,11 +96,19 @@ impl Journal {
     pub fn modify<F>(f: F) -> Result<()>
     where
         F: FnOnce(&mut Vec<Action>),
+    {
+        Self::modify_all(|journal| f(&mut journal.queue))
+    }
+
+    /// Transactional modification of the whole journal (queue and pending conflicts).
+    pub fn modify_all<F>(f: F) -> Result<()>
+    where
+        F: FnOnce(&mut Journal),
     {
         if let Some(path) = Self::get_path() {
             LocalStorage::with_lock(&path, || {
                 let mut journal = Self::load_internal(&path);
-                f(&mut journal.queue);
+                f(&mut journal);
                 let json = serde_json::to_string_pretty(&journal)?;
                 LocalStorage::atomic_write(&path, json)?;
                 Ok(())
@@ -65,11 +117,42 @@ impl Journal {
         Ok(())
     }
 
+    /// Queues an action, refusing writes to calendars known to be read-only
+    /// (the server would only answer 403).
     pub fn push(action: Action) -> Result<()> {
+        let calendars = Cache::load_calendars().unwrap_or_default();
+        let hrefs = action.calendar_hrefs();
+        if let Some(cal) = calendars
+            .iter()
+            .find(|c| c.read_only && hrefs.contains(&c.href.as_str()))
+        {
+            bail!("Calendar '{}' is read-only", cal.name);
+        }
         Self::modify(|queue| queue.push(action))
     }
 
     pub fn is_empty(&self) -> bool {
         self.queue.is_empty()
     }
+
+    /// Pending actions in the order they will be sent.
+    pub fn queue_summary() -> Vec<String> {
+        Self::load().queue.iter().map(Action::summary).collect()
+    }
+
+    /// Drops the queued action at `index`, e.g. one the server keeps rejecting.
+    pub fn remove_at(index: usize) -> Result<Option<Action>> {
+        let mut removed = None;
+        Self::modify(|queue| {
+            if index < queue.len() {
+                removed = Some(queue.remove(index));
+            }
+        })?;
+        Ok(removed)
+    }
+
+    /// Drops every queued action. Unsent local changes are lost.
+    pub fn clear_queue() -> Result<()> {
+        Self::modify(|queue| queue.clear())
+    }
 }
@@ -15,6 +15,25 @@ pub mod tui;
 #[cfg(feature = "gui")]
 pub mod gui;
 
+/// What `--version` prints: the version, the git commit when known and the
+/// interfaces built in.
+pub fn build_info() -> String {
+    let mut info = format!("cfait {}", env!("CARGO_PKG_VERSION"));
+    if let Some(hash) = option_env!("CFAIT_GIT_HASH") {
+        info.push_str(&format!(" ({})", hash));
+    }
+    let features: Vec<&str> = [
+        ("tui", cfg!(feature = "tui")),
+        ("gui", cfg!(feature = "gui")),
+        ("keyring", cfg!(feature = "keyring")),
+    ]
+    .into_iter()
+    .filter_map(|(name, enabled)| enabled.then_some(name))
+    .collect();
+    info.push_str(&format!("\nfeatures: {}", features.join(", ")));
+    info
+}
+
 // --- ANDROID SUPPORT ---
 #[cfg(target_os = "android")]
 pub mod mobile;
@@ -1,10 +1,10 @@
 // File: ./src/mobile.rs
 use crate::cache::Cache;
 use crate::client::RustyClient;
-use crate::config::Config;
+use crate::config::{CalendarDefaults, Config};
 use crate::model::Task;
 use crate::paths::AppPaths;
-use crate::storage::{LOCAL_CALENDAR_HREF, LOCAL_CALENDAR_NAME, LocalStorage};
+use crate::storage::{LOCAL_CALENDAR_HREF, LocalStorage};
 use crate::store::{FilterOptions, TaskStore, UNCATEGORIZED_ID};
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
@@ -237,7 +237,7 @@ impl CfaitMobile {
             }
         }
         self.modify_task_and_sync(child_uid, |t| {
-            t.parent_uid = parent_uid.clone();
+            t.set_parent(parent_uid.clone());
         })
         .await
     }
@@ -287,12 +287,14 @@ impl CfaitMobile {
     pub fn load_from_cache(&self) {
         let mut store = self.store.blocking_lock();
         store.clear();
-        if let Ok(local) = LocalStorage::load() {
-            store.insert(LOCAL_CALENDAR_HREF.to_string(), local);
+        for cal in LocalStorage::calendars() {
+            if let Ok(local) = LocalStorage::load(&cal.href) {
+                store.insert(cal.href, local);
+            }
         }
         if let Ok(cals) = Cache::load_calendars() {
             for cal in cals {
-                if cal.href == LOCAL_CALENDAR_HREF {
+                if LocalStorage::is_local(&cal.href) {
                     continue;
                 }
                 if let Ok((tasks, _)) = Cache::load(&cal.href) {
@@ -328,18 +330,19 @@ impl CfaitMobile {
         let config = Config::load().unwrap_or_default();
         let disabled_set: HashSet<String> = config.disabled_calendars.iter().cloned().collect();
         let mut result = Vec::new();
-        let local_href = LOCAL_CALENDAR_HREF.to_string();
-        result.push(MobileCalendar {
-            name: LOCAL_CALENDAR_NAME.to_string(),
-            href: local_href.clone(),
-            color: None,
-            is_visible: !config.hidden_calendars.contains(&local_href),
-            is_local: true,
-            is_disabled: false,
-        });
+        for c in LocalStorage::calendars() {
+            result.push(MobileCalendar {
+                name: c.name,
+                href: c.href.clone(),
+                color: c.color,
+                is_visible: !config.hidden_calendars.contains(&c.href),
+                is_local: true,
+                is_disabled: false,
+            });
+        }
         if let Ok(cals) = crate::cache::Cache::load_calendars() {
             for c in cals {
-                if c.href == LOCAL_CALENDAR_HREF {
+                if LocalStorage::is_local(&c.href) {
                     continue;
                 }
                 result.push(MobileCalendar {
@@ -367,6 +370,7 @@ impl CfaitMobile {
                 config.hide_fully_completed_tags,
                 &empty_includes,
                 &hidden_cals,
+                config.sort_tags_by_count,
             )
             .into_iter()
             .map(|(name, count)| MobileTag {
@@ -402,10 +406,19 @@ impl CfaitMobile {
             match_all_categories: false,
             search_term: &search_query,
             hide_completed_global: config.hide_completed,
+            hide_cancelled: config.hide_cancelled,
+            hide_blocked: config.hide_blocked,
             cutoff_date,
             min_duration: None,
             max_duration: None,
             include_unset_duration: true,
+            due_today_only: false,
+            completed_last: config.completed_last,
+            manual_order: config.manual_order,
+            hide_deferred: config.hide_deferred,
+            fuzzy_search: config.fuzzy_search,
+            week_start: config.week_start.weekday(),
+            priority_filter: None,
         });
         filtered
             .into_iter()
@@ -428,6 +441,11 @@ impl CfaitMobile {
             .default_calendar
             .clone()
             .unwrap_or(LOCAL_CALENDAR_HREF.to_string());
+        if let Some(defaults) =
+            CalendarDefaults::lookup(&config.calendar_defaults, &target_href, None)
+        {
+            defaults.apply(&mut task);
+        }
         task.calendar_href = target_href.clone();
         if let Some(client) = &*guard {
             client
@@ -436,9 +454,9 @@ impl CfaitMobile {
                 .map(|_| ())
                 .map_err(MobileError::from)?;
         } else {
-            let mut all = LocalStorage::load().unwrap_or_default();
+            let mut all = LocalStorage::load(&target_href).unwrap_or_default();
             all.push(task.clone());
-            LocalStorage::save(&all).map_err(MobileError::from)?;
+            LocalStorage::save(&target_href, &all).map_err(MobileError::from)?;
         }
         self.store.lock().await.add_task(task);
         Ok(())
@@ -468,21 +486,21 @@ impl CfaitMobile {
     }
     pub async fn set_status_process(&self, uid: String) -> Result<(), MobileError> {
         self.modify_task_and_sync(uid, |t| {
-            t.status = if t.status == crate::model::TaskStatus::InProcess {
+            t.set_status(if t.status == crate::model::TaskStatus::InProcess {
                 crate::model::TaskStatus::NeedsAction
             } else {
                 crate::model::TaskStatus::InProcess
-            };
+            });
         })
         .await
     }
     pub async fn set_status_cancelled(&self, uid: String) -> Result<(), MobileError> {
         self.modify_task_and_sync(uid, |t| {
-            t.status = if t.status == crate::model::TaskStatus::Cancelled {
+            t.set_status(if t.status == crate::model::TaskStatus::Cancelled {
                 crate::model::TaskStatus::NeedsAction
             } else {
                 crate::model::TaskStatus::Cancelled
-            };
+            });
         })
         .await
     }
@@ -510,9 +528,9 @@ impl CfaitMobile {
     pub async fn toggle_task(&self, uid: String) -> Result<(), MobileError> {
         self.modify_task_and_sync(uid, |t| {
             if t.status.is_done() {
-                t.status = crate::model::TaskStatus::NeedsAction;
+                t.set_status(crate::model::TaskStatus::NeedsAction);
             } else {
-                t.status = crate::model::TaskStatus::Completed;
+                t.set_status(crate::model::TaskStatus::Completed);
             }
         })
         .await
@@ -541,11 +559,11 @@ impl CfaitMobile {
         let client_guard = self.client.lock().await;
         if let Some(client) = &*client_guard {
             client.delete_task(&task).await.map_err(MobileError::from)?;
-        } else if task.calendar_href == LOCAL_CALENDAR_HREF {
-            let mut local = LocalStorage::load().unwrap_or_default();
+        } else if LocalStorage::is_local(&task.calendar_href) {
+            let mut local = LocalStorage::load(&task.calendar_href).unwrap_or_default();
             if let Some(pos) = local.iter().position(|t| t.uid == uid) {
                 local.remove(pos);
-                LocalStorage::save(&local).map_err(MobileError::from)?;
+                LocalStorage::save(&task.calendar_href, &local).map_err(MobileError::from)?;
             }
         }
         Ok(())
@@ -558,14 +576,16 @@ impl CfaitMobile {
 
 impl CfaitMobile {
     async fn apply_connection(&self, config: Config) -> Result<String, MobileError> {
-        let (client, cals, _, _, warning) = RustyClient::connect_with_fallback(config)
+        let (client, cals, _, _, warning) = RustyClient::connect_with_fallback(config, |_, _| {})
             .await
             .map_err(MobileError::from)?;
         *self.client.lock().await = Some(client.clone());
         let mut store = self.store.lock().await;
         store.clear();
-        if let Ok(local) = LocalStorage::load() {
-            store.insert(LOCAL_CALENDAR_HREF.to_string(), local);
+        for cal in LocalStorage::calendars() {
+            if let Ok(local) = LocalStorage::load(&cal.href) {
+                store.insert(cal.href, local);
+            }
         }
 
         match client.get_all_tasks(&cals).await {
@@ -576,7 +596,9 @@ impl CfaitMobile {
             }
             Err(e) => {
                 for cal in &cals {
-                    if cal.href != LOCAL_CALENDAR_HREF && !store.calendars.contains_key(&cal.href) {
+                    if !LocalStorage::is_local(&cal.href)
+                        && !store.calendars.contains_key(&cal.href)
+                    {
                         if let Ok((cached, _)) = crate::cache::Cache::load(&cal.href) {
                             store.insert(cal.href.clone(), cached);
                         }
@@ -607,11 +629,12 @@ impl CfaitMobile {
                 .update_task(&mut task_copy.clone())
                 .await
                 .map_err(MobileError::from)?;
-        } else if task_copy.calendar_href == LOCAL_CALENDAR_HREF {
-            let mut local = LocalStorage::load().unwrap_or_default();
+        } else if LocalStorage::is_local(&task_copy.calendar_href) {
+            let href = task_copy.calendar_href.clone();
+            let mut local = LocalStorage::load(&href).unwrap_or_default();
             if let Some(idx) = local.iter().position(|t| t.uid == uid) {
                 local[idx] = task_copy;
-                LocalStorage::save(&local).map_err(MobileError::from)?;
+                LocalStorage::save(&href, &local).map_err(MobileError::from)?;
             }
         }
         Ok(())
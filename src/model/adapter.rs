@@ -1,7 +1,9 @@
 // File: src/model/adapter.rs
 use crate::model::item::{RawProperty, Task, TaskStatus};
 use chrono::{DateTime, NaiveDate, NaiveDateTime, TimeZone, Utc};
-use icalendar::{Calendar, CalendarComponent, Component, Todo, TodoStatus};
+use icalendar::{
+    Alarm, Calendar, CalendarComponent, Component, EventLike, Todo, TodoStatus, Trigger,
+};
 use rrule::RRuleSet;
 use std::str::FromStr;
 use uuid::Uuid;
@@ -26,43 +28,161 @@ const HANDLED_KEYS: &[&str] = &[
     "PRODID",
     "VERSION",
     "CALSCALE",
+    "X-CFAIT-COMPLETED",
+    "COMPLETED",
+    "PERCENT-COMPLETE",
+    "URL",
+    "X-APPLE-SORT-ORDER",
+    "X-CFAIT-DEFERRED",
+    "X-CFAIT-TIME-SPENT",
+    "GEO",
+    "ATTENDEE",
+    "COLOR",
 ];
 
+/// Occurrences `Task::catch_up` skips at most in one go.
+const MAX_CATCH_UP_STEPS: usize = 1000;
+
 impl Task {
     pub fn respawn(&self) -> Option<Task> {
+        let next_start = self.next_occurrence()?;
+
+        let mut next_task = self.clone();
+        next_task.uid = Uuid::new_v4().to_string();
+        next_task.href = String::new();
+        next_task.etag = String::new();
+        next_task.set_status(TaskStatus::NeedsAction);
+        next_task.dependencies.clear();
+        next_task.completed_instances.push(Utc::now());
+        next_task.shift_to(next_start);
+        Some(next_task)
+    }
+
+    /// Start of the occurrence following the current one, seeded from the start
+    /// date if present, else the due date.
+    fn next_occurrence(&self) -> Option<DateTime<Utc>> {
         let rule_str = self.rrule.as_ref()?;
         let seed_date = self.dtstart.or(self.due)?;
 
         let dtstart_str = seed_date.format("%Y%m%dT%H%M%SZ").to_string();
         let rrule_string = format!("DTSTART:{}\nRRULE:{}", dtstart_str, rule_str);
 
-        if let Ok(rrule_set) = RRuleSet::from_str(&rrule_string) {
-            let result = rrule_set.all(2);
-            let dates = result.dates;
-            if dates.len() > 1 {
-                let next_occurrence = dates[1];
-                let next_start = Utc.from_utc_datetime(&next_occurrence.naive_utc());
-
-                let mut next_task = self.clone();
-                next_task.uid = Uuid::new_v4().to_string();
-                next_task.href = String::new();
-                next_task.etag = String::new();
-                next_task.status = TaskStatus::NeedsAction;
-                next_task.dependencies.clear();
-
-                if self.dtstart.is_some() {
-                    next_task.dtstart = Some(next_start);
-                }
+        let rrule_set = RRuleSet::from_str(&rrule_string).ok()?;
+        let dates = rrule_set.all(2).dates;
+        let next_occurrence = dates.get(1)?;
+        Some(Utc.from_utc_datetime(&next_occurrence.naive_utc()))
+    }
 
-                if let Some(old_due) = self.due {
-                    let duration = old_due - seed_date;
-                    next_task.due = Some(next_start + duration);
-                }
+    /// Moves the start and due dates to `next_start`, keeping their distance.
+    fn shift_to(&mut self, next_start: DateTime<Utc>) {
+        let Some(seed_date) = self.dtstart.or(self.due) else {
+            return;
+        };
+        if self.dtstart.is_some() {
+            self.dtstart = Some(next_start);
+        }
+        if let Some(old_due) = self.due {
+            self.due = Some(next_start + (old_due - seed_date));
+        }
+    }
+
+    /// Skips the current occurrence of a recurring task without completing it:
+    /// the dates move to the next occurrence and a COUNT limit is decremented.
+    /// Skipping the last occurrence (COUNT or UNTIL reached) drops the
+    /// recurrence and leaves the dates as they are. Returns false when the
+    /// task does not recur.
+    pub fn skip_occurrence(&mut self) -> bool {
+        if self.rrule.is_none() {
+            return false;
+        }
+        if !self.advance_occurrence() {
+            self.rrule = None;
+        }
+        true
+    }
 
-                return Some(next_task);
+    /// Rolls a stale recurring task forward to its first occurrence due (or
+    /// starting, without a due date) today or later. Stale means the occurrence
+    /// after the current one is already past, so a task overdue by less than
+    /// one interval stays put. Returns whether the dates moved.
+    pub fn catch_up(&mut self, today: NaiveDate) -> bool {
+        if self.rrule.is_none() || self.status.is_done() {
+            return false;
+        }
+        let is_past = |t: &Task| {
+            t.due_date()
+                .or_else(|| {
+                    t.dtstart
+                        .map(|d| d.with_timezone(&chrono::Local).date_naive())
+                })
+                .is_some_and(|day| day < today)
+        };
+        let mut ahead = self.clone();
+        if !ahead.advance_occurrence() || !is_past(&ahead) {
+            return false;
+        }
+        // Bounded in case of a rule with an absurdly small interval
+        for _ in 0..MAX_CATCH_UP_STEPS {
+            if !is_past(&ahead) || !ahead.advance_occurrence() {
+                break;
             }
         }
-        None
+        self.dtstart = ahead.dtstart;
+        self.due = ahead.due;
+        self.rrule = ahead.rrule;
+        true
+    }
+
+    /// Moves the dates to the next occurrence, spending one of a COUNT limit.
+    /// Returns false, leaving the task as is, when there is none.
+    fn advance_occurrence(&mut self) -> bool {
+        let Some(rule) = self.rrule.clone() else {
+            return false;
+        };
+        let Some(next_start) = self.next_occurrence() else {
+            return false;
+        };
+        self.shift_to(next_start);
+
+        let parts: Vec<String> = rule
+            .split(';')
+            .map(
+                |part| match part.strip_prefix("COUNT=").map(str::parse::<u32>) {
+                    Some(Ok(count)) => format!("COUNT={}", count.saturating_sub(1)),
+                    _ => part.to_string(),
+                },
+            )
+            .collect();
+        self.rrule = Some(parts.join(";"));
+        true
+    }
+
+    /// Absolute times at which the reminders fire, earliest first.
+    pub fn reminder_times(&self) -> Vec<DateTime<Utc>> {
+        let Some(due) = self.due else {
+            return Vec::new();
+        };
+        let mut times: Vec<DateTime<Utc>> = self
+            .reminders
+            .iter()
+            .map(|mins| due - chrono::Duration::minutes(*mins as i64))
+            .collect();
+        times.sort();
+        times
+    }
+
+    /// The next reminder still to fire, if the task is open and has a due date.
+    pub fn next_reminder_time(&self) -> Option<DateTime<Utc>> {
+        if self.status.is_done() {
+            return None;
+        }
+        let now = Utc::now();
+        self.reminder_times().into_iter().find(|t| *t > now)
+    }
+
+    /// Number of times a recurring task has been completed so far.
+    pub fn completion_count(&self) -> usize {
+        self.completed_instances.len()
     }
 
     pub fn to_ics(&self) -> String {
@@ -70,7 +190,7 @@ impl Task {
         todo.uid(&self.uid);
         todo.summary(&self.summary);
         if !self.description.is_empty() {
-            todo.description(&self.description);
+            todo.description(&normalize_newlines(&self.description));
         }
         todo.timestamp(Utc::now());
 
@@ -80,6 +200,11 @@ impl Task {
             TaskStatus::Completed => todo.status(TodoStatus::Completed),
             TaskStatus::Cancelled => todo.status(TodoStatus::Cancelled),
         };
+        if self.status == TaskStatus::Completed
+            && let Some(done_at) = self.completed_at
+        {
+            todo.add_property("COMPLETED", done_at.format("%Y%m%dT%H%M%SZ").to_string());
+        }
 
         fn format_iso_duration(mins: u32) -> String {
             if mins.is_multiple_of(24 * 60) {
@@ -97,8 +222,14 @@ impl Task {
         }
 
         if let Some(dt) = self.due {
-            let formatted = dt.format("%Y%m%dT%H%M%SZ").to_string();
-            todo.add_property("DUE", &formatted);
+            if self.due_is_date_only {
+                let mut prop = icalendar::Property::new("DUE", dt.format("%Y%m%d").to_string());
+                prop.add_parameter("VALUE", "DATE");
+                todo.append_property(prop);
+            } else {
+                let formatted = dt.format("%Y%m%dT%H%M%SZ").to_string();
+                todo.add_property("DUE", &formatted);
+            }
             if let Some(mins) = self.estimated_duration {
                 let val = format_iso_duration(mins);
                 todo.add_property("X-ESTIMATED-DURATION", &val);
@@ -110,9 +241,36 @@ impl Task {
         if self.priority > 0 {
             todo.priority(self.priority.into());
         }
+        if self.percent_complete > 0 {
+            todo.percent_complete(self.percent_complete.min(100));
+        }
         if let Some(rrule) = &self.rrule {
             todo.add_property("RRULE", rrule.as_str());
         }
+        if let Some(url) = &self.url {
+            todo.add_property("URL", url.as_str());
+        }
+        if let Some(color) = &self.color {
+            todo.add_property("COLOR", color.as_str());
+        }
+        if let Some(order) = self.sort_order {
+            todo.add_property("X-APPLE-SORT-ORDER", order.to_string().as_str());
+        }
+        if self.deferred {
+            todo.add_property("X-CFAIT-DEFERRED", "TRUE");
+        }
+        if self.time_spent > 0 {
+            todo.add_property("X-CFAIT-TIME-SPENT", self.time_spent.to_string().as_str());
+        }
+        if let Some((lat, lon)) = self.geo {
+            todo.add_property("GEO", format!("{};{}", lat, lon));
+        }
+        if let Some(modified) = self.last_modified {
+            todo.add_property(
+                "LAST-MODIFIED",
+                modified.format("%Y%m%dT%H%M%SZ").to_string(),
+            );
+        }
 
         // --- HIERARCHY & DEPENDENCIES ---
         if let Some(p_uid) = &self.parent_uid {
@@ -126,8 +284,44 @@ impl Task {
             todo.append_multi_property(prop);
         }
 
+        // Reminders are relative to DUE, which is the "end" of a VTODO (RFC 5545 3.8.6.3)
+        for mins in &self.reminders {
+            todo.alarm(Alarm::display(
+                &self.summary,
+                Trigger::before_end(chrono::Duration::minutes(*mins as i64)),
+            ));
+        }
+
+        // Attendees read from the server are written back as they came, below
+        let known: Vec<String> = self
+            .unmapped_properties
+            .iter()
+            .filter(|raw| raw.key == "ATTENDEE")
+            .map(attendee_name)
+            .collect();
+        for who in self.attendees.iter().filter(|who| !known.contains(who)) {
+            // ATTENDEE must be an address, so plain names also go in CN
+            let mut prop = icalendar::Property::new("ATTENDEE", format!("mailto:{}", who));
+            if !who.contains('@') {
+                prop.add_parameter("CN", who);
+            }
+            todo.append_multi_property(prop);
+        }
+
+        for done_at in &self.completed_instances {
+            let prop = icalendar::Property::new(
+                "X-CFAIT-COMPLETED",
+                done_at.format("%Y%m%dT%H%M%SZ").to_string(),
+            );
+            todo.append_multi_property(prop);
+        }
+
         // --- WRITE BACK UNMAPPED PROPERTIES ---
         for raw in &self.unmapped_properties {
+            // Unless the attendee was removed from the task
+            if raw.key == "ATTENDEE" && !self.attendees.contains(&attendee_name(raw)) {
+                continue;
+            }
             let mut prop = icalendar::Property::new(&raw.key, &raw.value);
             for (k, v) in &raw.params {
                 prop.add_parameter(k, v);
@@ -219,7 +413,7 @@ impl Task {
         };
 
         let summary = todo.get_summary().unwrap_or("No Title").to_string();
-        let description = todo.get_description().unwrap_or("").to_string();
+        let description = normalize_newlines(todo.get_description().unwrap_or(""));
         let uid = todo.get_uid().unwrap_or_default().to_string();
 
         let status = if let Some(prop) = todo.properties().get("STATUS") {
@@ -258,8 +452,14 @@ impl Task {
             }
         };
 
+        let due_is_date_only = todo.properties().get("DUE").is_some_and(|p| {
+            p.value().trim().len() == 8
+                || p.params()
+                    .get("VALUE")
+                    .is_some_and(|v| v.value().eq_ignore_ascii_case("DATE"))
+        });
         let due = todo.properties().get("DUE").and_then(|p| {
-            let val = p.value();
+            let val = p.value().trim();
             if val.len() == 8 {
                 NaiveDate::parse_from_str(val, "%Y%m%d")
                     .ok()
@@ -275,6 +475,58 @@ impl Task {
             .get("DTSTART")
             .and_then(|p| parse_date_prop(p.value()));
 
+        let percent_complete = todo
+            .properties()
+            .get("PERCENT-COMPLETE")
+            .and_then(|p| p.value().trim().parse::<u32>().ok())
+            .map_or(0, |p| p.min(100) as u8);
+
+        let url = todo
+            .properties()
+            .get("URL")
+            .map(|p| p.value().trim().to_string())
+            .filter(|u| !u.is_empty());
+
+        let color = todo
+            .properties()
+            .get("COLOR")
+            .map(|p| p.value().trim().to_string())
+            .filter(|c| !c.is_empty());
+
+        let sort_order = todo
+            .properties()
+            .get("X-APPLE-SORT-ORDER")
+            .and_then(|p| p.value().trim().parse::<i64>().ok());
+
+        let deferred = todo
+            .properties()
+            .get("X-CFAIT-DEFERRED")
+            .is_some_and(|p| p.value().trim().eq_ignore_ascii_case("TRUE"));
+
+        let time_spent = todo
+            .properties()
+            .get("X-CFAIT-TIME-SPENT")
+            .and_then(|p| p.value().trim().parse::<u32>().ok())
+            .unwrap_or(0);
+
+        let completed_at = todo
+            .properties()
+            .get("COMPLETED")
+            .and_then(|p| parse_date_prop(p.value()));
+
+        let last_modified = todo
+            .properties()
+            .get("LAST-MODIFIED")
+            .and_then(|p| parse_date_prop(p.value()));
+
+        // "lat;lon", only kept when both parts are valid coordinates
+        let geo = todo.properties().get("GEO").and_then(|p| {
+            let (lat, lon) = p.value().split_once(';')?;
+            let lat: f64 = lat.trim().parse().ok()?;
+            let lon: f64 = lon.trim().parse().ok()?;
+            ((-90.0..=90.0).contains(&lat) && (-180.0..=180.0).contains(&lon)).then_some((lat, lon))
+        });
+
         let rrule = todo
             .properties()
             .get("RRULE")
@@ -303,6 +555,7 @@ impl Task {
                                 minutes += n
                             }
                         }
+                        'S' if in_time => minutes += n / 60,
                         'W' => minutes += n * 7 * 24 * 60,
                         _ => {}
                     }
@@ -348,13 +601,62 @@ impl Task {
         categories.sort();
         categories.dedup();
 
+        // Same duplicate-key problem as RELATED-TO, so read these from the raw text.
+        let mut completed_instances: Vec<DateTime<Utc>> =
+            parse_multi_values_manually(raw_ics, "X-CFAIT-COMPLETED")
+                .iter()
+                .filter_map(|v| parse_date_prop(v))
+                .collect();
+        completed_instances.sort();
+
+        // Kept whole (PARTSTAT, ROLE...) so other participants' data survives an edit
+        let attendee_properties = parse_attendees_manually(raw_ics);
+        let mut attendees: Vec<String> = Vec::new();
+        for who in attendee_properties.iter().map(attendee_name) {
+            if !who.is_empty() && !attendees.contains(&who) {
+                attendees.push(who);
+            }
+        }
+
+        // Only relative triggers map onto reminders; absolute ones are dropped.
+        // RELATED defaults to START, which only means "due" if there is no DTSTART.
+        let mut reminders: Vec<u32> = todo
+            .components()
+            .iter()
+            .filter(|c| c.component_kind().eq_ignore_ascii_case("VALARM"))
+            .filter_map(|alarm| alarm.properties().get("TRIGGER"))
+            .filter(|trigger| {
+                let related_to_end = trigger
+                    .params()
+                    .get("RELATED")
+                    .is_some_and(|p| p.value().eq_ignore_ascii_case("END"));
+                related_to_end || dtstart.is_none()
+            })
+            .filter_map(|trigger| {
+                let val = trigger.value().trim();
+                let unsigned = val.trim_start_matches(['+', '-']);
+                if !unsigned.starts_with('P') {
+                    return None;
+                }
+                // Offsets after the due date aren't representable, zero means "at due"
+                match parse_dur(unsigned) {
+                    Some(mins) if val.starts_with('-') => Some(mins),
+                    Some(_) => None,
+                    None => Some(0),
+                }
+            })
+            .collect();
+        reminders.sort_unstable();
+        reminders.dedup();
+
         // --- OPTIMIZED RELATION EXTRACTION (MANUAL PARSE) ---
         // Use manual parsing to avoid issues where icalendar library overwrites duplicate keys
         // (e.g. RELATED-TO) when they are not explicitly handled as multi-properties.
-        let (parent_uid, dependencies) = parse_related_to_manually(raw_ics);
+        let (parent_uid, dependencies, other_relations) = parse_related_to_manually(raw_ics);
 
         // --- CAPTURE UNMAPPED PROPERTIES ---
-        let mut unmapped_properties = Vec::new();
+        let mut unmapped_properties = other_relations;
+        unmapped_properties.extend(attendee_properties);
 
         let to_raw = |prop: &icalendar::Property| -> RawProperty {
             let mut params = Vec::new();
@@ -374,7 +676,9 @@ impl Task {
 
         // Use case-insensitive check for handled keys
         for (key, prop) in todo.properties() {
-            if !HANDLED_KEYS.contains(&key.to_uppercase().as_str()) {
+            // A GEO we can't read is kept as is rather than dropped
+            let unreadable_geo = geo.is_none() && key.eq_ignore_ascii_case("GEO");
+            if unreadable_geo || !HANDLED_KEYS.contains(&key.to_uppercase().as_str()) {
                 unmapped_properties.push(to_raw(prop));
             }
         }
@@ -400,6 +704,7 @@ impl Task {
             due,
             dtstart,
             priority,
+            due_is_date_only: due_is_date_only && due.is_some(),
             parent_uid,
             dependencies,
             etag,
@@ -410,6 +715,18 @@ impl Task {
             rrule,
             unmapped_properties,
             raw_components,
+            completed_instances,
+            reminders,
+            completed_at,
+            percent_complete,
+            url,
+            sort_order,
+            deferred,
+            time_spent,
+            last_modified,
+            geo,
+            attendees,
+            color,
         })
     }
 }
@@ -417,44 +734,160 @@ impl Task {
 /// Helper: Manually parse RELATED-TO from raw ICS string.
 /// This handles unfolding lines and ensures we catch ALL occurrences,
 /// bypassing potential overwrites in the icalendar parser.
-fn parse_related_to_manually(raw_ics: &str) -> (Option<String>, Vec<String>) {
+/// Returns the parent (the first PARENT relation, the default RELTYPE), the
+/// DEPENDS-ON targets, and the relations we don't model (SIBLING, CHILD, extra
+/// parents...) to keep as unmapped properties.
+fn parse_related_to_manually(raw_ics: &str) -> (Option<String>, Vec<String>, Vec<RawProperty>) {
     let mut parent = None;
     let mut deps = Vec::new();
-    let mut current_line = String::new();
-
-    let process_line = |line: &str, p: &mut Option<String>, d: &mut Vec<String>| {
-        if line.to_uppercase().starts_with("RELATED-TO")
-            && let Some((params_part, value)) = line.split_once(':') {
-                let params_upper = params_part.to_uppercase();
-                // Naive check usually sufficient for RELTYPE=DEPENDS-ON
-                let is_dependency = params_upper.contains("RELTYPE=DEPENDS-ON");
-                let val = value.trim().to_string();
-                if is_dependency {
-                    if !d.contains(&val) {
-                        d.push(val);
-                    }
-                } else {
-                    *p = Some(val);
+    let mut others = Vec::new();
+
+    for line in unfold_lines(raw_ics) {
+        let Some((params_part, value)) = line.split_once(':') else {
+            continue;
+        };
+        let mut parts = params_part.split(';');
+        if !parts
+            .next()
+            .unwrap_or("")
+            .eq_ignore_ascii_case("RELATED-TO")
+        {
+            continue;
+        }
+        let mut params: Vec<(String, String)> = parts
+            .filter_map(|p| p.split_once('='))
+            .map(|(k, v)| (k.to_uppercase(), v.trim_matches('"').to_string()))
+            .collect();
+        params.sort_unstable();
+        let reltype = params
+            .iter()
+            .find(|(k, _)| k == "RELTYPE")
+            .map_or("PARENT".to_string(), |(_, v)| v.to_uppercase());
+        let val = value.trim().to_string();
+
+        match reltype.as_str() {
+            "DEPENDS-ON" => {
+                if !deps.contains(&val) {
+                    deps.push(val);
                 }
             }
-    };
+            "PARENT" if parent.is_none() => parent = Some(val),
+            _ => others.push(RawProperty {
+                key: "RELATED-TO".to_string(),
+                value: val,
+                params,
+            }),
+        }
+    }
 
+    (parent, deps, others)
+}
+
+/// Raw lines with the folded continuation lines joined back.
+fn unfold_lines(raw_ics: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
     for raw_line in raw_ics.lines() {
-        // Handle folding: Lines starting with whitespace are continuations
-        if raw_line.starts_with(' ') || raw_line.starts_with('\t') {
-            current_line.push_str(raw_line.trim_start());
+        if (raw_line.starts_with(' ') || raw_line.starts_with('\t'))
+            && let Some(last) = lines.last_mut()
+        {
+            last.push_str(raw_line.trim_start());
         } else {
-            if !current_line.is_empty() {
-                process_line(&current_line, &mut parent, &mut deps);
+            lines.push(raw_line.to_string());
+        }
+    }
+    lines
+}
+
+/// Helper: Collect the values of every occurrence of `key` in the raw ICS string,
+/// unfolding continuation lines first.
+fn parse_multi_values_manually(raw_ics: &str, key: &str) -> Vec<String> {
+    let mut values = Vec::new();
+    for line in unfold_lines(raw_ics) {
+        if let Some((name, value)) = line.split_once(':') {
+            let name = name.split(';').next().unwrap_or("");
+            if name.eq_ignore_ascii_case(key) {
+                values.push(value.trim().to_string());
+            }
+        }
+    }
+    values
+}
+
+/// Helper: The VTODO's ATTENDEE properties (not those of its alarms), with their
+/// parameters as written (quotes included) so they can be sent back unchanged.
+fn parse_attendees_manually(raw_ics: &str) -> Vec<RawProperty> {
+    let mut attendees = Vec::new();
+    let mut nested = 0usize;
+    for line in unfold_lines(raw_ics) {
+        let Some(mut prop) = parse_content_line(&line) else {
+            continue;
+        };
+        prop.key = prop.key.to_uppercase();
+        let inner = !matches!(prop.value.to_uppercase().as_str(), "VTODO" | "VCALENDAR");
+        match prop.key.as_str() {
+            "BEGIN" if inner => nested += 1,
+            "END" if inner => nested = nested.saturating_sub(1),
+            "ATTENDEE" if nested == 0 => attendees.push(prop),
+            _ => {}
+        }
+    }
+    attendees
+}
+
+/// Splits a content line into name, parameters and value. Colons and semicolons
+/// inside quoted parameter values (e.g. `DELEGATED-FROM="mailto:a@b.c"`) don't count.
+fn parse_content_line(line: &str) -> Option<RawProperty> {
+    let mut quoted = false;
+    let mut fields = Vec::new();
+    let mut start = 0;
+    for (i, c) in line.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            ';' | ':' if !quoted => {
+                fields.push(&line[start..i]);
+                start = i + 1;
+                if c == ':' {
+                    let params = fields[1..]
+                        .iter()
+                        .filter_map(|p| p.split_once('='))
+                        .map(|(k, v)| (k.to_uppercase(), v.to_string()))
+                        .collect();
+                    return Some(RawProperty {
+                        key: fields[0].to_string(),
+                        value: line[start..].trim().to_string(),
+                        params,
+                    });
+                }
             }
-            current_line = raw_line.to_string();
+            _ => {}
         }
     }
-    if !current_line.is_empty() {
-        process_line(&current_line, &mut parent, &mut deps);
+    None
+}
+
+/// The name an ATTENDEE is shown and typed as: the address when it is an email,
+/// else the common name, else the URI.
+fn attendee_name(raw: &RawProperty) -> String {
+    let value = raw.value.as_str();
+    let address = value
+        .get(..7)
+        .filter(|p| p.eq_ignore_ascii_case("mailto:"))
+        .map_or(value, |_| &value[7..]);
+    let cn = raw
+        .params
+        .iter()
+        .find(|(k, _)| k.eq_ignore_ascii_case("CN"))
+        .map(|(_, v)| v.trim_matches('"').to_string());
+    match cn {
+        Some(cn) if !address.contains('@') && !cn.is_empty() => cn,
+        _ => address.to_string(),
     }
+}
 
-    (parent, deps)
+/// Line breaks as plain `\n`: a CR would end up unescaped inside the
+/// DESCRIPTION line (editors on Windows and pasted text use CRLF).
+fn normalize_newlines(text: &str) -> String {
+    text.replace("\r\n", "\n").replace('\r', "\n")
 }
 
 #[cfg(test)]
@@ -496,6 +929,67 @@ END:VCALENDAR";
         );
     }
 
+    #[test]
+    fn test_unknown_relations_round_trip() {
+        let ics = "BEGIN:VCALENDAR
+VERSION:2.0
+BEGIN:VTODO
+UID:task-uid
+SUMMARY:Related
+RELATED-TO;RELTYPE=PARENT:first-parent
+RELATED-TO:second-parent
+RELATED-TO;RELTYPE=SIBLING:sibling-uid
+RELATED-TO;RELTYPE=DEPENDS-ON:blocker-uid
+RELATED-TO;RELTYPE=CHILD:child-uid
+END:VTODO
+END:VCALENDAR";
+        let parse = |ics: &str| {
+            Task::from_ics(
+                ics,
+                "etag".to_string(),
+                "/href".to_string(),
+                "/cal/".to_string(),
+            )
+            .expect("Failed to parse ICS")
+        };
+
+        let task = parse(ics);
+        assert_eq!(task.parent_uid.as_deref(), Some("first-parent"));
+        assert_eq!(task.dependencies, vec!["blocker-uid"]);
+        let kept: Vec<&str> = task
+            .unmapped_properties
+            .iter()
+            .map(|p| p.value.as_str())
+            .collect();
+        assert_eq!(kept, vec!["child-uid", "second-parent", "sibling-uid"]);
+
+        let ics = task.to_ics();
+        assert!(ics.contains("RELATED-TO;RELTYPE=SIBLING:sibling-uid"));
+        assert!(ics.contains("RELATED-TO;RELTYPE=CHILD:child-uid"));
+        assert!(ics.contains("RELATED-TO:second-parent"));
+        let reparsed = parse(&ics);
+        assert_eq!(reparsed.parent_uid.as_deref(), Some("first-parent"));
+        assert_eq!(reparsed.dependencies, task.dependencies);
+        assert_eq!(reparsed.unmapped_properties, task.unmapped_properties);
+
+        // Outdenting drops the second parent too, so it doesn't come back as the parent
+        let mut outdented = reparsed;
+        outdented.set_parent(None);
+        let reparsed = parse(&outdented.to_ics());
+        assert_eq!(reparsed.parent_uid, None);
+        assert!(
+            reparsed
+                .to_ics()
+                .contains("RELATED-TO;RELTYPE=SIBLING:sibling-uid")
+        );
+
+        let mut moved = parse(&ics);
+        moved.set_parent(Some("new-parent".to_string()));
+        let reparsed = parse(&moved.to_ics());
+        assert_eq!(reparsed.parent_uid.as_deref(), Some("new-parent"));
+        assert!(!reparsed.to_ics().contains("second-parent"));
+    }
+
     #[test]
     fn test_ghost_properties_exclusion_case_insensitive() {
         // Validates that properties with different casing (e.g. Related-To vs RELATED-TO)
@@ -553,4 +1047,575 @@ END:VCALENDAR";
             "Folded lines should be unwrapped correctly"
         );
     }
+
+    #[test]
+    fn test_completion_history_round_trip() {
+        let ics = "BEGIN:VCALENDAR
+VERSION:2.0
+BEGIN:VTODO
+UID:habit
+SUMMARY:Daily habit
+DTSTART:20240101T080000Z
+RRULE:FREQ=DAILY
+END:VTODO
+END:VCALENDAR";
+
+        let task = Task::from_ics(
+            ics,
+            "etag".to_string(),
+            "/href".to_string(),
+            "/cal/".to_string(),
+        )
+        .expect("Failed to parse ICS");
+        assert_eq!(task.completion_count(), 0);
+
+        let next = task.respawn().expect("Recurring task should respawn");
+        let next = next.respawn().expect("Recurring task should respawn");
+        assert_eq!(next.completion_count(), 2);
+
+        let reparsed = Task::from_ics(
+            &next.to_ics(),
+            "etag".to_string(),
+            "/href".to_string(),
+            "/cal/".to_string(),
+        )
+        .expect("Failed to parse generated ICS");
+        assert_eq!(reparsed.completion_count(), 2);
+        assert!(
+            reparsed.unmapped_properties.is_empty(),
+            "Completion history should not leak into unmapped properties"
+        );
+    }
+
+    #[test]
+    fn test_valarm_reminders_round_trip() {
+        let ics = "BEGIN:VCALENDAR
+VERSION:2.0
+BEGIN:VTODO
+UID:alarm
+SUMMARY:Pay rent
+DUE:20300101T120000Z
+BEGIN:VALARM
+ACTION:DISPLAY
+DESCRIPTION:Pay rent
+TRIGGER;RELATED=END:-PT15M
+END:VALARM
+BEGIN:VALARM
+ACTION:DISPLAY
+DESCRIPTION:Pay rent
+TRIGGER:-P1D
+END:VALARM
+END:VTODO
+END:VCALENDAR";
+
+        let task = Task::from_ics(
+            ics,
+            "etag".to_string(),
+            "/href".to_string(),
+            "/cal/".to_string(),
+        )
+        .expect("Failed to parse ICS");
+        assert_eq!(task.reminders, vec![15, 1440]);
+        assert_eq!(
+            task.next_reminder_time(),
+            Some(Utc.with_ymd_and_hms(2029, 12, 31, 12, 0, 0).unwrap())
+        );
+
+        let reparsed = Task::from_ics(
+            &task.to_ics(),
+            "etag".to_string(),
+            "/href".to_string(),
+            "/cal/".to_string(),
+        )
+        .expect("Failed to parse generated ICS");
+        assert_eq!(reparsed.reminders, vec![15, 1440]);
+    }
+
+    #[test]
+    fn test_completed_timestamp_round_trip() {
+        let ics = "BEGIN:VCALENDAR
+VERSION:2.0
+BEGIN:VTODO
+UID:done
+SUMMARY:File taxes
+STATUS:COMPLETED
+COMPLETED:20250301T093000Z
+END:VTODO
+END:VCALENDAR";
+
+        let task = Task::from_ics(
+            ics,
+            "etag".to_string(),
+            "/href".to_string(),
+            "/cal/".to_string(),
+        )
+        .expect("Failed to parse ICS");
+        let done_at = Utc.with_ymd_and_hms(2025, 3, 1, 9, 30, 0).unwrap();
+        assert_eq!(task.completed_at, Some(done_at));
+        assert!(
+            task.unmapped_properties
+                .iter()
+                .all(|p| p.key != "COMPLETED")
+        );
+
+        let reparsed = Task::from_ics(
+            &task.to_ics(),
+            "etag".to_string(),
+            "/href".to_string(),
+            "/cal/".to_string(),
+        )
+        .expect("Failed to parse generated ICS");
+        assert_eq!(reparsed.completed_at, Some(done_at));
+    }
+
+    #[test]
+    fn test_percent_complete() {
+        let ics = "BEGIN:VCALENDAR
+VERSION:2.0
+BEGIN:VTODO
+UID:pct
+SUMMARY:Paint fence
+STATUS:IN-PROCESS
+PERCENT-COMPLETE:150
+END:VTODO
+END:VCALENDAR";
+
+        let mut task = Task::from_ics(
+            ics,
+            "etag".to_string(),
+            "/href".to_string(),
+            "/cal/".to_string(),
+        )
+        .expect("Failed to parse ICS");
+        assert_eq!(task.percent_complete, 100);
+
+        task.apply_smart_input("Paint fence ~%40", &std::collections::HashMap::new());
+        assert_eq!(task.percent_complete, 40);
+        assert_eq!(task.to_smart_string(), "Paint fence ~%40");
+        assert!(task.to_ics().contains("PERCENT-COMPLETE:40"));
+
+        task.set_status(TaskStatus::Completed);
+        assert_eq!(task.percent_complete, 100);
+        task.set_status(TaskStatus::NeedsAction);
+        assert_eq!(task.percent_complete, 100);
+    }
+
+    #[test]
+    fn test_url_round_trip() {
+        let mut task = Task::new(
+            "Review PR url:https://github.com/trougnouf/cfait/pull/1 #dev",
+            &std::collections::HashMap::new(),
+        );
+        assert_eq!(task.summary, "Review PR");
+        assert_eq!(
+            task.url.as_deref(),
+            Some("https://github.com/trougnouf/cfait/pull/1")
+        );
+        assert_eq!(
+            task.to_smart_string(),
+            "Review PR url:https://github.com/trougnouf/cfait/pull/1 #dev"
+        );
+
+        task.uid = "url".to_string();
+        let ics = task.to_ics();
+        assert!(ics.contains("URL:https://github.com/trougnouf/cfait/pull/1"));
+        let reparsed = Task::from_ics(
+            &ics,
+            "etag".to_string(),
+            "/href".to_string(),
+            "/cal/".to_string(),
+        )
+        .expect("Failed to parse ICS");
+        assert_eq!(reparsed.url, task.url);
+        assert!(reparsed.unmapped_properties.iter().all(|p| p.key != "URL"));
+    }
+
+    #[test]
+    fn test_color_round_trip() {
+        let mut task = Task::new(
+            "Pay rent color:Red color:nope #home",
+            &std::collections::HashMap::new(),
+        );
+        assert_eq!(task.summary, "Pay rent color:nope");
+        assert_eq!(task.color.as_deref(), Some("red"));
+        assert_eq!(
+            task.to_smart_string(),
+            "Pay rent color:nope color:red #home"
+        );
+
+        task.uid = "color".to_string();
+        let ics = task.to_ics();
+        assert!(ics.contains("COLOR:red"));
+        let reparsed = Task::from_ics(
+            &ics,
+            "etag".to_string(),
+            "/href".to_string(),
+            "/cal/".to_string(),
+        )
+        .expect("Failed to parse ICS");
+        assert_eq!(reparsed.color.as_deref(), Some("red"));
+        assert!(
+            reparsed
+                .unmapped_properties
+                .iter()
+                .all(|p| p.key != "COLOR")
+        );
+    }
+
+    #[test]
+    fn test_unknown_color_survives_an_edit() {
+        let ics = "BEGIN:VCALENDAR
+VERSION:2.0
+BEGIN:VTODO
+UID:teal-uid
+SUMMARY:Water plants
+COLOR:teal
+END:VTODO
+END:VCALENDAR";
+        let mut task = Task::from_ics(
+            ics,
+            "etag".to_string(),
+            "/href".to_string(),
+            "/cal/".to_string(),
+        )
+        .expect("Failed to parse ICS");
+        assert_eq!(task.color.as_deref(), Some("teal"));
+
+        // Not offered for editing, and not dropped by it
+        let input = task.to_smart_string();
+        assert_eq!(input, "Water plants");
+        task.apply_smart_input(&input, &std::collections::HashMap::new());
+        assert_eq!(task.color.as_deref(), Some("teal"));
+        assert!(task.to_ics().contains("COLOR:teal"));
+
+        // Picking a color replaces it
+        task.apply_smart_input(
+            "Water plants color:green",
+            &std::collections::HashMap::new(),
+        );
+        assert_eq!(task.color.as_deref(), Some("green"));
+    }
+
+    #[test]
+    fn test_attendees_round_trip() {
+        let mut task = Task::new(
+            "Get the keys @@alice @@bob@example.com @tomorrow",
+            &std::collections::HashMap::new(),
+        );
+        assert_eq!(task.summary, "Get the keys");
+        assert_eq!(task.attendees, ["alice", "bob@example.com"]);
+        assert!(task.due.is_some());
+        assert!(
+            task.to_smart_string()
+                .contains(" @@alice @@bob@example.com")
+        );
+        assert!(task.matches_search_term("@@ali"));
+        assert!(!task.matches_search_term("@@carol"));
+
+        task.uid = "attendees".to_string();
+        let ics = task.to_ics();
+        assert!(ics.contains("ATTENDEE;CN=alice:mailto:alice"));
+        assert!(ics.contains("ATTENDEE:mailto:bob@example.com"));
+        let reparsed = Task::from_ics(
+            &ics,
+            "etag".to_string(),
+            "/href".to_string(),
+            "/cal/".to_string(),
+        )
+        .expect("Failed to parse ICS");
+        assert_eq!(reparsed.attendees, task.attendees);
+        assert!(
+            reparsed
+                .unmapped_properties
+                .iter()
+                .all(|p| p.key == "ATTENDEE")
+        );
+        assert_eq!(reparsed.to_ics().matches("ATTENDEE").count(), 2);
+
+        // Server attendees use their address, alarm attendees are ignored
+        let ics = "BEGIN:VCALENDAR\nVERSION:2.0\nBEGIN:VTODO\nUID:a\nSUMMARY:Sign\n\
+            ATTENDEE;CN=\"Carol\";PARTSTAT=NEEDS-ACTION:MAILTO:carol@example.com\n\
+            BEGIN:VALARM\nACTION:EMAIL\nATTENDEE:mailto:me@example.com\nEND:VALARM\n\
+            END:VTODO\nEND:VCALENDAR";
+        let task = Task::from_ics(ics, String::new(), String::new(), String::new()).unwrap();
+        assert_eq!(task.attendees, ["carol@example.com"]);
+    }
+
+    #[test]
+    fn test_attendee_parameters_survive_an_edit() {
+        let ics = "BEGIN:VCALENDAR\nVERSION:2.0\nBEGIN:VTODO\nUID:a\nSUMMARY:Sign\n\
+            ATTENDEE;CN=\"Carol\";PARTSTAT=ACCEPTED;ROLE=REQ-PARTICIPANT;RSVP=TRUE;\n \
+            DELEGATED-FROM=\"mailto:dave@example.com\":mailto:carol@example.com\n\
+            ATTENDEE;CN=Erin;PARTSTAT=DECLINED:urn:uuid:1234\n\
+            END:VTODO\nEND:VCALENDAR";
+        let mut task = Task::from_ics(ics, String::new(), String::new(), String::new()).unwrap();
+        assert_eq!(task.attendees, ["carol@example.com", "Erin"]);
+
+        task.apply_smart_input(&task.to_smart_string(), &std::collections::HashMap::new());
+        let unfolded = task.to_ics().replace("\r\n ", "");
+        let carol = unfolded
+            .lines()
+            .find(|l| l.contains("carol@example.com"))
+            .unwrap();
+        for param in [
+            "CN=\"Carol\"",
+            "PARTSTAT=ACCEPTED",
+            "ROLE=REQ-PARTICIPANT",
+            "RSVP=TRUE",
+            "DELEGATED-FROM=\"mailto:dave@example.com\"",
+        ] {
+            assert!(carol.contains(param), "{} lost in {}", param, carol);
+        }
+        assert!(carol.ends_with(":mailto:carol@example.com"));
+        assert!(unfolded.contains(":urn:uuid:1234"));
+        assert!(!unfolded.contains("mailto:Erin"));
+        assert_eq!(unfolded.matches("ATTENDEE").count(), 2);
+
+        // Removing someone drops their line, adding someone writes a new one
+        task.apply_smart_input("Sign @@Erin @@frank@example.com", &Default::default());
+        let ics = task.to_ics();
+        assert!(!ics.contains("carol@example.com"));
+        assert!(ics.contains("PARTSTAT=DECLINED"));
+        assert!(ics.contains("ATTENDEE:mailto:frank@example.com"));
+    }
+
+    #[test]
+    fn test_sort_order_round_trip() {
+        let mut task = Task::new("Ordered", &std::collections::HashMap::new());
+        task.uid = "ordered".to_string();
+        task.sort_order = Some(3000);
+        let ics = task.to_ics();
+        assert!(ics.contains("X-APPLE-SORT-ORDER:3000"));
+        let reparsed = Task::from_ics(
+            &ics,
+            "etag".to_string(),
+            "/href".to_string(),
+            "/cal/".to_string(),
+        )
+        .expect("Failed to parse ICS");
+        assert_eq!(reparsed.sort_order, Some(3000));
+        assert!(reparsed.unmapped_properties.is_empty());
+    }
+
+    #[test]
+    fn test_last_modified_round_trip() {
+        let mut task = Task::new("Edited", &std::collections::HashMap::new());
+        assert!(!task.to_ics().contains("LAST-MODIFIED"));
+        task.last_modified = Some(Utc.with_ymd_and_hms(2025, 3, 1, 9, 30, 0).unwrap());
+        let ics = task.to_ics();
+        assert!(ics.contains("LAST-MODIFIED:20250301T093000Z"));
+        let reparsed = Task::from_ics(
+            &ics,
+            "etag".to_string(),
+            "/href".to_string(),
+            "/cal/".to_string(),
+        )
+        .expect("Failed to parse ICS");
+        assert_eq!(reparsed.last_modified, task.last_modified);
+        assert!(reparsed.unmapped_properties.is_empty());
+    }
+
+    #[test]
+    fn test_all_day_due_round_trip() {
+        let ics = "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nBEGIN:VTODO\r\nUID:allday\r\n\
+                   SUMMARY:Water plants\r\nDUE;VALUE=DATE:20250601\r\nEND:VTODO\r\n\
+                   END:VCALENDAR\r\n";
+        let task = Task::from_ics(
+            ics,
+            "etag".to_string(),
+            "/href".to_string(),
+            "/cal/".to_string(),
+        )
+        .expect("Failed to parse ICS");
+        assert!(task.due_is_date_only);
+        // The stored day, whatever the local timezone
+        assert_eq!(task.due_date(), NaiveDate::from_ymd_opt(2025, 6, 1));
+        assert_eq!(task.format_due().as_deref(), Some("2025-06-01"));
+        let out = task.to_ics();
+        assert!(out.contains("DUE;VALUE=DATE:20250601"), "{}", out);
+        assert!(task.unmapped_properties.is_empty());
+
+        // Smart input dates are all-day, timed dues stay timed
+        let typed = Task::new("Call @2025-06-01", &std::collections::HashMap::new());
+        assert!(typed.due_is_date_only);
+        assert!(typed.to_ics().contains("DUE;VALUE=DATE:20250601"));
+        let timed = out.replace("DUE;VALUE=DATE:20250601", "DUE:20250601T140000Z");
+        let timed = Task::from_ics(
+            &timed,
+            "etag".to_string(),
+            "/href".to_string(),
+            "/cal/".to_string(),
+        )
+        .expect("Failed to parse ICS");
+        assert!(!timed.due_is_date_only);
+        assert!(timed.to_ics().contains("DUE:20250601T140000Z"));
+    }
+
+    #[test]
+    fn test_geo_round_trip() {
+        let mut task = Task::new("Pick up parcel", &std::collections::HashMap::new());
+        task.geo = Some((50.8503, 4.3517));
+        let ics = task.to_ics();
+        assert!(ics.contains("GEO:50.8503;4.3517"));
+        let reparsed = Task::from_ics(
+            &ics,
+            "etag".to_string(),
+            "/href".to_string(),
+            "/cal/".to_string(),
+        )
+        .expect("Failed to parse ICS");
+        assert_eq!(reparsed.geo, task.geo);
+        assert!(reparsed.unmapped_properties.is_empty());
+
+        // Brussels to Paris, about 264 km
+        let km = reparsed.distance_to(48.8566, 2.3522).unwrap();
+        assert!((km - 264.0).abs() < 2.0, "{}", km);
+        assert_eq!(
+            Task::new("Nowhere", &Default::default()).distance_to(0.0, 0.0),
+            None
+        );
+
+        let bad = ics.replace("GEO:50.8503;4.3517", "GEO:north;4.35");
+        let reparsed = Task::from_ics(
+            &bad,
+            "etag".to_string(),
+            "/href".to_string(),
+            "/cal/".to_string(),
+        )
+        .expect("Failed to parse ICS");
+        assert_eq!(reparsed.geo, None);
+        assert!(reparsed.to_ics().contains("GEO:north;4.35"));
+    }
+
+    #[test]
+    fn test_deferred_round_trip() {
+        let mut task = Task::new("Learn the cello", &std::collections::HashMap::new());
+        task.uid = "someday".to_string();
+        assert!(!task.to_ics().contains("X-CFAIT-DEFERRED"));
+        task.deferred = true;
+        let ics = task.to_ics();
+        assert!(ics.contains("X-CFAIT-DEFERRED:TRUE"));
+        let reparsed = Task::from_ics(
+            &ics,
+            "etag".to_string(),
+            "/href".to_string(),
+            "/cal/".to_string(),
+        )
+        .expect("Failed to parse ICS");
+        assert!(reparsed.deferred);
+        assert!(reparsed.unmapped_properties.is_empty());
+    }
+
+    #[test]
+    fn test_time_spent_round_trip() {
+        let mut task = Task::new("Write report ~1h", &std::collections::HashMap::new());
+        assert!(!task.to_ics().contains("X-CFAIT-TIME-SPENT"));
+        task.time_spent = 95;
+        let ics = task.to_ics();
+        assert!(ics.contains("X-CFAIT-TIME-SPENT:95"));
+        let reparsed = Task::from_ics(
+            &ics,
+            "etag".to_string(),
+            "/href".to_string(),
+            "/cal/".to_string(),
+        )
+        .expect("Failed to parse ICS");
+        assert_eq!(reparsed.time_spent, 95);
+        assert_eq!(reparsed.estimated_duration, Some(60));
+        assert!(reparsed.unmapped_properties.is_empty());
+    }
+
+    #[test]
+    fn test_skip_occurrence() {
+        use std::collections::HashMap;
+        let start = Utc.with_ymd_and_hms(2025, 3, 3, 9, 0, 0).unwrap();
+        let mut task = Task::new("Standup", &HashMap::new());
+        task.dtstart = Some(start);
+        task.due = Some(start + chrono::Duration::hours(1));
+        task.rrule = Some("FREQ=DAILY;COUNT=3".to_string());
+
+        assert!(task.skip_occurrence());
+        assert_eq!(task.dtstart, Some(start + chrono::Duration::days(1)));
+        assert_eq!(
+            task.due,
+            Some(start + chrono::Duration::days(1) + chrono::Duration::hours(1))
+        );
+        assert_eq!(task.rrule.as_deref(), Some("FREQ=DAILY;COUNT=2"));
+        assert!(task.completed_instances.is_empty());
+        assert_eq!(task.status, TaskStatus::NeedsAction);
+
+        // Down to the last occurrence, then past it
+        assert!(task.skip_occurrence());
+        assert_eq!(task.rrule.as_deref(), Some("FREQ=DAILY;COUNT=1"));
+        let last = task.dtstart;
+        assert!(task.skip_occurrence());
+        assert!(task.rrule.is_none());
+        assert_eq!(task.dtstart, last);
+
+        // UNTIL reached
+        let mut until = Task::new("Trip prep", &HashMap::new());
+        until.due = Some(start);
+        until.rrule = Some("FREQ=WEEKLY;UNTIL=20250305T000000Z".to_string());
+        assert!(until.skip_occurrence());
+        assert!(until.rrule.is_none());
+        assert_eq!(until.due, Some(start));
+
+        assert!(!Task::new("Plain", &HashMap::new()).skip_occurrence());
+    }
+
+    #[test]
+    fn test_catch_up_stale_occurrences() {
+        use std::collections::HashMap;
+        let today = NaiveDate::from_ymd_opt(2025, 3, 10).unwrap();
+        let mut plants = Task::new("Water plants", &HashMap::new());
+        plants.due = Some(Utc.with_ymd_and_hms(2025, 3, 4, 0, 0, 0).unwrap());
+        plants.due_is_date_only = true;
+        plants.rrule = Some("FREQ=DAILY;COUNT=10".to_string());
+
+        assert!(plants.catch_up(today));
+        assert_eq!(plants.due_date(), Some(today));
+        // Six occurrences skipped
+        assert_eq!(plants.rrule.as_deref(), Some("FREQ=DAILY;COUNT=4"));
+        assert!(!plants.catch_up(today));
+
+        // Overdue by less than one interval: left alone
+        let mut weekly = Task::new("Review", &HashMap::new());
+        weekly.due = Some(Utc.with_ymd_and_hms(2025, 3, 6, 0, 0, 0).unwrap());
+        weekly.due_is_date_only = true;
+        weekly.rrule = Some("FREQ=WEEKLY".to_string());
+        assert!(!weekly.catch_up(today));
+
+        let mut plain = Task::new("Once", &HashMap::new());
+        plain.due = Some(Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap());
+        assert!(!plain.catch_up(today));
+        assert_eq!(
+            plain.due,
+            Some(Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap())
+        );
+    }
+
+    #[test]
+    fn test_multiline_description_round_trip() {
+        let mut task = Task::new("Notes", &std::collections::HashMap::new());
+        task.description = "First paragraph, with; punctuation\\\n\n\nAfter two blank lines \
+                            and a line long enough to be folded by the serializer\r\n- [ ] item"
+            .to_string();
+        let ics = task.to_ics();
+        assert!(!ics.contains("\r\\n") && !ics.contains("\\n\r"));
+
+        let parsed = Task::from_ics(&ics, "e".to_string(), "/h".to_string(), "/c/".to_string())
+            .expect("Failed to parse ICS");
+        assert_eq!(
+            parsed.description,
+            "First paragraph, with; punctuation\\\n\n\nAfter two blank lines and a line long \
+             enough to be folded by the serializer\n- [ ] item"
+        );
+
+        // Uppercase \N is a valid escape too
+        let ics = "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nBEGIN:VTODO\r\nUID:n\r\nSUMMARY:x\r\n\
+                   DESCRIPTION:a\\N\\nb\r\nEND:VTODO\r\nEND:VCALENDAR\r\n";
+        let parsed = Task::from_ics(ics, "e".to_string(), "/h".to_string(), "/c/".to_string())
+            .expect("Failed to parse ICS");
+        assert_eq!(parsed.description, "a\n\nb");
+    }
 }
@@ -0,0 +1,87 @@
+// File: src/model/checklist.rs
+// Markdown-style checklists ("- [ ] item") inside task descriptions
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DescriptionLine<'a> {
+    Text(&'a str),
+    Checkbox { checked: bool, label: &'a str },
+}
+
+/// Recognizes `- [ ] label`, `- [x] label` (also `*` bullets and `X`), ignoring indentation.
+fn parse_checkbox(line: &str) -> Option<(bool, &str)> {
+    let rest = line
+        .trim_start()
+        .strip_prefix("- ")
+        .or_else(|| line.trim_start().strip_prefix("* "))?;
+    let (checked, label) = if let Some(l) = rest.strip_prefix("[ ]") {
+        (false, l)
+    } else if let Some(l) = rest
+        .strip_prefix("[x]")
+        .or_else(|| rest.strip_prefix("[X]"))
+    {
+        (true, l)
+    } else {
+        return None;
+    };
+    Some((checked, label.trim()))
+}
+
+/// Splits a description into lines, tagging the ones that are checklist items.
+pub fn parse_description(description: &str) -> Vec<DescriptionLine<'_>> {
+    description
+        .lines()
+        .map(|line| match parse_checkbox(line) {
+            Some((checked, label)) => DescriptionLine::Checkbox { checked, label },
+            None => DescriptionLine::Text(line),
+        })
+        .collect()
+}
+
+/// Flips the checkbox on line `line_idx`, leaving everything else untouched.
+/// Returns `None` if that line is not a checklist item.
+pub fn toggle_checkbox(description: &str, line_idx: usize) -> Option<String> {
+    let mut lines: Vec<String> = description.split('\n').map(String::from).collect();
+    let line = lines.get_mut(line_idx)?;
+    let (checked, _) = parse_checkbox(line)?;
+
+    let (from, to) = if checked {
+        (if line.contains("[x]") { "[x]" } else { "[X]" }, "[ ]")
+    } else {
+        ("[ ]", "[x]")
+    };
+    *line = line.replacen(from, to, 1);
+    Some(lines.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_toggle_checklist() {
+        let desc = "Groceries:\n- [ ] milk\n  * [X] eggs\n- not a box";
+        let lines = parse_description(desc);
+        assert_eq!(lines[0], DescriptionLine::Text("Groceries:"));
+        assert_eq!(
+            lines[1],
+            DescriptionLine::Checkbox {
+                checked: false,
+                label: "milk"
+            }
+        );
+        assert_eq!(
+            lines[2],
+            DescriptionLine::Checkbox {
+                checked: true,
+                label: "eggs"
+            }
+        );
+        assert_eq!(lines[3], DescriptionLine::Text("- not a box"));
+
+        let toggled = toggle_checkbox(desc, 1).unwrap();
+        assert_eq!(toggled, "Groceries:\n- [x] milk\n  * [X] eggs\n- not a box");
+        let toggled = toggle_checkbox(&toggled, 2).unwrap();
+        assert_eq!(toggled, "Groceries:\n- [x] milk\n  * [ ] eggs\n- not a box");
+        assert!(toggle_checkbox(desc, 0).is_none());
+    }
+}
@@ -0,0 +1,165 @@
+// File: src/model/completion.rs
+// Tab-completion of `#tag` tokens in the smart input
+use std::collections::HashMap;
+
+/// Matches being cycled through by repeated Tab presses.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TagCompletion {
+    /// Char index of the `#` starting the completed token
+    start: usize,
+    matches: Vec<String>,
+    index: usize,
+}
+
+/// Known tags and alias names, sorted and without duplicates.
+pub fn tag_candidates<I: IntoIterator<Item = String>>(
+    categories: I,
+    aliases: &HashMap<String, Vec<String>>,
+) -> Vec<String> {
+    let mut all: Vec<String> = categories
+        .into_iter()
+        .chain(aliases.keys().cloned())
+        .collect();
+    all.sort();
+    all.dedup();
+    all
+}
+
+/// The `#tag` token ending at char index `cursor`, as the char index of its `#`
+/// and the partial tag after it.
+pub fn tag_token_at(input: &str, cursor: usize) -> Option<(usize, String)> {
+    let before: Vec<char> = input.chars().take(cursor).collect();
+    let start = before
+        .iter()
+        .rposition(|c| c.is_whitespace())
+        .map_or(0, |i| i + 1);
+    let token: String = before[start..].iter().collect();
+    let partial = token.strip_prefix('#')?;
+    // `#a=#b` alias definitions are not completed
+    if partial.contains('=') || partial.contains('#') {
+        return None;
+    }
+    Some((start, partial.to_string()))
+}
+
+/// Candidates starting with `partial` (case-insensitive).
+pub fn matching_tags(partial: &str, candidates: &[String]) -> Vec<String> {
+    let lower = partial.to_lowercase();
+    candidates
+        .iter()
+        .filter(|c| c.to_lowercase().starts_with(&lower) && c.as_str() != partial)
+        .cloned()
+        .collect()
+}
+
+/// Suggestions for the token at `cursor`, for display under the input.
+pub fn suggestions(input: &str, cursor: usize, candidates: &[String]) -> Vec<String> {
+    tag_token_at(input, cursor)
+        .map(|(_, partial)| matching_tags(&partial, candidates))
+        .unwrap_or_default()
+}
+
+fn common_prefix(matches: &[String]) -> String {
+    let Some(first) = matches.first() else {
+        return String::new();
+    };
+    let mut len = first.chars().count();
+    for other in &matches[1..] {
+        len = first
+            .chars()
+            .zip(other.chars())
+            .take(len)
+            .take_while(|(a, b)| a.to_lowercase().eq(b.to_lowercase()))
+            .count();
+    }
+    first.chars().take(len).collect()
+}
+
+/// Completes the `#tag` token at `cursor`: first to the common prefix of the
+/// matches, then to each match in turn on repeated calls. `cycle` carries the
+/// state between calls and should be cleared when any other key is pressed.
+/// Returns the new input and cursor.
+pub fn complete_tag(
+    input: &str,
+    cursor: usize,
+    candidates: &[String],
+    cycle: &mut Option<TagCompletion>,
+) -> Option<(String, usize)> {
+    let (start, partial) = tag_token_at(input, cursor)?;
+
+    let replacement = match cycle {
+        Some(c) if c.start == start && c.matches.get(c.index) == Some(&partial) => {
+            c.index = (c.index + 1) % c.matches.len();
+            c.matches[c.index].clone()
+        }
+        _ => {
+            let matches = matching_tags(&partial, candidates);
+            if matches.is_empty() {
+                *cycle = None;
+                return None;
+            }
+            let prefix = common_prefix(&matches);
+            if prefix.chars().count() > partial.chars().count() {
+                *cycle = None;
+                prefix
+            } else {
+                let first = matches[0].clone();
+                *cycle = Some(TagCompletion {
+                    start,
+                    matches,
+                    index: 0,
+                });
+                first
+            }
+        }
+    };
+
+    let head: String = input.chars().take(start).collect();
+    let tail: String = input.chars().skip(cursor).collect();
+    let new_cursor = start + 1 + replacement.chars().count();
+    Some((format!("{}#{}{}", head, replacement, tail), new_cursor))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tags(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_complete_tag_prefix_then_cycle() {
+        let candidates = tags(&["work", "work:dev", "world", "home"]);
+        let mut cycle = None;
+
+        // "wo" -> common prefix "wor"
+        let (input, cursor) = complete_tag("Call #wo", 8, &candidates, &mut cycle).unwrap();
+        assert_eq!(input, "Call #wor");
+        assert_eq!(cursor, 9);
+        assert!(cycle.is_none());
+
+        // No longer prefix: cycle through the matches
+        let (input, cursor) = complete_tag(&input, cursor, &candidates, &mut cycle).unwrap();
+        assert_eq!(input, "Call #work");
+        let (input, cursor) = complete_tag(&input, cursor, &candidates, &mut cycle).unwrap();
+        assert_eq!(input, "Call #work:dev");
+        let (input, cursor) = complete_tag(&input, cursor, &candidates, &mut cycle).unwrap();
+        assert_eq!(input, "Call #world");
+        let (input, _) = complete_tag(&input, cursor, &candidates, &mut cycle).unwrap();
+        assert_eq!(input, "Call #work");
+    }
+
+    #[test]
+    fn test_complete_tag_mid_input_and_no_match() {
+        let candidates = tags(&["Shopping", "home"]);
+        let mut cycle = None;
+        let (input, cursor) = complete_tag("#sh !2", 3, &candidates, &mut cycle).unwrap();
+        assert_eq!(input, "#Shopping !2");
+        assert_eq!(cursor, 9);
+
+        assert!(complete_tag("#xyz", 4, &candidates, &mut cycle).is_none());
+        assert!(complete_tag("plain", 5, &candidates, &mut cycle).is_none());
+        assert_eq!(suggestions("Buy #h", 6, &candidates), tags(&["home"]));
+    }
+}
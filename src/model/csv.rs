@@ -0,0 +1,95 @@
+// File: src/model/csv.rs
+// CSV export of a task list, for spreadsheets and reports
+use crate::model::{CalendarListEntry, Task, TaskStatus};
+use anyhow::Result;
+use std::path::Path;
+
+const HEADER: &str = "uid,summary,status,priority,due,categories,calendar";
+
+/// One CSV line per task, after a header row. The calendar column holds the
+/// calendar's name when it is known, its href otherwise.
+pub fn tasks_to_csv(tasks: &[Task], calendars: &[CalendarListEntry]) -> String {
+    let mut out = String::from(HEADER);
+    out.push_str("\r\n");
+    for task in tasks {
+        let status = match task.status {
+            TaskStatus::NeedsAction => "NEEDS-ACTION",
+            TaskStatus::InProcess => "IN-PROCESS",
+            TaskStatus::Completed => "COMPLETED",
+            TaskStatus::Cancelled => "CANCELLED",
+        };
+        let priority = if task.priority > 0 {
+            task.priority.to_string()
+        } else {
+            String::new()
+        };
+        let calendar = calendars
+            .iter()
+            .find(|c| c.href == task.calendar_href)
+            .map_or(task.calendar_href.as_str(), |c| c.name.as_str());
+        let fields = [
+            task.uid.as_str(),
+            task.summary.as_str(),
+            status,
+            priority.as_str(),
+            &task.format_due().unwrap_or_default(),
+            &task.categories.join(";"),
+            calendar,
+        ];
+        let line: Vec<String> = fields.iter().map(|f| escape(f)).collect();
+        out.push_str(&line.join(","));
+        out.push_str("\r\n");
+    }
+    out
+}
+
+/// Writes `tasks` as CSV to `path`, replacing the file.
+pub fn export_csv(tasks: &[Task], calendars: &[CalendarListEntry], path: &Path) -> Result<()> {
+    std::fs::write(path, tasks_to_csv(tasks, calendars))?;
+    Ok(())
+}
+
+/// Quotes a field holding a comma, quote or line break (RFC 4180).
+fn escape(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_tasks_to_csv_escapes_fields() {
+        let mut task = Task::new("Buy \"good\" bread, eggs !1 #shop", &HashMap::new());
+        task.uid = "u1".to_string();
+        task.calendar_href = "/cal/home/".to_string();
+        let mut multiline = Task::new("Draft", &HashMap::new());
+        multiline.uid = "u2".to_string();
+        multiline.summary = "Line one\nline two".to_string();
+        multiline.calendar_href = "/cal/other/".to_string();
+        multiline.set_status(TaskStatus::Completed);
+        let calendars = vec![CalendarListEntry {
+            name: "Home".to_string(),
+            href: "/cal/home/".to_string(),
+            color: None,
+            read_only: false,
+        }];
+
+        let csv = tasks_to_csv(&[task, multiline], &calendars);
+        let lines: Vec<&str> = csv.split("\r\n").collect();
+        assert_eq!(lines[0], HEADER);
+        assert_eq!(
+            lines[1],
+            "u1,\"Buy \"\"good\"\" bread, eggs\",NEEDS-ACTION,1,,shop,Home"
+        );
+        assert_eq!(
+            lines[2],
+            "u2,\"Line one\nline two\",COMPLETED,,,,/cal/other/"
+        );
+    }
+}
@@ -1,5 +1,5 @@
 // File: src/model/item.rs
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Datelike, Local, NaiveDate, NaiveTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
 use std::collections::{HashMap, HashSet};
@@ -10,6 +10,9 @@ pub struct CalendarListEntry {
     pub name: String,
     pub href: String,
     pub color: Option<String>,
+    /// The user may view but not modify the calendar (shared or subscribed)
+    #[serde(default)]
+    pub read_only: bool,
 }
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
@@ -24,6 +27,15 @@ impl TaskStatus {
     pub fn is_done(&self) -> bool {
         matches!(self, Self::Completed | Self::Cancelled)
     }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::NeedsAction => "Needs action",
+            Self::InProcess => "In process",
+            Self::Completed => "Completed",
+            Self::Cancelled => "Cancelled",
+        }
+    }
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
@@ -33,7 +45,7 @@ pub struct RawProperty {
     pub params: Vec<(String, String)>,
 }
 
-#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Task {
     pub uid: String,
     pub summary: String,
@@ -41,6 +53,10 @@ pub struct Task {
     pub status: TaskStatus,
     pub estimated_duration: Option<u32>,
     pub due: Option<DateTime<Utc>>,
+    /// All-day due (`DUE;VALUE=DATE`): `due` then holds the date at 23:59:59 UTC
+    /// and is not converted to local time.
+    #[serde(default)]
+    pub due_is_date_only: bool,
     pub dtstart: Option<DateTime<Utc>>,
     pub priority: u8,
     pub parent_uid: Option<String>,
@@ -54,6 +70,43 @@ pub struct Task {
     pub unmapped_properties: Vec<RawProperty>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub raw_components: Vec<String>,
+    /// Completion dates of previous occurrences of a recurring task.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub completed_instances: Vec<DateTime<Utc>>,
+    /// Reminder offsets in minutes before the due date (VALARM triggers).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub reminders: Vec<u32>,
+    /// When the task was marked completed (iCalendar COMPLETED).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub completed_at: Option<DateTime<Utc>>,
+    /// Progress in percent, 0..=100 (iCalendar PERCENT-COMPLETE).
+    #[serde(default)]
+    pub percent_complete: u8,
+    /// Link attached to the task (iCalendar URL).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// Position in the manual order, lowest first (X-APPLE-SORT-ORDER).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sort_order: Option<i64>,
+    /// Someday/maybe: kept out of the today and agenda views (X-CFAIT-DEFERRED).
+    #[serde(default)]
+    pub deferred: bool,
+    /// Minutes logged with the focus timer (X-CFAIT-TIME-SPENT).
+    #[serde(default)]
+    pub time_spent: u32,
+    /// Last change, ours or the server's (iCalendar LAST-MODIFIED).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_modified: Option<DateTime<Utc>>,
+    /// Latitude and longitude in degrees (iCalendar GEO).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub geo: Option<(f64, f64)>,
+    /// People the task is waiting on (iCalendar ATTENDEE), by name or address.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attendees: Vec<String>,
+    /// Flag color (iCalendar COLOR): a name from `color_utils::FLAG_COLORS`, or
+    /// another client's value kept as it came.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
 }
 
 impl Task {
@@ -65,6 +118,7 @@ impl Task {
             status: TaskStatus::NeedsAction,
             estimated_duration: None,
             due: None,
+            due_is_date_only: false,
             dtstart: None,
             priority: 0,
             parent_uid: None,
@@ -77,11 +131,160 @@ impl Task {
             rrule: None,
             unmapped_properties: Vec::new(),
             raw_components: Vec::new(),
+            completed_instances: Vec::new(),
+            reminders: Vec::new(),
+            completed_at: None,
+            percent_complete: 0,
+            url: None,
+            sort_order: None,
+            deferred: false,
+            time_spent: 0,
+            last_modified: None,
+            geo: None,
+            attendees: Vec::new(),
+            color: None,
         };
         task.apply_smart_input(input, aliases);
         task
     }
 
+    /// Tasks of a pasted outline, one per line in smart input syntax, parents first.
+    /// A line becomes a subtask of the closest line above it that is less indented
+    /// (a tab counts as four spaces), so overly deep lines just go one level down.
+    /// `- `/`* `/`+ ` bullets are dropped and `[x]` checklist items come completed.
+    pub fn tree_from_outline(text: &str, aliases: &HashMap<String, Vec<String>>) -> Vec<Task> {
+        let mut tasks = Vec::new();
+        // Indentation and uid of the lines the next ones can be nested under
+        let mut ancestors: Vec<(usize, String)> = Vec::new();
+        for line in text.lines() {
+            let content = line.trim();
+            let content = ["- ", "* ", "+ "]
+                .iter()
+                .find_map(|b| content.strip_prefix(b))
+                .unwrap_or(content);
+            let (done, input) = match ["[x]", "[X]", "[ ]"]
+                .iter()
+                .find_map(|b| content.strip_prefix(b).map(|rest| (*b != "[ ]", rest)))
+            {
+                Some((done, rest)) => (done, rest.trim()),
+                None => (false, content),
+            };
+            if input.is_empty() {
+                continue;
+            }
+            let indent: usize = line
+                .chars()
+                .take_while(|c| c.is_whitespace())
+                .map(|c| if c == '\t' { 4 } else { 1 })
+                .sum();
+            while ancestors.last().is_some_and(|(i, _)| *i >= indent) {
+                ancestors.pop();
+            }
+            let mut task = Task::new(input, aliases);
+            task.parent_uid = ancestors.last().map(|(_, uid)| uid.clone());
+            if done {
+                task.set_status(TaskStatus::Completed);
+            }
+            ancestors.push((indent, task.uid.clone()));
+            tasks.push(task);
+        }
+        tasks
+    }
+
+    /// A fresh open copy to create in the same calendar. Parent and dependency
+    /// links and the completion history are not carried over.
+    pub fn duplicate(&self) -> Task {
+        let mut copy = self.clone();
+        copy.uid = Uuid::new_v4().to_string();
+        copy.href = String::new();
+        copy.etag = String::new();
+        copy.set_parent(None);
+        copy.dependencies.clear();
+        copy.depth = 0;
+        copy.status = TaskStatus::NeedsAction;
+        copy.completed_at = None;
+        copy.completed_instances.clear();
+        copy.percent_complete = 0;
+        copy.time_spent = 0;
+        copy.last_modified = None;
+        copy
+    }
+
+    /// The calendar day the task is due: as stored for all-day dues, in local
+    /// time for timed ones.
+    pub fn due_date(&self) -> Option<NaiveDate> {
+        let due = self.due?;
+        Some(if self.due_is_date_only {
+            due.date_naive()
+        } else {
+            due.with_timezone(&Local).date_naive()
+        })
+    }
+
+    /// Local time of day of a timed due. `None` for all-day dues and for timed
+    /// ones at midnight, which some clients use for all-day tasks.
+    pub fn due_time(&self) -> Option<NaiveTime> {
+        if self.due_is_date_only {
+            return None;
+        }
+        let time = self.due?.with_timezone(&Local).time();
+        (time != NaiveTime::MIN).then_some(time)
+    }
+
+    /// The due date for display, with the local time unless it is an all-day due.
+    pub fn format_due(&self) -> Option<String> {
+        self.format_due_as(DEFAULT_DATE_FORMAT)
+    }
+
+    /// Same as `format_due` with the day in `date_format` (see `is_valid_date_format`).
+    pub fn format_due_as(&self, date_format: &str) -> Option<String> {
+        let day = self.due_date()?.format(date_format);
+        Some(match self.due_time() {
+            Some(time) => format!("{} {}", day, time.format("%H:%M")),
+            None => day.to_string(),
+        })
+    }
+
+    /// How much the task carries besides its title, to pick which of two
+    /// duplicates to keep.
+    pub fn metadata_score(&self) -> usize {
+        [
+            !self.description.is_empty(),
+            self.due.is_some(),
+            self.dtstart.is_some(),
+            self.priority > 0,
+            self.parent_uid.is_some(),
+            self.rrule.is_some(),
+            self.url.is_some(),
+            self.estimated_duration.is_some(),
+            self.geo.is_some(),
+            self.percent_complete > 0,
+            self.time_spent > 0,
+            self.color.is_some(),
+        ]
+        .into_iter()
+        .filter(|set| *set)
+        .count()
+            + self.categories.len()
+            + self.dependencies.len()
+            + self.reminders.len()
+            + self.attendees.len()
+            + self.completed_instances.len()
+            + self.unmapped_properties.len()
+    }
+
+    /// Great-circle distance in kilometres from the task's GEO position, if it has one.
+    pub fn distance_to(&self, lat: f64, lon: f64) -> Option<f64> {
+        const EARTH_RADIUS_KM: f64 = 6371.0;
+        let (task_lat, task_lon) = self.geo?;
+        let (phi1, phi2) = (task_lat.to_radians(), lat.to_radians());
+        let d_phi = (lat - task_lat).to_radians();
+        let d_lambda = (lon - task_lon).to_radians();
+        let a =
+            (d_phi / 2.0).sin().powi(2) + phi1.cos() * phi2.cos() * (d_lambda / 2.0).sin().powi(2);
+        Some(2.0 * EARTH_RADIUS_KM * a.sqrt().asin())
+    }
+
     // --- View Helpers ---
 
     pub fn format_duration_short(&self) -> String {
@@ -115,15 +318,60 @@ impl Task {
 
     // --- Logic ---
 
+    /// Changes the status, stamping `completed_at` when the task becomes completed
+    /// and clearing it when it no longer is. Completing also sets the progress to
+    /// 100%; un-completing leaves the progress as it was.
+    pub fn set_status(&mut self, status: TaskStatus) {
+        if status != TaskStatus::Completed {
+            self.completed_at = None;
+        } else if self.status != TaskStatus::Completed || self.completed_at.is_none() {
+            self.completed_at = Some(Utc::now());
+        }
+        if status == TaskStatus::Completed {
+            self.percent_complete = 100;
+        }
+        self.status = status;
+    }
+
+    /// Changes the parent. The extra parents kept from the server are dropped
+    /// with the old one, or the next parse would take the first of them as the
+    /// parent and undo the change.
+    pub fn set_parent(&mut self, parent_uid: Option<String>) {
+        if self.parent_uid != parent_uid {
+            self.unmapped_properties.retain(|p| {
+                !p.key.eq_ignore_ascii_case("RELATED-TO")
+                    || p.params
+                        .iter()
+                        .any(|(k, v)| k == "RELTYPE" && !v.eq_ignore_ascii_case("PARENT"))
+            });
+        }
+        self.parent_uid = parent_uid;
+    }
+
     pub fn compare_with_cutoff(&self, other: &Self, cutoff: Option<DateTime<Utc>>) -> Ordering {
-        fn status_prio(s: TaskStatus) -> u8 {
+        self.compare_with_options(other, cutoff, true, false)
+    }
+
+    /// Like `compare_with_cutoff`; with `completed_last` off, completed and cancelled
+    /// tasks are ordered by date and priority together with the open ones.
+    /// With `manual_order`, `sort_order` comes right after the status; tasks that
+    /// were never reordered follow the ordered ones.
+    pub fn compare_with_options(
+        &self,
+        other: &Self,
+        cutoff: Option<DateTime<Utc>>,
+        completed_last: bool,
+        manual_order: bool,
+    ) -> Ordering {
+        let status_prio = |s: TaskStatus| -> u8 {
             match s {
                 TaskStatus::InProcess => 0,
                 TaskStatus::NeedsAction => 1,
-                TaskStatus::Completed => 2,
-                TaskStatus::Cancelled => 3,
+                TaskStatus::Completed if completed_last => 2,
+                TaskStatus::Cancelled if completed_last => 3,
+                TaskStatus::Completed | TaskStatus::Cancelled => 1,
             }
-        }
+        };
 
         let s1 = status_prio(self.status);
         let s2 = status_prio(other.status);
@@ -131,6 +379,28 @@ impl Task {
             return s1.cmp(&s2);
         }
 
+        if manual_order && self.sort_order != other.sort_order {
+            return match (self.sort_order, other.sort_order) {
+                (Some(a), Some(b)) => a.cmp(&b),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => Ordering::Equal,
+            };
+        }
+
+        // Completed tasks: most recently finished first, undated ones after
+        if completed_last
+            && self.status == TaskStatus::Completed
+            && self.completed_at != other.completed_at
+        {
+            return match (self.completed_at, other.completed_at) {
+                (Some(a), Some(b)) => b.cmp(&a),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => Ordering::Equal,
+            };
+        }
+
         let now = Utc::now();
         let self_future = self.dtstart.map(|d| d > now).unwrap_or(false);
         let other_future = other.dtstart.map(|d| d > now).unwrap_or(false);
@@ -188,12 +458,17 @@ impl Task {
         self.summary.cmp(&other.summary)
     }
 
-    pub fn organize_hierarchy(mut tasks: Vec<Task>, cutoff: Option<DateTime<Utc>>) -> Vec<Task> {
+    pub fn organize_hierarchy(
+        mut tasks: Vec<Task>,
+        cutoff: Option<DateTime<Utc>>,
+        completed_last: bool,
+        manual_order: bool,
+    ) -> Vec<Task> {
         let present_uids: HashSet<String> = tasks.iter().map(|t| t.uid.clone()).collect();
         let mut children_map: HashMap<String, Vec<Task>> = HashMap::new();
         let mut roots: Vec<Task> = Vec::new();
 
-        tasks.sort_by(|a, b| a.compare_with_cutoff(b, cutoff));
+        tasks.sort_by(|a, b| a.compare_with_options(b, cutoff, completed_last, manual_order));
 
         for mut task in tasks {
             let is_orphan = match &task.parent_uid {
@@ -211,6 +486,12 @@ impl Task {
                 children_map.entry(p_uid).or_default().push(task);
             }
         }
+        // Subtasks moved with Alt+j/k keep their place even in automatic order
+        if !manual_order {
+            for children in children_map.values_mut() {
+                children.sort_by(|a, b| a.compare_with_options(b, cutoff, completed_last, true));
+            }
+        }
 
         let mut result = Vec::new();
         for root in roots {
@@ -235,3 +516,171 @@ impl Task {
         }
     }
 }
+
+/// Appended to the summary of the copy kept when an update can't be merged.
+pub const CONFLICT_COPY_SUFFIX: &str = " (Conflict Copy)";
+
+/// How dates are shown unless the config sets `date_format`.
+pub const DEFAULT_DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// Whether chrono can render a calendar day with `format`: no unknown specifiers
+/// and no time fields, which would make the formatting panic.
+pub fn is_valid_date_format(format: &str) -> bool {
+    use std::fmt::Write;
+    let Some(day) = NaiveDate::from_ymd_opt(2000, 1, 1) else {
+        return false;
+    };
+    !format.trim().is_empty() && write!(String::new(), "{}", day.format(format)).is_ok()
+}
+
+/// Due date relative to today in local time ("today", "in 3d", "2d ago"); dates
+/// more than a week away are shown in `date_format`.
+pub fn humanize_due(due: DateTime<Utc>, date_format: &str) -> String {
+    humanize_due_from(due, Local::now().date_naive(), date_format)
+}
+
+/// Same as `humanize_due` for a calendar day, see `Task::due_date`.
+pub fn humanize_date(date: NaiveDate, date_format: &str) -> String {
+    humanize_date_from(date, Local::now().date_naive(), date_format)
+}
+
+/// ISO week number of a date, e.g. "W23".
+pub fn week_label(date: NaiveDate) -> String {
+    format!("W{:02}", date.iso_week().week())
+}
+
+fn humanize_due_from(due: DateTime<Utc>, today: NaiveDate, date_format: &str) -> String {
+    humanize_date_from(due.with_timezone(&Local).date_naive(), today, date_format)
+}
+
+fn humanize_date_from(date: NaiveDate, today: NaiveDate, date_format: &str) -> String {
+    match (date - today).num_days() {
+        0 => "today".to_string(),
+        1 => "tomorrow".to_string(),
+        -1 => "yesterday".to_string(),
+        n @ 2..=6 => format!("in {}d", n),
+        n @ -6..=-2 => format!("{}d ago", -n),
+        _ => date.format(date_format).to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_humanize_due() {
+        let today = Local::now().date_naive();
+        let at = |days: i64| {
+            (today + chrono::Duration::days(days))
+                .and_hms_opt(12, 0, 0)
+                .unwrap()
+                .and_local_timezone(Local)
+                .unwrap()
+                .with_timezone(&Utc)
+        };
+        assert_eq!(
+            humanize_due_from(at(0), today, DEFAULT_DATE_FORMAT),
+            "today"
+        );
+        assert_eq!(
+            humanize_due_from(at(1), today, DEFAULT_DATE_FORMAT),
+            "tomorrow"
+        );
+        assert_eq!(
+            humanize_due_from(at(-1), today, DEFAULT_DATE_FORMAT),
+            "yesterday"
+        );
+        assert_eq!(
+            humanize_due_from(at(3), today, DEFAULT_DATE_FORMAT),
+            "in 3d"
+        );
+        assert_eq!(
+            humanize_due_from(at(-2), today, DEFAULT_DATE_FORMAT),
+            "2d ago"
+        );
+        let far = today + chrono::Duration::days(30);
+        assert_eq!(
+            humanize_due_from(at(30), today, DEFAULT_DATE_FORMAT),
+            far.format("%Y-%m-%d").to_string()
+        );
+        assert_eq!(
+            humanize_due_from(at(30), today, "%d/%m/%Y"),
+            far.format("%d/%m/%Y").to_string()
+        );
+    }
+
+    #[test]
+    fn test_date_format_validation() {
+        assert!(is_valid_date_format("%Y-%m-%d"));
+        assert!(is_valid_date_format("%b %e"));
+        assert!(is_valid_date_format("%d/%m/%Y"));
+        // Unknown specifier, time field on a date, nothing
+        assert!(!is_valid_date_format("%Q"));
+        assert!(!is_valid_date_format("%Y %H:%M"));
+        assert!(!is_valid_date_format(" "));
+    }
+
+    #[test]
+    fn test_week_label() {
+        let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+        assert_eq!(week_label(date(2025, 6, 2)), "W23");
+        // ISO weeks belong to the year of their Thursday
+        assert_eq!(week_label(date(2024, 12, 30)), "W01");
+        assert_eq!(week_label(date(2021, 1, 3)), "W53");
+    }
+
+    #[test]
+    fn test_duplicate_drops_links() {
+        let mut task = Task::new("Water plants #home !3 ~15m", &HashMap::new());
+        task.href = "/cal/a.ics".to_string();
+        task.etag = "\"1\"".to_string();
+        task.calendar_href = "/cal/".to_string();
+        task.parent_uid = Some("parent".to_string());
+        task.dependencies.push("other".to_string());
+        task.set_status(TaskStatus::Completed);
+
+        let copy = task.duplicate();
+        assert_ne!(copy.uid, task.uid);
+        assert!(copy.href.is_empty() && copy.etag.is_empty());
+        assert_eq!(copy.calendar_href, "/cal/");
+        assert_eq!(copy.summary, "Water plants");
+        assert_eq!(copy.categories, vec!["home"]);
+        assert_eq!(copy.priority, 3);
+        assert_eq!(copy.estimated_duration, Some(15));
+        assert!(copy.parent_uid.is_none() && copy.dependencies.is_empty());
+        assert_eq!(copy.status, TaskStatus::NeedsAction);
+        assert!(copy.completed_at.is_none());
+    }
+
+    #[test]
+    fn test_tree_from_outline() {
+        let outline = "Trip !2\n  - Book flights\n        [x] Compare prices\n    Pack\n\
+                       \tPassport #docs\n\n- After";
+        let tasks = Task::tree_from_outline(outline, &HashMap::new());
+        let summaries: Vec<_> = tasks.iter().map(|t| t.summary.as_str()).collect();
+        assert_eq!(
+            summaries,
+            [
+                "Trip",
+                "Book flights",
+                "Compare prices",
+                "Pack",
+                "Passport",
+                "After"
+            ]
+        );
+        let parent = |i: usize| tasks[i].parent_uid.as_deref();
+        assert_eq!(tasks[0].priority, 2);
+        assert_eq!(parent(0), None);
+        assert_eq!(parent(1), Some(tasks[0].uid.as_str()));
+        // Far deeper than its parent, still only one level down
+        assert_eq!(parent(2), Some(tasks[1].uid.as_str()));
+        assert!(tasks[2].status.is_done());
+        // Between two levels: under the closest less indented line
+        assert_eq!(parent(3), Some(tasks[1].uid.as_str()));
+        assert_eq!(parent(4), Some(tasks[1].uid.as_str()));
+        assert_eq!(tasks[4].categories, vec!["docs"]);
+        assert_eq!(parent(5), None);
+    }
+}
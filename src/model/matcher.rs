@@ -2,6 +2,8 @@
 // Handles logic for checking if a task matches a search query
 use crate::model::item::{Task, TaskStatus};
 use chrono::Utc;
+use fuzzy_matcher::FuzzyMatcher;
+use fuzzy_matcher::skim::SkimMatcherV2;
 
 impl Task {
     pub fn matches_search_term(&self, term: &str) -> bool {
@@ -196,6 +198,18 @@ impl Task {
                 }
             }
 
+            // Attendee Filter (@@alice)
+            if let Some(who) = part.strip_prefix("@@") {
+                if !self
+                    .attendees
+                    .iter()
+                    .any(|a| a.to_lowercase().contains(who))
+                {
+                    return false;
+                }
+                continue;
+            }
+
             // 3. Due Date Filter (@<2025-01-01, @>today)
             if part.starts_with('@') {
                 let (op, val_str) = if let Some(stripped) = part.strip_prefix("@<=") {
@@ -287,6 +301,23 @@ impl Task {
                 continue;
             }
 
+            // Scoped Text Filters (desc:foo, title:foo)
+            if let Some(q) = part.strip_prefix("desc:") {
+                if !self.description.to_lowercase().contains(q) {
+                    return false;
+                }
+                continue;
+            }
+            if let Some(q) = part
+                .strip_prefix("title:")
+                .or_else(|| part.strip_prefix("summary:"))
+            {
+                if !self.summary.to_lowercase().contains(q) {
+                    return false;
+                }
+                continue;
+            }
+
             // 3. Status Filter (is:done, is:active)
             if part == "is:done" {
                 if !self.status.is_done() {
@@ -321,4 +352,94 @@ impl Task {
         }
         true
     }
+
+    /// Fuzzy variant of `matches_search_term`: the plain words of the query are
+    /// matched as a subsequence of the summary, description or tags, while the
+    /// operators (`#tag`, `is:done`, `@<today`...) still have to match exactly.
+    /// Returns the best score, or `None` when the task doesn't match.
+    pub fn fuzzy_score(&self, term: &str) -> Option<i64> {
+        let (plain, filters): (Vec<&str>, Vec<&str>) = term
+            .split_whitespace()
+            .partition(|p| !p.contains(':') && !p.starts_with(['~', '!', '@', '^', '#']));
+        if !self.matches_search_term(&filters.join(" ")) {
+            return None;
+        }
+        if plain.is_empty() {
+            return Some(0);
+        }
+
+        let pattern = plain.join(" ");
+        let matcher = SkimMatcherV2::default().ignore_case();
+        std::iter::once(self.summary.as_str())
+            .chain(std::iter::once(self.description.as_str()))
+            .chain(self.categories.iter().map(|c| c.as_str()))
+            .filter_map(|field| matcher.fuzzy_match(field, &pattern))
+            .max()
+    }
+
+    /// Explains why a task matched a search when the summary alone doesn't show it,
+    /// e.g. "description" or "tag #work". Only plain text terms are considered.
+    pub fn search_match_reason(&self, term: &str) -> Option<String> {
+        let term_lower = term.to_lowercase();
+        let mut reasons: Vec<String> = Vec::new();
+
+        for part in term_lower.split_whitespace() {
+            let desc_query = part.strip_prefix("desc:");
+            let is_plain = !part.contains(':') && !part.starts_with(['~', '!', '@', '^', '#']);
+            if desc_query.is_none() && !is_plain {
+                continue;
+            }
+            let q = desc_query.unwrap_or(part);
+            if q.is_empty() || (is_plain && self.summary.to_lowercase().contains(q)) {
+                continue;
+            }
+
+            let reason = if self.description.to_lowercase().contains(q) {
+                "description".to_string()
+            } else if let Some(cat) = self
+                .categories
+                .iter()
+                .find(|c| c.to_lowercase().contains(q))
+            {
+                format!("tag #{}", cat)
+            } else {
+                continue;
+            };
+            if !reasons.contains(&reason) {
+                reasons.push(reason);
+            }
+        }
+
+        if reasons.is_empty() {
+            None
+        } else {
+            Some(reasons.join(", "))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_fuzzy_score() {
+        let aliases = HashMap::new();
+        let task = Task::new("Buy groceries #shopping !2", &aliases);
+
+        // Subsequence and typo-tolerant matches, exact search would miss them
+        assert!(!task.matches_search_term("bgrc"));
+        assert!(task.fuzzy_score("bgrc").is_some());
+        assert!(task.fuzzy_score("grocries").is_some());
+        assert!(task.fuzzy_score("xyz").is_none());
+
+        // A closer match scores higher
+        assert!(task.fuzzy_score("groceries") > task.fuzzy_score("gres"));
+
+        // Operators keep filtering exactly
+        assert!(task.fuzzy_score("grc #shopping").is_some());
+        assert!(task.fuzzy_score("grc #work").is_none());
+        assert_eq!(task.fuzzy_score("!<3"), Some(0));
+    }
 }
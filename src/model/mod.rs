@@ -1,8 +1,17 @@
 // File: src/model/mod.rs
 pub mod adapter;
+pub mod checklist;
+pub mod completion;
+pub mod csv;
 pub mod item;
 pub mod matcher;
 pub mod parser;
+pub mod recurrence;
+pub mod timer;
 
-pub use item::{CalendarListEntry, Task, TaskStatus};
+pub use item::{
+    CONFLICT_COPY_SUFFIX, CalendarListEntry, DEFAULT_DATE_FORMAT, Task, TaskStatus, humanize_date,
+    humanize_due, is_valid_date_format, week_label,
+};
 pub use parser::extract_inline_aliases;
+pub use timer::TimerState;
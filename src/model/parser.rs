@@ -1,7 +1,9 @@
 // File: src/model/parser.rs
 // Handles smart text input parsing
+use crate::color_utils::flag_color_name;
 use crate::model::item::Task;
-use chrono::{DateTime, Local, NaiveDate, Utc};
+use crate::model::recurrence::validate_rrule;
+use chrono::{DateTime, Datelike, Local, NaiveDate, NaiveTime, TimeZone, Utc, Weekday};
 use std::collections::HashMap;
 
 impl Task {
@@ -10,10 +12,19 @@ impl Task {
         // Reset fields
         self.priority = 0;
         self.due = None;
+        self.due_is_date_only = false;
         self.dtstart = None;
         self.rrule = None;
         self.estimated_duration = None;
+        self.reminders.clear();
+        self.percent_complete = 0;
+        self.url = None;
+        // A color the input can't name (set by another client) is kept
+        if self.color.as_deref().and_then(flag_color_name).is_some() {
+            self.color = None;
+        }
         self.categories.clear();
+        self.attendees.clear();
 
         let tokens: Vec<&str> = input.split_whitespace().collect();
         let mut i = 0;
@@ -22,15 +33,48 @@ impl Task {
             let word = tokens[i];
 
             // 1. Priority (!1 - !9)
-            if word.starts_with('!')
-                && let Ok(p) = word[1..].parse::<u8>()
-                && (1..=9).contains(&p)
-            {
+            if let Some(p) = priority_token(word) {
                 self.priority = p;
                 i += 1;
                 continue;
             }
 
+            // Progress (~%50)
+            if let Some(val) = word.strip_prefix("~%")
+                && let Ok(p) = val.trim_end_matches('%').parse::<u8>()
+            {
+                self.percent_complete = p.min(100);
+                i += 1;
+                continue;
+            }
+
+            // Link (url:https://...)
+            if let Some(val) = word.strip_prefix("url:")
+                && !val.is_empty()
+            {
+                self.url = Some(val.to_string());
+                i += 1;
+                continue;
+            }
+
+            // Flag color (color:red), other values stay in the summary
+            if let Some(name) = word.strip_prefix("color:").and_then(flag_color_name) {
+                self.color = Some(name.to_string());
+                i += 1;
+                continue;
+            }
+
+            // Waiting on someone (@@alice), before '@' is read as a date
+            if let Some(who) = word.strip_prefix("@@")
+                && !who.is_empty()
+            {
+                if !self.attendees.iter().any(|a| a == who) {
+                    self.attendees.push(who.to_string());
+                }
+                i += 1;
+                continue;
+            }
+
             // 2. Duration (est:30m, ~30m)
             if let Some(val) = word.strip_prefix("est:").or_else(|| word.strip_prefix('~'))
                 && let Some(m) = parse_duration(val)
@@ -40,6 +84,17 @@ impl Task {
                 continue;
             }
 
+            // Reminder before due (*15m, *1d)
+            if let Some(val) = word.strip_prefix('*')
+                && let Some(m) = parse_duration(val)
+            {
+                if !self.reminders.contains(&m) {
+                    self.reminders.push(m);
+                }
+                i += 1;
+                continue;
+            }
+
             // 3. Tags (#tag)
             if let Some(stripped) = word.strip_prefix('#') {
                 let cat = stripped.to_string();
@@ -71,10 +126,8 @@ impl Task {
                 }
             }
 
-            // 4. Recurrence (rec:weekly, @weekly)
-            if let Some(val) = word.strip_prefix("rec:").or_else(|| word.strip_prefix('@'))
-                && let Some(rrule) = parse_recurrence(val)
-            {
+            // 4. Recurrence (rec:weekly, @weekly, rec:FREQ=WEEKLY;BYDAY=MO)
+            if let Some(rrule) = recurrence_word(word) {
                 self.rrule = Some(rrule);
                 i += 1;
                 continue;
@@ -96,12 +149,29 @@ impl Task {
                 }
             }
 
-            // 6. Due Date (due:2025-01-01, @2025-01-01)
+            // 6. Due Date (due:2025-01-01, @2025-01-01), with an optional local time
+            // (@tomorrow 14:30, @2025-01-01T09:00)
+            if let Some(val) = word.strip_prefix("due:").or_else(|| word.strip_prefix('@')) {
+                let timed = match val.split_once('T') {
+                    Some((day, time)) => parse_smart_day(day).zip(parse_time(time)),
+                    None => parse_smart_day(val)
+                        .zip(tokens.get(i + 1).and_then(|next| parse_time(next))),
+                };
+                if let Some((day, time)) = timed
+                    && let Some(dt) = Local.from_local_datetime(&day.and_time(time)).earliest()
+                {
+                    self.due = Some(dt.with_timezone(&Utc));
+                    self.due_is_date_only = false;
+                    i += if val.contains('T') { 1 } else { 2 };
+                    continue;
+                }
+            }
             if let Some(val) = word.strip_prefix("due:").or_else(|| word.strip_prefix('@'))
                 && let Some(dt) = parse_smart_date(val, true)
             {
                 // true = end of day
                 self.due = Some(dt);
+                self.due_is_date_only = true;
                 i += 1;
                 continue;
             }
@@ -125,6 +195,31 @@ impl Task {
         self.summary = summary_words.join(" ");
     }
 
+    /// Moves the due date to a snooze preset ("tomorrow", "3d", "next week", a date),
+    /// counted from the due date, or from today when there is none or it has passed.
+    /// A due time of day is kept. Returns false when the preset can't be read.
+    pub fn snooze(&mut self, preset: &str, week_start: Weekday) -> bool {
+        let today = Local::now().date_naive();
+        let from = self.due_date().map_or(today, |due| due.max(today));
+        let Some(day) = snooze_day(preset, from, week_start) else {
+            return false;
+        };
+        let time = self.due_time();
+        let due = match time {
+            Some(time) => Local
+                .from_local_datetime(&day.and_time(time))
+                .earliest()
+                .map(|dt| dt.with_timezone(&Utc)),
+            None => finalize_date(day, true),
+        };
+        if due.is_none() {
+            return false;
+        }
+        self.due = due;
+        self.due_is_date_only = time.is_none();
+        true
+    }
+
     pub fn to_smart_string(&self) -> String {
         let mut s = self.summary.clone();
 
@@ -138,44 +233,48 @@ impl Task {
             s.push_str(&format!(" ^{}", start.format("%Y-%m-%d")));
         }
 
-        // Due: @YYYY-MM-DD
-        if let Some(d) = self.due {
-            s.push_str(&format!(" @{}", d.format("%Y-%m-%d")));
+        // Due: @YYYY-MM-DD, or @YYYY-MM-DDTHH:MM in local time
+        if let Some(d) = self.due_date() {
+            match self.due_time() {
+                Some(t) => s.push_str(&format!(" @{}T{}", d.format("%Y-%m-%d"), t.format("%H:%M"))),
+                None => s.push_str(&format!(" @{}", d.format("%Y-%m-%d"))),
+            }
         }
 
         // Duration: ~30m
         if let Some(mins) = self.estimated_duration {
-            let dur_str = if mins >= 525600 {
-                format!("~{}y", mins / 525600)
-            } else if mins >= 43200 {
-                format!("~{}mo", mins / 43200)
-            } else if mins >= 10080 {
-                format!("~{}w", mins / 10080)
-            } else if mins >= 1440 {
-                format!("~{}d", mins / 1440)
-            } else if mins >= 60 {
-                format!("~{}h", mins / 60)
-            } else {
-                format!("~{}m", mins)
-            };
-            s.push_str(&format!(" {}", dur_str));
+            s.push_str(&format!(" ~{}", format_duration(mins)));
+        }
+
+        // Progress: ~%50
+        if self.percent_complete > 0 {
+            s.push_str(&format!(" ~%{}", self.percent_complete));
+        }
+
+        // Link: url:https://...
+        if let Some(url) = &self.url {
+            s.push_str(&format!(" url:{}", url));
+        }
+
+        // Flag color: color:red
+        if let Some(name) = self.color.as_deref().and_then(flag_color_name) {
+            s.push_str(&format!(" color:{}", name));
+        }
+
+        // Attendees: @@alice
+        for who in &self.attendees {
+            s.push_str(&format!(" @@{}", who));
+        }
+
+        // Reminders: *15m
+        for mins in &self.reminders {
+            s.push_str(&format!(" *{}", format_duration(*mins)));
         }
 
         // Recurrence: @weekly or @every ...
         if let Some(r) = &self.rrule {
-            if r == "FREQ=DAILY" {
-                s.push_str(" @daily");
-            } else if r == "FREQ=WEEKLY" {
-                s.push_str(" @weekly");
-            } else if r == "FREQ=MONTHLY" {
-                s.push_str(" @monthly");
-            } else if r == "FREQ=YEARLY" {
-                s.push_str(" @yearly");
-            } else if let Some(simple) = reconstruct_simple_rrule(r) {
-                s.push_str(&format!(" {}", simple));
-            } else {
-                s.push_str(" rec:custom"); // Fallback for complex RRULEs
-            }
+            s.push(' ');
+            s.push_str(&recurrence_token(r));
         }
 
         // Tags: #tag
@@ -186,6 +285,69 @@ impl Task {
     }
 }
 
+/// Priority of a `!1` - `!9` token.
+fn priority_token(word: &str) -> Option<u8> {
+    let p = word.strip_prefix('!')?.parse::<u8>().ok()?;
+    (1..=9).contains(&p).then_some(p)
+}
+
+/// Priority set by a smart input (its last `!n`), 0 when there is none.
+pub fn input_priority(input: &str) -> u8 {
+    input
+        .split_whitespace()
+        .rev()
+        .find_map(priority_token)
+        .unwrap_or(0)
+}
+
+/// The smart input with its priority tokens replaced by `!priority`, or
+/// removed for 0.
+pub fn with_priority(input: &str, priority: u8) -> String {
+    let mut words: Vec<String> = input
+        .split_whitespace()
+        .filter(|w| priority_token(w).is_none())
+        .map(str::to_string)
+        .collect();
+    if (1..=9).contains(&priority) {
+        words.push(format!("!{}", priority));
+    }
+    words.join(" ")
+}
+
+/// Recurrence rule set by a smart input, if any.
+pub fn input_recurrence(input: &str) -> Option<String> {
+    Task::new(input, &HashMap::new()).rrule
+}
+
+/// The smart input with its recurrence tokens replaced by one for `rrule`, or
+/// removed for `None`.
+pub fn with_recurrence(input: &str, rrule: Option<&str>) -> String {
+    let tokens: Vec<&str> = input.split_whitespace().collect();
+    let mut words = Vec::new();
+    let mut i = 0;
+    while i < tokens.len() {
+        let word = tokens[i];
+        if recurrence_word(word).is_some() {
+            i += 1;
+            continue;
+        }
+        if (word == "rec:every" || word == "@every")
+            && i + 2 < tokens.len()
+            && tokens[i + 1].parse::<u32>().is_ok()
+            && !parse_freq_unit(tokens[i + 2]).is_empty()
+        {
+            i += 3;
+            continue;
+        }
+        words.push(word.to_string());
+        i += 1;
+    }
+    if let Some(r) = rrule {
+        words.push(recurrence_token(r));
+    }
+    words.join(" ")
+}
+
 /// Helper to extract inline alias definitions from an input string.
 /// Syntax: #alias=#tag1,#tag2
 /// Returns:
@@ -224,9 +386,37 @@ pub fn extract_inline_aliases(input: &str) -> (String, HashMap<String, Vec<Strin
 
 // --- Helpers ---
 
+/// Single-word recurrence: a preset (`@weekly`, `rec:daily`) or a raw rule
+/// (`rec:FREQ=WEEKLY;BYDAY=MO,WE`) the recurrence engine accepts.
+fn recurrence_word(word: &str) -> Option<String> {
+    if let Some(raw) = word.strip_prefix("rec:")
+        && raw.to_ascii_uppercase().starts_with("FREQ=")
+    {
+        let rule = raw.to_ascii_uppercase();
+        return validate_rrule(&rule).is_ok().then_some(rule);
+    }
+    word.strip_prefix("rec:")
+        .or_else(|| word.strip_prefix('@'))
+        .and_then(parse_recurrence)
+}
+
+/// Smart input token for a rule: a preset, `@every N units`, or the raw rule.
+fn recurrence_token(rrule: &str) -> String {
+    match rrule {
+        "FREQ=DAILY" => "@daily".to_string(),
+        "FREQ=WEEKLY" => "@weekly".to_string(),
+        "FREQ=MONTHLY" => "@monthly".to_string(),
+        "FREQ=YEARLY" => "@yearly".to_string(),
+        _ => reconstruct_simple_rrule(rrule).unwrap_or_else(|| format!("rec:{}", rrule)),
+    }
+}
+
 fn reconstruct_simple_rrule(rrule: &str) -> Option<String> {
     // Basic parser to handle FREQ=X;INTERVAL=Y -> @every Y X(s)
     let parts: HashMap<&str, &str> = rrule.split(';').filter_map(|s| s.split_once('=')).collect();
+    if parts.keys().any(|k| *k != "FREQ" && *k != "INTERVAL") {
+        return None;
+    }
 
     let freq = parts.get("FREQ")?;
     let interval = parts.get("INTERVAL").unwrap_or(&"1");
@@ -242,25 +432,74 @@ fn reconstruct_simple_rrule(rrule: &str) -> Option<String> {
     Some(format!("@every {} {}", interval, unit))
 }
 
+// Largest first, so formatting can greedily take whole units
+const DURATION_UNITS: [(&str, u32); 6] = [
+    ("y", 525600),
+    ("mo", 43200),
+    ("w", 10080),
+    ("d", 1440),
+    ("h", 60),
+    ("m", 1),
+];
+
+fn duration_parts(mins: u32) -> Vec<String> {
+    if mins == 0 {
+        return vec!["0m".to_string()];
+    }
+    let mut rest = mins;
+    let mut parts = Vec::new();
+    for (unit, size) in DURATION_UNITS {
+        if rest >= size {
+            parts.push(format!("{}{}", rest / size, unit));
+            rest %= size;
+        }
+    }
+    parts
+}
+
+/// Most compact combined form, e.g. 90 -> "1h30m"
+fn format_duration(mins: u32) -> String {
+    duration_parts(mins).concat()
+}
+
+/// Human readable duration for display, e.g. 260 -> "4h 20m"
+pub fn format_mins(mins: u32) -> String {
+    duration_parts(mins).join(" ")
+}
+
+/// Parses "30m", "2h", "1h30m", "2d4h" or bare minutes ("90") into minutes.
 fn parse_duration(val: &str) -> Option<u32> {
     let lower = val.to_lowercase();
-    if let Some(n) = lower.strip_suffix("min") {
-        return n.parse::<u32>().ok();
+    if lower.is_empty() {
+        return None;
     }
-    if let Some(n) = lower.strip_suffix('m') {
-        return n.parse::<u32>().ok();
-    } else if let Some(n) = lower.strip_suffix('h') {
-        return n.parse::<u32>().ok().map(|h| h * 60);
-    } else if let Some(n) = lower.strip_suffix('d') {
-        return n.parse::<u32>().ok().map(|d| d * 24 * 60);
-    } else if let Some(n) = lower.strip_suffix('w') {
-        return n.parse::<u32>().ok().map(|w| w * 7 * 24 * 60);
-    } else if let Some(n) = lower.strip_suffix("mo") {
-        return n.parse::<u32>().ok().map(|mo| mo * 30 * 24 * 60);
-    } else if let Some(n) = lower.strip_suffix('y') {
-        return n.parse::<u32>().ok().map(|y| y * 365 * 24 * 60);
+    if lower.bytes().all(|b| b.is_ascii_digit()) {
+        return lower.parse::<u32>().ok();
     }
-    None
+
+    let mut total: u32 = 0;
+    let mut rest = lower.as_str();
+    while !rest.is_empty() {
+        let digits = rest.len() - rest.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+        let amount: u32 = rest[..digits].parse().ok()?;
+        rest = &rest[digits..];
+        let letters = rest.len()
+            - rest
+                .trim_start_matches(|c: char| c.is_ascii_alphabetic())
+                .len();
+        let size = match &rest[..letters] {
+            "m" | "min" => 1,
+            "h" => 60,
+            "d" => 1440,
+            "w" => 10080,
+            "mo" => 43200,
+            "y" => 525600,
+            _ => return None,
+        };
+        rest = &rest[letters..];
+        total = total.checked_add(amount.checked_mul(size)?)?;
+    }
+    Some(total)
 }
 
 fn parse_recurrence(val: &str) -> Option<String> {
@@ -289,38 +528,65 @@ fn parse_freq_unit(unit: &str) -> &'static str {
 }
 
 fn parse_smart_date(val: &str, end_of_day: bool) -> Option<DateTime<Utc>> {
+    finalize_date(parse_smart_day(val)?, end_of_day)
+}
+
+fn parse_smart_day(val: &str) -> Option<NaiveDate> {
+    parse_day_from(val, Local::now().date_naive())
+}
+
+/// Where a snooze preset lands when counted from `from`. Offsets may start with
+/// '+' ("+3d"), and "next week" is the first day of the following week.
+pub fn snooze_day(preset: &str, from: NaiveDate, week_start: Weekday) -> Option<NaiveDate> {
+    let preset = preset.trim().to_lowercase();
+    if preset == "next week" {
+        let ahead = 7 - from.weekday().days_since(week_start) as i64;
+        return Some(from + chrono::Duration::days(ahead));
+    }
+    parse_day_from(preset.trim_start_matches('+'), from)
+}
+
+/// `parse_smart_day` with relative keywords and offsets counted from `now`.
+fn parse_day_from(val: &str, now: NaiveDate) -> Option<NaiveDate> {
     // 1. Specific Date YYYY-MM-DD
     if let Ok(date) = NaiveDate::parse_from_str(val, "%Y-%m-%d") {
-        return finalize_date(date, end_of_day);
+        return Some(date);
     }
 
-    let now = Local::now().date_naive();
-
     // 2. Relative Keywords
     if val == "today" {
-        return finalize_date(now, end_of_day);
+        return Some(now);
     }
     if val == "tomorrow" {
-        return finalize_date(now + chrono::Duration::days(1), end_of_day);
+        return Some(now + chrono::Duration::days(1));
     }
 
     // 3. "1w", "2d" offsets (from now)
     if let Some(n) = val.strip_suffix('d').and_then(|s| s.parse::<i64>().ok()) {
-        return finalize_date(now + chrono::Duration::days(n), end_of_day);
+        return Some(now + chrono::Duration::days(n));
     }
     if let Some(n) = val.strip_suffix('w').and_then(|s| s.parse::<i64>().ok()) {
-        return finalize_date(now + chrono::Duration::days(n * 7), end_of_day);
+        return Some(now + chrono::Duration::days(n * 7));
     }
     if let Some(n) = val.strip_suffix("mo").and_then(|s| s.parse::<i64>().ok()) {
-        return finalize_date(now + chrono::Duration::days(n * 30), end_of_day);
+        return Some(now + chrono::Duration::days(n * 30));
     }
     if let Some(n) = val.strip_suffix('y').and_then(|s| s.parse::<i64>().ok()) {
-        return finalize_date(now + chrono::Duration::days(n * 365), end_of_day);
+        return Some(now + chrono::Duration::days(n * 365));
     }
 
     None
 }
 
+/// A time of day written `14:30` or `9:05`.
+fn parse_time(val: &str) -> Option<NaiveTime> {
+    let (h, m) = val.split_once(':')?;
+    if m.len() != 2 {
+        return None;
+    }
+    NaiveTime::from_hms_opt(h.parse().ok()?, m.parse().ok()?, 0)
+}
+
 fn finalize_date(d: NaiveDate, end_of_day: bool) -> Option<DateTime<Utc>> {
     let t = if end_of_day {
         d.and_hms_opt(23, 59, 59)?
@@ -329,3 +595,137 @@ fn finalize_date(d: NaiveDate, end_of_day: bool) -> Option<DateTime<Utc>> {
     };
     Some(t.and_utc())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_with_priority() {
+        assert_eq!(
+            with_priority("Call mom !5 @tomorrow", 1),
+            "Call mom @tomorrow !1"
+        );
+        assert_eq!(with_priority("Call mom !5 !2", 0), "Call mom");
+        assert_eq!(with_priority("Call mom", 3), "Call mom !3");
+        // Not priority tokens
+        assert_eq!(with_priority("Shout !!", 2), "Shout !! !2");
+        assert_eq!(input_priority("Call mom !5 !2"), 2);
+        assert_eq!(input_priority("Call mom !10"), 0);
+    }
+
+    #[test]
+    fn test_snooze() {
+        let wed = NaiveDate::from_ymd_opt(2025, 6, 4).unwrap();
+        let day = |p: &str| snooze_day(p, wed, Weekday::Mon);
+        assert_eq!(day("tomorrow"), NaiveDate::from_ymd_opt(2025, 6, 5));
+        assert_eq!(day("+3d"), NaiveDate::from_ymd_opt(2025, 6, 7));
+        assert_eq!(day("Next Week"), NaiveDate::from_ymd_opt(2025, 6, 9));
+        assert_eq!(
+            snooze_day("next week", wed, Weekday::Sun),
+            NaiveDate::from_ymd_opt(2025, 6, 8)
+        );
+        assert_eq!(day("someday"), None);
+
+        let aliases = HashMap::new();
+        let today = Local::now().date_naive();
+        // No due date, or an overdue one: counted from today
+        let mut task = Task::new("Call bank", &aliases);
+        assert!(task.snooze("tomorrow", Weekday::Mon));
+        assert_eq!(task.due_date(), Some(today + chrono::Duration::days(1)));
+        assert!(task.due_is_date_only);
+        let mut late = Task::new("Call bank @2020-01-01 09:30", &aliases);
+        assert!(late.snooze("2d", Weekday::Mon));
+        assert_eq!(late.due_date(), Some(today + chrono::Duration::days(2)));
+        assert_eq!(late.due_time(), NaiveTime::from_hms_opt(9, 30, 0));
+        // A later due date is pushed further
+        let mut ahead = Task::new("Call bank @10d", &aliases);
+        assert!(ahead.snooze("tomorrow", Weekday::Mon));
+        assert_eq!(ahead.due_date(), Some(today + chrono::Duration::days(11)));
+        assert!(!ahead.snooze("whenever", Weekday::Mon));
+    }
+
+    #[test]
+    fn test_due_time() {
+        let aliases = HashMap::new();
+        let at = |task: &Task| task.due.map(|d| d.with_timezone(&Local).naive_local());
+        let tomorrow = Local::now().date_naive() + chrono::Duration::days(1);
+
+        let task = Task::new("Call @tomorrow 14:30 #work", &aliases);
+        assert_eq!(task.summary, "Call");
+        assert!(!task.due_is_date_only);
+        assert_eq!(at(&task), tomorrow.and_hms_opt(14, 30, 0));
+        let smart = task.to_smart_string();
+        assert_eq!(
+            smart,
+            format!("Call @{}T14:30 #work", tomorrow.format("%Y-%m-%d"))
+        );
+        assert_eq!(at(&Task::new(&smart, &aliases)), at(&task));
+
+        let task = Task::new("Dentist due:2024-06-01T09:00", &aliases);
+        assert_eq!(task.summary, "Dentist");
+        let day = NaiveDate::from_ymd_opt(2024, 6, 1).unwrap();
+        assert_eq!(at(&task), day.and_hms_opt(9, 0, 0));
+        assert_eq!(task.format_due().as_deref(), Some("2024-06-01 09:00"));
+
+        // No time: an all-day due, and a lone time stays in the summary
+        let task = Task::new("Pay rent @2024-06-01 at 9:00", &aliases);
+        assert!(task.due_is_date_only);
+        assert_eq!(task.summary, "Pay rent at 9:00");
+        assert_eq!(task.due_time(), None);
+        assert_eq!(task.to_smart_string(), "Pay rent at 9:00 @2024-06-01");
+
+        // A timed due at midnight is shown as a plain day
+        let task = Task::new("Renew @2024-06-01T00:00", &aliases);
+        assert!(!task.due_is_date_only);
+        assert_eq!(task.format_due().as_deref(), Some("2024-06-01"));
+    }
+
+    #[test]
+    fn test_with_recurrence() {
+        let weekly = "FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,WE";
+        let input = with_recurrence("Gym @every 3 days #health", Some(weekly));
+        assert_eq!(input, "Gym #health rec:FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,WE");
+        assert_eq!(input_recurrence(&input).as_deref(), Some(weekly));
+        assert_eq!(
+            with_recurrence(&input, Some("FREQ=DAILY")),
+            "Gym #health @daily"
+        );
+        assert_eq!(with_recurrence("Gym @weekly", None), "Gym");
+
+        // Complex rules survive editing instead of becoming "rec:custom"
+        let mut task = Task::new("Gym", &HashMap::new());
+        task.rrule = Some(weekly.to_string());
+        let reparsed = Task::new(&task.to_smart_string(), &HashMap::new());
+        assert_eq!(reparsed.rrule.as_deref(), Some(weekly));
+    }
+
+    #[test]
+    fn test_combined_durations() {
+        assert_eq!(parse_duration("1h30m"), Some(90));
+        assert_eq!(parse_duration("2d4h"), Some(2 * 1440 + 240));
+        assert_eq!(parse_duration("90"), Some(90));
+        assert_eq!(parse_duration("45min"), Some(45));
+        assert_eq!(parse_duration("3mo"), Some(3 * 43200));
+        assert_eq!(parse_duration("1x"), None);
+        assert_eq!(parse_duration("1h30"), None);
+        assert_eq!(parse_duration("h"), None);
+
+        assert_eq!(format_duration(90), "1h30m");
+        assert_eq!(format_duration(120), "2h");
+        assert_eq!(format_duration(1440 + 30), "1d30m");
+        assert_eq!(format_mins(260), "4h 20m");
+
+        let aliases = HashMap::new();
+        let task = Task::new("Write report ~1h30m *1d2h", &aliases);
+        assert_eq!(task.summary, "Write report");
+        assert_eq!(task.estimated_duration, Some(90));
+        assert_eq!(task.reminders, vec![1560]);
+        assert_eq!(task.to_smart_string(), "Write report ~1h30m *1d2h");
+
+        // Not a duration, so it stays in the summary
+        let task = Task::new("Fix ~1x", &aliases);
+        assert_eq!(task.summary, "Fix ~1x");
+        assert_eq!(task.estimated_duration, None);
+    }
+}
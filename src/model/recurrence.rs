@@ -0,0 +1,194 @@
+// File: src/model/recurrence.rs
+// Simple RRULEs (frequency, interval, weekdays) for the recurrence builder
+use chrono::{Utc, Weekday};
+use rrule::RRuleSet;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+impl Frequency {
+    pub const ALL: [Frequency; 4] = [
+        Frequency::Daily,
+        Frequency::Weekly,
+        Frequency::Monthly,
+        Frequency::Yearly,
+    ];
+
+    fn rrule_name(self) -> &'static str {
+        match self {
+            Frequency::Daily => "DAILY",
+            Frequency::Weekly => "WEEKLY",
+            Frequency::Monthly => "MONTHLY",
+            Frequency::Yearly => "YEARLY",
+        }
+    }
+
+    fn unit(self) -> &'static str {
+        match self {
+            Frequency::Daily => "day",
+            Frequency::Weekly => "week",
+            Frequency::Monthly => "month",
+            Frequency::Yearly => "year",
+        }
+    }
+}
+
+impl fmt::Display for Frequency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Frequency::Daily => "Daily",
+            Frequency::Weekly => "Weekly",
+            Frequency::Monthly => "Monthly",
+            Frequency::Yearly => "Yearly",
+        })
+    }
+}
+
+const WEEKDAYS: [(Weekday, &str); 7] = [
+    (Weekday::Mon, "MO"),
+    (Weekday::Tue, "TU"),
+    (Weekday::Wed, "WE"),
+    (Weekday::Thu, "TH"),
+    (Weekday::Fri, "FR"),
+    (Weekday::Sat, "SA"),
+    (Weekday::Sun, "SU"),
+];
+
+/// A recurrence the builder can edit: every `interval` periods, on `weekdays`
+/// (weekly rules only, empty meaning the weekday of the start date).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Recurrence {
+    pub freq: Frequency,
+    pub interval: u32,
+    pub weekdays: Vec<Weekday>,
+}
+
+impl Recurrence {
+    pub fn new(freq: Frequency) -> Self {
+        Self {
+            freq,
+            interval: 1,
+            weekdays: Vec::new(),
+        }
+    }
+
+    /// Reads a rule made only of FREQ, INTERVAL and (for weekly rules) BYDAY.
+    /// Anything else can't be shown in the builder and returns `None`.
+    pub fn from_rrule(rrule: &str) -> Option<Self> {
+        let mut rec: Option<Self> = None;
+        let mut interval = 1;
+        let mut weekdays = Vec::new();
+        for part in rrule.split(';').filter(|p| !p.is_empty()) {
+            let (key, value) = part.split_once('=')?;
+            match key.to_ascii_uppercase().as_str() {
+                "FREQ" => {
+                    let freq = Frequency::ALL
+                        .into_iter()
+                        .find(|f| f.rrule_name().eq_ignore_ascii_case(value))?;
+                    rec = Some(Self::new(freq));
+                }
+                "INTERVAL" => interval = value.parse().ok().filter(|i| *i > 0)?,
+                "BYDAY" => {
+                    for day in value.split(',') {
+                        let (wd, _) = WEEKDAYS
+                            .iter()
+                            .find(|(_, code)| code.eq_ignore_ascii_case(day))?;
+                        weekdays.push(*wd);
+                    }
+                }
+                _ => return None,
+            }
+        }
+        let mut rec = rec?;
+        if !weekdays.is_empty() && rec.freq != Frequency::Weekly {
+            return None;
+        }
+        rec.interval = interval;
+        rec.weekdays = weekdays;
+        Some(rec)
+    }
+
+    pub fn to_rrule(&self) -> String {
+        let mut rule = format!("FREQ={}", self.freq.rrule_name());
+        if self.interval > 1 {
+            rule.push_str(&format!(";INTERVAL={}", self.interval));
+        }
+        if self.freq == Frequency::Weekly && !self.weekdays.is_empty() {
+            let days: Vec<&str> = WEEKDAYS
+                .iter()
+                .filter(|(wd, _)| self.weekdays.contains(wd))
+                .map(|(_, code)| *code)
+                .collect();
+            rule.push_str(&format!(";BYDAY={}", days.join(",")));
+        }
+        rule
+    }
+
+    pub fn toggle_weekday(&mut self, day: Weekday) {
+        if let Some(pos) = self.weekdays.iter().position(|d| *d == day) {
+            self.weekdays.remove(pos);
+        } else {
+            self.weekdays.push(day);
+            self.weekdays.sort_by_key(|d| d.num_days_from_monday());
+        }
+    }
+
+    /// Human summary, e.g. "Every day" or "Every 2 weeks on Mon, Wed".
+    pub fn summary(&self) -> String {
+        let mut s = if self.interval == 1 {
+            format!("Every {}", self.freq.unit())
+        } else {
+            format!("Every {} {}s", self.interval, self.freq.unit())
+        };
+        if self.freq == Frequency::Weekly && !self.weekdays.is_empty() {
+            let days: Vec<String> = WEEKDAYS
+                .iter()
+                .filter(|(wd, _)| self.weekdays.contains(wd))
+                .map(|(wd, _)| wd.to_string())
+                .collect();
+            s.push_str(&format!(" on {}", days.join(", ")));
+        }
+        s
+    }
+}
+
+/// Checks that the rule is one the recurrence engine can expand.
+pub fn validate_rrule(rrule: &str) -> Result<(), String> {
+    let start = Utc::now().format("%Y%m%dT%H%M%SZ");
+    RRuleSet::from_str(&format!("DTSTART:{}\nRRULE:{}", start, rrule))
+        .map(|_| ())
+        .map_err(|e| format!("Invalid recurrence: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recurrence_round_trip() {
+        let mut rec = Recurrence::new(Frequency::Weekly);
+        rec.interval = 2;
+        rec.toggle_weekday(Weekday::Wed);
+        rec.toggle_weekday(Weekday::Mon);
+        assert_eq!(rec.to_rrule(), "FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,WE");
+        assert_eq!(rec.summary(), "Every 2 weeks on Mon, Wed");
+        assert!(validate_rrule(&rec.to_rrule()).is_ok());
+        assert_eq!(Recurrence::from_rrule(&rec.to_rrule()), Some(rec));
+
+        let daily = Recurrence::from_rrule("FREQ=DAILY").unwrap();
+        assert_eq!(daily.summary(), "Every day");
+        assert_eq!(daily.to_rrule(), "FREQ=DAILY");
+
+        // Rules the builder can't show
+        assert_eq!(Recurrence::from_rrule("FREQ=MONTHLY;BYMONTHDAY=15"), None);
+        assert_eq!(Recurrence::from_rrule("FREQ=MONTHLY;BYDAY=MO"), None);
+        assert!(validate_rrule("FREQ=SOMETIMES").is_err());
+    }
+}
@@ -0,0 +1,82 @@
+// File: src/model/timer.rs
+// Focus timer for the task being worked on
+use crate::model::Task;
+use chrono::{DateTime, Utc};
+
+/// Timer started with a task. Counts down from the task's estimate when it has
+/// one, up otherwise; the elapsed minutes go to `Task::time_spent` on stop.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TimerState {
+    pub uid: String,
+    pub summary: String,
+    pub started: DateTime<Utc>,
+    /// Minutes to count down from (the estimated duration at start).
+    pub countdown: Option<u32>,
+}
+
+impl TimerState {
+    pub fn start(task: &Task, now: DateTime<Utc>) -> Self {
+        Self {
+            uid: task.uid.clone(),
+            summary: task.summary.clone(),
+            started: now,
+            countdown: task.estimated_duration.filter(|m| *m > 0),
+        }
+    }
+
+    /// Minutes to log, rounded to the nearest one.
+    pub fn elapsed_mins(&self, now: DateTime<Utc>) -> u32 {
+        let secs = (now - self.started).num_seconds().max(0);
+        ((secs + 30) / 60) as u32
+    }
+
+    /// Clock for the status line: "12:34" counting up, "12:34 left" counting
+    /// down and "+1:05" once past the estimate.
+    pub fn display(&self, now: DateTime<Utc>) -> String {
+        let elapsed = (now - self.started).num_seconds().max(0);
+        match self.countdown {
+            None => format_clock(elapsed),
+            Some(mins) => {
+                let left = mins as i64 * 60 - elapsed;
+                if left >= 0 {
+                    format!("{} left", format_clock(left))
+                } else {
+                    format!("+{}", format_clock(-left))
+                }
+            }
+        }
+    }
+}
+
+fn format_clock(secs: i64) -> String {
+    let (h, m, s) = (secs / 3600, secs / 60 % 60, secs % 60);
+    if h > 0 {
+        format!("{}:{:02}:{:02}", h, m, s)
+    } else {
+        format!("{}:{:02}", m, s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_timer_display() {
+        let now = Utc::now();
+        let timer = TimerState::start(&Task::new("Inbox zero", &HashMap::new()), now);
+        assert_eq!(timer.display(now + Duration::seconds(754)), "12:34");
+        assert_eq!(timer.display(now + Duration::seconds(3725)), "1:02:05");
+        assert_eq!(timer.elapsed_mins(now + Duration::seconds(89)), 1);
+        assert_eq!(timer.elapsed_mins(now + Duration::seconds(90)), 2);
+
+        let timer = TimerState::start(&Task::new("Report ~25m", &HashMap::new()), now);
+        assert_eq!(timer.display(now + Duration::minutes(5)), "20:00 left");
+        assert_eq!(
+            timer.display(now + Duration::seconds(25 * 60 + 65)),
+            "+1:05"
+        );
+    }
+}
@@ -87,4 +87,13 @@ impl AppPaths {
     pub fn get_local_task_path() -> Option<PathBuf> {
         Self::get_data_dir().ok().map(|p| p.join("local.json"))
     }
+
+    /// Directory for the local calendars besides the default one.
+    pub fn get_local_calendars_dir() -> Result<PathBuf> {
+        Self::ensure_exists(Self::get_data_dir()?.join("local_calendars"))
+    }
+
+    pub fn get_window_state_path() -> Option<PathBuf> {
+        Self::get_data_dir().ok().map(|p| p.join("window.json"))
+    }
 }
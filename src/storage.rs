@@ -1,5 +1,5 @@
 // File: src/storage.rs
-use crate::model::Task;
+use crate::model::{CalendarListEntry, Task};
 use crate::paths::AppPaths;
 use anyhow::Result;
 use std::fs;
@@ -11,6 +11,8 @@ use fs2::FileExt;
 // Constants for identification
 pub const LOCAL_CALENDAR_HREF: &str = "local://default";
 pub const LOCAL_CALENDAR_NAME: &str = "Local";
+/// Every local calendar href starts with this; the rest names its file.
+pub const LOCAL_CALENDAR_PREFIX: &str = "local://";
 
 pub struct LocalStorage;
 
@@ -19,6 +21,102 @@ impl LocalStorage {
         AppPaths::get_local_task_path()
     }
 
+    /// Whether the calendar lives on disk rather than on the server.
+    pub fn is_local(href: &str) -> bool {
+        href.starts_with(LOCAL_CALENDAR_PREFIX)
+    }
+
+    /// File holding the tasks of a local calendar: `local.json` for the default
+    /// one, `local_calendars/<id>.json` for the others.
+    fn path_for(href: &str) -> Option<PathBuf> {
+        if href == LOCAL_CALENDAR_HREF {
+            return Self::get_path();
+        }
+        let id = href.strip_prefix(LOCAL_CALENDAR_PREFIX)?;
+        if id.is_empty() || !id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+            return None;
+        }
+        AppPaths::get_local_calendars_dir()
+            .ok()
+            .map(|dir| dir.join(format!("{}.json", id)))
+    }
+
+    fn index_path() -> Option<PathBuf> {
+        AppPaths::get_local_calendars_dir()
+            .ok()
+            .map(|dir| dir.join("calendars.json"))
+    }
+
+    fn load_index() -> Vec<CalendarListEntry> {
+        let Some(path) = Self::index_path().filter(|p| p.exists()) else {
+            return vec![];
+        };
+        Self::with_lock(&path, || {
+            let json = fs::read_to_string(&path)?;
+            Ok(serde_json::from_str::<Vec<CalendarListEntry>>(&json)?)
+        })
+        .unwrap_or_default()
+    }
+
+    fn save_index(cals: &[CalendarListEntry]) -> Result<()> {
+        let path = Self::index_path()
+            .ok_or_else(|| anyhow::anyhow!("Could not determine local calendars directory"))?;
+        Self::with_lock(&path, || {
+            let json = serde_json::to_string_pretty(cals)?;
+            Self::atomic_write(&path, json)
+        })
+    }
+
+    /// The default local calendar followed by the ones made with `create_calendar`.
+    pub fn calendars() -> Vec<CalendarListEntry> {
+        let mut cals = vec![CalendarListEntry {
+            name: LOCAL_CALENDAR_NAME.to_string(),
+            href: LOCAL_CALENDAR_HREF.to_string(),
+            color: None,
+            read_only: false,
+        }];
+        cals.extend(Self::load_index());
+        cals
+    }
+
+    /// Appends the local calendars missing from a (server or cached) calendar list.
+    pub fn inject_calendars(cals: &mut Vec<CalendarListEntry>) {
+        for local in Self::calendars() {
+            if !cals.iter().any(|c| c.href == local.href) {
+                cals.push(local);
+            }
+        }
+    }
+
+    /// Creates an empty local calendar, usable without any server.
+    pub fn create_calendar(name: &str) -> Result<CalendarListEntry> {
+        let name = name.trim();
+        if name.is_empty() {
+            anyhow::bail!("Calendar name is empty");
+        }
+        let cal = CalendarListEntry {
+            name: name.to_string(),
+            href: format!("{}{}", LOCAL_CALENDAR_PREFIX, uuid::Uuid::new_v4()),
+            color: None,
+            read_only: false,
+        };
+        Self::save(&cal.href, &[])?;
+        let mut index = Self::load_index();
+        index.push(cal.clone());
+        Self::save_index(&index)?;
+        Ok(cal)
+    }
+
+    pub fn rename_calendar(href: &str, new_name: &str) -> Result<()> {
+        let mut index = Self::load_index();
+        let cal = index
+            .iter_mut()
+            .find(|c| c.href == href)
+            .ok_or_else(|| anyhow::anyhow!("The default local calendar cannot be renamed"))?;
+        cal.name = new_name.to_string();
+        Self::save_index(&index)
+    }
+
     /// Helper to get a sidecar lock file path (e.g., "local.json.lock")
     #[cfg(not(target_os = "android"))]
     fn get_lock_path(file_path: &Path) -> PathBuf {
@@ -71,8 +169,8 @@ impl LocalStorage {
         Ok(())
     }
 
-    pub fn save(tasks: &[Task]) -> Result<()> {
-        if let Some(path) = Self::get_path() {
+    pub fn save(href: &str, tasks: &[Task]) -> Result<()> {
+        if let Some(path) = Self::path_for(href) {
             Self::with_lock(&path, || {
                 let json = serde_json::to_string_pretty(tasks)?;
                 Self::atomic_write(&path, json)?;
@@ -82,8 +180,8 @@ impl LocalStorage {
         Ok(())
     }
 
-    pub fn load() -> Result<Vec<Task>> {
-        if let Some(path) = Self::get_path() {
+    pub fn load(href: &str) -> Result<Vec<Task>> {
+        if let Some(path) = Self::path_for(href) {
             if !path.exists() {
                 return Ok(vec![]);
             }
@@ -1,18 +1,23 @@
 // File: src/store.rs
 use crate::cache::Cache;
-use crate::model::{Task, TaskStatus};
-use chrono::{DateTime, Utc};
+use crate::model::{CONFLICT_COPY_SUFFIX, Task, TaskStatus, TimerState, checklist};
+use chrono::{DateTime, Datelike, Local, NaiveDate, Utc, Weekday};
 use std::collections::{HashMap, HashSet};
 
 pub const UNCATEGORIZED_ID: &str = ":::uncategorized:::";
+/// How many days back the review view looks, today included.
+pub const REVIEW_DAYS: i64 = 7;
 
 #[derive(Debug, Clone, Default)]
 pub struct TaskStore {
     pub calendars: HashMap<String, Vec<Task>>,
     /// Reverse index: Maps Task UID -> Calendar HREF for O(1) lookups
     pub index: HashMap<String, String>,
+    /// Last server sync per calendar, as recorded in the cache
+    pub synced_at: HashMap<String, DateTime<Utc>>,
 }
 
+#[derive(Clone, Copy)]
 pub struct FilterOptions<'a> {
     pub active_cal_href: Option<&'a str>,
     pub hidden_calendars: &'a std::collections::HashSet<String>,
@@ -20,10 +25,211 @@ pub struct FilterOptions<'a> {
     pub match_all_categories: bool,
     pub search_term: &'a str,
     pub hide_completed_global: bool,
+    /// Hide cancelled tasks; `hide_completed_global` only covers completed ones.
+    pub hide_cancelled: bool,
+    /// Hide tasks waiting on an unfinished dependency instead of badging them.
+    pub hide_blocked: bool,
     pub cutoff_date: Option<DateTime<Utc>>,
     pub min_duration: Option<u32>,
     pub max_duration: Option<u32>,
     pub include_unset_duration: bool,
+    /// Only keep tasks due today, plus open overdue ones.
+    pub due_today_only: bool,
+    /// Sort completed and cancelled tasks below the open ones of their level.
+    pub completed_last: bool,
+    /// Sort by the manual order (`Task::sort_order`) instead of dates and priority.
+    pub manual_order: bool,
+    /// Leave out someday/maybe tasks (`Task::deferred`).
+    pub hide_deferred: bool,
+    /// Match the search term fuzzily and rank the results by score.
+    pub fuzzy_search: bool,
+    /// First day of the week, for the "This Week" agenda section.
+    pub week_start: Weekday,
+    /// Only keep tasks with a priority from 1 up to this one (1 is the highest).
+    pub priority_filter: Option<u8>,
+}
+
+/// Date sections used by the agenda view, in display order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum DueBucket {
+    Overdue,
+    Today,
+    Tomorrow,
+    ThisWeek,
+    Later,
+    NoDate,
+}
+
+impl DueBucket {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Overdue => "Overdue",
+            Self::Today => "Today",
+            Self::Tomorrow => "Tomorrow",
+            Self::ThisWeek => "This Week",
+            Self::Later => "Later",
+            Self::NoDate => "No Date",
+        }
+    }
+
+    pub fn for_date(due: Option<DateTime<Utc>>, today: NaiveDate, week_start: Weekday) -> Self {
+        Self::for_day(
+            due.map(|d| d.with_timezone(&Local).date_naive()),
+            today,
+            week_start,
+        )
+    }
+
+    /// Same as `for_date` for a calendar day (see `Task::due_date`).
+    pub fn for_day(date: Option<NaiveDate>, today: NaiveDate, week_start: Weekday) -> Self {
+        let Some(date) = date else {
+            return Self::NoDate;
+        };
+        let days_left_in_week = 6 - today.weekday().days_since(week_start) as i64;
+        let delta = (date - today).num_days();
+        if delta < 0 {
+            Self::Overdue
+        } else if delta == 0 {
+            Self::Today
+        } else if delta == 1 {
+            Self::Tomorrow
+        } else if delta <= days_left_in_week {
+            Self::ThisWeek
+        } else {
+            Self::Later
+        }
+    }
+}
+
+/// Section header of the review view: "Today", "Yesterday", then e.g. "Monday 12 October".
+pub fn day_label(day: NaiveDate, today: NaiveDate) -> String {
+    match (today - day).num_days() {
+        0 => "Today".to_string(),
+        1 => "Yesterday".to_string(),
+        _ => day.format("%A %-d %B").to_string(),
+    }
+}
+
+/// Groups a flat list of tasks into due date buckets, keeping the incoming order within
+/// each bucket. Empty buckets are omitted.
+pub fn group_by_due_bucket(
+    tasks: Vec<Task>,
+    today: NaiveDate,
+    week_start: Weekday,
+) -> Vec<(DueBucket, Vec<Task>)> {
+    let mut groups: Vec<(DueBucket, Vec<Task>)> = Vec::new();
+    for task in tasks {
+        let bucket = DueBucket::for_day(task.due_date(), today, week_start);
+        match groups.iter_mut().find(|(b, _)| *b == bucket) {
+            Some((_, list)) => list.push(task),
+            None => groups.push((bucket, vec![task])),
+        }
+    }
+    groups.sort_by_key(|(b, _)| *b);
+    groups
+}
+
+/// Total estimated minutes of the open tasks in `tasks` (done ones are left out).
+pub fn sum_estimated_duration(tasks: &[Task]) -> u32 {
+    tasks
+        .iter()
+        .filter(|t| !t.status.is_done())
+        .filter_map(|t| t.estimated_duration)
+        .fold(0, u32::saturating_add)
+}
+
+/// Gap between manual positions, leaving room to move tasks in between.
+pub const SORT_ORDER_STEP: i64 = 1000;
+
+/// Tasks shown at the same level as `uid` in `visible` (same parent, same status
+/// group, since the sort never mixes those), in display order.
+fn manual_siblings<'a>(visible: &'a [Task], uid: &str) -> Vec<&'a Task> {
+    fn group(status: TaskStatus) -> u8 {
+        match status {
+            TaskStatus::InProcess => 0,
+            TaskStatus::NeedsAction => 1,
+            TaskStatus::Completed | TaskStatus::Cancelled => 2,
+        }
+    }
+    let Some(task) = visible.iter().find(|t| t.uid == uid) else {
+        return Vec::new();
+    };
+    visible
+        .iter()
+        .filter(|t| {
+            t.depth == task.depth
+                && (task.depth == 0 || t.parent_uid == task.parent_uid)
+                && group(t.status) == group(task.status)
+        })
+        .collect()
+}
+
+/// The sibling displayed right above (`up`) or below `uid`, to swap places with.
+pub fn manual_neighbour(visible: &[Task], uid: &str, up: bool) -> Option<String> {
+    let siblings = manual_siblings(visible, uid);
+    let idx = siblings.iter().position(|t| t.uid == uid)?;
+    let target = if up { idx.checked_sub(1)? } else { idx + 1 };
+    siblings.get(target).map(|t| t.uid.clone())
+}
+
+/// Position in `visible` of the parent of `uid` (`to_child == false`) or of its
+/// first child, when it is shown.
+pub fn hierarchy_target(visible: &[Task], uid: &str, to_child: bool) -> Option<usize> {
+    if to_child {
+        return visible
+            .iter()
+            .position(|t| t.parent_uid.as_deref() == Some(uid));
+    }
+    let parent = visible
+        .iter()
+        .find(|t| t.uid == uid)?
+        .parent_uid
+        .as_deref()?;
+    visible.iter().position(|t| t.uid == parent)
+}
+
+/// Enough context to revert the last destructive action (single level).
+#[derive(Debug, Clone)]
+pub enum UndoEntry {
+    Deleted(Box<Task>),
+    StatusChanged {
+        uid: String,
+        previous: TaskStatus,
+        /// Next occurrence created by completing a recurring task, once known
+        spawned: Option<String>,
+    },
+}
+
+impl UndoEntry {
+    pub fn label(&self) -> &'static str {
+        match self {
+            UndoEntry::Deleted(_) => "delete",
+            UndoEntry::StatusChanged { .. } => "status change",
+        }
+    }
+
+    /// Records the status a task had before it was modified.
+    pub fn status_of(task: &Task) -> Self {
+        UndoEntry::StatusChanged {
+            uid: task.uid.clone(),
+            previous: task.status,
+            spawned: None,
+        }
+    }
+
+    /// Remembers that completing `uid` created the occurrence `next`, so undoing
+    /// the completion removes it again.
+    pub fn record_spawned(&mut self, uid: &str, next: &str) {
+        if let UndoEntry::StatusChanged {
+            uid: changed,
+            spawned,
+            ..
+        } = self
+            && changed == uid
+        {
+            *spawned = Some(next.to_string());
+        }
+    }
 }
 
 impl TaskStore {
@@ -35,9 +241,18 @@ impl TaskStore {
         for task in &tasks {
             self.index.insert(task.uid.clone(), calendar_href.clone());
         }
+        match Cache::synced_at(&calendar_href) {
+            Some(t) => self.synced_at.insert(calendar_href.clone(), t),
+            None => self.synced_at.remove(&calendar_href),
+        };
         self.calendars.insert(calendar_href, tasks);
     }
 
+    /// Time since the calendar was last fetched from the server; `None` if unknown.
+    pub fn sync_age(&self, calendar_href: &str) -> Option<chrono::Duration> {
+        self.synced_at.get(calendar_href).map(|t| Utc::now() - *t)
+    }
+
     pub fn add_task(&mut self, task: Task) {
         let href = task.calendar_href.clone();
         self.index.insert(task.uid.clone(), href.clone());
@@ -65,9 +280,26 @@ impl TaskStore {
         let _ = Cache::save(&href, list, token);
     }
 
+    /// Records the ETags the server returned for tasks sent from the journal, so
+    /// the next edit of those tasks isn't refused as a conflict.
+    pub fn set_etags(&mut self, etags: &HashMap<String, String>) {
+        let mut changed = HashSet::new();
+        for (uid, etag) in etags {
+            if let Some((task, href)) = self.get_task_mut(uid) {
+                task.etag = etag.clone();
+                changed.insert(href);
+            }
+        }
+        for href in changed {
+            let (_, token) = Cache::load(&href).unwrap_or((vec![], None));
+            let _ = Cache::save(&href, &self.calendars[&href], token);
+        }
+    }
+
     pub fn clear(&mut self) {
         self.calendars.clear();
         self.index.clear();
+        self.synced_at.clear();
     }
 
     // --- Core Logic Helpers ---
@@ -85,13 +317,35 @@ impl TaskStore {
         None
     }
 
+    /// Moves a recurring task to its next occurrence without completing it.
+    pub fn skip_occurrence(&mut self, uid: &str) -> Option<Task> {
+        let (task, _) = self.get_task_mut(uid)?;
+        if !task.skip_occurrence() {
+            return None;
+        }
+        Some(task.clone())
+    }
+
+    /// Moves the task in or out of someday/maybe.
+    pub fn toggle_deferred(&mut self, uid: &str) -> Option<Task> {
+        let (task, _) = self.get_task_mut(uid)?;
+        task.deferred = !task.deferred;
+        Some(task.clone())
+    }
+
+    pub fn set_color(&mut self, uid: &str, color: Option<String>) -> Option<Task> {
+        let (task, _) = self.get_task_mut(uid)?;
+        task.color = color;
+        Some(task.clone())
+    }
+
     pub fn toggle_task(&mut self, uid: &str) -> Option<Task> {
         if let Some((task, _)) = self.get_task_mut(uid) {
-            task.status = if task.status == TaskStatus::Completed {
+            task.set_status(if task.status == TaskStatus::Completed {
                 TaskStatus::NeedsAction
             } else {
                 TaskStatus::Completed
-            };
+            });
             return Some(task.clone());
         }
         None
@@ -100,15 +354,52 @@ impl TaskStore {
     pub fn set_status(&mut self, uid: &str, status: TaskStatus) -> Option<Task> {
         if let Some((task, _)) = self.get_task_mut(uid) {
             if task.status == status {
-                task.status = TaskStatus::NeedsAction;
+                task.set_status(TaskStatus::NeedsAction);
             } else {
-                task.status = status;
+                task.set_status(status);
             }
             return Some(task.clone());
         }
         None
     }
 
+    /// Stops the focus timer, adding its minutes to the timed task.
+    pub fn stop_timer(
+        &mut self,
+        timer: &mut Option<TimerState>,
+        now: DateTime<Utc>,
+    ) -> Option<Task> {
+        let timer = timer.take()?;
+        let (task, _) = self.get_task_mut(&timer.uid)?;
+        task.time_spent += timer.elapsed_mins(now);
+        Some(task.clone())
+    }
+
+    /// Keeps the focus timer in step after `updated` changed status: leaving
+    /// InProcess stops its timer, and with `auto_start` a started task takes the
+    /// timer over from any other one. Returns the task to sync, with any time
+    /// just logged, and the other tasks whose time spent grew.
+    pub fn follow_timer(
+        &mut self,
+        timer: &mut Option<TimerState>,
+        updated: Task,
+        auto_start: bool,
+        now: DateTime<Utc>,
+    ) -> (Task, Vec<Task>) {
+        let timed_here = timer.as_ref().is_some_and(|t| t.uid == updated.uid);
+        let started = updated.status == TaskStatus::InProcess;
+        if started && auto_start && !timed_here {
+            let logged = self.stop_timer(timer, now).into_iter().collect();
+            *timer = Some(TimerState::start(&updated, now));
+            (updated, logged)
+        } else if !started && timed_here {
+            let own = self.stop_timer(timer, now).unwrap_or(updated);
+            (own, Vec::new())
+        } else {
+            (updated, Vec::new())
+        }
+    }
+
     pub fn change_priority(&mut self, uid: &str, delta: i8) -> Option<Task> {
         if let Some((task, _)) = self.get_task_mut(uid) {
             task.priority = if delta > 0 {
@@ -133,6 +424,26 @@ impl TaskStore {
         None
     }
 
+    /// Sets the priority directly, 0 (none) to 9.
+    pub fn set_priority(&mut self, uid: &str, priority: u8) -> Option<Task> {
+        let (task, _) = self.get_task_mut(uid)?;
+        task.priority = priority.min(9);
+        Some(task.clone())
+    }
+
+    /// Pushes the due date to a snooze preset (see `Task::snooze`).
+    pub fn snooze(&mut self, uid: &str, preset: &str, week_start: Weekday) -> Option<Task> {
+        let (task, _) = self.get_task_mut(uid)?;
+        task.snooze(preset, week_start).then(|| task.clone())
+    }
+
+    /// Adds `delta` percentage points to a task's progress, clamped to 0..=100.
+    pub fn change_progress(&mut self, uid: &str, delta: i16) -> Option<Task> {
+        let (task, _) = self.get_task_mut(uid)?;
+        task.percent_complete = (task.percent_complete as i16 + delta).clamp(0, 100) as u8;
+        Some(task.clone())
+    }
+
     pub fn delete_task(&mut self, uid: &str) -> Option<Task> {
         let href = self.index.get(uid)?.clone();
 
@@ -148,9 +459,42 @@ impl TaskStore {
         None
     }
 
+    /// Applies an undo entry to the local store and returns the task to sync, with
+    /// the occurrence its completion had spawned, now removed from the store.
+    /// Deleted tasks come back as fresh resources and must be sent via `create_task`;
+    /// status changes return the reverted task, to be sent via `update_task`.
+    pub fn undo(&mut self, entry: UndoEntry) -> Option<(Task, Option<Task>)> {
+        match entry {
+            UndoEntry::Deleted(task) => {
+                let mut task = *task;
+                task.etag = String::new();
+                self.update_or_add_task(task.clone());
+                Some((task, None))
+            }
+            UndoEntry::StatusChanged {
+                uid,
+                previous,
+                spawned,
+            } => {
+                let (task, _) = self.get_task_mut(&uid)?;
+                task.set_status(previous);
+                let restored = task.clone();
+                let removed = spawned.and_then(|next| self.delete_task(&next));
+                Some((restored, removed))
+            }
+        }
+    }
+
+    /// Ticks or unticks a `- [ ]` item in the task description.
+    pub fn toggle_checklist_item(&mut self, uid: &str, line_idx: usize) -> Option<Task> {
+        let (task, _) = self.get_task_mut(uid)?;
+        task.description = checklist::toggle_checkbox(&task.description, line_idx)?;
+        Some(task.clone())
+    }
+
     pub fn set_parent(&mut self, child_uid: &str, parent_uid: Option<String>) -> Option<Task> {
         if let Some((task, _)) = self.get_task_mut(child_uid) {
-            task.parent_uid = parent_uid;
+            task.set_parent(parent_uid);
             return Some(task.clone());
         }
         None
@@ -259,6 +603,7 @@ impl TaskStore {
         hide_fully_completed_tags: bool,
         forced_includes: &HashSet<String>,
         hidden_calendars: &HashSet<String>,
+        sort_by_count: bool,
     ) -> Vec<(String, usize)> {
         let mut active_counts: HashMap<String, usize> = HashMap::new();
         let mut present_tags: HashSet<String> = HashSet::new();
@@ -330,7 +675,17 @@ impl TaskStore {
             result.push((UNCATEGORIZED_ID.to_string(), count));
         }
 
-        result.sort_by(|a, b| a.0.cmp(&b.0));
+        if sort_by_count {
+            // Busiest tags first, ties alphabetical, "Uncategorized" stays on top
+            result.sort_by(|a, b| {
+                (b.0 == UNCATEGORIZED_ID)
+                    .cmp(&(a.0 == UNCATEGORIZED_ID))
+                    .then(b.1.cmp(&a.1))
+                    .then(a.0.cmp(&b.0))
+            });
+        } else {
+            result.sort_by(|a, b| a.0.cmp(&b.0));
+        }
         result
     }
 
@@ -366,6 +721,8 @@ impl TaskStore {
             }
         }
 
+        let today = Local::now().date_naive();
+        let mut scores: HashMap<String, i64> = HashMap::new();
         let filtered: Vec<Task> = raw_tasks
             .into_iter()
             .filter(|t| {
@@ -374,10 +731,32 @@ impl TaskStore {
                     || search_lower.contains("is:active")
                     || search_lower.contains("is:ongoing");
 
-                if !has_status_filter && t.status.is_done() && options.hide_completed_global {
+                let hidden_status = match t.status {
+                    TaskStatus::Completed => options.hide_completed_global,
+                    TaskStatus::Cancelled => options.hide_cancelled,
+                    _ => false,
+                };
+                if !has_status_filter && hidden_status {
+                    return false;
+                }
+
+                if options.hide_blocked && self.is_blocked(t) {
                     return false;
                 }
 
+                // Someday tasks never count as due today
+                if t.deferred && (options.hide_deferred || options.due_today_only) {
+                    return false;
+                }
+
+                if options.due_today_only {
+                    match DueBucket::for_day(t.due_date(), today, options.week_start) {
+                        DueBucket::Today => {}
+                        DueBucket::Overdue if !t.status.is_done() => {}
+                        _ => return false,
+                    }
+                }
+
                 match t.estimated_duration {
                     Some(mins) => {
                         if let Some(min) = options.min_duration
@@ -398,6 +777,12 @@ impl TaskStore {
                     }
                 }
 
+                if let Some(max) = options.priority_filter
+                    && (t.priority == 0 || t.priority > max)
+                {
+                    return false;
+                }
+
                 if !options.selected_categories.is_empty() {
                     let filter_uncategorized =
                         options.selected_categories.contains(UNCATEGORIZED_ID);
@@ -457,13 +842,260 @@ impl TaskStore {
                 }
 
                 if !options.search_term.is_empty() {
+                    if options.fuzzy_search {
+                        let Some(score) = t.fuzzy_score(options.search_term) else {
+                            return false;
+                        };
+                        scores.insert(t.uid.clone(), score);
+                        return true;
+                    }
                     return t.matches_search_term(options.search_term);
                 }
                 true
             })
             .collect();
 
-        Task::organize_hierarchy(filtered, options.cutoff_date)
+        let organized = Task::organize_hierarchy(
+            filtered,
+            options.cutoff_date,
+            options.completed_last,
+            options.manual_order,
+        );
+        if scores.is_empty() {
+            organized
+        } else {
+            Self::rank_trees(organized, &scores)
+        }
+    }
+
+    /// Orders the top-level trees of an organized list by the best score found in
+    /// each of them, keeping every subtree together.
+    fn rank_trees(tasks: Vec<Task>, scores: &HashMap<String, i64>) -> Vec<Task> {
+        let mut trees: Vec<(i64, Vec<Task>)> = Vec::new();
+        for task in tasks {
+            let score = scores.get(&task.uid).copied().unwrap_or(i64::MIN);
+            match trees.last_mut() {
+                Some((best, tree)) if task.depth > 0 => {
+                    *best = (*best).max(score);
+                    tree.push(task);
+                }
+                _ => trees.push((score, vec![task])),
+            }
+        }
+        // Stable, so equal scores keep the usual order
+        trees.sort_by_key(|(best, _)| std::cmp::Reverse(*best));
+        trees.into_iter().flat_map(|(_, tree)| tree).collect()
+    }
+
+    /// Completed and cancelled tasks of a calendar, as moved by "Archive Completed".
+    pub fn archivable_tasks(&self, href: &str) -> Vec<Task> {
+        self.calendars
+            .get(href)
+            .map(|tasks| {
+                tasks
+                    .iter()
+                    .filter(|t| t.status.is_done())
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Completed tasks of a calendar that "Clear Completed" deletes for good.
+    /// Recurring tasks are left alone, and so are parents still holding a
+    /// subtask that stays.
+    pub fn clearable_tasks(&self, href: &str) -> Vec<Task> {
+        let Some(tasks) = self.calendars.get(href) else {
+            return Vec::new();
+        };
+        let mut uids: HashSet<&str> = tasks
+            .iter()
+            .filter(|t| t.status == TaskStatus::Completed && t.rrule.is_none())
+            .map(|t| t.uid.as_str())
+            .collect();
+        loop {
+            let kept_parents: Vec<&str> = self
+                .calendars
+                .values()
+                .flatten()
+                .filter(|t| !uids.contains(t.uid.as_str()))
+                .filter_map(|t| t.parent_uid.as_deref())
+                .filter(|p| uids.contains(p))
+                .collect();
+            if kept_parents.is_empty() {
+                break;
+            }
+            for parent in kept_parents {
+                uids.remove(parent);
+            }
+        }
+        tasks
+            .iter()
+            .filter(|t| uids.contains(t.uid.as_str()))
+            .cloned()
+            .collect()
+    }
+
+    /// Number of tasks in a calendar for the sidebar badge: the open ones, plus
+    /// the completed and cancelled ones unless `hide_completed`.
+    pub fn open_count(&self, href: &str, hide_completed: bool) -> usize {
+        self.calendars.get(href).map_or(0, |tasks| {
+            tasks
+                .iter()
+                .filter(|t| !hide_completed || !t.status.is_done())
+                .count()
+        })
+    }
+
+    /// The open task due soonest after `now` and at most `within` later.
+    pub fn next_due_within(&self, now: DateTime<Utc>, within: chrono::Duration) -> Option<&Task> {
+        self.calendars
+            .values()
+            .flatten()
+            .filter(|t| !t.status.is_done())
+            .filter(|t| t.due.is_some_and(|d| d > now && d <= now + within))
+            .min_by_key(|t| t.due)
+    }
+
+    /// Returns every task (across all calendars) matching the search query.
+    /// Uses the same syntax as the search bar (see `Task::matches_search_term`).
+    pub fn search(&self, query: &str) -> Vec<&Task> {
+        self.calendars
+            .values()
+            .flatten()
+            .filter(|t| t.matches_search_term(query))
+            .collect()
+    }
+
+    /// Fuzzy counterpart of `search` (see `Task::fuzzy_score`), best match first.
+    pub fn fuzzy_search(&self, query: &str) -> Vec<(Task, i64)> {
+        let mut results: Vec<(Task, i64)> = self
+            .calendars
+            .values()
+            .flatten()
+            .filter_map(|t| t.fuzzy_score(query).map(|score| (t.clone(), score)))
+            .collect();
+        results.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.summary.cmp(&b.0.summary)));
+        results
+    }
+
+    /// Agenda view: every visible task, flattened (no hierarchy) and split into
+    /// due date buckets. Completed tasks are always left out.
+    /// Returns the flat list plus the index at which each bucket starts.
+    pub fn agenda(&self, options: FilterOptions) -> (Vec<Task>, Vec<(usize, DueBucket)>) {
+        let tasks = self.open_flattened(options);
+        let mut flat = Vec::with_capacity(tasks.len());
+        let mut sections = Vec::new();
+        let today = Local::now().date_naive();
+        for (bucket, list) in group_by_due_bucket(tasks, today, options.week_start) {
+            sections.push((flat.len(), bucket));
+            flat.extend(list);
+        }
+        (flat, sections)
+    }
+
+    /// Open tasks without a due date matching the other filters, sorted and flattened
+    /// like the agenda. They make the "No Date" section, which the today filter
+    /// would otherwise leave out.
+    pub fn undated(&self, options: FilterOptions) -> Vec<Task> {
+        let mut tasks = self.open_flattened(FilterOptions {
+            due_today_only: false,
+            ..options
+        });
+        tasks.retain(|t| t.due_date().is_none());
+        tasks
+    }
+
+    /// Ends the list of the agenda or today view with the "No Date" section, left
+    /// out when `collapsed`. Returns the number of undated tasks either way.
+    pub fn add_no_date_section(
+        &self,
+        options: FilterOptions,
+        tasks: &mut Vec<Task>,
+        sections: &mut Vec<(usize, DueBucket)>,
+        collapsed: bool,
+    ) -> usize {
+        // The agenda without the today filter already ends with them
+        if let Some(&(start, DueBucket::NoDate)) = sections.last() {
+            sections.pop();
+            tasks.truncate(start);
+        }
+        let shown: HashSet<&str> = tasks.iter().map(|t| t.uid.as_str()).collect();
+        let undated: Vec<Task> = self
+            .undated(options)
+            .into_iter()
+            .filter(|t| !shown.contains(t.uid.as_str()))
+            .collect();
+        let count = undated.len();
+        if !collapsed && count > 0 {
+            sections.push((tasks.len(), DueBucket::NoDate));
+            tasks.extend(undated);
+        }
+        count
+    }
+
+    /// Open, not deferred tasks passing `options`, at depth 0 in the agenda order.
+    fn open_flattened(&self, options: FilterOptions) -> Vec<Task> {
+        let cutoff = options.cutoff_date;
+        let mut tasks: Vec<Task> = self
+            .filter(FilterOptions {
+                hide_completed_global: true,
+                hide_cancelled: true,
+                ..options
+            })
+            .into_iter()
+            .filter(|t| !t.status.is_done() && !t.deferred)
+            .map(|mut t| {
+                t.depth = 0;
+                t
+            })
+            .collect();
+        tasks.sort_by(|a, b| a.compare_with_cutoff(b, cutoff));
+        tasks
+    }
+
+    /// Review view: tasks completed in the last `REVIEW_DAYS` days, across all visible
+    /// calendars, newest first and flattened. Tasks without a COMPLETED stamp go by
+    /// their last modification. Returns the list plus the index at which each day starts.
+    pub fn recently_completed(
+        &self,
+        hidden_calendars: &HashSet<String>,
+    ) -> (Vec<Task>, Vec<(usize, NaiveDate)>) {
+        let today = Local::now().date_naive();
+        let since = today - chrono::Duration::days(REVIEW_DAYS - 1);
+        let done_on = |t: &Task| {
+            t.completed_at
+                .or(t.last_modified)
+                .map(|d| d.with_timezone(&Local).date_naive())
+        };
+
+        let mut tasks: Vec<(NaiveDate, Task)> = self
+            .calendars
+            .iter()
+            .filter(|(href, _)| !hidden_calendars.contains(*href))
+            .flat_map(|(_, tasks)| tasks.iter())
+            .filter(|t| t.status == TaskStatus::Completed)
+            .filter_map(|t| {
+                let day = done_on(t).filter(|d| *d >= since)?;
+                let mut t = t.clone();
+                t.depth = 0;
+                Some((day, t))
+            })
+            .collect();
+        tasks.sort_by(|(_, a), (_, b)| {
+            let at = |t: &Task| t.completed_at.or(t.last_modified);
+            at(b).cmp(&at(a)).then_with(|| a.summary.cmp(&b.summary))
+        });
+
+        let mut flat = Vec::with_capacity(tasks.len());
+        let mut sections: Vec<(usize, NaiveDate)> = Vec::new();
+        for (day, task) in tasks {
+            if sections.last().is_none_or(|(_, d)| *d != day) {
+                sections.push((flat.len(), day));
+            }
+            flat.push(task);
+        }
+        (flat, sections)
     }
 
     pub fn is_task_done(&self, uid: &str) -> Option<bool> {
@@ -494,6 +1126,246 @@ impl TaskStore {
         false
     }
 
+    /// `child_progress` of every parent at once, for the list views.
+    pub fn child_progress_map(&self) -> HashMap<String, (usize, usize)> {
+        let mut progress: HashMap<String, (usize, usize)> = HashMap::new();
+        for t in self.calendars.values().flatten() {
+            if let Some(parent) = &t.parent_uid
+                && t.status != TaskStatus::Cancelled
+            {
+                let (done, total) = progress.entry(parent.clone()).or_default();
+                *total += 1;
+                if t.status == TaskStatus::Completed {
+                    *done += 1;
+                }
+            }
+        }
+        progress
+    }
+
+    /// Counts (completed, total) direct children of a task.
+    /// Cancelled children are left out of both numbers. Returns None if there are no children.
+    pub fn child_progress(&self, parent_uid: &str) -> Option<(usize, usize)> {
+        let mut done = 0;
+        let mut total = 0;
+        for tasks in self.calendars.values() {
+            for t in tasks {
+                if t.parent_uid.as_deref() != Some(parent_uid) {
+                    continue;
+                }
+                match t.status {
+                    TaskStatus::Cancelled => {}
+                    TaskStatus::Completed => {
+                        done += 1;
+                        total += 1;
+                    }
+                    _ => total += 1,
+                }
+            }
+        }
+        if total > 0 { Some((done, total)) } else { None }
+    }
+
+    /// Fresh copies of a task and all its descendants, parents first, for pasting
+    /// the tree elsewhere. Links inside the tree point to the copies; the root
+    /// loses its parent, and dependencies on tasks outside the tree are dropped.
+    pub fn clone_subtree(&self, root_uid: &str) -> Vec<Task> {
+        let all: Vec<&Task> = self.calendars.values().flatten().collect();
+        let Some(root) = all.iter().find(|t| t.uid == root_uid) else {
+            return Vec::new();
+        };
+        let mut originals: Vec<&Task> = vec![root];
+        let mut i = 0;
+        while i < originals.len() {
+            let uid = originals[i].uid.as_str();
+            let children: Vec<&Task> = all
+                .iter()
+                .filter(|t| t.parent_uid.as_deref() == Some(uid))
+                .filter(|t| !originals.iter().any(|o| o.uid == t.uid))
+                .copied()
+                .collect();
+            originals.extend(children);
+            i += 1;
+        }
+
+        let copies: Vec<Task> = originals.iter().map(|t| t.duplicate()).collect();
+        let new_uid: HashMap<&str, &str> = originals
+            .iter()
+            .zip(&copies)
+            .map(|(o, c)| (o.uid.as_str(), c.uid.as_str()))
+            .collect();
+        let links: Vec<(Option<String>, Vec<String>)> = originals
+            .iter()
+            .map(|o| {
+                let parent = o
+                    .parent_uid
+                    .as_deref()
+                    .and_then(|p| new_uid.get(p))
+                    .map(|p| p.to_string());
+                let deps = o
+                    .dependencies
+                    .iter()
+                    .filter_map(|d| new_uid.get(d.as_str()))
+                    .map(|d| d.to_string())
+                    .collect();
+                (parent, deps)
+            })
+            .collect();
+        copies
+            .into_iter()
+            .zip(links)
+            .map(|(mut copy, (parent, deps))| {
+                copy.parent_uid = parent;
+                copy.dependencies = deps;
+                copy
+            })
+            .collect()
+    }
+
+    /// If every child of `child_uid`'s parent is now completed, marks the parent as completed
+    /// and returns it so the caller can sync it.
+    pub fn complete_parent_if_done(&mut self, child_uid: &str) -> Option<Task> {
+        let href = self.index.get(child_uid)?;
+        let parent_uid = self
+            .calendars
+            .get(href)?
+            .iter()
+            .find(|t| t.uid == child_uid)?
+            .parent_uid
+            .clone()?;
+
+        let (done, total) = self.child_progress(&parent_uid)?;
+        if done < total {
+            return None;
+        }
+
+        if self.get_task_mut(&parent_uid)?.0.status.is_done() {
+            return None;
+        }
+        // Completed like a manual toggle, so a recurring parent comes back
+        self.toggle_task(&parent_uid)
+    }
+
+    /// Moves `uid` to the place of `target_uid` among its siblings in `visible` (the
+    /// list as currently shown in manual order) and returns the tasks whose
+    /// `sort_order` changed. Positions are spaced by `SORT_ORDER_STEP`, so a move
+    /// usually rewrites only the moved task; siblings are renumbered when there is
+    /// no gap left or when some of them were never ordered.
+    pub fn reorder_task(&mut self, uid: &str, target_uid: &str, visible: &[Task]) -> Vec<Task> {
+        let mut siblings = manual_siblings(visible, uid);
+        let (Some(from), Some(to)) = (
+            siblings.iter().position(|t| t.uid == uid),
+            siblings.iter().position(|t| t.uid == target_uid),
+        ) else {
+            return Vec::new();
+        };
+        if from == to {
+            return Vec::new();
+        }
+        let moved = siblings.remove(from);
+        siblings.insert(to, moved);
+
+        let others: Vec<Option<i64>> = siblings
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| *i != to)
+            .map(|(_, t)| t.sort_order)
+            .collect();
+        let ordered = others.iter().all(|o| o.is_some()) && others.windows(2).all(|w| w[0] < w[1]);
+
+        let mut new_orders: Vec<(String, i64)> = Vec::new();
+        let gap = if ordered {
+            let prev = to.checked_sub(1).and_then(|i| siblings[i].sort_order);
+            let next = siblings.get(to + 1).and_then(|t| t.sort_order);
+            match (prev, next) {
+                (Some(p), Some(n)) if n - p > 1 => Some(p + (n - p) / 2),
+                (Some(p), None) => Some(p + SORT_ORDER_STEP),
+                (None, Some(n)) => Some(n - SORT_ORDER_STEP),
+                _ => None,
+            }
+        } else {
+            None
+        };
+        match gap {
+            Some(order) => new_orders.push((uid.to_string(), order)),
+            None => {
+                for (i, t) in siblings.iter().enumerate() {
+                    let order = (i as i64 + 1) * SORT_ORDER_STEP;
+                    if t.sort_order != Some(order) {
+                        new_orders.push((t.uid.clone(), order));
+                    }
+                }
+            }
+        }
+
+        new_orders
+            .into_iter()
+            .filter_map(|(uid, order)| {
+                let (task, _) = self.get_task_mut(&uid)?;
+                task.sort_order = Some(order);
+                Some(task.clone())
+            })
+            .collect()
+    }
+
+    /// Tasks sharing a calendar, a title (ignoring case, spacing and the conflict
+    /// copy suffix), a due date and whether they are done, in groups of two or more.
+    /// Each group starts with the task worth keeping: most subtasks and metadata,
+    /// then not a conflict copy, then the most recently modified.
+    pub fn find_duplicates(&self) -> Vec<Vec<Task>> {
+        let normalized = |summary: &str| {
+            let mut s = summary.trim();
+            while let Some(rest) = s.strip_suffix(CONFLICT_COPY_SUFFIX.trim_start()) {
+                s = rest.trim_end();
+            }
+            s.split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" ")
+                .to_lowercase()
+        };
+        let mut children: HashMap<&str, usize> = HashMap::new();
+        for task in self.calendars.values().flatten() {
+            if let Some(parent) = &task.parent_uid {
+                *children.entry(parent.as_str()).or_default() += 1;
+            }
+        }
+        let rank = |t: &Task| {
+            (
+                children.get(t.uid.as_str()).copied().unwrap_or(0) + t.metadata_score(),
+                !t.summary.ends_with(CONFLICT_COPY_SUFFIX),
+                t.last_modified,
+            )
+        };
+
+        let mut hrefs: Vec<&String> = self.calendars.keys().collect();
+        hrefs.sort();
+        let mut groups = Vec::new();
+        for href in hrefs {
+            let mut by_key: HashMap<(String, Option<NaiveDate>, bool), Vec<&Task>> = HashMap::new();
+            for task in &self.calendars[href] {
+                let key = (
+                    normalized(&task.summary),
+                    task.due_date(),
+                    task.status.is_done(),
+                );
+                if !key.0.is_empty() {
+                    by_key.entry(key).or_default().push(task);
+                }
+            }
+            let mut found: Vec<Vec<Task>> = by_key
+                .into_values()
+                .filter(|tasks| tasks.len() > 1)
+                .map(|mut tasks| {
+                    tasks.sort_by(|a, b| rank(b).cmp(&rank(a)).then_with(|| a.uid.cmp(&b.uid)));
+                    tasks.into_iter().cloned().collect()
+                })
+                .collect();
+            found.sort_by(|a, b| a[0].summary.cmp(&b[0].summary));
+            groups.extend(found);
+        }
+        groups
+    }
+
     pub fn get_summary(&self, uid: &str) -> Option<String> {
         if let Some(href) = self.index.get(uid)
             && let Some(tasks) = self.calendars.get(href)
@@ -504,3 +1376,804 @@ impl TaskStore {
         None
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::LazyLock;
+
+    static NONE: LazyLock<HashSet<String>> = LazyLock::new(HashSet::new);
+
+    /// Options that filter nothing out, to override field by field.
+    fn opts() -> FilterOptions<'static> {
+        FilterOptions {
+            active_cal_href: None,
+            hidden_calendars: &NONE,
+            selected_categories: &NONE,
+            match_all_categories: false,
+            search_term: "",
+            hide_completed_global: false,
+            hide_cancelled: false,
+            hide_blocked: false,
+            cutoff_date: None,
+            min_duration: None,
+            max_duration: None,
+            include_unset_duration: true,
+            due_today_only: false,
+            completed_last: true,
+            manual_order: false,
+            hide_deferred: false,
+            fuzzy_search: false,
+            week_start: Weekday::Mon,
+            priority_filter: None,
+        }
+    }
+
+    fn names(tasks: &[Task]) -> Vec<&str> {
+        tasks.iter().map(|t| t.summary.as_str()).collect()
+    }
+
+    fn child_of(parent: &str, status: TaskStatus) -> Task {
+        let mut t = Task::new("child", &HashMap::new());
+        t.parent_uid = Some(parent.to_string());
+        t.status = status;
+        t.calendar_href = "/cal/".to_string();
+        t
+    }
+
+    /// Adds a task to `/cal/` and returns its uid, to adjust through `get_task_mut`.
+    fn add(store: &mut TaskStore, name: &str) -> String {
+        let mut t = Task::new(name, &HashMap::new());
+        t.calendar_href = "/cal/".to_string();
+        let uid = t.uid.clone();
+        store.add_task(t);
+        uid
+    }
+
+    #[test]
+    fn test_child_progress_excludes_cancelled() {
+        let mut store = TaskStore::new();
+        let parent_uid = add(&mut store, "parent");
+
+        assert_eq!(store.child_progress(&parent_uid), None);
+
+        store.add_task(child_of(&parent_uid, TaskStatus::Completed));
+        store.add_task(child_of(&parent_uid, TaskStatus::NeedsAction));
+        store.add_task(child_of(&parent_uid, TaskStatus::Cancelled));
+        assert_eq!(store.child_progress(&parent_uid), Some((1, 2)));
+        assert_eq!(
+            store.child_progress_map(),
+            HashMap::from([(parent_uid, (1, 2))])
+        );
+    }
+
+    #[test]
+    fn test_complete_parent_if_done() {
+        let mut store = TaskStore::new();
+        let parent_uid = add(&mut store, "parent");
+
+        let first = child_of(&parent_uid, TaskStatus::Completed);
+        let second = child_of(&parent_uid, TaskStatus::NeedsAction);
+        let (first_uid, second_uid) = (first.uid.clone(), second.uid.clone());
+        store.add_task(first);
+        store.add_task(second);
+
+        assert!(store.complete_parent_if_done(&first_uid).is_none());
+
+        store.toggle_task(&second_uid);
+        let parent = store
+            .complete_parent_if_done(&second_uid)
+            .expect("Parent should be completed");
+        assert_eq!(parent.status, TaskStatus::Completed);
+    }
+
+    #[test]
+    fn test_auto_completed_recurring_parent_respawns() {
+        let mut store = TaskStore::new();
+        let parent_uid = add(&mut store, "Weekly review @tomorrow @weekly");
+        let child = child_of(&parent_uid, TaskStatus::NeedsAction);
+        let child_uid = child.uid.clone();
+        store.add_task(child);
+
+        store.toggle_task(&child_uid);
+        let parent = store.complete_parent_if_done(&child_uid).unwrap();
+        // Same completion as a manual one: stamped, and ready for its next occurrence
+        assert!(parent.completed_at.is_some());
+        let next = parent.respawn().expect("Recurring parent should respawn");
+        assert_eq!(next.status, TaskStatus::NeedsAction);
+        assert!(next.due > parent.due);
+    }
+
+    #[test]
+    fn test_undo_reverts_status() {
+        let mut store = TaskStore::new();
+        let mut task = Task::new("Water plants", &HashMap::new());
+        task.calendar_href = "/cal/".to_string();
+        let uid = task.uid.clone();
+        let entry = UndoEntry::status_of(&task);
+        store.add_task(task);
+
+        let done = store.toggle_task(&uid).unwrap();
+        assert_eq!(store.is_task_done(&uid), Some(true));
+        assert!(done.completed_at.is_some());
+
+        let (restored, removed) = store.undo(entry).expect("Task should be restored");
+        assert!(removed.is_none());
+        assert_eq!(restored.status, TaskStatus::NeedsAction);
+        assert_eq!(restored.completed_at, None);
+        assert_eq!(store.is_task_done(&uid), Some(false));
+    }
+
+    #[test]
+    fn test_undo_completion_removes_next_occurrence() {
+        let mut store = TaskStore::new();
+        let mut task = Task::new("Water plants @daily @tomorrow", &HashMap::new());
+        task.calendar_href = "/cal/".to_string();
+        let uid = task.uid.clone();
+        let mut entry = UndoEntry::status_of(&task);
+        store.add_task(task);
+
+        // What the client does once the completion is synced
+        let done = store.toggle_task(&uid).unwrap();
+        let next = done.respawn().expect("Recurring task should respawn");
+        entry.record_spawned(&uid, &next.uid);
+        store.add_task(next.clone());
+        assert_eq!(store.calendars["/cal/"].len(), 2);
+
+        let (restored, removed) = store.undo(entry).unwrap();
+        assert_eq!(restored.status, TaskStatus::NeedsAction);
+        assert_eq!(removed.map(|t| t.uid), Some(next.uid));
+        assert_eq!(store.calendars["/cal/"].len(), 1);
+        assert_eq!(store.calendars["/cal/"][0].uid, uid);
+    }
+
+    #[test]
+    fn test_search_scopes_and_reason() {
+        let mut store = TaskStore::new();
+        let uid = add(&mut store, "Call plumber #home");
+        store.get_task_mut(&uid).unwrap().0.description = "Ask about the kitchen sink".to_string();
+
+        assert_eq!(store.search("sink").len(), 1);
+        assert_eq!(store.search("desc:sink").len(), 1);
+        assert!(store.search("title:sink").is_empty());
+        assert_eq!(store.search("title:plumber").len(), 1);
+
+        let found = store.search("kitchen");
+        assert_eq!(
+            found[0].search_match_reason("kitchen"),
+            Some("description".to_string())
+        );
+        assert_eq!(found[0].search_match_reason("plumber"), None);
+    }
+
+    #[test]
+    fn test_fuzzy_search_ranks_trees() {
+        let mut store = TaskStore::new();
+        add(&mut store, "Fencing lessons");
+        let fix = add(&mut store, "Fix fence");
+        let paint = add(&mut store, "Paint fence");
+        store.get_task_mut(&paint).unwrap().0.parent_uid = Some(fix);
+        add(&mut store, "Taxes");
+
+        let best = store.fuzzy_search("fence");
+        assert_eq!(best.len(), 3);
+        assert_eq!(best[2].0.summary, "Fencing lessons");
+
+        let tasks = store.filter(FilterOptions {
+            search_term: "fence",
+            fuzzy_search: true,
+            ..opts()
+        });
+        // Best tree first, with its subtask kept under it
+        assert_eq!(
+            names(&tasks),
+            vec!["Fix fence", "Paint fence", "Fencing lessons"]
+        );
+    }
+
+    #[test]
+    fn test_due_today_only_filter() {
+        let mut store = TaskStore::new();
+        let now = Utc::now();
+        let days = chrono::Duration::days;
+        for (name, due, status) in [
+            ("today", Some(now), TaskStatus::NeedsAction),
+            ("overdue", Some(now - days(3)), TaskStatus::NeedsAction),
+            ("done overdue", Some(now - days(3)), TaskStatus::Completed),
+            ("next week", Some(now + days(7)), TaskStatus::NeedsAction),
+            ("undated", None, TaskStatus::NeedsAction),
+        ] {
+            let uid = add(&mut store, name);
+            let (t, _) = store.get_task_mut(&uid).unwrap();
+            t.due = due;
+            t.status = status;
+        }
+
+        let tasks = store.filter(FilterOptions {
+            due_today_only: true,
+            ..opts()
+        });
+        let mut names = names(&tasks);
+        names.sort();
+        assert_eq!(names, vec!["overdue", "today"]);
+    }
+
+    #[test]
+    fn test_deferred_tasks_filtering() {
+        let mut store = TaskStore::new();
+        for (name, deferred) in [("now", false), ("someday", true)] {
+            let uid = add(&mut store, name);
+            let (t, _) = store.get_task_mut(&uid).unwrap();
+            t.due = Some(Utc::now());
+            t.deferred = deferred;
+        }
+        let options = |hide_deferred: bool, due_today_only: bool| FilterOptions {
+            due_today_only,
+            hide_deferred,
+            ..opts()
+        };
+
+        assert_eq!(store.filter(options(false, false)).len(), 2);
+        assert_eq!(names(&store.filter(options(true, false))), vec!["now"]);
+        // Today and the agenda leave someday tasks out even when they are shown
+        assert_eq!(names(&store.filter(options(false, true))), vec!["now"]);
+        assert_eq!(names(&store.agenda(options(false, false)).0), vec!["now"]);
+    }
+
+    #[test]
+    fn test_no_date_section() {
+        let mut store = TaskStore::new();
+        for (name, dated) in [("due", true), ("floating", false), ("done", false)] {
+            let uid = add(&mut store, name);
+            let (t, _) = store.get_task_mut(&uid).unwrap();
+            t.due = dated.then(Utc::now);
+            if name == "done" {
+                t.status = TaskStatus::Completed;
+            }
+        }
+        let options = |due_today_only: bool| FilterOptions {
+            due_today_only,
+            ..opts()
+        };
+
+        // The today filter drops undated tasks, the section brings the open ones back
+        let mut tasks = store.filter(options(true));
+        let mut sections = Vec::new();
+        let count = store.add_no_date_section(options(true), &mut tasks, &mut sections, false);
+        assert_eq!(count, 1);
+        assert_eq!(names(&tasks), vec!["due", "floating"]);
+        assert_eq!(sections, vec![(1, DueBucket::NoDate)]);
+
+        // The agenda already ends with the section, so it is not listed twice
+        let (mut tasks, mut sections) = store.agenda(options(false));
+        store.add_no_date_section(options(false), &mut tasks, &mut sections, false);
+        assert_eq!(names(&tasks), vec!["due", "floating"]);
+        assert_eq!(
+            sections,
+            vec![(0, DueBucket::Today), (1, DueBucket::NoDate)]
+        );
+
+        // Folded, only the count is left
+        let count = store.add_no_date_section(options(false), &mut tasks, &mut sections, true);
+        assert_eq!(count, 1);
+        assert_eq!(names(&tasks), vec!["due"]);
+        assert_eq!(sections, vec![(0, DueBucket::Today)]);
+    }
+
+    #[test]
+    fn test_priority_filter() {
+        let mut store = TaskStore::new();
+        for name in ["urgent !1", "soon !2", "later !5", "unset"] {
+            add(&mut store, name);
+        }
+        let options = |priority_filter: Option<u8>| FilterOptions {
+            priority_filter,
+            ..opts()
+        };
+
+        assert_eq!(store.filter(options(None)).len(), 4);
+        assert_eq!(names(&store.filter(options(Some(1)))), vec!["urgent"]);
+        assert_eq!(
+            names(&store.filter(options(Some(2)))),
+            vec!["urgent", "soon"]
+        );
+    }
+
+    #[test]
+    fn test_hide_cancelled_separately() {
+        let mut store = TaskStore::new();
+        for (name, status) in [
+            ("open", TaskStatus::NeedsAction),
+            ("done", TaskStatus::Completed),
+            ("dropped", TaskStatus::Cancelled),
+        ] {
+            let uid = add(&mut store, name);
+            store.get_task_mut(&uid).unwrap().0.status = status;
+        }
+        let options = |hide_completed_global: bool, hide_cancelled: bool| FilterOptions {
+            hide_completed_global,
+            hide_cancelled,
+            ..opts()
+        };
+
+        assert_eq!(
+            names(&store.filter(options(false, true))),
+            vec!["open", "done"]
+        );
+        assert_eq!(
+            names(&store.filter(options(true, false))),
+            vec!["open", "dropped"]
+        );
+        assert_eq!(names(&store.filter(options(true, true))), vec!["open"]);
+    }
+
+    #[test]
+    fn test_hide_blocked() {
+        let mut store = TaskStore::new();
+        let blocker_uid = add(&mut store, "Get quote");
+        let waiting = add(&mut store, "Sign contract");
+        store.get_task_mut(&waiting).unwrap().0.dependencies = vec![blocker_uid.clone()];
+
+        let options = |hide_blocked: bool| FilterOptions {
+            hide_blocked,
+            ..opts()
+        };
+
+        assert_eq!(names(&store.filter(options(false))).len(), 2);
+        assert_eq!(names(&store.filter(options(true))), vec!["Get quote"]);
+        // Back once the blocker is done
+        store.toggle_task(&blocker_uid);
+        assert_eq!(names(&store.filter(options(true))).len(), 2);
+    }
+
+    #[test]
+    fn test_categories_sorted_by_count() {
+        let mut store = TaskStore::new();
+        for (name, tags, done) in [
+            ("a", vec!["work"], false),
+            ("b", vec!["work", "home"], false),
+            ("c", vec!["zoo"], false),
+            ("d", vec!["zoo"], false),
+            ("e", vec!["work"], true),
+            ("f", vec![], false),
+        ] {
+            let uid = add(&mut store, name);
+            let (task, _) = store.get_task_mut(&uid).unwrap();
+            task.categories = tags.into_iter().map(String::from).collect();
+            if done {
+                task.status = TaskStatus::Completed;
+            }
+        }
+        let none = HashSet::new();
+
+        let by_name = store.get_all_categories(false, true, &none, &none, false);
+        let names: Vec<_> = by_name.iter().map(|(n, _)| n.as_str()).collect();
+        assert_eq!(names, [UNCATEGORIZED_ID, "home", "work", "zoo"]);
+
+        // Completed tasks don't count, ties are alphabetical, Uncategorized stays on top
+        let by_count = store.get_all_categories(false, true, &none, &none, true);
+        assert_eq!(
+            by_count,
+            vec![
+                (UNCATEGORIZED_ID.to_string(), 1),
+                ("work".to_string(), 2),
+                ("zoo".to_string(), 2),
+                ("home".to_string(), 1),
+            ]
+        );
+
+        let hidden: HashSet<String> = ["/cal/".to_string()].into();
+        assert!(
+            store
+                .get_all_categories(false, true, &none, &hidden, true)
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_recently_completed() {
+        let mut store = TaskStore::new();
+        let now = Utc::now();
+        for (name, status, days_ago) in [
+            ("today", TaskStatus::Completed, Some(0)),
+            ("last week", TaskStatus::Completed, Some(6)),
+            ("too old", TaskStatus::Completed, Some(8)),
+            ("dropped", TaskStatus::Cancelled, Some(1)),
+            ("open", TaskStatus::NeedsAction, None),
+        ] {
+            let uid = add(&mut store, name);
+            let (task, _) = store.get_task_mut(&uid).unwrap();
+            task.status = status;
+            task.completed_at = days_ago.map(|d| now - chrono::Duration::days(d));
+        }
+        let mut hidden = HashSet::new();
+
+        let (tasks, sections) = store.recently_completed(&hidden);
+        assert_eq!(names(&tasks), ["today", "last week"]);
+        let today = Local::now().date_naive();
+        assert_eq!(sections.len(), 2);
+        assert_eq!(sections[0], (0, today));
+        assert_eq!(day_label(sections[0].1, today), "Today");
+        assert_eq!(
+            day_label(today - chrono::Duration::days(1), today),
+            "Yesterday"
+        );
+
+        hidden.insert("/cal/".to_string());
+        assert!(store.recently_completed(&hidden).0.is_empty());
+    }
+
+    #[test]
+    fn test_open_count() {
+        let mut store = TaskStore::new();
+        for (name, status) in [
+            ("open", TaskStatus::NeedsAction),
+            ("started", TaskStatus::InProcess),
+            ("done", TaskStatus::Completed),
+        ] {
+            let uid = add(&mut store, name);
+            store.get_task_mut(&uid).unwrap().0.status = status;
+        }
+        assert_eq!(store.open_count("/cal/", true), 2);
+        assert_eq!(store.open_count("/cal/", false), 3);
+        assert_eq!(store.open_count("/other/", true), 0);
+    }
+
+    #[test]
+    fn test_follow_timer() {
+        let mut store = TaskStore::new();
+        let uids = ["first", "second"].map(|name| add(&mut store, name));
+        let (first, second) = (uids[0].as_str(), uids[1].as_str());
+        let start = Utc::now();
+        let mut timer = None;
+
+        let started = store.set_status(first, TaskStatus::InProcess).unwrap();
+        let (_, logged) = store.follow_timer(&mut timer, started.clone(), false, start);
+        assert!(logged.is_empty() && timer.is_none());
+        store.follow_timer(&mut timer, started, true, start);
+        assert_eq!(timer.as_ref().map(|t| t.uid.as_str()), Some(first));
+
+        // Starting another task logs the first one's time
+        let started = store.set_status(second, TaskStatus::InProcess).unwrap();
+        let later = start + chrono::Duration::minutes(20);
+        let (_, logged) = store.follow_timer(&mut timer, started, true, later);
+        assert_eq!(logged.len(), 1);
+        assert_eq!((logged[0].uid.as_str(), logged[0].time_spent), (first, 20));
+        assert_eq!(timer.as_ref().map(|t| t.uid.as_str()), Some(second));
+
+        // Completing the timed task stops the timer
+        let done = store.toggle_task(second).unwrap();
+        let (done, logged) =
+            store.follow_timer(&mut timer, done, true, later + chrono::Duration::minutes(5));
+        assert_eq!((done.status, done.time_spent), (TaskStatus::Completed, 5));
+        assert!(logged.is_empty() && timer.is_none());
+    }
+
+    #[test]
+    fn test_sum_estimated_duration() {
+        let aliases = HashMap::new();
+        let mut done = Task::new("done ~2h", &aliases);
+        done.set_status(TaskStatus::Completed);
+        let tasks = vec![
+            Task::new("a ~1h30m", &aliases),
+            Task::new("b ~50m", &aliases),
+            Task::new("no estimate", &aliases),
+            done,
+        ];
+        assert_eq!(sum_estimated_duration(&tasks), 140);
+    }
+
+    #[test]
+    fn test_hierarchy_target() {
+        let task = |uid: &str, parent: Option<&str>| {
+            let mut t = Task::new(uid, &HashMap::new());
+            t.uid = uid.to_string();
+            t.parent_uid = parent.map(|p| p.to_string());
+            t
+        };
+        let visible = vec![
+            task("root", None),
+            task("child", Some("root")),
+            task("grandchild", Some("child")),
+            task("orphan", Some("hidden")),
+        ];
+        assert_eq!(hierarchy_target(&visible, "grandchild", false), Some(1));
+        assert_eq!(hierarchy_target(&visible, "child", false), Some(0));
+        assert_eq!(hierarchy_target(&visible, "root", true), Some(1));
+        assert_eq!(hierarchy_target(&visible, "root", false), None);
+        assert_eq!(hierarchy_target(&visible, "grandchild", true), None);
+        // The parent exists but is filtered out of the view
+        assert_eq!(hierarchy_target(&visible, "orphan", false), None);
+    }
+
+    #[test]
+    fn test_next_due_within() {
+        let now = Utc::now();
+        let mut store = TaskStore::new();
+        for (name, mins, status) in [
+            ("later", 50, TaskStatus::NeedsAction),
+            ("soon", 20, TaskStatus::NeedsAction),
+            ("done", 10, TaskStatus::Completed),
+            ("missed", -5, TaskStatus::NeedsAction),
+            ("tomorrow", 24 * 60, TaskStatus::NeedsAction),
+        ] {
+            let uid = add(&mut store, name);
+            let (t, _) = store.get_task_mut(&uid).unwrap();
+            t.due = Some(now + chrono::Duration::minutes(mins));
+            t.status = status;
+        }
+        let hour = chrono::Duration::hours(1);
+        assert_eq!(store.next_due_within(now, hour).unwrap().summary, "soon");
+        assert!(
+            store
+                .next_due_within(now, chrono::Duration::minutes(5))
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_archivable_tasks() {
+        let mut store = TaskStore::new();
+        for (name, status, href) in [
+            ("open", TaskStatus::NeedsAction, "/cal/"),
+            ("done", TaskStatus::Completed, "/cal/"),
+            ("dropped", TaskStatus::Cancelled, "/cal/"),
+            ("elsewhere", TaskStatus::Completed, "/other/"),
+        ] {
+            let mut t = Task::new(name, &HashMap::new());
+            t.calendar_href = href.to_string();
+            t.status = status;
+            store.add_task(t);
+        }
+        let archivable = store.archivable_tasks("/cal/");
+        let mut names = names(&archivable);
+        names.sort();
+        assert_eq!(names, vec!["done", "dropped"]);
+        assert!(store.archivable_tasks("/missing/").is_empty());
+    }
+
+    #[test]
+    fn test_clearable_tasks() {
+        let mut store = TaskStore::new();
+        let mut uids: HashMap<&str, String> = HashMap::new();
+        for (name, status, parent) in [
+            ("done", TaskStatus::Completed, None),
+            ("dropped", TaskStatus::Cancelled, None),
+            ("project", TaskStatus::Completed, None),
+            ("step", TaskStatus::Completed, Some("project")),
+            ("loose end", TaskStatus::NeedsAction, Some("step")),
+            ("finished", TaskStatus::Completed, None),
+            ("part", TaskStatus::Completed, Some("finished")),
+            ("weekly", TaskStatus::Completed, None),
+        ] {
+            let uid = add(&mut store, name);
+            let (t, _) = store.get_task_mut(&uid).unwrap();
+            t.status = status;
+            t.parent_uid = parent.map(|p| uids[p].clone());
+            uids.insert(name, uid);
+        }
+        store.get_task_mut(&uids["weekly"]).unwrap().0.rrule = Some("FREQ=WEEKLY".to_string());
+
+        let clearable = store.clearable_tasks("/cal/");
+        let mut names = names(&clearable);
+        names.sort();
+        assert_eq!(names, vec!["done", "finished", "part"]);
+    }
+
+    #[test]
+    fn test_reorder_task_sparse() {
+        let mut store = TaskStore::new();
+        let [a, b, c] = ["a", "b", "c"].map(|name| add(&mut store, name));
+        let view = |store: &TaskStore| {
+            store.filter(FilterOptions {
+                manual_order: true,
+                ..opts()
+            })
+        };
+
+        // Never ordered: the first move numbers every sibling
+        let visible = view(&store);
+        assert_eq!(names(&visible), vec!["a", "b", "c"]);
+        let target = manual_neighbour(&visible, &c, true).unwrap();
+        assert_eq!(target, b);
+        let changed = store.reorder_task(&c, &target, &visible);
+        assert_eq!(changed.len(), 3);
+        assert_eq!(names(&view(&store)), vec!["a", "c", "b"]);
+
+        // Later moves fit in the gap and only rewrite the moved task
+        let visible = view(&store);
+        let changed = store.reorder_task(&b, &a, &visible);
+        assert_eq!(names(&changed), vec!["b"]);
+        assert_eq!(changed[0].sort_order, Some(0));
+        assert_eq!(names(&view(&store)), vec!["b", "a", "c"]);
+        assert_eq!(manual_neighbour(&view(&store), &b, true), None);
+    }
+
+    #[test]
+    fn test_reorder_subtasks_in_automatic_order() {
+        let mut store = TaskStore::new();
+        let parent = add(&mut store, "parent");
+        let [a, b] = ["a !1", "b !5"].map(|input| add(&mut store, input));
+        add(&mut store, "top !9");
+        for step in [&a, &b] {
+            store.get_task_mut(step).unwrap().0.parent_uid = Some(parent.clone());
+        }
+        let view = |store: &TaskStore| {
+            let tasks = store.filter(opts());
+            tasks.into_iter().map(|t| t.summary).collect::<Vec<_>>()
+        };
+        let visible = store.filter(opts());
+        assert_eq!(view(&store), vec!["parent", "a", "b", "top"]);
+        assert_eq!(manual_neighbour(&visible, &b, false), None);
+
+        // The lower priority step moves first and stays there
+        let changed = store.reorder_task(&b, &a, &visible);
+        assert_eq!(changed.len(), 2);
+        assert_eq!(view(&store), vec!["parent", "b", "a", "top"]);
+    }
+
+    #[test]
+    fn test_completed_last_ordering() {
+        let mut store = TaskStore::new();
+        for (input, status) in [
+            ("done urgent !1", TaskStatus::Completed),
+            ("open low !9", TaskStatus::NeedsAction),
+            ("open medium !5", TaskStatus::NeedsAction),
+            ("cancelled high !2", TaskStatus::Cancelled),
+        ] {
+            let uid = add(&mut store, input);
+            store.get_task_mut(&uid).unwrap().0.set_status(status);
+        }
+
+        let order = |completed_last: bool| {
+            store.filter(FilterOptions {
+                completed_last,
+                ..opts()
+            })
+        };
+        assert_eq!(
+            names(&order(true)),
+            vec!["open medium", "open low", "done urgent", "cancelled high"]
+        );
+        assert_eq!(
+            names(&order(false)),
+            vec!["done urgent", "cancelled high", "open medium", "open low"]
+        );
+    }
+
+    #[test]
+    fn test_due_buckets() {
+        // 2025-01-08 is a Wednesday
+        let today = NaiveDate::from_ymd_opt(2025, 1, 8).unwrap();
+        let at = |d: u32| {
+            NaiveDate::from_ymd_opt(2025, 1, d)
+                .unwrap()
+                .and_hms_opt(12, 0, 0)
+                .unwrap()
+                .and_local_timezone(Local)
+                .unwrap()
+                .with_timezone(&Utc)
+        };
+
+        assert_eq!(
+            DueBucket::for_date(None, today, Weekday::Mon),
+            DueBucket::NoDate
+        );
+        assert_eq!(
+            DueBucket::for_date(Some(at(7)), today, Weekday::Mon),
+            DueBucket::Overdue
+        );
+        assert_eq!(
+            DueBucket::for_date(Some(at(8)), today, Weekday::Mon),
+            DueBucket::Today
+        );
+        assert_eq!(
+            DueBucket::for_date(Some(at(9)), today, Weekday::Mon),
+            DueBucket::Tomorrow
+        );
+        assert_eq!(
+            DueBucket::for_date(Some(at(12)), today, Weekday::Mon),
+            DueBucket::ThisWeek
+        );
+        assert_eq!(
+            DueBucket::for_date(Some(at(13)), today, Weekday::Mon),
+            DueBucket::Later
+        );
+        // Weeks starting on Sunday end on Saturday the 11th
+        assert_eq!(
+            DueBucket::for_date(Some(at(11)), today, Weekday::Sun),
+            DueBucket::ThisWeek
+        );
+        assert_eq!(
+            DueBucket::for_date(Some(at(12)), today, Weekday::Sun),
+            DueBucket::Later
+        );
+
+        let mut late = Task::new("late", &HashMap::new());
+        late.due = Some(at(20));
+        let undated = Task::new("undated", &HashMap::new());
+        let mut urgent = Task::new("urgent", &HashMap::new());
+        urgent.due = Some(at(1));
+
+        let groups = group_by_due_bucket(vec![late, undated, urgent], today, Weekday::Mon);
+        let order: Vec<DueBucket> = groups.iter().map(|(b, _)| *b).collect();
+        assert_eq!(
+            order,
+            vec![DueBucket::Overdue, DueBucket::Later, DueBucket::NoDate]
+        );
+    }
+
+    #[test]
+    fn test_clone_subtree() {
+        let mut store = TaskStore::new();
+        let mut root = Task::new("Trip", &HashMap::new());
+        root.calendar_href = "/cal/".to_string();
+        root.parent_uid = Some("outside".to_string());
+        let root_uid = root.uid.clone();
+        let mut pack = child_of(&root_uid, TaskStatus::Completed);
+        pack.summary = "Pack".to_string();
+        let mut socks = child_of(&pack.uid, TaskStatus::NeedsAction);
+        socks.summary = "Socks".to_string();
+        let mut book = child_of(&root_uid, TaskStatus::NeedsAction);
+        book.summary = "Book".to_string();
+        book.dependencies = vec![pack.uid.clone(), "outside".to_string()];
+        let unrelated = child_of("outside", TaskStatus::NeedsAction);
+        for t in [root, pack, socks, book, unrelated] {
+            store.add_task(t);
+        }
+
+        let copies = store.clone_subtree(&root_uid);
+        assert_eq!(copies.len(), 4);
+        assert_eq!(copies[0].summary, "Trip");
+        assert!(copies.iter().all(|c| store.get_summary(&c.uid).is_none()));
+        let uid_of = |s: &str| copies.iter().find(|t| t.summary == s).unwrap().uid.clone();
+        let by_name = |s: &str| copies.iter().find(|t| t.summary == s).unwrap();
+        assert_eq!(by_name("Trip").parent_uid, None);
+        assert_eq!(by_name("Pack").parent_uid, Some(uid_of("Trip")));
+        assert_eq!(by_name("Pack").status, TaskStatus::NeedsAction);
+        assert_eq!(by_name("Socks").parent_uid, Some(uid_of("Pack")));
+        assert_eq!(by_name("Book").dependencies, [uid_of("Pack")]);
+        assert!(store.clone_subtree("missing").is_empty());
+    }
+
+    #[test]
+    fn test_find_duplicates() {
+        let mut store = TaskStore::new();
+        let add = |store: &mut TaskStore, summary: &str, cal: &str| {
+            let mut t = Task::new("x", &HashMap::new());
+            t.summary = summary.to_string();
+            t.calendar_href = cal.to_string();
+            let uid = t.uid.clone();
+            store.add_task(t);
+            uid
+        };
+        let bare = add(&mut store, "Buy  milk", "/cal/");
+        let copy = add(&mut store, "buy milk (Conflict Copy)", "/cal/");
+        let rich = add(&mut store, "Buy milk", "/cal/");
+        add(&mut store, "Buy milk", "/other/");
+        add(&mut store, "Buy bread", "/cal/");
+        store.get_task_mut(&rich).unwrap().0.description = "2 litres".to_string();
+
+        let groups = store.find_duplicates();
+        assert_eq!(groups.len(), 1);
+        let uids: Vec<_> = groups[0].iter().map(|t| t.uid.clone()).collect();
+        assert_eq!(uids, [rich, bare, copy]);
+    }
+
+    #[test]
+    fn test_done_tasks_are_not_duplicates_of_open_ones() {
+        let mut store = TaskStore::new();
+        for status in [
+            TaskStatus::NeedsAction,
+            TaskStatus::Completed,
+            TaskStatus::Cancelled,
+        ] {
+            let uid = add(&mut store, "Take out the bins");
+            store.get_task_mut(&uid).unwrap().0.status = status;
+        }
+        // The completed and cancelled ones only match each other
+        let groups = store.find_duplicates();
+        assert_eq!(groups.len(), 1);
+        assert!(groups[0].iter().all(|t| t.status.is_done()));
+    }
+}
@@ -1,4 +1,7 @@
+use crate::client::ServerCapabilities;
+use crate::journal::MergeConflict;
 use crate::model::{CalendarListEntry, Task};
+use std::collections::HashMap;
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum SidebarMode {
@@ -11,6 +14,7 @@ pub enum Action {
     SwitchCalendar(String),
 
     CreateTask(Task),
+    CreateTasks(Vec<Task>), // Pasted tree, parents first
 
     UpdateTask(Task),
     ToggleTask(Task),
@@ -18,12 +22,18 @@ pub enum Action {
     MarkCancelled(Task),
     DeleteTask(Task),
     Refresh,
+    SyncJournal, // Send the queued offline changes now
     Quit,
-    MoveTask(Task, String),   // Task, New Calendar Href
-    StartCreateChild(String), // Parent Task UID
-    MigrateLocal(String),     // target_href
+    EditConfig,                      // Handled by the UI loop, which owns the terminal
+    MoveTask(Task, String),          // Task, New Calendar Href
+    StartCreateChild(String),        // Parent Task UID
+    MigrateLocal(String, String),    // source_href, target_href
+    ArchiveTasks(Vec<Task>, String), // Completed tasks, archive calendar href
+    CreateCalendar(String),          // display name
+    RenameCalendar(String, String),  // href, new display name
     ToggleCalendarVisibility(String),
     IsolateCalendar(String),
+    ResolveConflict(Task, Option<Vec<String>>), // Local version, fields where it wins (None: keep both)
 }
 
 #[derive(Debug)]
@@ -32,4 +42,14 @@ pub enum AppEvent {
     TasksLoaded(Vec<(String, Vec<Task>)>),
     Error(String),
     Status(String),
+    Conflicts(Vec<MergeConflict>),
+    Unsynced(usize), // Actions left in the journal
+    /// An explicit sync ended, with this many actions left and the new ETags by UID
+    JournalFlushed(usize, HashMap<String, String>),
+    Capabilities(ServerCapabilities),
+    /// Completing the recurring task `uid` created its occurrence `next`
+    Respawned {
+        uid: String,
+        next: String,
+    },
 }
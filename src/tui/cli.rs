@@ -0,0 +1,258 @@
+// File: ./src/tui/cli.rs
+// Non-interactive entry points (scripting helpers that never start the TUI)
+use crate::cache::Cache;
+use crate::client::RustyClient;
+use crate::config::{CalendarDefaults, Config};
+use crate::model::{CalendarListEntry, Task};
+use crate::storage::{LOCAL_CALENDAR_HREF, LocalStorage};
+use anyhow::{Result, anyhow};
+use std::collections::BTreeMap;
+
+pub const USAGE: &str = "Usage: cfait [OPTIONS]
+       cfait add [--calendar <NAME>] <TASK>
+
+Commands:
+  add <TASK>             Create a task from smart input (\"Buy milk @tomorrow !1\"),
+                         print its UID and exit
+
+Options:
+  -h, --help             Show this help and exit
+  -V, --version          Show the version and build details and exit
+      --json             Print tasks as JSON to stdout and exit
+      --calendar <NAME>  Calendar to dump with --json (name or href, or 'all'),
+                         or to add the task to";
+
+/// Parsed command-line options.
+#[derive(Debug, Default, PartialEq)]
+pub struct CliArgs {
+    pub help: bool,
+    pub version: bool,
+    pub json: bool,
+    pub calendar: Option<String>,
+    /// Smart input of the task to create with `cfait add`
+    pub add: Option<String>,
+}
+
+impl CliArgs {
+    pub fn parse(args: &[String]) -> Result<Self> {
+        let mut parsed = Self::default();
+        let mut iter = args.iter().skip(1).peekable();
+        let adding = iter.next_if(|a| a.as_str() == "add").is_some();
+        let mut words: Vec<&str> = Vec::new();
+        while let Some(arg) = iter.next() {
+            match arg.as_str() {
+                "-h" | "--help" => parsed.help = true,
+                "-V" | "--version" => parsed.version = true,
+                "--json" => parsed.json = true,
+                "--calendar" => {
+                    let value = iter
+                        .next()
+                        .ok_or_else(|| anyhow!("--calendar requires a value"))?;
+                    parsed.calendar = Some(value.clone());
+                }
+                other => {
+                    if let Some(value) = other.strip_prefix("--calendar=") {
+                        parsed.calendar = Some(value.to_string());
+                    } else if adding && !other.starts_with("--") {
+                        words.push(other);
+                    } else {
+                        return Err(anyhow!("Unknown argument: {}\n\n{}", other, USAGE));
+                    }
+                }
+            }
+        }
+        if adding {
+            if words.is_empty() && !parsed.help {
+                return Err(anyhow!("add requires the task to create\n\n{}", USAGE));
+            }
+            parsed.add = Some(words.join(" "));
+        }
+        if parsed.calendar.is_some() && !parsed.json && !adding {
+            return Err(anyhow!(
+                "--calendar can only be used together with --json or add"
+            ));
+        }
+        Ok(parsed)
+    }
+
+    /// True if the arguments ask for a non-interactive run.
+    pub fn is_batch(&self) -> bool {
+        self.help || self.version || self.json || self.add.is_some()
+    }
+}
+
+pub async fn run_batch(args: &CliArgs) -> Result<()> {
+    if args.help {
+        println!("{}", USAGE);
+        return Ok(());
+    }
+    if args.version {
+        println!("{}", crate::build_info());
+        return Ok(());
+    }
+    if let Some(input) = &args.add {
+        return add_task(input, args.calendar.as_deref()).await;
+    }
+    if args.json {
+        return dump_json(args.calendar.as_deref()).await;
+    }
+    Ok(())
+}
+
+fn load_config() -> Result<Config> {
+    Config::load().map_err(|_| {
+        let path_str = Config::get_path_string().unwrap_or("[path unknown]".to_string());
+        anyhow!("Config file not found: {}", path_str)
+    })
+}
+
+/// The enabled calendars (from the cache when the server is unreachable) and
+/// whether the server answered.
+async fn load_calendars(client: &RustyClient, config: &Config) -> (Vec<CalendarListEntry>, bool) {
+    let mut online = true;
+    let mut calendars = match client.get_calendars().await {
+        Ok(cals) => cals,
+        Err(e) => {
+            eprintln!("Warning: could not reach server ({}), using cache.", e);
+            online = false;
+            Cache::load_calendars().unwrap_or_default()
+        }
+    };
+    LocalStorage::inject_calendars(&mut calendars);
+    calendars.retain(|c| !config.disabled_calendars.contains(&c.href));
+    (calendars, online)
+}
+
+/// The calendar named (or with the href) `wanted`, else the default one.
+async fn resolve_calendar(
+    client: &RustyClient,
+    calendars: &[CalendarListEntry],
+    wanted: Option<&str>,
+    online: bool,
+) -> Result<String> {
+    match wanted {
+        Some(name) => calendars
+            .iter()
+            .find(|c| c.name == name || c.href == name)
+            .map(|c| c.href.clone())
+            .ok_or_else(|| anyhow!("Calendar not found: {}", name)),
+        None if online => client.discover_calendar().await.map_err(|e| anyhow!(e)),
+        None => Ok(LOCAL_CALENDAR_HREF.to_string()),
+    }
+}
+
+/// `cfait add`: creates the task through the journal and prints its UID. When
+/// the server can't be reached the task stays queued for the next sync.
+async fn add_task(input: &str, calendar: Option<&str>) -> Result<()> {
+    let config = load_config()?;
+    let client = RustyClient::from_config(&config).map_err(|e| anyhow!(e))?;
+    let (calendars, online) = load_calendars(&client, &config).await;
+
+    let wanted = calendar.or(config.default_calendar.as_deref());
+    let href = match resolve_calendar(&client, &calendars, wanted, online).await {
+        Ok(href) => href,
+        // No server calendar to default to (e.g. offline mode)
+        Err(_) if wanted.is_none() => LOCAL_CALENDAR_HREF.to_string(),
+        Err(e) => return Err(e),
+    };
+
+    let mut task = Task::new(input, &config.tag_aliases);
+    if task.summary.is_empty() {
+        return Err(anyhow!("The task has no title: {}", input));
+    }
+    let name = calendars
+        .iter()
+        .find(|c| c.href == href)
+        .map(|c| c.name.as_str());
+    if let Some(defaults) = CalendarDefaults::lookup(&config.calendar_defaults, &href, name) {
+        defaults.apply(&mut task);
+    }
+    task.calendar_href = href;
+
+    if let Err(e) = client.create_task(&mut task).await {
+        if LocalStorage::is_local(&task.calendar_href) {
+            return Err(anyhow!(e));
+        }
+        eprintln!("Warning: not synced yet ({}), the task is queued.", e);
+    }
+    println!("{}", task.uid);
+    Ok(())
+}
+
+async fn dump_json(calendar: Option<&str>) -> Result<()> {
+    let config = load_config()?;
+    let client = RustyClient::from_config(&config).map_err(|e| anyhow!(e))?;
+    let (calendars, online) = load_calendars(&client, &config).await;
+
+    let json = if calendar == Some("all") {
+        let mut all: BTreeMap<String, Vec<Task>> = BTreeMap::new();
+        if online {
+            for (href, tasks) in client
+                .get_all_tasks(&calendars)
+                .await
+                .map_err(|e| anyhow!(e))?
+            {
+                all.insert(href, tasks);
+            }
+        } else {
+            for cal in &calendars {
+                all.insert(cal.href.clone(), fetch_one(&client, &cal.href, false).await);
+            }
+        }
+        serde_json::to_string_pretty(&all)?
+    } else {
+        let wanted = calendar.or(config.default_calendar.as_deref());
+        let href = resolve_calendar(&client, &calendars, wanted, online).await?;
+        let tasks = fetch_one(&client, &href, online).await;
+        serde_json::to_string_pretty(&tasks)?
+    };
+
+    println!("{}", json);
+    Ok(())
+}
+
+async fn fetch_one(client: &RustyClient, href: &str, online: bool) -> Vec<Task> {
+    if online || LocalStorage::is_local(href) {
+        match client.get_tasks(href).await {
+            Ok(tasks) => return tasks,
+            Err(e) => eprintln!("Warning: failed to fetch {} ({}), using cache.", href, e),
+        }
+    }
+    Cache::load(href).map(|res| res.0).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        std::iter::once("cfait")
+            .chain(list.iter().copied())
+            .map(String::from)
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_add() {
+        let parsed = CliArgs::parse(&args(&["add", "Buy milk @tomorrow !1"])).unwrap();
+        assert_eq!(parsed.add.as_deref(), Some("Buy milk @tomorrow !1"));
+        assert!(parsed.is_batch());
+
+        // Unquoted words and the calendar option in any position
+        let parsed = CliArgs::parse(&args(&["add", "Call", "--calendar", "Work", "mom"])).unwrap();
+        assert_eq!(parsed.add.as_deref(), Some("Call mom"));
+        assert_eq!(parsed.calendar.as_deref(), Some("Work"));
+
+        assert!(CliArgs::parse(&args(&["add"])).is_err());
+        assert!(CliArgs::parse(&args(&["Buy milk"])).is_err());
+        assert!(CliArgs::parse(&args(&["--calendar", "Work"])).is_err());
+    }
+
+    #[test]
+    fn test_parse_version() {
+        let parsed = CliArgs::parse(&args(&["--version"])).unwrap();
+        assert!(parsed.version && parsed.is_batch());
+        assert!(CliArgs::parse(&args(&["-V"])).unwrap().version);
+        assert!(crate::build_info().starts_with(concat!("cfait ", env!("CARGO_PKG_VERSION"))));
+    }
+}
@@ -1,10 +1,13 @@
 // File: src/tui/handlers.rs
-use crate::config::Config;
+use crate::config::{CalendarDefaults, Config};
+use crate::journal::Journal;
 use crate::model::{Task, TaskStatus, extract_inline_aliases};
-use crate::storage::LOCAL_CALENDAR_HREF;
+use crate::storage::{LOCAL_CALENDAR_HREF, LocalStorage};
+use crate::store::{REVIEW_DAYS, UndoEntry, hierarchy_target, manual_neighbour};
 use crate::tui::action::{Action, AppEvent, SidebarMode};
 use crate::tui::state::{AppState, Focus, InputMode};
-use crossterm::event::{KeyCode, KeyEvent};
+use crossterm::clipboard::CopyToClipboard;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use tokio::sync::mpsc::Sender;
 
 pub fn handle_app_event(state: &mut AppState, event: AppEvent, default_cal: &Option<String>) {
@@ -35,6 +38,46 @@ pub fn handle_app_event(state: &mut AppState, event: AppEvent, default_cal: &Opt
             }
             state.refresh_filtered_view();
         }
+        AppEvent::Conflicts(conflicts) => {
+            state.conflicts = conflicts;
+            if state.mode == InputMode::Normal {
+                state.start_resolving();
+            }
+        }
+        AppEvent::Unsynced(count) => {
+            state.unsynced_count = count;
+            if state.mode == InputMode::Journal {
+                state.refresh_journal();
+            }
+        }
+        AppEvent::JournalFlushed(left, etags) => {
+            state.unsynced_count = left;
+            state.store.set_etags(&etags);
+            if state.quit_after_sync && left == 0 {
+                state.should_quit = true;
+            } else if state.quit_after_sync {
+                state.quit_after_sync = false;
+                state.mode = InputMode::ConfirmQuit;
+                state.message = format!(
+                    "{} changes still queued, the server may be unreachable. \
+                     Retry (y), quit anyway (n) or stay (Esc)?",
+                    left
+                );
+            } else if left == 0 {
+                state.message = "All changes synced.".to_string();
+            } else {
+                state.message = format!(
+                    "{} changes still queued, the server may be unreachable.",
+                    left
+                );
+            }
+        }
+        AppEvent::Capabilities(caps) => state.server_capabilities = Some(caps.summary()),
+        AppEvent::Respawned { uid, next } => {
+            if let Some(undo) = &mut state.last_action {
+                undo.record_spawned(&uid, &next);
+            }
+        }
         AppEvent::TasksLoaded(results) => {
             for (href, tasks) in results {
                 state.store.insert(href, tasks);
@@ -50,9 +93,36 @@ pub async fn handle_key_event(
     state: &mut AppState,
     action_tx: &Sender<Action>,
 ) -> Option<Action> {
+    let mut key = key;
+    if state.mode == InputMode::Normal
+        && state.pending_key.is_none()
+        && let KeyCode::Char(c) = key.code
+    {
+        match state.keymap.resolve(c) {
+            Some(default) => key.code = KeyCode::Char(default),
+            // Default key of an action the user moved elsewhere
+            None => return None,
+        }
+    }
+
+    if key.code != KeyCode::Tab {
+        state.tag_completion = None;
+    }
+
     match state.mode {
+        InputMode::Creating | InputMode::Editing | InputMode::Searching
+            if key.code == KeyCode::Tab =>
+        {
+            state.complete_tag();
+        }
         InputMode::Creating => match key.code {
-            KeyCode::Enter if !state.input_buffer.is_empty() => {
+            KeyCode::Enter | KeyCode::Char('s')
+                if !state.input_buffer.is_empty()
+                    && (key.code == KeyCode::Enter
+                        || key.modifiers.contains(KeyModifiers::CONTROL)) =>
+            {
+                // Ctrl+S (or Ctrl+Enter, where the terminal tells it apart) also starts it
+                let start = key.modifiers.contains(KeyModifiers::CONTROL);
                 // --- 1. Extract Inline Aliases ---
                 let (clean_input, new_aliases) = extract_inline_aliases(&state.input_buffer);
 
@@ -71,7 +141,7 @@ pub async fn handle_key_event(
                     // Persist Aliases
                     if let Ok(mut cfg) = Config::load() {
                         cfg.tag_aliases = state.tag_aliases.clone();
-                        let _ = cfg.save();
+                        save_config(&cfg, state);
                     }
                 }
 
@@ -102,19 +172,56 @@ pub async fn handle_key_event(
                     }
                 }
 
-                let target_href = state
-                    .active_cal_href
-                    .clone()
-                    .or_else(|| state.calendars.first().map(|c| c.href.clone()));
+                // Quick-adds go to the inbox, unless Shift/Alt+Enter keeps them here
+                let here = key
+                    .modifiers
+                    .intersects(KeyModifiers::SHIFT | KeyModifiers::ALT);
+                let target_href = quick_add_target(state, here);
 
+                if let Some(href) = &target_href
+                    && state.is_read_only(href)
+                {
+                    state.message = "This calendar is read-only.".to_string();
+                    return None;
+                }
                 if let Some(href) = target_href {
                     let mut task = Task::new(&clean_input, &state.tag_aliases);
+                    let name = state
+                        .calendars
+                        .iter()
+                        .find(|c| c.href == href)
+                        .map(|c| c.name.as_str());
+                    if let Some(defaults) =
+                        CalendarDefaults::lookup(&state.calendar_defaults, &href, name)
+                    {
+                        defaults.apply(&mut task);
+                    }
                     task.calendar_href = href.clone();
                     task.parent_uid = state.creating_child_of.clone();
+                    if start {
+                        task.set_status(TaskStatus::InProcess);
+                    }
 
                     state.store.add_task(task.clone());
+                    if start {
+                        let (started, logged) = state.follow_timer(task);
+                        task = started;
+                        for t in logged {
+                            let _ = action_tx.send(Action::UpdateTask(t)).await;
+                        }
+                        state.message = if state.timer.as_ref().is_some_and(|t| t.uid == task.uid) {
+                            format!("Timer started for '{}'", task.summary)
+                        } else {
+                            format!("Started '{}'", task.summary)
+                        };
+                    }
                     state.refresh_filtered_view();
 
+                    if state.active_cal_href.as_deref() != Some(href.as_str())
+                        && state.inbox_href() == Some(href.as_str())
+                    {
+                        state.message = format!("Added to the inbox: {}", task.summary);
+                    }
                     state.mode = InputMode::Normal;
                     state.reset_input();
                     state.creating_child_of = None;
@@ -146,7 +253,7 @@ pub async fn handle_key_event(
                     }
                     if let Ok(mut cfg) = Config::load() {
                         cfg.tag_aliases = state.tag_aliases.clone();
-                        let _ = cfg.save();
+                        save_config(&cfg, state);
                     }
                 }
 
@@ -176,6 +283,67 @@ pub async fn handle_key_event(
             KeyCode::Right => state.move_cursor_right(),
             _ => {}
         },
+        InputMode::CreatingCalendar => match key.code {
+            KeyCode::Enter => {
+                let name = state.input_buffer.trim().to_string();
+                state.mode = InputMode::Normal;
+                state.reset_input();
+                if !name.is_empty() {
+                    state.message = format!("Creating calendar '{}'...", name);
+                    return Some(Action::CreateCalendar(name));
+                }
+            }
+            KeyCode::Esc => {
+                state.mode = InputMode::Normal;
+                state.reset_input();
+            }
+            KeyCode::Char(c) => state.enter_char(c),
+            KeyCode::Backspace => state.delete_char(),
+            KeyCode::Left => state.move_cursor_left(),
+            KeyCode::Right => state.move_cursor_right(),
+            _ => {}
+        },
+        InputMode::RenamingCalendar => match key.code {
+            KeyCode::Enter => {
+                let name = state.input_buffer.trim().to_string();
+                state.mode = InputMode::Normal;
+                state.reset_input();
+                if let Some(href) = state.renaming_calendar.take()
+                    && !name.is_empty()
+                {
+                    state.message = format!("Renaming calendar to '{}'...", name);
+                    return Some(Action::RenameCalendar(href, name));
+                }
+            }
+            KeyCode::Esc => {
+                state.mode = InputMode::Normal;
+                state.renaming_calendar = None;
+                state.reset_input();
+            }
+            KeyCode::Char(c) => state.enter_char(c),
+            KeyCode::Backspace => state.delete_char(),
+            KeyCode::Left => state.move_cursor_left(),
+            KeyCode::Right => state.move_cursor_right(),
+            _ => {}
+        },
+        InputMode::Searching => match key.code {
+            KeyCode::Enter | KeyCode::Esc => {
+                state.mode = InputMode::Normal;
+                state.reset_input();
+                state.refresh_filtered_view();
+            }
+            KeyCode::Char(c) => {
+                state.enter_char(c);
+                state.refresh_filtered_view();
+            }
+            KeyCode::Backspace => {
+                state.delete_char();
+                state.refresh_filtered_view();
+            }
+            KeyCode::Left => state.move_cursor_left(),
+            KeyCode::Right => state.move_cursor_right(),
+            _ => {}
+        },
         InputMode::EditingDescription => match key.code {
             KeyCode::Enter => {
                 if key.modifiers.contains(crossterm::event::KeyModifiers::ALT)
@@ -213,16 +381,222 @@ pub async fn handle_key_event(
             KeyCode::Right => state.move_cursor_right(),
             _ => {}
         },
+        InputMode::Normal
+            if state.visual_select
+                && state.active_focus == Focus::Main
+                && matches!(
+                    key.code,
+                    KeyCode::Char(' ' | 'v' | 'd' | '+' | '-' | 'M')
+                        | KeyCode::Enter
+                        | KeyCode::Esc
+                ) =>
+        {
+            return handle_bulk_key(key, state, action_tx).await;
+        }
+        InputMode::Normal if state.pending_key.is_some() => {
+            let prefix = state.pending_key.take();
+            state.message.clear();
+            if prefix == Some('g') && key.code == KeyCode::Char('g') {
+                state.jump_backward(usize::MAX);
+            } else if prefix == Some('g')
+                && let KeyCode::Char(c) = key.code
+                && let Some(n) = c.to_digit(10)
+                && n >= 1
+            {
+                let idx = n as usize - 1;
+                let target = state
+                    .get_filtered_calendars()
+                    .get(idx)
+                    .map(|c| (c.href.clone(), c.name.clone()));
+
+                if let Some((href, name)) = target {
+                    state.active_cal_href = Some(href.clone());
+                    state.hidden_calendars.remove(&href);
+                    if state.sidebar_mode == SidebarMode::Calendars {
+                        state.cal_state.select(Some(idx));
+                    }
+                    state.refresh_filtered_view();
+                    state.message = format!("Switched to {}", name);
+                    if !LocalStorage::is_local(&href) {
+                        return Some(Action::SwitchCalendar(href));
+                    }
+                } else {
+                    state.message = format!("No calendar #{}", n);
+                }
+            } else if prefix == Some('P')
+                && let KeyCode::Char(c) = key.code
+                && let Some(n) = c.to_digit(10)
+                && let Some(uid) = state.get_selected_task().map(|t| t.uid.clone())
+                && let Some(updated) = state.store.set_priority(&uid, n as u8)
+            {
+                state.message = if n == 0 {
+                    "Priority cleared".to_string()
+                } else {
+                    format!("Priority {}", n)
+                };
+                state.refresh_filtered_view();
+                return Some(Action::UpdateTask(updated));
+            } else if prefix == Some('f')
+                && let KeyCode::Char(c) = key.code
+                && let Some(n) = c.to_digit(10)
+                && n >= 1
+                && let Some(preset) = state.snooze_presets.get(n as usize - 1).cloned()
+                && let Some(uid) = state.get_selected_task().map(|t| t.uid.clone())
+            {
+                match state.store.snooze(&uid, &preset, state.week_start) {
+                    Some(updated) => {
+                        state.message = format!(
+                            "Snoozed to {}",
+                            updated
+                                .format_due_as(&state.date_format)
+                                .unwrap_or_default()
+                        );
+                        state.refresh_filtered_view();
+                        return Some(Action::UpdateTask(updated));
+                    }
+                    None => state.message = format!("Can't read snooze preset '{}'", preset),
+                }
+            } else if prefix == Some('!')
+                && let KeyCode::Char(c) = key.code
+                && let Some(n) = c.to_digit(10)
+            {
+                // The same digit again (or 0) clears the filter
+                let n = n as u8;
+                state.priority_filter = if n == 0 || state.priority_filter == Some(n) {
+                    None
+                } else {
+                    Some(n)
+                };
+                state.message = match state.priority_filter {
+                    Some(1) => "Showing priority 1 only".to_string(),
+                    Some(max) => format!("Showing priority 1 to {}", max),
+                    None => "Priority filter cleared".to_string(),
+                };
+                state.refresh_filtered_view();
+            }
+        }
+        InputMode::Normal if hits_read_only(&key, state) => {
+            state.message = "This calendar is read-only.".to_string();
+        }
+        InputMode::Normal if state.review_view && edits_in_review(&key) => {
+            state.message = "The review is read-only (Space reopens a task, L leaves).".to_string();
+        }
         InputMode::Normal => match key.code {
+            KeyCode::Char('g') => {
+                state.pending_key = Some('g');
+                state.message = "g- (g: first task, 1-9: calendar)".to_string();
+            }
+            KeyCode::Char('P')
+                if state.active_focus == Focus::Main && state.get_selected_task().is_some() =>
+            {
+                state.pending_key = Some('P');
+                state.message = "P- (1-9: set priority, 0: none)".to_string();
+            }
+            KeyCode::Char('f')
+                if state.active_focus == Focus::Main && state.get_selected_task().is_some() =>
+            {
+                if state.snooze_presets.is_empty() {
+                    state.message = "No snooze presets in the config.".to_string();
+                } else {
+                    let choices: Vec<String> = state
+                        .snooze_presets
+                        .iter()
+                        .take(9)
+                        .enumerate()
+                        .map(|(i, p)| format!("{}:{}", i + 1, p))
+                        .collect();
+                    state.pending_key = Some('f');
+                    state.message = format!("Snooze: {}", choices.join("  "));
+                }
+            }
+            KeyCode::Char('!') => {
+                state.pending_key = Some('!');
+                state.message = "!- (1-9: priority filter, 0: clear)".to_string();
+            }
             KeyCode::Char('?') => state.show_full_help = !state.show_full_help,
+            KeyCode::Char(c @ ('[' | ']')) if state.active_focus == Focus::Main => {
+                let to_child = c == ']';
+                if let Some(task) = state.get_selected_task() {
+                    match hierarchy_target(&state.tasks, &task.uid, to_child) {
+                        Some(idx) => state.list_state.select(Some(idx)),
+                        None => {
+                            state.message = match (to_child, &task.parent_uid) {
+                                (true, _) => "No subtasks shown.",
+                                (false, Some(_)) => "The parent is not in this view.",
+                                (false, None) => "No parent task.",
+                            }
+                            .to_string();
+                        }
+                    }
+                }
+            }
+            KeyCode::Char('h') if state.active_focus == Focus::Main => {
+                let uid = state.get_selected_task().map(|t| t.uid.clone())?;
+                if state.collapsed_uids.remove(&uid) {
+                    state.message.clear();
+                } else if state.shows_hierarchy()
+                    && hierarchy_target(&state.tasks, &uid, true).is_some()
+                {
+                    state.collapsed_uids.insert(uid);
+                    state.message.clear();
+                } else {
+                    state.message = "No subtasks shown to collapse.".to_string();
+                    return None;
+                }
+                state.refresh_filtered_view();
+            }
+            KeyCode::Char('J') => {
+                state.journal_state.select(Some(0));
+                state.refresh_journal();
+                state.journal_confirm_clear = false;
+                state.mode = InputMode::Journal;
+                state.message = format!("{} pending sync actions", state.journal_entries.len());
+            }
+            KeyCode::Char('I') => {
+                state.load_duplicates();
+                if state.duplicates.is_empty() {
+                    state.message = "No duplicate tasks found.".to_string();
+                    return None;
+                }
+                state.mode = InputMode::Duplicates;
+                state.message = format!(
+                    "{} duplicates to delete, Space to keep one",
+                    state.duplicate_delete.len()
+                );
+            }
+            KeyCode::Char('v') if state.active_focus == Focus::Main => {
+                state.visual_select = true;
+                state.selected_uids.clear();
+                state.message = "Select: Space to pick, Esc to cancel".to_string();
+            }
+            KeyCode::Char('q') if state.confirm_quit_unsynced && state.unsynced_count > 0 => {
+                state.mode = InputMode::ConfirmQuit;
+                state.message = format!(
+                    "You have {} unsynced changes, sync before quitting? (y/n, Esc to stay)",
+                    state.unsynced_count
+                );
+            }
             KeyCode::Char('q') => return Some(Action::Quit),
             KeyCode::Char('r') => return Some(Action::Refresh),
+            KeyCode::Char('o') => return Some(Action::EditConfig),
 
             KeyCode::Char(' ') => {
                 if state.active_focus == Focus::Main {
+                    let undo = state.get_selected_task().map(UndoEntry::status_of);
                     if let Some(uid) = state.get_selected_task().map(|t| t.uid.clone())
                         && let Some(updated) = state.store.toggle_task(&uid)
                     {
+                        state.last_action = undo;
+                        if state.auto_complete_parent
+                            && updated.status == TaskStatus::Completed
+                            && let Some(parent) = state.store.complete_parent_if_done(&uid)
+                        {
+                            let _ = action_tx.send(Action::ToggleTask(parent)).await;
+                        }
+                        let (updated, logged) = state.follow_timer(updated);
+                        for t in logged {
+                            let _ = action_tx.send(Action::UpdateTask(t)).await;
+                        }
                         state.refresh_filtered_view();
                         return Some(Action::ToggleTask(updated));
                     }
@@ -250,24 +624,51 @@ pub async fn handle_key_event(
                 }
             }
             KeyCode::Char('s') => {
+                let undo = state.get_selected_task().map(UndoEntry::status_of);
                 if let Some(uid) = state.get_selected_task().map(|t| t.uid.clone())
                     && let Some(updated) = state.store.set_status(&uid, TaskStatus::InProcess)
                 {
+                    state.last_action = undo;
+                    let (updated, logged) = state.follow_timer(updated);
+                    for t in logged {
+                        let _ = action_tx.send(Action::UpdateTask(t)).await;
+                    }
+                    if let Some(timer) = state.timer.as_ref().filter(|t| t.uid == uid) {
+                        state.message = format!("Timer started for '{}'", timer.summary);
+                    }
                     state.refresh_filtered_view();
                     return Some(Action::MarkInProcess(updated));
                 }
             }
             KeyCode::Char('x') => {
+                let undo = state.get_selected_task().map(UndoEntry::status_of);
                 if let Some(uid) = state.get_selected_task().map(|t| t.uid.clone())
                     && let Some(updated) = state.store.set_status(&uid, TaskStatus::Cancelled)
                 {
+                    state.last_action = undo;
+                    let (updated, logged) = state.follow_timer(updated);
+                    for t in logged {
+                        let _ = action_tx.send(Action::UpdateTask(t)).await;
+                    }
                     state.refresh_filtered_view();
                     return Some(Action::MarkCancelled(updated));
                 }
             }
+            KeyCode::Char('%') => {
+                state.progress_mode = !state.progress_mode;
+                state.message = if state.progress_mode {
+                    "Progress mode: +/- change % complete (% to leave)".to_string()
+                } else {
+                    "+/- change priority".to_string()
+                };
+            }
             KeyCode::Char('+') => {
                 if let Some(uid) = state.get_selected_task().map(|t| t.uid.clone())
-                    && let Some(updated) = state.store.change_priority(&uid, 1)
+                    && let Some(updated) = if state.progress_mode {
+                        state.store.change_progress(&uid, 10)
+                    } else {
+                        state.store.change_priority(&uid, 1)
+                    }
                 {
                     state.refresh_filtered_view();
                     return Some(Action::UpdateTask(updated));
@@ -275,18 +676,49 @@ pub async fn handle_key_event(
             }
             KeyCode::Char('-') => {
                 if let Some(uid) = state.get_selected_task().map(|t| t.uid.clone())
-                    && let Some(updated) = state.store.change_priority(&uid, -1)
+                    && let Some(updated) = if state.progress_mode {
+                        state.store.change_progress(&uid, -10)
+                    } else {
+                        state.store.change_priority(&uid, -1)
+                    }
                 {
                     state.refresh_filtered_view();
                     return Some(Action::UpdateTask(updated));
                 }
             }
             KeyCode::Char('d') => {
-                if let Some(uid) = state.get_selected_task().map(|t| t.uid.clone())
-                    && let Some(deleted) = state.store.delete_task(&uid)
+                if let Some((uid, summary)) = state
+                    .get_selected_task()
+                    .map(|t| (t.uid.clone(), t.summary.clone()))
                 {
-                    state.refresh_filtered_view();
-                    return Some(Action::DeleteTask(deleted));
+                    if state.confirm_delete {
+                        state.pending_delete = vec![uid];
+                        state.mode = InputMode::ConfirmDelete;
+                        state.message = format!("Delete '{}'? (y/n)", summary);
+                    } else {
+                        return delete_tasks(vec![uid], state, action_tx).await;
+                    }
+                }
+            }
+            KeyCode::Char('u') => {
+                if let Some(entry) = state.last_action.take() {
+                    let label = entry.label();
+                    let is_delete = matches!(entry, UndoEntry::Deleted(_));
+                    if let Some((task, spawned)) = state.store.undo(entry) {
+                        state.refresh_filtered_view();
+                        state.message = format!("Undid {}.", label);
+                        if let Some(next) = spawned {
+                            let _ = action_tx.send(Action::DeleteTask(next)).await;
+                        }
+                        // Compensating action: goes through the journal like any other edit.
+                        return Some(if is_delete {
+                            Action::CreateTask(task)
+                        } else {
+                            Action::UpdateTask(task)
+                        });
+                    }
+                } else {
+                    state.message = "Nothing to undo.".to_string();
                 }
             }
             KeyCode::Char('c') => {
@@ -339,6 +771,43 @@ pub async fn handle_key_event(
                     state.message = format!("Yanked: {}", summary);
                 }
             }
+            KeyCode::Char('V') => {
+                let Some(root_uid) = state.yanked_uid.clone() else {
+                    state.message = "Yank a task first (y).".to_string();
+                    return None;
+                };
+                let href = state
+                    .active_cal_href
+                    .clone()
+                    .or_else(|| state.calendars.first().map(|c| c.href.clone()))?;
+                let mut copies = state.store.clone_subtree(&root_uid);
+                if copies.is_empty() {
+                    return None;
+                }
+                for copy in &mut copies {
+                    copy.calendar_href = href.clone();
+                    state.store.add_task(copy.clone());
+                }
+                state.yanked_uid = None;
+                state.refresh_filtered_view();
+                if let Some(idx) = state.tasks.iter().position(|t| t.uid == copies[0].uid) {
+                    state.list_state.select(Some(idx));
+                }
+                state.message = format!(
+                    "Pasted '{}' with {} subtasks",
+                    copies[0].summary,
+                    copies.len() - 1
+                );
+                return Some(Action::CreateTasks(copies));
+            }
+            KeyCode::Char('Y') => {
+                if let Some(summary) = state.get_selected_task().map(|t| t.summary.clone()) {
+                    state.message = match copy_to_clipboard(&summary) {
+                        Ok(()) => format!("Sent to terminal clipboard (OSC52): {}", summary),
+                        Err(e) => format!("Could not copy to clipboard: {}", e),
+                    };
+                }
+            }
             KeyCode::Char('b') => {
                 let data = if let Some(yanked) = &state.yanked_uid
                     && let Some(current) = state.get_selected_task()
@@ -386,12 +855,16 @@ pub async fn handle_key_event(
                 }
             }
             KeyCode::Char('X') => {
-                if state.active_cal_href.as_deref() == Some(LOCAL_CALENDAR_HREF) {
+                if let Some(source) = state.active_cal_href.clone()
+                    && LocalStorage::is_local(&source)
+                {
+                    state.export_source = Some(source);
                     state.export_targets = state
                         .calendars
                         .iter()
                         .filter(|c| {
-                            c.href != LOCAL_CALENDAR_HREF
+                            !LocalStorage::is_local(&c.href)
+                                && !c.read_only
                                 && !state.disabled_calendars.contains(&c.href)
                         })
                         .cloned()
@@ -402,6 +875,48 @@ pub async fn handle_key_event(
                     }
                 }
             }
+            KeyCode::Char('T') => {
+                if state.templates.is_empty() {
+                    state.message =
+                        "No templates yet, add them under [templates] in the config (o)."
+                            .to_string();
+                    return None;
+                }
+                let mut names: Vec<String> = state.templates.keys().cloned().collect();
+                names.sort();
+                state.template_names = names;
+                state.template_state.select(Some(0));
+                state.mode = InputMode::PickingTemplate;
+            }
+            KeyCode::Char('W') => {
+                let from = state.active_cal_href.clone()?;
+                if state.store.archivable_tasks(&from).is_empty() {
+                    state.message = "No completed tasks to archive.".to_string();
+                    return None;
+                }
+                match state.archive_calendar.clone() {
+                    Some(target) if target != from => return archive_completed(state, target),
+                    Some(_) => {
+                        state.message = "This is the archive calendar.".to_string();
+                    }
+                    None => {
+                        state.export_targets = state
+                            .calendars
+                            .iter()
+                            .filter(|c| {
+                                c.href != from
+                                    && !c.read_only
+                                    && !state.disabled_calendars.contains(&c.href)
+                            })
+                            .cloned()
+                            .collect();
+                        if !state.export_targets.is_empty() {
+                            state.export_selection_state.select(Some(0));
+                            state.mode = InputMode::Archiving;
+                        }
+                    }
+                }
+            }
             KeyCode::Char('M') => {
                 if let Some(task) = state.get_selected_task() {
                     let current_href = task.calendar_href.clone();
@@ -409,7 +924,9 @@ pub async fn handle_key_event(
                         .calendars
                         .iter()
                         .filter(|c| {
-                            c.href != current_href && !state.disabled_calendars.contains(&c.href)
+                            c.href != current_href
+                                && !c.read_only
+                                && !state.disabled_calendars.contains(&c.href)
                         })
                         .cloned()
                         .collect();
@@ -420,11 +937,118 @@ pub async fn handle_key_event(
                     }
                 }
             }
+            KeyCode::Down | KeyCode::Up | KeyCode::Char('j' | 'k')
+                if key.modifiers.contains(KeyModifiers::ALT)
+                    && state.active_focus == Focus::Main =>
+            {
+                // Without manual order, only subtasks can be moved among their siblings
+                if !state.manual_order && state.get_selected_task().is_none_or(|t| t.depth == 0) {
+                    state.message =
+                        "Manual order is off (O to turn it on), Alt+j/k only moves subtasks"
+                            .to_string();
+                    return None;
+                }
+                let up = matches!(key.code, KeyCode::Up | KeyCode::Char('k'));
+                if let Some(uid) = state.get_selected_task().map(|t| t.uid.clone())
+                    && let Some(target) = manual_neighbour(&state.tasks, &uid, up)
+                {
+                    let updated = state.store.reorder_task(&uid, &target, &state.tasks);
+                    state.refresh_filtered_view();
+                    if let Some(idx) = state.tasks.iter().position(|t| t.uid == uid) {
+                        state.list_state.select(Some(idx));
+                    }
+                    for t in updated {
+                        let _ = action_tx.send(Action::UpdateTask(t)).await;
+                    }
+                }
+            }
+            KeyCode::Char('z') => {
+                if let Some(uid) = state.get_selected_task().map(|t| t.uid.clone())
+                    && let Some(updated) = state.store.toggle_deferred(&uid)
+                {
+                    state.message = if updated.deferred {
+                        format!("'{}' moved to someday", updated.summary)
+                    } else {
+                        format!("'{}' is actionable again", updated.summary)
+                    };
+                    state.refresh_filtered_view();
+                    return Some(Action::UpdateTask(updated));
+                }
+            }
+            KeyCode::Char('p') if state.active_focus == Focus::Main => {
+                if let Some(copy) = state.get_selected_task().map(Task::duplicate) {
+                    state.store.add_task(copy.clone());
+                    state.refresh_filtered_view();
+                    if let Some(idx) = state.tasks.iter().position(|t| t.uid == copy.uid) {
+                        state.list_state.select(Some(idx));
+                    }
+                    state.message = format!("Duplicated '{}'", copy.summary);
+                    return Some(Action::CreateTask(copy));
+                }
+            }
+            KeyCode::Char('S') => {
+                if let Some(uid) = state.get_selected_task().map(|t| t.uid.clone()) {
+                    let Some(updated) = state.store.skip_occurrence(&uid) else {
+                        state.message = "Only recurring tasks can be skipped.".to_string();
+                        return None;
+                    };
+                    state.message =
+                        match (&updated.rrule, updated.format_due_as(&state.date_format)) {
+                            (None, _) => format!("'{}' has no more occurrences", updated.summary),
+                            (Some(_), Some(due)) => format!("Skipped to {}", due),
+                            (Some(_), None) => format!("Skipped '{}'", updated.summary),
+                        };
+                    state.refresh_filtered_view();
+                    return Some(Action::UpdateTask(updated));
+                }
+            }
+            // Debug aid, not in the keymap: `D` is the usual key to remap delete to
+            KeyCode::Char('D') => {
+                state.show_debug_ids = !state.show_debug_ids;
+                state.message = if state.show_debug_ids {
+                    "Showing UIDs and calendar hrefs".to_string()
+                } else {
+                    "UIDs hidden".to_string()
+                };
+            }
+            KeyCode::Char('Z') => {
+                state.hide_deferred = !state.hide_deferred;
+                state.refresh_filtered_view();
+                state.message = if state.hide_deferred {
+                    "Someday tasks hidden".to_string()
+                } else {
+                    "Someday tasks shown".to_string()
+                };
+            }
+            KeyCode::Char('O') => {
+                state.manual_order = !state.manual_order;
+                state.refresh_filtered_view();
+                state.message = if state.manual_order {
+                    "Manual order (Alt+j/k to move tasks)".to_string()
+                } else {
+                    "Automatic order".to_string()
+                };
+            }
             KeyCode::Down | KeyCode::Char('j') => state.next(),
             KeyCode::Up | KeyCode::Char('k') => state.previous(),
             KeyCode::PageDown => state.jump_forward(10),
             KeyCode::PageUp => state.jump_backward(10),
+            KeyCode::Char('G') | KeyCode::End => state.jump_forward(usize::MAX),
+            KeyCode::Home => state.jump_backward(usize::MAX),
+            // Tab brings a hidden sidebar back
+            KeyCode::Tab if state.focus_mode => {
+                toggle_focus_mode(state);
+                state.active_focus = Focus::Sidebar;
+            }
             KeyCode::Tab => state.toggle_focus(),
+            KeyCode::Char('F') => {
+                toggle_focus_mode(state);
+                state.message = if state.focus_mode {
+                    "Focus mode: sidebar hidden (F or Tab shows it)".to_string()
+                } else {
+                    "Sidebar shown".to_string()
+                };
+            }
             KeyCode::Char('1') => {
                 state.sidebar_mode = SidebarMode::Calendars;
                 state.refresh_filtered_view();
@@ -437,10 +1061,65 @@ pub async fn handle_key_event(
                 state.match_all_categories = !state.match_all_categories;
                 state.refresh_filtered_view();
             }
+            KeyCode::Char('A') => {
+                state.agenda_view = !state.agenda_view;
+                state.message = if state.agenda_view {
+                    "Agenda view".to_string()
+                } else {
+                    "List view".to_string()
+                };
+                state.refresh_filtered_view();
+            }
+            KeyCode::Char('L') => {
+                state.review_view = !state.review_view;
+                state.message = if state.review_view {
+                    format!("Completed in the last {} days", REVIEW_DAYS)
+                } else {
+                    "List view".to_string()
+                };
+                state.refresh_filtered_view();
+            }
+            KeyCode::Char('t') => {
+                state.today_view = !state.today_view;
+                state.message = if state.today_view {
+                    "Showing tasks due today or overdue".to_string()
+                } else {
+                    "Showing all tasks".to_string()
+                };
+                state.refresh_filtered_view();
+            }
+            KeyCode::Char('U') => {
+                if !state.agenda_view && !state.today_view {
+                    state.message =
+                        "The No Date section is part of the agenda and today views.".to_string();
+                    return None;
+                }
+                state.no_date_collapsed = !state.no_date_collapsed;
+                state.message.clear();
+                state.refresh_filtered_view();
+            }
             KeyCode::Char('H') => {
                 state.hide_completed = !state.hide_completed;
                 state.refresh_filtered_view();
             }
+            KeyCode::Char('K') => {
+                state.hide_cancelled = !state.hide_cancelled;
+                state.message = if state.hide_cancelled {
+                    "Hiding cancelled tasks".to_string()
+                } else {
+                    "Showing cancelled tasks".to_string()
+                };
+                state.refresh_filtered_view();
+            }
+            KeyCode::Char('B') => {
+                state.hide_blocked = !state.hide_blocked;
+                state.message = if state.hide_blocked {
+                    "Hiding blocked tasks".to_string()
+                } else {
+                    "Showing blocked tasks".to_string()
+                };
+                state.refresh_filtered_view();
+            }
             KeyCode::Char('*') => {
                 if state.active_focus == Focus::Sidebar {
                     match state.sidebar_mode {
@@ -496,7 +1175,7 @@ pub async fn handle_key_event(
                             }
                         }
                         state.refresh_filtered_view();
-                        if href != LOCAL_CALENDAR_HREF {
+                        if !LocalStorage::is_local(&href) {
                             return Some(Action::IsolateCalendar(href));
                         }
                     }
@@ -519,7 +1198,7 @@ pub async fn handle_key_event(
                                 state.active_cal_href = Some(href.clone());
                                 state.hidden_calendars.remove(&href);
                                 state.refresh_filtered_view();
-                                if href != LOCAL_CALENDAR_HREF {
+                                if !LocalStorage::is_local(&href) {
                                     return Some(Action::SwitchCalendar(href));
                                 }
                             }
@@ -530,6 +1209,7 @@ pub async fn handle_key_event(
                                 state.hide_fully_completed_tags,
                                 &state.selected_categories,
                                 &state.hidden_calendars,
+                                state.sort_tags_by_count,
                             );
                             if let Some(idx) = state.cal_state.selected()
                                 && let Some((c, _)) = cats.get(idx)
@@ -550,6 +1230,34 @@ pub async fn handle_key_event(
                 state.mode = InputMode::Searching;
                 state.reset_input();
             }
+            KeyCode::Char('R')
+                if state.active_focus == Focus::Sidebar
+                    && state.sidebar_mode == SidebarMode::Calendars =>
+            {
+                let target = state.cal_state.selected().and_then(|idx| {
+                    state
+                        .get_filtered_calendars()
+                        .get(idx)
+                        .map(|c| (c.href.clone(), c.name.clone()))
+                });
+                match target {
+                    Some((href, _)) if href == LOCAL_CALENDAR_HREF => {
+                        state.message = "The local calendar cannot be renamed".to_string();
+                    }
+                    Some((href, name)) => {
+                        state.renaming_calendar = Some(href);
+                        state.input_buffer = name;
+                        state.cursor_position = state.input_buffer.len();
+                        state.mode = InputMode::RenamingCalendar;
+                    }
+                    None => {}
+                }
+            }
+            KeyCode::Char('N') => {
+                state.mode = InputMode::CreatingCalendar;
+                state.reset_input();
+                state.message = "New calendar name...".to_string();
+            }
             KeyCode::Char('a') => {
                 state.mode = InputMode::Creating;
                 state.reset_input();
@@ -575,6 +1283,146 @@ pub async fn handle_key_event(
             }
             _ => {}
         },
+        InputMode::Journal if state.journal_confirm_clear => {
+            state.journal_confirm_clear = false;
+            if key.code == KeyCode::Char('y') {
+                let result = Journal::clear_queue();
+                state.refresh_journal();
+                match result {
+                    Ok(()) => {
+                        state.message = "Cleared the sync queue".to_string();
+                        // Drop the optimistic local copies of the discarded changes
+                        return Some(Action::Refresh);
+                    }
+                    Err(e) => state.message = format!("Error: {}", e),
+                }
+            } else {
+                state.message = "Kept the sync queue".to_string();
+            }
+        }
+        InputMode::Journal => {
+            let count = state.journal_entries.len();
+            match key.code {
+                KeyCode::Down | KeyCode::Char('j') if count > 0 => {
+                    let i = state
+                        .journal_state
+                        .selected()
+                        .map_or(0, |i| (i + 1) % count);
+                    state.journal_state.select(Some(i));
+                }
+                KeyCode::Up | KeyCode::Char('k') if count > 0 => {
+                    let i = state
+                        .journal_state
+                        .selected()
+                        .map_or(0, |i| (i + count - 1) % count);
+                    state.journal_state.select(Some(i));
+                }
+                KeyCode::Char('d') => {
+                    if let Some(i) = state.journal_state.selected() {
+                        let result = Journal::remove_at(i);
+                        state.refresh_journal();
+                        match result {
+                            Ok(Some(action)) => {
+                                state.message = format!("Dropped {}", action.summary());
+                                return Some(Action::Refresh);
+                            }
+                            Ok(None) => state.message = "Nothing to drop".to_string(),
+                            Err(e) => state.message = format!("Error: {}", e),
+                        }
+                    }
+                }
+                KeyCode::Char('s') if count > 0 => {
+                    state.message = "Syncing...".to_string();
+                    return Some(Action::SyncJournal);
+                }
+                KeyCode::Char('C') if count > 0 => {
+                    state.journal_confirm_clear = true;
+                    state.message = format!(
+                        "Drop all {} pending actions? Unsynced changes will be lost. (y/n)",
+                        count
+                    );
+                }
+                KeyCode::Esc | KeyCode::Char('q') => {
+                    state.mode = InputMode::Normal;
+                    state.message.clear();
+                }
+                _ => {}
+            }
+        }
+        InputMode::Duplicates => {
+            let count = state.duplicates.len();
+            match key.code {
+                KeyCode::Down | KeyCode::Char('j') if count > 0 => {
+                    let i = state
+                        .duplicate_state
+                        .selected()
+                        .map_or(0, |i| (i + 1) % count);
+                    state.duplicate_state.select(Some(i));
+                }
+                KeyCode::Up | KeyCode::Char('k') if count > 0 => {
+                    let i = state
+                        .duplicate_state
+                        .selected()
+                        .map_or(0, |i| (i + count - 1) % count);
+                    state.duplicate_state.select(Some(i));
+                }
+                KeyCode::Char(' ') => {
+                    if let Some((_, task)) = state
+                        .duplicate_state
+                        .selected()
+                        .and_then(|i| state.duplicates.get(i))
+                        && !state.duplicate_delete.remove(&task.uid)
+                    {
+                        state.duplicate_delete.insert(task.uid.clone());
+                    }
+                }
+                KeyCode::Enter => {
+                    state.mode = InputMode::Normal;
+                    let uids: Vec<String> = state.duplicate_delete.drain().collect();
+                    state.duplicates.clear();
+                    if uids.is_empty() {
+                        state.message = "Kept all duplicates".to_string();
+                        return None;
+                    }
+                    return delete_tasks(uids, state, action_tx).await;
+                }
+                KeyCode::Esc | KeyCode::Char('q') => {
+                    state.mode = InputMode::Normal;
+                    state.duplicates.clear();
+                    state.duplicate_delete.clear();
+                    state.message.clear();
+                }
+                _ => {}
+            }
+        }
+        // Unsent changes stay in the journal either way and go out on the next start
+        InputMode::ConfirmQuit => match key.code {
+            KeyCode::Char('y' | 'Y') => {
+                state.mode = InputMode::Normal;
+                state.quit_after_sync = true;
+                state.message = "Syncing before quitting...".to_string();
+                return Some(Action::SyncJournal);
+            }
+            KeyCode::Char('n' | 'N') => return Some(Action::Quit),
+            KeyCode::Esc | KeyCode::Char('c') => {
+                state.mode = InputMode::Normal;
+                state.message.clear();
+            }
+            _ => {}
+        },
+        InputMode::ConfirmDelete => match key.code {
+            KeyCode::Char('y' | 'Y') => {
+                state.mode = InputMode::Normal;
+                let uids = std::mem::take(&mut state.pending_delete);
+                return delete_tasks(uids, state, action_tx).await;
+            }
+            KeyCode::Char('n' | 'N') | KeyCode::Esc => {
+                state.mode = InputMode::Normal;
+                state.pending_delete.clear();
+                state.message = "Delete cancelled".to_string();
+            }
+            _ => {}
+        },
         InputMode::Moving => match key.code {
             KeyCode::Esc => {
                 state.mode = InputMode::Normal;
@@ -582,6 +1430,29 @@ pub async fn handle_key_event(
             }
             KeyCode::Down | KeyCode::Char('j') => state.next_move_target(),
             KeyCode::Up | KeyCode::Char('k') => state.previous_move_target(),
+            KeyCode::Enter if !state.selected_uids.is_empty() => {
+                let target = state
+                    .move_selection_state
+                    .selected()
+                    .and_then(|idx| state.move_targets.get(idx))
+                    .map(|c| c.href.clone());
+                state.mode = InputMode::Normal;
+                if let Some(target_href) = target {
+                    let (uids, skipped) = writable_selection(state);
+                    let mut count = 0;
+                    for uid in uids {
+                        if let Some(updated) = state.store.move_task(&uid, target_href.clone()) {
+                            let _ = action_tx
+                                .send(Action::MoveTask(updated, target_href.clone()))
+                                .await;
+                            count += 1;
+                        }
+                    }
+                    state.clear_selection();
+                    state.refresh_filtered_view();
+                    state.message = format!("Moving {} tasks...{}", count, skipped_note(skipped));
+                }
+            }
             KeyCode::Enter => {
                 let data = if let Some(task) = state.get_selected_task()
                     && let Some(idx) = state.move_selection_state.selected()
@@ -604,6 +1475,52 @@ pub async fn handle_key_event(
             }
             _ => {}
         },
+        InputMode::Resolving => {
+            let field_count = state.conflicts.first().map_or(0, |c| c.fields.len());
+            match key.code {
+                KeyCode::Down | KeyCode::Char('j') if field_count > 0 => {
+                    let i = state
+                        .conflict_state
+                        .selected()
+                        .map_or(0, |i| (i + 1) % field_count);
+                    state.conflict_state.select(Some(i));
+                }
+                KeyCode::Up | KeyCode::Char('k') if field_count > 0 => {
+                    let i = state
+                        .conflict_state
+                        .selected()
+                        .map_or(0, |i| (i + field_count - 1) % field_count);
+                    state.conflict_state.select(Some(i));
+                }
+                KeyCode::Char(' ') => {
+                    if let Some(conflict) = state.conflicts.first()
+                        && let Some(diff) = state
+                            .conflict_state
+                            .selected()
+                            .and_then(|i| conflict.fields.get(i))
+                        && !state.conflict_keep_local.remove(&diff.field)
+                    {
+                        state.conflict_keep_local.insert(diff.field.clone());
+                    }
+                }
+                KeyCode::Enter | KeyCode::Esc if !state.conflicts.is_empty() => {
+                    let conflict = state.conflicts.remove(0);
+                    let keep_local = if key.code == KeyCode::Enter {
+                        Some(state.conflict_keep_local.drain().collect())
+                    } else {
+                        None
+                    };
+                    state.mode = InputMode::Normal;
+                    state.start_resolving();
+                    if state.mode == InputMode::Normal {
+                        state.message = "Resolving conflict...".to_string();
+                    }
+                    return Some(Action::ResolveConflict(conflict.local, keep_local));
+                }
+                KeyCode::Enter | KeyCode::Esc => state.mode = InputMode::Normal,
+                _ => {}
+            }
+        }
         InputMode::Exporting => match key.code {
             KeyCode::Esc => {
                 state.mode = InputMode::Normal;
@@ -614,15 +1531,591 @@ pub async fn handle_key_event(
             KeyCode::Enter => {
                 if let Some(idx) = state.export_selection_state.selected()
                     && let Some(target) = state.export_targets.get(idx)
+                    && let Some(source) = state.export_source.take()
                 {
                     let href = target.href.clone();
                     state.mode = InputMode::Normal;
-                    return Some(Action::MigrateLocal(href));
+                    return Some(Action::MigrateLocal(source, href));
                 }
             }
             _ => {}
         },
+        InputMode::PickingTemplate => {
+            let count = state.template_names.len();
+            match key.code {
+                KeyCode::Esc => state.mode = InputMode::Normal,
+                KeyCode::Down | KeyCode::Char('j') if count > 0 => {
+                    let i = state
+                        .template_state
+                        .selected()
+                        .map_or(0, |i| (i + 1) % count);
+                    state.template_state.select(Some(i));
+                }
+                KeyCode::Up | KeyCode::Char('k') if count > 0 => {
+                    let i = state
+                        .template_state
+                        .selected()
+                        .map_or(0, |i| (i + count - 1) % count);
+                    state.template_state.select(Some(i));
+                }
+                KeyCode::Enter => {
+                    state.mode = InputMode::Normal;
+                    return create_from_template(state);
+                }
+                _ => {}
+            }
+        }
+        InputMode::Archiving => match key.code {
+            KeyCode::Esc => state.mode = InputMode::Normal,
+            KeyCode::Down | KeyCode::Char('j') => state.next_export_target(),
+            KeyCode::Up | KeyCode::Char('k') => state.previous_export_target(),
+            KeyCode::Enter => {
+                if let Some(idx) = state.export_selection_state.selected()
+                    && let Some(target) = state.export_targets.get(idx)
+                {
+                    let href = target.href.clone();
+                    state.mode = InputMode::Normal;
+                    state.archive_calendar = Some(href.clone());
+                    if let Ok(mut cfg) = Config::load() {
+                        cfg.archive_calendar = Some(href.clone());
+                        save_config(&cfg, state);
+                    }
+                    return archive_completed(state, href);
+                }
+            }
+            _ => {}
+        },
+    }
+    None
+}
+
+/// Pasted text. A multi-line paste into the quick-add creates a task tree from the
+/// outline, anything else is typed into the input being edited.
+pub fn handle_paste(text: &str, state: &mut AppState) -> Option<Action> {
+    let multi_line = text.lines().filter(|l| !l.trim().is_empty()).count() > 1;
+    match state.mode {
+        InputMode::Creating if multi_line => create_from_outline(text, state),
+        InputMode::EditingDescription => {
+            text.replace("\r\n", "\n")
+                .chars()
+                .for_each(|c| state.enter_char(c));
+            None
+        }
+        InputMode::Creating
+        | InputMode::Editing
+        | InputMode::CreatingCalendar
+        | InputMode::RenamingCalendar
+        | InputMode::Searching => {
+            let line = text.lines().map(str::trim).collect::<Vec<_>>().join(" ");
+            line.chars().for_each(|c| state.enter_char(c));
+            if state.mode == InputMode::Searching {
+                state.refresh_filtered_view();
+            }
+            None
+        }
+        _ => None,
+    }
+}
+
+/// Calendar a quick-add goes to: the inbox, unless `here` keeps the task in
+/// the active calendar or it is a subtask.
+fn quick_add_target(state: &AppState, here: bool) -> Option<String> {
+    state
+        .inbox_href()
+        .filter(|_| !here && state.creating_child_of.is_none())
+        .map(String::from)
+        .or_else(|| state.active_cal_href.clone())
+        .or_else(|| state.calendars.first().map(|c| c.href.clone()))
+}
+
+/// Creates the tasks of a pasted outline, where the quick-add would put the task.
+fn create_from_outline(text: &str, state: &mut AppState) -> Option<Action> {
+    let href = quick_add_target(state, false)?;
+    if state.is_read_only(&href) {
+        state.message = "This calendar is read-only.".to_string();
+        return None;
+    }
+    let mut tasks = Task::tree_from_outline(text, &state.tag_aliases);
+    if tasks.is_empty() {
+        return None;
+    }
+    let cal_name = state
+        .calendars
+        .iter()
+        .find(|c| c.href == href)
+        .map(|c| c.name.as_str());
+    let defaults = CalendarDefaults::lookup(&state.calendar_defaults, &href, cal_name).cloned();
+    for task in &mut tasks {
+        if let Some(defaults) = &defaults {
+            defaults.apply(task);
+        }
+        task.calendar_href = href.clone();
+        if task.parent_uid.is_none() {
+            task.parent_uid = state.creating_child_of.clone();
+        }
+        state.store.add_task(task.clone());
+    }
+    state.refresh_filtered_view();
+    if let Some(idx) = state.tasks.iter().position(|t| t.uid == tasks[0].uid) {
+        state.list_state.select(Some(idx));
+    }
+    state.message = format!(
+        "Added '{}' and {} more tasks from the outline",
+        tasks[0].summary,
+        tasks.len() - 1
+    );
+    state.mode = InputMode::Normal;
+    state.reset_input();
+    state.creating_child_of = None;
+    Some(Action::CreateTasks(tasks))
+}
+
+/// True when the Normal mode key would modify a task of a read-only calendar:
+/// the selected task, or a new task/archive in the active calendar.
+fn hits_read_only(key: &KeyEvent, state: &AppState) -> bool {
+    let selected_read_only = || {
+        state.active_focus == Focus::Main
+            && state
+                .get_selected_task()
+                .is_some_and(|t| state.is_read_only(&t.calendar_href))
+    };
+    if key.modifiers.contains(KeyModifiers::ALT) {
+        return matches!(
+            key.code,
+            KeyCode::Up | KeyCode::Down | KeyCode::Char('j' | 'k')
+        ) && selected_read_only();
+    }
+    match key.code {
+        // The inbox is checked when the tasks are added, Shift+Enter may still pick this one
+        KeyCode::Char('a' | 'T') if state.inbox_href().is_some() => false,
+        KeyCode::Char(c) if creates_in_calendar(c) => state
+            .active_cal_href
+            .as_deref()
+            .is_some_and(|href| state.is_read_only(href)),
+        KeyCode::Char(c) if edits_selected(c) => selected_read_only(),
+        _ => false,
+    }
+}
+
+/// Normal mode keys that add tasks to the active calendar.
+fn creates_in_calendar(c: char) -> bool {
+    matches!(c, 'a' | 'W' | 'V' | 'T')
+}
+
+/// Normal mode keys that modify the selected task.
+fn edits_selected(c: char) -> bool {
+    matches!(
+        c,
+        ' ' | 's'
+            | 'x'
+            | '%'
+            | '+'
+            | '-'
+            | 'd'
+            | 'c'
+            | 'C'
+            | 'b'
+            | '.'
+            | ','
+            | 'e'
+            | 'E'
+            | 'z'
+            | 'S'
+            | 'M'
+            | 'p'
+            | 'P'
+            | 'f'
+    )
+}
+
+/// True when the key would change tasks from the review view, where only Space
+/// (to reopen the selected task) is allowed.
+fn edits_in_review(key: &KeyEvent) -> bool {
+    if key.modifiers.contains(KeyModifiers::ALT) {
+        return matches!(
+            key.code,
+            KeyCode::Up | KeyCode::Down | KeyCode::Char('j' | 'k')
+        );
+    }
+    match key.code {
+        KeyCode::Char(' ') => false,
+        KeyCode::Char(c) => creates_in_calendar(c) || edits_selected(c) || c == 'v',
+        _ => false,
+    }
+}
+
+/// Creates the tasks of the picked template, where the quick-add would put the task.
+fn create_from_template(state: &mut AppState) -> Option<Action> {
+    let href = quick_add_target(state, false)?;
+    if state.is_read_only(&href) {
+        state.message = "This calendar is read-only.".to_string();
+        return None;
+    }
+    let name = state
+        .template_state
+        .selected()
+        .and_then(|i| state.template_names.get(i))?
+        .clone();
+    let template = state.templates.get(&name)?;
+    let mut tasks = template.instantiate(&state.tag_aliases, chrono::Local::now().date_naive());
+    if tasks.is_empty() {
+        state.message = format!(
+            "Template '{}' has no task, set its `task` in the config.",
+            name
+        );
+        return None;
+    }
+    let cal_name = state
+        .calendars
+        .iter()
+        .find(|c| c.href == href)
+        .map(|c| c.name.as_str());
+    let defaults = CalendarDefaults::lookup(&state.calendar_defaults, &href, cal_name).cloned();
+    for task in &mut tasks {
+        if let Some(defaults) = &defaults {
+            defaults.apply(task);
+        }
+        task.calendar_href = href.clone();
+        state.store.add_task(task.clone());
+    }
+    state.refresh_filtered_view();
+    if let Some(idx) = state.tasks.iter().position(|t| t.uid == tasks[0].uid) {
+        state.list_state.select(Some(idx));
+    }
+    state.message = format!(
+        "Created '{}' with {} subtasks",
+        tasks[0].summary,
+        tasks.len() - 1
+    );
+    Some(Action::CreateTasks(tasks))
+}
+
+/// Moves the finished tasks of the active calendar to `target`.
+fn archive_completed(state: &mut AppState, target: String) -> Option<Action> {
+    let from = state.active_cal_href.clone()?;
+    let tasks = state.store.archivable_tasks(&from);
+    let name = state
+        .calendars
+        .iter()
+        .find(|c| c.href == target)
+        .map_or(target.as_str(), |c| c.name.as_str());
+    state.message = format!("Archiving {} tasks to {}...", tasks.len(), name);
+    Some(Action::ArchiveTasks(tasks, target))
+}
+
+/// Saves the config, telling when the keyring refused the password.
+fn save_config(cfg: &Config, state: &mut AppState) {
+    if let Ok(Some(warning)) = cfg.save_with_warning() {
+        state.message = warning;
+    }
+}
+
+/// Hides or shows the sidebar and remembers the choice in the config.
+fn toggle_focus_mode(state: &mut AppState) {
+    state.toggle_focus_mode();
+    if let Ok(mut cfg) = Config::load() {
+        cfg.focus_mode = state.focus_mode;
+        save_config(&cfg, state);
+    }
+}
+
+/// Keys of the visual-select mode. Each bulk operation emits one `Action` per picked task.
+async fn handle_bulk_key(
+    key: KeyEvent,
+    state: &mut AppState,
+    action_tx: &Sender<Action>,
+) -> Option<Action> {
+    match key.code {
+        KeyCode::Char(' ') => {
+            let picked = state
+                .get_selected_task()
+                .map(|t| (t.uid.clone(), state.is_read_only(&t.calendar_href)));
+            state.next();
+            state.message = match picked {
+                Some((_, true)) => "This calendar is read-only.".to_string(),
+                Some((uid, false)) => {
+                    if !state.selected_uids.remove(&uid) {
+                        state.selected_uids.insert(uid);
+                    }
+                    format!("{} selected", state.selected_uids.len())
+                }
+                None => format!("{} selected", state.selected_uids.len()),
+            };
+            return None;
+        }
+        KeyCode::Char('v') | KeyCode::Esc => {
+            state.clear_selection();
+            state.message.clear();
+            return None;
+        }
         _ => {}
     }
+
+    if state.selected_uids.is_empty() {
+        state.message = "Nothing selected (Space to pick tasks)".to_string();
+        return None;
+    }
+
+    if key.code == KeyCode::Char('M') {
+        // Reuse the move picker; its Enter handler moves the whole selection.
+        state.move_targets = state
+            .calendars
+            .iter()
+            .filter(|c| !c.read_only && !state.disabled_calendars.contains(&c.href))
+            .cloned()
+            .collect();
+        if !state.move_targets.is_empty() {
+            state.move_selection_state.select(Some(0));
+            state.mode = InputMode::Moving;
+            state.message = format!(
+                "Move {} tasks: select a calendar and press Enter.",
+                state.selected_uids.len()
+            );
+        }
+        return None;
+    }
+
+    let (uids, skipped) = writable_selection(state);
+    if uids.is_empty() {
+        state.message = "The selected tasks are read-only.".to_string();
+        return None;
+    }
+    if key.code == KeyCode::Char('d') {
+        if state.confirm_delete {
+            state.message = format!(
+                "Delete {} tasks?{} (y/n)",
+                uids.len(),
+                skipped_note(skipped)
+            );
+            state.pending_delete = uids;
+            state.mode = InputMode::ConfirmDelete;
+            return None;
+        }
+        let action = delete_tasks(uids, state, action_tx).await;
+        state.message.push_str(&skipped_note(skipped));
+        return action;
+    }
+
+    let mut count = 0;
+    for uid in uids {
+        let action = match key.code {
+            KeyCode::Enter => state.store.toggle_task(&uid).map(Action::ToggleTask),
+            KeyCode::Char('+') => state.store.change_priority(&uid, 1).map(Action::UpdateTask),
+            KeyCode::Char('-') => state
+                .store
+                .change_priority(&uid, -1)
+                .map(Action::UpdateTask),
+            _ => None,
+        };
+        if let Some(action) = action {
+            let _ = action_tx.send(action).await;
+            count += 1;
+        }
+    }
+
+    let verb = match key.code {
+        KeyCode::Enter => "Toggled",
+        _ => "Updated",
+    };
+    state.message = format!("{} {} tasks{}", verb, count, skipped_note(skipped));
+    state.refresh_filtered_view();
     None
 }
+
+/// The picked tasks that can be modified, and how many read-only ones were left out.
+fn writable_selection(state: &AppState) -> (Vec<String>, usize) {
+    let (writable, read_only): (Vec<String>, Vec<String>) =
+        state.selected_uids.iter().cloned().partition(|uid| {
+            !state
+                .store
+                .index
+                .get(uid)
+                .is_some_and(|href| state.is_read_only(href))
+        });
+    (writable, read_only.len())
+}
+
+fn skipped_note(skipped: usize) -> String {
+    if skipped == 0 {
+        String::new()
+    } else {
+        format!(" ({} read-only skipped)", skipped)
+    }
+}
+
+/// Deletes right away; a single task can be brought back with undo.
+async fn delete_tasks(
+    uids: Vec<String>,
+    state: &mut AppState,
+    action_tx: &Sender<Action>,
+) -> Option<Action> {
+    state.clear_selection();
+    if let [uid] = uids.as_slice() {
+        let deleted = state.store.delete_task(uid)?;
+        state.last_action = Some(UndoEntry::Deleted(Box::new(deleted.clone())));
+        state.refresh_filtered_view();
+        state.message = format!("Deleted '{}' (u to undo)", deleted.summary);
+        return Some(Action::DeleteTask(deleted));
+    }
+
+    let mut count = 0;
+    for uid in uids {
+        if let Some(deleted) = state.store.delete_task(&uid) {
+            let _ = action_tx.send(Action::DeleteTask(deleted)).await;
+            count += 1;
+        }
+    }
+    state.message = format!("Deleted {} tasks", count);
+    state.refresh_filtered_view();
+    None
+}
+
+/// Copies through the terminal (OSC 52), so it also works over SSH when the
+/// terminal emulator supports it.
+fn copy_to_clipboard(text: &str) -> std::io::Result<()> {
+    crossterm::execute!(std::io::stdout(), CopyToClipboard::to_clipboard_from(text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::TaskTemplate;
+    use crate::model::CalendarListEntry;
+    use std::collections::HashMap;
+
+    fn state_with(summaries: &[&str]) -> AppState {
+        let mut state = AppState::new();
+        for summary in summaries {
+            let mut task = Task::new(summary, &HashMap::new());
+            task.calendar_href = LOCAL_CALENDAR_HREF.to_string();
+            state.store.add_task(task);
+        }
+        state.refresh_filtered_view();
+        state
+    }
+
+    #[test]
+    fn test_paste_into_search_filters_the_list() {
+        let mut state = state_with(&["Buy milk", "Call mom"]);
+        state.mode = InputMode::Searching;
+
+        assert!(handle_paste("milk\n", &mut state).is_none());
+
+        assert_eq!(state.input_buffer, "milk");
+        let summaries: Vec<_> = state.tasks.iter().map(|t| t.summary.as_str()).collect();
+        assert_eq!(summaries, vec!["Buy milk"]);
+    }
+
+    #[tokio::test]
+    async fn test_ctrl_s_creates_the_task_started() {
+        let mut state = state_with(&[]);
+        state.active_cal_href = Some(LOCAL_CALENDAR_HREF.to_string());
+        state.focus_timer = true;
+        state.mode = InputMode::Creating;
+        "Write report".chars().for_each(|c| state.enter_char(c));
+        let (action_tx, _action_rx) = tokio::sync::mpsc::channel(8);
+
+        let ctrl_s = KeyEvent::new(KeyCode::Char('s'), KeyModifiers::CONTROL);
+        let Some(Action::CreateTask(task)) = handle_key_event(ctrl_s, &mut state, &action_tx).await
+        else {
+            panic!("no task created");
+        };
+
+        assert_eq!(task.summary, "Write report");
+        assert_eq!(task.status, TaskStatus::InProcess);
+        assert_eq!(
+            state.timer.as_ref().map(|t| t.uid.as_str()),
+            Some(task.uid.as_str())
+        );
+        assert!(state.mode == InputMode::Normal);
+
+        // Without Ctrl, s is typed
+        state.mode = InputMode::Creating;
+        let s = KeyEvent::new(KeyCode::Char('s'), KeyModifiers::NONE);
+        assert!(handle_key_event(s, &mut state, &action_tx).await.is_none());
+        assert_eq!(state.input_buffer, "s");
+    }
+
+    #[tokio::test]
+    async fn test_bulk_keys_skip_read_only_tasks() {
+        let mut state = state_with(&["Mine"]);
+        state.calendars = vec![CalendarListEntry {
+            name: "Shared".to_string(),
+            href: "/cal/shared/".to_string(),
+            color: None,
+            read_only: true,
+        }];
+        let mut shared = Task::new("Theirs", &HashMap::new());
+        shared.calendar_href = "/cal/shared/".to_string();
+        state.store.add_task(shared);
+        state.refresh_filtered_view();
+        state.visual_select = true;
+        let (action_tx, mut action_rx) = tokio::sync::mpsc::channel(8);
+        let space = KeyEvent::new(KeyCode::Char(' '), KeyModifiers::NONE);
+
+        for _ in 0..state.tasks.len() {
+            handle_key_event(space, &mut state, &action_tx).await;
+        }
+        assert_eq!(state.selected_uids.len(), 1);
+
+        // Picked before the calendar turned read-only
+        let theirs = state.tasks.iter().find(|t| t.summary == "Theirs");
+        state.selected_uids.insert(theirs.unwrap().uid.clone());
+        let enter = KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE);
+        handle_key_event(enter, &mut state, &action_tx).await;
+
+        assert_eq!(state.message, "Toggled 1 tasks (1 read-only skipped)");
+        let Ok(Action::ToggleTask(toggled)) = action_rx.try_recv() else {
+            panic!("nothing toggled");
+        };
+        assert_eq!(toggled.summary, "Mine");
+        assert!(action_rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_template_goes_where_the_quick_add_would() {
+        let mut state = state_with(&[]);
+        state.calendars = ["Work", "Inbox"]
+            .iter()
+            .map(|name| CalendarListEntry {
+                name: name.to_string(),
+                href: format!("/cal/{}/", name.to_lowercase()),
+                color: None,
+                read_only: false,
+            })
+            .collect();
+        state.active_cal_href = Some("/cal/work/".to_string());
+        state.inbox_calendar = Some("Inbox".to_string());
+        state.templates.insert(
+            "Deploy".to_string(),
+            TaskTemplate {
+                task: "Deploy".to_string(),
+                children: vec!["Run tests".to_string()],
+            },
+        );
+        state.template_names = vec!["Deploy".to_string()];
+        state.template_state.select(Some(0));
+
+        let Some(Action::CreateTasks(tasks)) = create_from_template(&mut state) else {
+            panic!("no tasks created");
+        };
+        assert!(tasks.iter().all(|t| t.calendar_href == "/cal/inbox/"));
+
+        state.calendars[1].read_only = true;
+        assert!(create_from_template(&mut state).is_none());
+        assert_eq!(state.message, "This calendar is read-only.");
+    }
+
+    #[test]
+    fn test_template_without_title_is_refused() {
+        let mut state = state_with(&[]);
+        state
+            .templates
+            .insert("Empty".to_string(), TaskTemplate::default());
+        state.template_names = vec!["Empty".to_string()];
+        state.template_state.select(Some(0));
+
+        assert!(create_from_template(&mut state).is_none());
+        assert!(state.store.calendars.values().all(|tasks| tasks.is_empty()));
+    }
+}
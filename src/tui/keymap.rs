@@ -0,0 +1,184 @@
+// File: src/tui/keymap.rs
+// User remapping of the Normal mode keys ([keybindings] in the config)
+use std::collections::HashMap;
+
+/// Action names usable in `[keybindings]`, with their default key.
+pub const ACTIONS: &[(&str, char)] = &[
+    ("quit", 'q'),
+    ("refresh", 'r'),
+    ("edit_config", 'o'),
+    ("help", '?'),
+    ("select", 'v'),
+    ("complete", ' '),
+    ("start", 's'),
+    ("cancel", 'x'),
+    ("progress", '%'),
+    ("priority_up", '+'),
+    ("priority_down", '-'),
+    ("set_priority", 'P'),
+    ("snooze", 'f'),
+    ("delete", 'd'),
+    ("undo", 'u'),
+    ("link_child", 'c'),
+    ("create_child", 'C'),
+    ("yank", 'y'),
+    ("copy", 'Y'),
+    ("duplicate", 'p'),
+    ("paste_tree", 'V'),
+    ("template", 'T'),
+    ("block", 'b'),
+    ("indent", '.'),
+    ("outdent", ','),
+    ("export", 'X'),
+    ("move", 'M'),
+    ("down", 'j'),
+    ("up", 'k'),
+    ("last", 'G'),
+    ("calendars", '1'),
+    ("tags", '2'),
+    ("match_all", 'm'),
+    ("agenda", 'A'),
+    ("review", 'L'),
+    ("today", 't'),
+    ("no_date", 'U'),
+    ("priority_filter", '!'),
+    ("hide_completed", 'H'),
+    ("hide_cancelled", 'K'),
+    ("hide_blocked", 'B'),
+    ("toggle_all", '*'),
+    ("search", '/'),
+    ("add", 'a'),
+    ("edit", 'e'),
+    ("edit_description", 'E'),
+    ("goto_calendar", 'g'),
+    ("journal", 'J'),
+    ("manual_order", 'O'),
+    ("someday", 'z'),
+    ("skip", 'S'),
+    ("hide_someday", 'Z'),
+    ("new_calendar", 'N'),
+    ("rename_calendar", 'R'),
+    ("archive", 'W'),
+    ("parent", '['),
+    ("first_child", ']'),
+    ("collapse", 'h'),
+    ("duplicates", 'I'),
+    ("focus_mode", 'F'),
+];
+
+/// Translates pressed keys into the default key of the action they are bound to,
+/// so the handlers keep matching on the defaults.
+#[derive(Debug, Clone, Default)]
+pub struct KeyMap {
+    remapped: HashMap<char, char>,
+}
+
+impl KeyMap {
+    /// Builds the map from the config table, returning warnings for unknown
+    /// actions, invalid keys and conflicting bindings (which are ignored).
+    pub fn from_config(bindings: &HashMap<String, String>) -> (Self, Vec<String>) {
+        let mut warnings = Vec::new();
+        let mut custom: Vec<(&str, char, char)> = Vec::new();
+
+        // Sorted so that conflicts resolve the same way on every start
+        let mut entries: Vec<_> = bindings.iter().collect();
+        entries.sort();
+        for (action, key) in entries {
+            let Some(&(name, default)) = ACTIONS.iter().find(|(n, _)| n == action) else {
+                warnings.push(format!("Unknown keybinding action '{}'", action));
+                continue;
+            };
+            let mut chars = key.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => custom.push((name, default, c)),
+                _ => warnings.push(format!(
+                    "Keybinding for '{}' must be a single character, got '{}'",
+                    name, key
+                )),
+            }
+        }
+
+        let mut remapped: HashMap<char, char> = HashMap::new();
+        let mut owners: HashMap<char, &str> = HashMap::new();
+        for &(name, default, key) in &custom {
+            if let Some(other) = owners.get(&key) {
+                warnings.push(format!(
+                    "Key '{}' is bound to both '{}' and '{}', keeping '{}'",
+                    key, other, name, other
+                ));
+                continue;
+            }
+            owners.insert(key, name);
+            remapped.insert(key, default);
+        }
+
+        // Unmapped actions keep their default key unless another action took it
+        for &(name, default) in ACTIONS {
+            if remapped.values().any(|d| *d == default) {
+                continue;
+            }
+            if let Some(other) = owners.get(&default) {
+                warnings.push(format!(
+                    "Key '{}' of '{}' is now bound to '{}'",
+                    default, name, other
+                ));
+            }
+        }
+
+        (Self { remapped }, warnings)
+    }
+
+    /// The default key to act on for `key`, or `None` when `key` is the default
+    /// of an action that was moved elsewhere.
+    pub fn resolve(&self, key: char) -> Option<char> {
+        if let Some(default) = self.remapped.get(&key) {
+            return Some(*default);
+        }
+        let moved =
+            self.remapped.values().any(|d| *d == key) && ACTIONS.iter().any(|(_, d)| *d == key);
+        if moved { None } else { Some(key) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bindings(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(a, k)| (a.to_string(), k.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_keymap_remaps_and_frees_defaults() {
+        let (map, warnings) =
+            KeyMap::from_config(&bindings(&[("delete", "D"), ("down", "n"), ("up", "i")]));
+        assert!(warnings.is_empty());
+        assert_eq!(map.resolve('D'), Some('d'));
+        assert_eq!(map.resolve('d'), None);
+        assert_eq!(map.resolve('n'), Some('j'));
+        assert_eq!(map.resolve('j'), None);
+        // Untouched actions and keys outside the map pass through
+        assert_eq!(map.resolve('q'), Some('q'));
+        assert_eq!(map.resolve('w'), Some('w'));
+    }
+
+    #[test]
+    fn test_keymap_conflicts_warn() {
+        let (map, warnings) = KeyMap::from_config(&bindings(&[
+            ("delete", "w"),
+            ("undo", "w"),
+            ("quit", "QQ"),
+            ("launch", "l"),
+            ("complete", "s"),
+        ]));
+        // delete sorts first and keeps 'w'
+        assert_eq!(map.resolve('w'), Some('d'));
+        // 's' was start's default key, now shadowed by complete
+        assert_eq!(map.resolve('s'), Some(' '));
+        assert_eq!(warnings.len(), 4);
+        assert!(warnings.iter().any(|w| w.contains("'start'")));
+    }
+}
@@ -1,6 +1,8 @@
 // File: ./src/tui/mod.rs
 pub mod action;
+pub mod cli;
 pub mod handlers;
+pub mod keymap;
 pub mod network;
 pub mod state;
 pub mod view;
@@ -11,20 +13,26 @@ use crate::tui::view::draw;
 
 use anyhow::Result;
 use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event, MouseEventKind},
+    event::{
+        self, DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture,
+        Event, MouseEventKind,
+    },
     execute,
     terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
 };
 use ratatui::{Terminal, backend::CrosstermBackend};
-use std::{env, io, time::Duration};
+use std::{
+    env, io,
+    time::{Duration, Instant},
+};
 use tokio::sync::mpsc;
 
 pub async fn run() -> Result<()> {
     // --- 1. PREAMBLE & CONFIG ---
     let args: Vec<String> = env::args().collect();
-    if args.len() > 1 && (args[1] == "--help" || args[1] == "-h") {
-        println!("Usage: cfait [OPTIONS]");
-        return Ok(());
+    let cli_args = cli::CliArgs::parse(&args)?;
+    if cli_args.is_batch() {
+        return cli::run_batch(&cli_args).await;
     }
 
     // Panic Hook
@@ -41,33 +49,8 @@ pub async fn run() -> Result<()> {
         default_hook(info);
     }));
 
-    let config_result = config::Config::load();
-    let (
-        url,
-        user,
-        pass,
-        default_cal,
-        hide_completed,
-        hide_fully_completed_tags,
-        tag_aliases,
-        sort_cutoff,
-        allow_insecure,
-        hidden_calendars,
-        disabled_calendars,
-    ) = match config_result {
-        Ok(cfg) => (
-            cfg.url,
-            cfg.username,
-            cfg.password,
-            cfg.default_calendar,
-            cfg.hide_completed,
-            cfg.hide_fully_completed_tags,
-            cfg.tag_aliases,
-            cfg.sort_cutoff_months,
-            cfg.allow_insecure_certs,
-            cfg.hidden_calendars,
-            cfg.disabled_calendars,
-        ),
+    let cfg = match config::Config::load() {
+        Ok(cfg) => cfg,
         Err(_) => {
             let path_str =
                 config::Config::get_path_string().unwrap_or("[path unknown]".to_string());
@@ -75,44 +58,53 @@ pub async fn run() -> Result<()> {
             return Ok(());
         }
     };
+    let default_cal = cfg.default_calendar.clone();
+    let refresh_interval = cfg.refresh_interval();
+    let network_cfg = cfg.clone();
 
     // --- 2. TERMINAL SETUP ---
     enable_raw_mode()?;
     let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
+    // Bracketed paste hands over a pasted outline at once instead of as keystrokes
+    execute!(
+        stdout,
+        EnterAlternateScreen,
+        EnableMouseCapture,
+        EnableBracketedPaste
+    )?;
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
     // --- 3. STATE INIT ---
     let mut app_state = AppState::new();
-    app_state.hide_completed = hide_completed;
-    app_state.hide_fully_completed_tags = hide_fully_completed_tags;
-    app_state.tag_aliases = tag_aliases;
-    app_state.sort_cutoff_months = sort_cutoff;
-    app_state.hidden_calendars = hidden_calendars.into_iter().collect();
-    app_state.disabled_calendars = disabled_calendars.into_iter().collect();
+    apply_config(&mut app_state, &cfg);
 
     let (action_tx, action_rx) = mpsc::channel(10);
     let (event_tx, mut event_rx) = mpsc::channel(10);
 
     // --- 4. NETWORK THREAD ---
-    tokio::spawn(network::run_network_actor(
-        url,
-        user,
-        pass,
-        allow_insecure,
-        default_cal.clone(), // Clone for the thread
-        action_rx,
-        event_tx,
-    ));
+    tokio::spawn(network::run_network_actor(network_cfg, action_rx, event_tx));
 
     // --- 5. UI LOOP ---
+    let mut last_refresh = Instant::now();
     loop {
         terminal.draw(|f| draw(f, &mut app_state))?;
 
+        // Auto-refresh, postponed while the user is typing or picking something
+        if let Some(interval) = refresh_interval
+            && last_refresh.elapsed() >= interval
+            && matches!(app_state.mode, InputMode::Normal)
+        {
+            last_refresh = Instant::now();
+            let _ = action_tx.try_send(action::Action::Refresh);
+        }
+
         // A. Network Events
         if let Ok(event) = event_rx.try_recv() {
             handlers::handle_app_event(&mut app_state, event, &default_cal);
+            if app_state.should_quit {
+                break;
+            }
         }
 
         // B. Input Events
@@ -128,10 +120,15 @@ pub async fn run() -> Result<()> {
                     if let Some(action) =
                         handlers::handle_key_event(key, &mut app_state, &action_tx).await
                     {
-                        if matches!(action, action::Action::Quit) {
-                            break;
+                        match action {
+                            action::Action::Quit => break,
+                            action::Action::EditConfig => {
+                                edit_config(&mut terminal, &mut app_state)?;
+                            }
+                            action => {
+                                let _ = action_tx.send(action).await;
+                            }
                         }
-                        let _ = action_tx.send(action).await;
                     }
                     // Handle mode-specific transient state updates that don't produce Actions
                     // (e.g. typing characters into buffer)
@@ -144,6 +141,11 @@ pub async fn run() -> Result<()> {
                         // (Handler returns Action::Quit, dealt with above. This block is safe to skip)
                     }
                 }
+                Event::Paste(text) => {
+                    if let Some(action) = handlers::handle_paste(&text, &mut app_state) {
+                        let _ = action_tx.send(action).await;
+                    }
+                }
                 _ => {}
             }
         }
@@ -154,8 +156,104 @@ pub async fn run() -> Result<()> {
     execute!(
         terminal.backend_mut(),
         LeaveAlternateScreen,
-        DisableMouseCapture
+        DisableMouseCapture,
+        DisableBracketedPaste
     )?;
     terminal.show_cursor()?;
     Ok(())
 }
+
+/// Copies the settings of the config file into the app state.
+fn apply_config(state: &mut AppState, cfg: &config::Config) {
+    state.hide_completed = cfg.hide_completed;
+    state.hide_cancelled = cfg.hide_cancelled;
+    state.hide_blocked = cfg.hide_blocked;
+    state.hide_fully_completed_tags = cfg.hide_fully_completed_tags;
+    state.sort_tags_by_count = cfg.sort_tags_by_count;
+    state.tag_aliases = cfg.tag_aliases.clone();
+    state.calendar_defaults = cfg.calendar_defaults.clone();
+    state.templates = cfg.templates.clone();
+    state.tag_colors = cfg.tag_colors.clone();
+    state.sort_cutoff_months = cfg.sort_cutoff_months;
+    state.hidden_calendars = cfg.hidden_calendars.iter().cloned().collect();
+    state.disabled_calendars = cfg.disabled_calendars.iter().cloned().collect();
+    state.auto_complete_parent = cfg.auto_complete_parent;
+    state.confirm_delete = cfg.confirm_delete;
+    state.confirm_quit_unsynced = cfg.confirm_quit_unsynced;
+    state.completed_last = cfg.completed_last;
+    state.manual_order = cfg.manual_order;
+    state.hide_deferred = cfg.hide_deferred;
+    state.fuzzy_search = cfg.fuzzy_search;
+    state.archive_calendar = cfg.archive_calendar.clone();
+    state.inbox_calendar = cfg.inbox_calendar.clone();
+    state.show_clock = cfg.show_clock;
+    state.max_width = cfg.tui_max_width;
+    state.week_start = cfg.week_start.weekday();
+    state.show_week_numbers = cfg.show_week_numbers;
+    state.focus_mode = cfg.focus_mode;
+    state.date_format = cfg.date_format.clone();
+    state.focus_timer = cfg.focus_timer;
+    state.snooze_presets = cfg.snooze_presets.clone();
+    let (keymap, keymap_warnings) = keymap::KeyMap::from_config(&cfg.keybindings);
+    state.keymap = keymap;
+    if !keymap_warnings.is_empty() {
+        state.message = format!("Keybindings: {}", keymap_warnings.join("; "));
+    }
+    if let Some(warning) = &cfg.load_warning {
+        state.message = warning.clone();
+    }
+}
+
+/// Suspends the TUI to open the config file in `$VISUAL`/`$EDITOR` (vi if
+/// unset), then reloads it. Server settings only apply after a restart, as the
+/// network actor keeps its own copy.
+fn edit_config<B: ratatui::backend::Backend + io::Write>(
+    terminal: &mut Terminal<B>,
+    state: &mut AppState,
+) -> Result<()> {
+    let path = config::Config::get_path_string()?;
+    let editor = env::var("VISUAL")
+        .or_else(|_| env::var("EDITOR"))
+        .unwrap_or_else(|_| "vi".to_string());
+    // The variable may hold arguments, e.g. "code --wait"
+    let mut words = editor.split_whitespace();
+    let program = words.next().unwrap_or("vi");
+
+    disable_raw_mode()?;
+    execute!(
+        terminal.backend_mut(),
+        LeaveAlternateScreen,
+        DisableMouseCapture,
+        DisableBracketedPaste
+    )?;
+    let status = std::process::Command::new(program)
+        .args(words)
+        .arg(&path)
+        .status();
+    enable_raw_mode()?;
+    execute!(
+        terminal.backend_mut(),
+        EnterAlternateScreen,
+        EnableMouseCapture,
+        EnableBracketedPaste
+    )?;
+    terminal.clear()?;
+
+    state.message = match status {
+        Err(e) => format!("Could not run '{}': {}", program, e),
+        Ok(_) => match config::Config::load() {
+            Ok(cfg) => {
+                state.message.clear();
+                apply_config(state, &cfg);
+                state.refresh_filtered_view();
+                if state.message.is_empty() {
+                    "Config reloaded (server settings apply after a restart).".to_string()
+                } else {
+                    std::mem::take(&mut state.message)
+                }
+            }
+            Err(e) => format!("Config not reloaded: {}", e),
+        },
+    };
+    Ok(())
+}
@@ -1,18 +1,17 @@
 // File: ./src/tui/network.rs
 // New file: Encapsulates the network actor logic
 use crate::cache::Cache;
-use crate::client::RustyClient;
+use crate::client::{FetchedTasks, RustyClient};
+use crate::config::Config;
+use crate::journal::Journal;
 use crate::model::CalendarListEntry;
-use crate::storage::{LOCAL_CALENDAR_HREF, LOCAL_CALENDAR_NAME, LocalStorage};
+use crate::storage::LocalStorage;
 use crate::tui::action::{Action, AppEvent};
+use std::collections::HashMap;
 use tokio::sync::mpsc::{Receiver, Sender};
 
 pub async fn run_network_actor(
-    url: String,
-    user: String,
-    pass: String,
-    allow_insecure: bool,
-    _default_cal: Option<String>,
+    config: Config,
     mut action_rx: Receiver<Action>,
     event_tx: Sender<AppEvent>,
 ) {
@@ -20,28 +19,19 @@ pub async fn run_network_actor(
     // 0. LOAD CACHE IMMEDIATELY
     // ------------------------------------------------------------------
     if let Ok(mut cached_cals) = Cache::load_calendars() {
-        let local_cal = CalendarListEntry {
-            name: LOCAL_CALENDAR_NAME.to_string(),
-            href: LOCAL_CALENDAR_HREF.to_string(),
-            color: None,
-        };
-        if !cached_cals.iter().any(|c| c.href == LOCAL_CALENDAR_HREF) {
-            cached_cals.push(local_cal);
-        }
+        LocalStorage::inject_calendars(&mut cached_cals);
 
         let _ = event_tx
             .send(AppEvent::CalendarsLoaded(cached_cals.clone()))
             .await;
 
         let mut cached_tasks = Vec::new();
-        if let Ok(local_t) = LocalStorage::load() {
-            cached_tasks.push((LOCAL_CALENDAR_HREF.to_string(), local_t));
-        }
-
         for cal in &cached_cals {
-            if cal.href != LOCAL_CALENDAR_HREF
-                && let Ok((tasks, _)) = Cache::load(&cal.href)
-            {
+            if LocalStorage::is_local(&cal.href) {
+                if let Ok(local_t) = LocalStorage::load(&cal.href) {
+                    cached_tasks.push((cal.href.clone(), local_t));
+                }
+            } else if let Ok((tasks, _)) = Cache::load(&cal.href) {
                 cached_tasks.push((cal.href.clone(), tasks));
             }
         }
@@ -54,7 +44,7 @@ pub async fn run_network_actor(
     // ------------------------------------------------------------------
     // 1. CONNECT & SYNC
     // ------------------------------------------------------------------
-    let client = match RustyClient::new(&url, &user, &pass, allow_insecure) {
+    let client = match RustyClient::from_config(&config) {
         Ok(c) => c,
         Err(e) => {
             let _ = event_tx.send(AppEvent::Error(e)).await;
@@ -78,7 +68,7 @@ pub async fn run_network_actor(
                     crate::config::Config::get_path_string()
                         .unwrap_or_else(|_| "path unknown".to_string())
                 );
-                if !allow_insecure {
+                if !config.allow_insecure_certs {
                     helpful_msg.push_str(
                         "\nIf this is a self-hosted server, set 'allow_insecure_certs = true'.",
                     );
@@ -95,12 +85,7 @@ pub async fn run_network_actor(
         }
     };
 
-    let local_cal = CalendarListEntry {
-        name: LOCAL_CALENDAR_NAME.to_string(),
-        href: LOCAL_CALENDAR_HREF.to_string(),
-        color: None,
-    };
-    calendars.push(local_cal);
+    LocalStorage::inject_calendars(&mut calendars);
 
     let _ = event_tx
         .send(AppEvent::CalendarsLoaded(calendars.clone()))
@@ -113,7 +98,7 @@ pub async fn run_network_actor(
     // Load tasks again with validated calendars list
     let mut cached_results = Vec::new();
     for cal in &calendars {
-        if cal.href != LOCAL_CALENDAR_HREF
+        if !LocalStorage::is_local(&cal.href)
             && let Ok((tasks, _)) = Cache::load(&cal.href)
         {
             cached_results.push((cal.href.clone(), tasks));
@@ -123,10 +108,16 @@ pub async fn run_network_actor(
         let _ = event_tx.send(AppEvent::TasksLoaded(cached_results)).await;
     }
 
-    match client.get_all_tasks(&calendars).await {
-        Ok(results) => {
-            let _ = event_tx.send(AppEvent::TasksLoaded(results)).await;
-            let _ = event_tx.send(AppEvent::Status("Ready.".to_string())).await;
+    match fetch_all(&client, &calendars, &event_tx).await {
+        Ok(fetched) => {
+            let status = with_parse_warning(&fetched, "Ready.");
+            let _ = event_tx
+                .send(AppEvent::TasksLoaded(fetched.calendars))
+                .await;
+            let _ = event_tx.send(AppEvent::Status(status)).await;
+            if let Some(caps) = client.known_capabilities() {
+                let _ = event_tx.send(AppEvent::Capabilities(caps)).await;
+            }
         }
         Err(e) => {
             let _ = event_tx
@@ -134,6 +125,7 @@ pub async fn run_network_actor(
                 .await;
         }
     }
+    report_journal(&event_tx).await;
 
     // ------------------------------------------------------------------
     // 2. ACTION LOOP
@@ -141,6 +133,7 @@ pub async fn run_network_actor(
     while let Some(action) = action_rx.recv().await {
         match action {
             Action::Quit => break,
+            Action::EditConfig => {}
             Action::SwitchCalendar(href) => match client.get_tasks(&href).await {
                 Ok(t) => {
                     let _ = event_tx.send(AppEvent::TasksLoaded(vec![(href, t)])).await;
@@ -186,6 +179,27 @@ pub async fn run_network_actor(
                     }
                 }
             }
+            Action::CreateTasks(tasks) => {
+                let Some(href) = tasks.first().map(|t| t.calendar_href.clone()) else {
+                    continue;
+                };
+                let count = tasks.len();
+                let mut errors = Vec::new();
+                for mut task in tasks {
+                    if let Err(e) = client.create_task(&mut task).await {
+                        errors.push(e);
+                    }
+                }
+                if let Ok(t) = client.get_tasks(&href).await {
+                    let _ = event_tx.send(AppEvent::TasksLoaded(vec![(href, t)])).await;
+                }
+                let event = if errors.is_empty() {
+                    AppEvent::Status(format!("Created {} tasks.", count))
+                } else {
+                    AppEvent::Error(errors.join("; "))
+                };
+                let _ = event_tx.send(event).await;
+            }
             Action::UpdateTask(mut task) => {
                 let href = task.calendar_href.clone();
                 match client.update_task(&mut task).await {
@@ -206,16 +220,19 @@ pub async fn run_network_actor(
                     }
                 }
             }
+            // The handler already flipped the status in the store
             Action::ToggleTask(mut task) => {
                 let href = task.calendar_href.clone();
-                if task.status == crate::model::TaskStatus::Completed {
-                    task.status = crate::model::TaskStatus::NeedsAction;
-                } else {
-                    task.status = crate::model::TaskStatus::Completed;
-                }
-
                 match client.toggle_task(&mut task).await {
-                    Ok((_, _, msgs)) => {
+                    Ok((_, next, msgs)) => {
+                        if let Some(next) = next {
+                            let _ = event_tx
+                                .send(AppEvent::Respawned {
+                                    uid: task.uid.clone(),
+                                    next: next.uid,
+                                })
+                                .await;
+                        }
                         let s = if msgs.is_empty() {
                             "Synced.".to_string()
                         } else {
@@ -253,6 +270,28 @@ pub async fn run_network_actor(
                     }
                 }
             }
+            Action::ResolveConflict(task, keep_local) => {
+                let href = task.calendar_href.clone();
+                match client
+                    .resolve_conflict(&task.uid, keep_local.as_deref())
+                    .await
+                {
+                    Ok(msgs) => {
+                        let s = if msgs.is_empty() {
+                            "Conflict resolved.".to_string()
+                        } else {
+                            msgs.join("; ")
+                        };
+                        let _ = event_tx.send(AppEvent::Status(s)).await;
+                    }
+                    Err(e) => {
+                        let _ = event_tx.send(AppEvent::Error(e)).await;
+                    }
+                }
+                if let Ok(t) = client.get_tasks(&href).await {
+                    let _ = event_tx.send(AppEvent::TasksLoaded(vec![(href, t)])).await;
+                }
+            }
             Action::Refresh => {
                 let _ = event_tx
                     .send(AppEvent::Status("Refreshing...".to_string()))
@@ -266,23 +305,19 @@ pub async fn run_network_actor(
                     }
                 };
 
-                let local_cal = CalendarListEntry {
-                    name: LOCAL_CALENDAR_NAME.to_string(),
-                    href: LOCAL_CALENDAR_HREF.to_string(),
-                    color: None,
-                };
-                calendars.push(local_cal);
+                LocalStorage::inject_calendars(&mut calendars);
 
                 let _ = event_tx
                     .send(AppEvent::CalendarsLoaded(calendars.clone()))
                     .await;
 
-                match client.get_all_tasks(&calendars).await {
-                    Ok(results) => {
-                        let _ = event_tx.send(AppEvent::TasksLoaded(results)).await;
+                match fetch_all(&client, &calendars, &event_tx).await {
+                    Ok(fetched) => {
+                        let status = with_parse_warning(&fetched, "Refreshed.");
                         let _ = event_tx
-                            .send(AppEvent::Status("Refreshed.".to_string()))
+                            .send(AppEvent::TasksLoaded(fetched.calendars))
                             .await;
+                        let _ = event_tx.send(AppEvent::Status(status)).await;
                     }
                     Err(e) => {
                         let _ = event_tx.send(AppEvent::Error(e)).await;
@@ -291,9 +326,9 @@ pub async fn run_network_actor(
             }
             Action::MarkInProcess(mut task) => {
                 if task.status == crate::model::TaskStatus::InProcess {
-                    task.status = crate::model::TaskStatus::NeedsAction;
+                    task.set_status(crate::model::TaskStatus::NeedsAction);
                 } else {
-                    task.status = crate::model::TaskStatus::InProcess;
+                    task.set_status(crate::model::TaskStatus::InProcess);
                 }
                 match client.update_task(&mut task).await {
                     Ok(msgs) => {
@@ -311,9 +346,9 @@ pub async fn run_network_actor(
             }
             Action::MarkCancelled(mut task) => {
                 if task.status == crate::model::TaskStatus::Cancelled {
-                    task.status = crate::model::TaskStatus::NeedsAction;
+                    task.set_status(crate::model::TaskStatus::NeedsAction);
                 } else {
-                    task.status = crate::model::TaskStatus::Cancelled;
+                    task.set_status(crate::model::TaskStatus::Cancelled);
                 }
                 match client.update_task(&mut task).await {
                     Ok(msgs) => {
@@ -357,8 +392,8 @@ pub async fn run_network_actor(
                     }
                 }
             }
-            Action::MigrateLocal(target_href) => {
-                if let Ok(local_tasks) = LocalStorage::load() {
+            Action::MigrateLocal(source_href, target_href) => {
+                if let Ok(local_tasks) = LocalStorage::load(&source_href) {
                     let _ = event_tx
                         .send(AppEvent::Status(format!(
                             "Exporting {} tasks...",
@@ -370,12 +405,9 @@ pub async fn run_network_actor(
                             let _ = event_tx
                                 .send(AppEvent::Status(format!("Exported {} tasks.", count)))
                                 .await;
-                            if let Ok(t1) = client.get_tasks(LOCAL_CALENDAR_HREF).await {
+                            if let Ok(t1) = client.get_tasks(&source_href).await {
                                 let _ = event_tx
-                                    .send(AppEvent::TasksLoaded(vec![(
-                                        LOCAL_CALENDAR_HREF.to_string(),
-                                        t1,
-                                    )]))
+                                    .send(AppEvent::TasksLoaded(vec![(source_href, t1)]))
                                     .await;
                             }
                             if let Ok(t2) = client.get_tasks(&target_href).await {
@@ -392,9 +424,144 @@ pub async fn run_network_actor(
                     }
                 }
             }
+            Action::ArchiveTasks(tasks, target_href) => {
+                let Some(from_href) = tasks.first().map(|t| t.calendar_href.clone()) else {
+                    continue;
+                };
+                match client.migrate_tasks(tasks, &target_href).await {
+                    Ok(count) => {
+                        let _ = event_tx
+                            .send(AppEvent::Status(format!("Archived {} tasks.", count)))
+                            .await;
+                        for href in [from_href, target_href] {
+                            if let Ok(t) = client.get_tasks(&href).await {
+                                let _ = event_tx.send(AppEvent::TasksLoaded(vec![(href, t)])).await;
+                            }
+                        }
+                    }
+                    Err(e) => {
+                        let _ = event_tx
+                            .send(AppEvent::Error(format!("Archive failed: {}", e)))
+                            .await;
+                    }
+                }
+            }
+            Action::CreateCalendar(name) => match client.create_calendar(&name).await {
+                Ok(created) => {
+                    let mut calendars = client.get_calendars().await.unwrap_or_default();
+                    if !LocalStorage::is_local(&created.href)
+                        && !calendars.iter().any(|c| c.href == created.href)
+                    {
+                        calendars.push(created.clone());
+                    }
+                    let _ = Cache::save_calendars(&calendars);
+                    LocalStorage::inject_calendars(&mut calendars);
+                    let _ = event_tx.send(AppEvent::CalendarsLoaded(calendars)).await;
+                    let _ = event_tx
+                        .send(AppEvent::Status(format!(
+                            "Created calendar '{}'.",
+                            created.name
+                        )))
+                        .await;
+                }
+                Err(e) => {
+                    let _ = event_tx
+                        .send(AppEvent::Error(format!("Could not create calendar: {}", e)))
+                        .await;
+                }
+            },
+            Action::RenameCalendar(href, name) => {
+                match client.rename_calendar(&href, &name).await {
+                    Ok(()) => {
+                        let mut calendars = match client.get_calendars().await {
+                            Ok(c) => c,
+                            Err(_) => Cache::load_calendars().unwrap_or_default(),
+                        };
+                        LocalStorage::inject_calendars(&mut calendars);
+                        let _ = event_tx.send(AppEvent::CalendarsLoaded(calendars)).await;
+                        let _ = event_tx
+                            .send(AppEvent::Status(format!("Renamed calendar to '{}'.", name)))
+                            .await;
+                    }
+                    Err(e) => {
+                        let _ = event_tx.send(AppEvent::Error(e)).await;
+                    }
+                }
+            }
+            Action::SyncJournal => {
+                let etags = flush_journal(&client, &event_tx).await;
+                let left = Journal::load().queue.len();
+                let _ = event_tx.send(AppEvent::JournalFlushed(left, etags)).await;
+            }
             Action::StartCreateChild(_parent_uid) => {
                 // UI logic only
             }
         }
+        // Any of the above may have synced the journal and hit a conflict
+        report_journal(&event_tx).await;
+    }
+}
+
+/// Pushes queued offline changes, reporting "Syncing n/total" as it goes.
+/// Failures are left in the journal and retried by the next fetch.
+/// Returns the new ETags of the tasks sent, by UID.
+async fn flush_journal(
+    client: &RustyClient,
+    event_tx: &Sender<AppEvent>,
+) -> HashMap<String, String> {
+    let sync = client
+        .sync_journal_with_progress(|current, total| {
+            let _ = event_tx.try_send(AppEvent::Status(format!(
+                "Syncing {}/{}...",
+                current, total
+            )));
+        })
+        .await;
+    report_journal(event_tx).await;
+    sync.map(|sync| sync.etags).unwrap_or_default()
+}
+
+/// Fetches the tasks of all calendars, reporting "Syncing n/total" while queued
+/// changes are sent and "Synced n/total calendars" as the calendars land.
+async fn fetch_all(
+    client: &RustyClient,
+    calendars: &[CalendarListEntry],
+    event_tx: &Sender<AppEvent>,
+) -> Result<FetchedTasks, String> {
+    client
+        .get_all_tasks_with_progress(
+            calendars,
+            |current, total| {
+                let _ = event_tx.try_send(AppEvent::Status(format!(
+                    "Syncing {}/{}...",
+                    current, total
+                )));
+            },
+            |done, total| {
+                let _ = event_tx.try_send(AppEvent::Status(format!(
+                    "Synced {}/{} calendars...",
+                    done, total
+                )));
+            },
+        )
+        .await
+}
+
+/// Tells the UI how many changes are still queued, and hands it the updates that
+/// could not be merged automatically for resolution.
+async fn report_journal(event_tx: &Sender<AppEvent>) {
+    let journal = Journal::load();
+    let _ = event_tx.send(AppEvent::Unsynced(journal.queue.len())).await;
+    let conflicts = journal.conflicts;
+    if !conflicts.is_empty() {
+        let _ = event_tx.send(AppEvent::Conflicts(conflicts)).await;
+    }
+}
+
+/// Status message, followed by the warning about tasks that failed to parse.
+fn with_parse_warning(fetched: &FetchedTasks, status: &str) -> String {
+    match fetched.parse_warning() {
+        Some(warning) => format!("{} Warning: {}", status, warning),
+        None => status.to_string(),
     }
 }
@@ -1,7 +1,11 @@
 // File: ./src/tui/state.rs
-use crate::model::{CalendarListEntry, Task};
-use crate::store::{FilterOptions, TaskStore};
+use crate::config::{CalendarDefaults, TaskTemplate};
+use crate::journal::{Journal, MergeConflict};
+use crate::model::completion::{self, TagCompletion};
+use crate::model::{CalendarListEntry, DEFAULT_DATE_FORMAT, Task, TimerState};
+use crate::store::{DueBucket, FilterOptions, TaskStore, UNCATEGORIZED_ID, UndoEntry};
 use crate::tui::action::SidebarMode;
+use crate::tui::keymap::KeyMap;
 use ratatui::widgets::ListState;
 use std::collections::{HashMap, HashSet};
 
@@ -20,12 +24,30 @@ pub enum InputMode {
     EditingDescription,
     Moving,
     Exporting,
+    /// Picking the calendar "Archive Completed" moves tasks to (`export_targets`)
+    Archiving,
+    Resolving,
+    ConfirmDelete,
+    /// `q` with changes still in the journal: sync first, quit or stay
+    ConfirmQuit,
+    /// Inspecting the offline sync queue
+    Journal,
+    /// Typing the name of a new server calendar
+    CreatingCalendar,
+    /// Editing the name of the calendar in `renaming_calendar`
+    RenamingCalendar,
+    /// Picking the template to create tasks from (`template_names`)
+    PickingTemplate,
+    /// Reviewing the groups of duplicate tasks (`duplicates`)
+    Duplicates,
 }
 
 pub struct AppState {
     // Data
     pub store: TaskStore,
     pub tasks: Vec<Task>,
+    /// Subtask progress of each parent, taken by `refresh_filtered_view`
+    pub child_progress: HashMap<String, (usize, usize)>,
     pub calendars: Vec<CalendarListEntry>,
 
     // UI State
@@ -44,25 +66,113 @@ pub struct AppState {
     pub selected_categories: HashSet<String>,
     pub match_all_categories: bool,
     pub hide_completed: bool,
+    pub hide_cancelled: bool,
+    pub hide_blocked: bool,
     pub hide_fully_completed_tags: bool,
+    pub sort_tags_by_count: bool,
     pub sort_cutoff_months: Option<u32>,
+    pub auto_complete_parent: bool,
+    pub agenda_view: bool,
+    /// Only show tasks due today or overdue.
+    pub today_view: bool,
+    /// Agenda only: index in `tasks` where each date section starts.
+    pub agenda_sections: Vec<(usize, DueBucket)>,
+    /// Read-only list of what was completed lately, grouped by day.
+    pub review_view: bool,
+    /// Review only: index in `tasks` where each day starts.
+    pub review_sections: Vec<(usize, chrono::NaiveDate)>,
 
     // Input Buffers
     pub input_buffer: String,
     pub cursor_position: usize,
     pub editing_index: Option<usize>,
+    /// Tab cycling through `#tag` completions, cleared by any other key
+    pub tag_completion: Option<TagCompletion>,
     pub move_selection_state: ListState,
+    /// Sync conflicts awaiting a decision; the first one is shown in the resolve popup.
+    pub conflicts: Vec<MergeConflict>,
+    pub conflict_state: ListState,
+    pub conflict_keep_local: HashSet<String>,
     pub move_targets: Vec<CalendarListEntry>,
     pub export_selection_state: ListState,
     pub export_targets: Vec<CalendarListEntry>,
+    /// Local calendar being exported while in `InputMode::Exporting`
+    pub export_source: Option<String>,
+    pub templates: HashMap<String, TaskTemplate>,
+    pub template_names: Vec<String>,
+    pub template_state: ListState,
 
     pub yanked_uid: Option<String>,
     pub creating_child_of: Option<String>,
+    pub renaming_calendar: Option<String>,
+    pub archive_calendar: Option<String>,
+    /// Name or href of the calendar quick-adds go to (config `inbox_calendar`)
+    pub inbox_calendar: Option<String>,
+    pub show_clock: bool,
+    /// Columns the task list is centered in (config `tui_max_width`), full width if unset
+    pub max_width: Option<u16>,
+    pub week_start: chrono::Weekday,
+    pub show_week_numbers: bool,
+    /// Sidebar hidden, the task list takes the full width (`focus_mode`)
+    pub focus_mode: bool,
+    /// chrono format of the shown dates (config `date_format`)
+    pub date_format: String,
+    /// Only show tasks with a priority from 1 up to this one (`!` then a digit)
+    pub priority_filter: Option<u8>,
+    /// Start the focus timer with `s` (config `focus_timer`), and the running one
+    pub focus_timer: bool,
+    pub snooze_presets: Vec<String>,
+    pub timer: Option<TimerState>,
+    /// Append the short UID and calendar href to each row (`D`, not saved)
+    pub show_debug_ids: bool,
+    /// What the server supports, shown in the sync queue popup
+    pub server_capabilities: Option<String>,
     pub show_full_help: bool,
+    /// First key of a two-key sequence (e.g. `g` in `g1`), waiting for the second key.
+    pub pending_key: Option<char>,
+    /// The last delete or status change, restorable with `u`.
+    pub last_action: Option<UndoEntry>,
+    /// Visual-select mode (`v`): Space picks tasks, bulk keys act on all of them.
+    pub visual_select: bool,
+    /// Progress mode (`%`): `+`/`-` adjust the percent complete instead of the priority.
+    pub progress_mode: bool,
+    pub selected_uids: HashSet<String>,
+    /// Parents whose subtasks are folded away (`h`), kept by UID across refreshes
+    pub collapsed_uids: HashSet<String>,
+    /// Number of tasks hidden under each collapsed parent of the current list
+    pub collapsed_counts: HashMap<String, usize>,
+    /// Open tasks without a due date, listed last in the agenda and today views
+    pub undated_count: usize,
+    /// The "No Date" section is folded to its header (`U`)
+    pub no_date_collapsed: bool,
     pub tag_aliases: HashMap<String, Vec<String>>,
-
-    // Track unsynced status
-    pub unsynced_changes: bool,
+    pub calendar_defaults: HashMap<String, CalendarDefaults>,
+    pub tag_colors: HashMap<String, String>,
+    pub keymap: KeyMap,
+    pub confirm_delete: bool,
+    pub completed_last: bool,
+    pub manual_order: bool,
+    pub hide_deferred: bool,
+    pub fuzzy_search: bool,
+    /// Tasks awaiting `y`/`n` in `InputMode::ConfirmDelete`
+    pub pending_delete: Vec<String>,
+    pub journal_entries: Vec<String>,
+    pub journal_state: ListState,
+    /// `C` was pressed in the journal view; waiting for `y` to clear it all
+    pub journal_confirm_clear: bool,
+    /// Duplicate tasks with the number of their group, the kept one first in each
+    pub duplicates: Vec<(usize, Task)>,
+    pub duplicate_state: ListState,
+    /// Duplicates marked to be deleted when the review is applied
+    pub duplicate_delete: HashSet<String>,
+
+    /// Offline changes waiting in the journal
+    pub unsynced_count: usize,
+    pub confirm_quit_unsynced: bool,
+    /// Quit once the sync asked for from the quit prompt has emptied the journal
+    pub quit_after_sync: bool,
+    /// Set by events that end the session; checked by the UI loop
+    pub should_quit: bool,
 }
 
 impl Default for AppState {
@@ -81,6 +191,7 @@ impl AppState {
         Self {
             store: TaskStore::new(),
             tasks: vec![],
+            child_progress: HashMap::new(),
             calendars: vec![],
             list_state: l_state,
             cal_state: c_state,
@@ -96,24 +207,117 @@ impl AppState {
             selected_categories: HashSet::new(),
             match_all_categories: false,
             hide_completed: false,
+            hide_cancelled: false,
+            hide_blocked: false,
             hide_fully_completed_tags: false,
+            sort_tags_by_count: false,
             sort_cutoff_months: Some(6),
+            auto_complete_parent: false,
+            agenda_view: false,
+            today_view: false,
+            agenda_sections: Vec::new(),
+            review_view: false,
+            review_sections: Vec::new(),
 
             input_buffer: String::new(),
             cursor_position: 0,
             editing_index: None,
+            tag_completion: None,
             move_selection_state: ListState::default(),
+            conflicts: Vec::new(),
+            conflict_state: ListState::default(),
+            conflict_keep_local: HashSet::new(),
             move_targets: Vec::new(),
             yanked_uid: None,
             creating_child_of: None,
+            renaming_calendar: None,
+            archive_calendar: None,
+            inbox_calendar: None,
+            show_clock: true,
+            week_start: chrono::Weekday::Mon,
+            show_week_numbers: false,
+            focus_mode: false,
+            date_format: DEFAULT_DATE_FORMAT.to_string(),
+            max_width: None,
+            priority_filter: None,
+            focus_timer: false,
+            snooze_presets: Vec::new(),
+            timer: None,
+            show_debug_ids: false,
+            server_capabilities: None,
             show_full_help: false,
+            pending_key: None,
+            last_action: None,
+            visual_select: false,
+            progress_mode: false,
+            selected_uids: HashSet::new(),
+            collapsed_uids: HashSet::new(),
+            collapsed_counts: HashMap::new(),
+            undated_count: 0,
+            no_date_collapsed: false,
 
             tag_aliases: HashMap::new(),
+            calendar_defaults: HashMap::new(),
+            tag_colors: HashMap::new(),
+            keymap: KeyMap::default(),
+            confirm_delete: true,
+            completed_last: true,
+            manual_order: false,
+            hide_deferred: false,
+            fuzzy_search: false,
+            pending_delete: Vec::new(),
+            journal_entries: Vec::new(),
+            journal_state: ListState::default(),
+            journal_confirm_clear: false,
+            duplicates: Vec::new(),
+            duplicate_state: ListState::default(),
+            duplicate_delete: HashSet::new(),
             export_selection_state: ListState::default(),
             export_targets: Vec::new(),
+            export_source: None,
+            templates: HashMap::new(),
+            template_names: Vec::new(),
+            template_state: ListState::default(),
+
+            unsynced_count: 0,
+            confirm_quit_unsynced: true,
+            quit_after_sync: false,
+            should_quit: false,
+        }
+    }
 
-            unsynced_changes: false, // Default false
+    /// Href of the configured inbox, if it is one of the known calendars.
+    pub fn inbox_href(&self) -> Option<&str> {
+        let inbox = self.inbox_calendar.as_deref()?;
+        self.calendars
+            .iter()
+            .find(|c| c.href == inbox || c.name == inbox)
+            .map(|c| c.href.as_str())
+    }
+
+    /// Loads the duplicate groups of the writable calendars for review, marking
+    /// all but the first task of each group for deletion. Completed and cancelled
+    /// tasks are only deleted when picked.
+    pub fn load_duplicates(&mut self) {
+        self.duplicates.clear();
+        self.duplicate_delete.clear();
+        let mut groups = self.store.find_duplicates();
+        groups.retain(|group| !self.is_read_only(&group[0].calendar_href));
+        for (n, group) in groups.into_iter().enumerate() {
+            for (i, task) in group.into_iter().enumerate() {
+                if i > 0 && !task.status.is_done() {
+                    self.duplicate_delete.insert(task.uid.clone());
+                }
+                self.duplicates.push((n, task));
+            }
         }
+        self.duplicate_state
+            .select((!self.duplicates.is_empty()).then_some(0));
+    }
+
+    /// Whether `href` is a calendar whose tasks can't be modified.
+    pub fn is_read_only(&self, href: &str) -> bool {
+        self.calendars.iter().any(|c| c.href == href && c.read_only)
     }
 
     pub fn get_filtered_calendars(&self) -> Vec<&CalendarListEntry> {
@@ -123,8 +327,17 @@ impl AppState {
             .collect()
     }
 
+    /// Starts or stops the focus timer after `updated` changed status.
+    pub fn follow_timer(&mut self, updated: Task) -> (Task, Vec<Task>) {
+        let now = chrono::Utc::now();
+        self.store
+            .follow_timer(&mut self.timer, updated, self.focus_timer, now)
+    }
+
     pub fn refresh_filtered_view(&mut self) {
         let cal_filter = None;
+        // Follow the selected task by UID, the reload may move it
+        let selected_uid = self.get_selected_task().map(|t| t.uid.clone());
 
         let search_term = if self.mode == InputMode::Searching {
             &self.input_buffer
@@ -143,24 +356,67 @@ impl AppState {
         let mut effective_hidden = self.hidden_calendars.clone();
         effective_hidden.extend(self.disabled_calendars.clone());
 
-        self.tasks = self.store.filter(FilterOptions {
+        let options = FilterOptions {
             active_cal_href: cal_filter,
             selected_categories: &self.selected_categories,
             match_all_categories: self.match_all_categories,
             hidden_calendars: &effective_hidden,
             search_term,
             hide_completed_global: self.hide_completed,
+            hide_cancelled: self.hide_cancelled,
+            hide_blocked: self.hide_blocked,
             cutoff_date,
             // TUI currently doesn't implement duration filtering UI, so we disable it
             min_duration: None,
             max_duration: None,
             include_unset_duration: true,
-        });
+            due_today_only: self.today_view,
+            completed_last: self.completed_last,
+            manual_order: self.manual_order,
+            hide_deferred: self.hide_deferred,
+            fuzzy_search: self.fuzzy_search,
+            week_start: self.week_start,
+            priority_filter: self.priority_filter,
+        };
+
+        self.agenda_sections.clear();
+        self.review_sections.clear();
+        self.collapsed_counts.clear();
+        self.child_progress = self.store.child_progress_map();
+        if self.review_view {
+            (self.tasks, self.review_sections) = self.store.recently_completed(&effective_hidden);
+        } else if self.agenda_view {
+            (self.tasks, self.agenda_sections) = self.store.agenda(options);
+        } else {
+            self.tasks = self.store.filter(options);
+            if self.shows_hierarchy() {
+                Self::hide_collapsed_subtasks(
+                    &mut self.tasks,
+                    &self.collapsed_uids,
+                    &mut self.collapsed_counts,
+                );
+            }
+        }
+        self.undated_count = if !self.review_view && (self.agenda_view || self.today_view) {
+            self.store.add_no_date_section(
+                options,
+                &mut self.tasks,
+                &mut self.agenda_sections,
+                self.no_date_collapsed,
+            )
+        } else {
+            0
+        };
 
         let len = self.tasks.len();
         if len == 0 {
             self.list_state.select(None);
+        } else if let Some(uid) = selected_uid
+            && let Some(idx) = self.tasks.iter().position(|t| t.uid == uid)
+        {
+            self.list_state.select(Some(idx));
         } else {
+            // The task is gone: stay at the same position, on its nearest neighbour
             let current = self.list_state.selected().unwrap_or(0);
             if current >= len {
                 self.list_state.select(Some(len - 1)); // Clamp
@@ -170,6 +426,45 @@ impl AppState {
         }
     }
 
+    /// The list is indented by hierarchy: a calendar is open and no search is typed.
+    pub fn shows_hierarchy(&self) -> bool {
+        self.active_cal_href.is_some() && self.mode != InputMode::Searching
+    }
+
+    /// Drops the descendants of collapsed parents from `tasks`, counting them
+    /// in `counts`. Takes the fields apart as the filter options borrow the rest.
+    fn hide_collapsed_subtasks(
+        tasks: &mut Vec<Task>,
+        collapsed: &HashSet<String>,
+        counts: &mut HashMap<String, usize>,
+    ) {
+        if collapsed.is_empty() {
+            return;
+        }
+        let mut kept = Vec::with_capacity(tasks.len());
+        // Depth and UID of the collapsed parent being skipped
+        let mut hiding: Option<(usize, String)> = None;
+        for task in std::mem::take(tasks) {
+            if let Some((depth, uid)) = &hiding {
+                if task.depth > *depth {
+                    *counts.entry(uid.clone()).or_default() += 1;
+                    continue;
+                }
+                hiding = None;
+            }
+            if collapsed.contains(&task.uid) {
+                hiding = Some((task.depth, task.uid.clone()));
+            }
+            kept.push(task);
+        }
+        *tasks = kept;
+    }
+
+    pub fn clear_selection(&mut self) {
+        self.visual_select = false;
+        self.selected_uids.clear();
+    }
+
     pub fn get_selected_task(&self) -> Option<&Task> {
         if let Some(idx) = self.list_state.selected() {
             self.tasks.get(idx)
@@ -188,7 +483,13 @@ impl AppState {
         self.cursor_position = self.clamp_cursor(cursor_moved_right);
     }
     pub fn enter_char(&mut self, new_char: char) {
-        self.input_buffer.insert(self.cursor_position, new_char);
+        // The cursor counts chars, the insertion needs a byte offset
+        let at = self
+            .input_buffer
+            .char_indices()
+            .nth(self.cursor_position)
+            .map_or(self.input_buffer.len(), |(i, _)| i);
+        self.input_buffer.insert(at, new_char);
         self.move_cursor_right();
     }
     pub fn delete_char(&mut self) {
@@ -204,6 +505,28 @@ impl AppState {
         self.input_buffer.clear();
         self.cursor_position = 0;
     }
+    /// Existing tags and aliases offered when completing a `#tag`.
+    pub fn tag_candidates(&self) -> Vec<String> {
+        let categories = self
+            .store
+            .get_all_categories(false, false, &HashSet::new(), &HashSet::new(), false)
+            .into_iter()
+            .map(|(name, _)| name)
+            .filter(|name| name != UNCATEGORIZED_ID);
+        completion::tag_candidates(categories, &self.tag_aliases)
+    }
+    pub fn complete_tag(&mut self) {
+        let candidates = self.tag_candidates();
+        if let Some((input, cursor)) = completion::complete_tag(
+            &self.input_buffer,
+            self.cursor_position,
+            &candidates,
+            &mut self.tag_completion,
+        ) {
+            self.input_buffer = input;
+            self.cursor_position = cursor;
+        }
+    }
     fn clamp_cursor(&self, new_cursor_pos: usize) -> usize {
         new_cursor_pos.clamp(0, self.input_buffer.chars().count())
     }
@@ -223,6 +546,7 @@ impl AppState {
                     self.hide_fully_completed_tags,
                     &self.selected_categories,
                     &self.hidden_calendars,
+                    self.sort_tags_by_count,
                 )
                 .len(),
         }
@@ -309,14 +633,15 @@ impl AppState {
                 if !self.tasks.is_empty() {
                     let current = self.list_state.selected().unwrap_or(0);
                     self.list_state
-                        .select(Some((current + step).min(self.tasks.len() - 1)));
+                        .select(Some(current.saturating_add(step).min(self.tasks.len() - 1)));
                 }
             }
             Focus::Sidebar => {
                 let len = self.get_sidebar_len();
                 if len > 0 {
                     let current = self.cal_state.selected().unwrap_or(0);
-                    self.cal_state.select(Some((current + step).min(len - 1)));
+                    self.cal_state
+                        .select(Some(current.saturating_add(step).min(len - 1)));
                 }
             }
         }
@@ -338,12 +663,47 @@ impl AppState {
             }
         }
     }
+    /// Hides or shows the sidebar; a hidden sidebar gives the focus back to the tasks.
+    pub fn toggle_focus_mode(&mut self) {
+        self.focus_mode = !self.focus_mode;
+        if self.focus_mode {
+            self.active_focus = Focus::Main;
+        }
+    }
     pub fn toggle_focus(&mut self) {
         self.active_focus = match self.active_focus {
             Focus::Main => Focus::Sidebar,
             Focus::Sidebar => Focus::Main,
         }
     }
+    /// Reloads the pending sync actions into the journal view.
+    pub fn refresh_journal(&mut self) {
+        self.journal_entries = Journal::queue_summary();
+        self.unsynced_count = self.journal_entries.len();
+        let selected = self.journal_state.selected().unwrap_or(0);
+        self.journal_state
+            .select(if self.journal_entries.is_empty() {
+                None
+            } else {
+                Some(selected.min(self.journal_entries.len() - 1))
+            });
+    }
+
+    /// Opens the resolve popup for the first pending conflict, if any.
+    pub fn start_resolving(&mut self) {
+        self.conflict_keep_local.clear();
+        if let Some(conflict) = self.conflicts.first() {
+            self.conflict_state.select(Some(0));
+            self.mode = InputMode::Resolving;
+            self.message = format!(
+                "Conflict on '{}': Space picks a side, Enter applies, Esc keeps both.",
+                conflict.local.summary
+            );
+        } else if self.mode == InputMode::Resolving {
+            self.mode = InputMode::Normal;
+        }
+    }
+
     pub fn next_move_target(&mut self) {
         if self.move_targets.is_empty() {
             return;
@@ -415,6 +775,7 @@ impl AppState {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::model::TaskStatus;
     use std::collections::HashMap;
 
     fn dummy_task() -> Task {
@@ -440,6 +801,29 @@ mod tests {
         assert_eq!(state.list_state.selected(), Some(0));
     }
 
+    #[test]
+    fn test_jump_to_ends() {
+        let mut state = AppState::new();
+        state.tasks = vec![dummy_task(), dummy_task(), dummy_task()];
+        state.list_state.select(Some(1));
+
+        state.jump_forward(usize::MAX);
+        assert_eq!(state.list_state.selected(), Some(2));
+        state.jump_backward(usize::MAX);
+        assert_eq!(state.list_state.selected(), Some(0));
+    }
+
+    #[test]
+    fn test_focus_mode_takes_focus_from_sidebar() {
+        let mut state = AppState::new();
+        state.active_focus = Focus::Sidebar;
+        state.toggle_focus_mode();
+        assert!(state.focus_mode);
+        assert!(state.active_focus == Focus::Main);
+        state.toggle_focus_mode();
+        assert!(!state.focus_mode);
+    }
+
     #[test]
     fn test_navigation_previous_wraps() {
         let mut state = AppState::new();
@@ -466,6 +850,89 @@ mod tests {
         // Selection should stay None or safe default, but definitely no panic
     }
 
+    #[test]
+    fn test_refresh_keeps_selected_task() {
+        let mut state = AppState::new();
+        for (uid, summary) in [("a", "Alpha"), ("b", "Beta"), ("c", "Gamma")] {
+            let mut t = Task::new(summary, &HashMap::new());
+            t.uid = uid.to_string();
+            t.calendar_href = "/cal/".to_string();
+            state.store.add_task(t);
+        }
+        state.refresh_filtered_view();
+        state.list_state.select(Some(2));
+
+        // A task sorting before the selection lands in a refresh
+        let mut t = Task::new("Aardvark", &HashMap::new());
+        t.uid = "0".to_string();
+        t.calendar_href = "/cal/".to_string();
+        state.store.add_task(t);
+        state.refresh_filtered_view();
+        assert_eq!(state.get_selected_task().unwrap().uid, "c");
+
+        // The selected task disappears: its neighbour takes its place
+        state
+            .store
+            .calendars
+            .get_mut("/cal/")
+            .unwrap()
+            .retain(|t| t.uid != "c");
+        state.refresh_filtered_view();
+        assert_eq!(state.get_selected_task().unwrap().uid, "b");
+    }
+
+    #[test]
+    fn test_collapsed_parent_hides_subtasks() {
+        let mut state = AppState::new();
+        state.active_cal_href = Some("/cal/".to_string());
+        for (uid, parent) in [("p", None), ("c", Some("p")), ("g", Some("c")), ("s", None)] {
+            let mut t = Task::new(uid, &HashMap::new());
+            t.uid = uid.to_string();
+            t.calendar_href = "/cal/".to_string();
+            t.parent_uid = parent.map(String::from);
+            state.store.add_task(t);
+        }
+        let uids = |state: &AppState| {
+            state
+                .tasks
+                .iter()
+                .map(|t| t.uid.clone())
+                .collect::<Vec<_>>()
+        };
+        state.refresh_filtered_view();
+        assert_eq!(uids(&state), ["p", "c", "g", "s"]);
+
+        state.collapsed_uids.insert("p".to_string());
+        state.refresh_filtered_view();
+        assert_eq!(uids(&state), ["p", "s"]);
+        assert_eq!(state.collapsed_counts.get("p"), Some(&2));
+
+        // Searching flattens the list, so nothing is folded
+        state.mode = InputMode::Searching;
+        state.refresh_filtered_view();
+        assert_eq!(state.tasks.len(), 4);
+        assert!(state.collapsed_counts.is_empty());
+    }
+
+    #[test]
+    fn test_inbox_href() {
+        let mut state = AppState::new();
+        state.calendars = vec![CalendarListEntry {
+            name: "Inbox".to_string(),
+            href: "/cal/inbox/".to_string(),
+            color: None,
+            read_only: false,
+        }];
+        assert_eq!(state.inbox_href(), None);
+        state.inbox_calendar = Some("Inbox".to_string());
+        assert_eq!(state.inbox_href(), Some("/cal/inbox/"));
+        state.inbox_calendar = Some("/cal/inbox/".to_string());
+        assert_eq!(state.inbox_href(), Some("/cal/inbox/"));
+        // Unknown calendars fall back to the shown one
+        state.inbox_calendar = Some("Elsewhere".to_string());
+        assert_eq!(state.inbox_href(), None);
+    }
+
     #[test]
     fn test_cursor_clamping() {
         let mut state = AppState::new();
@@ -486,4 +953,45 @@ mod tests {
 
         assert_eq!(state.cursor_position, 0);
     }
+
+    #[test]
+    fn test_typing_wide_characters() {
+        let mut state = AppState::new();
+        for c in "日本 text".chars() {
+            state.enter_char(c);
+        }
+        state.cursor_position = 2;
+        state.enter_char('語');
+        state.enter_char(' ');
+        state.enter_char('📅');
+        assert_eq!(state.input_buffer, "日本語 📅 text");
+        state.delete_char();
+        assert_eq!(state.input_buffer, "日本語  text");
+        assert_eq!(state.cursor_position, 4);
+    }
+
+    #[test]
+    fn test_done_duplicates_are_not_marked_for_deletion() {
+        let mut state = AppState::new();
+        for (summary, status) in [
+            ("Call mom", TaskStatus::NeedsAction),
+            ("Call mom", TaskStatus::NeedsAction),
+            ("Pay rent", TaskStatus::Completed),
+            ("Pay rent", TaskStatus::Completed),
+        ] {
+            let mut task = Task::new(summary, &HashMap::new());
+            task.calendar_href = "/cal/".to_string();
+            task.status = status;
+            state.store.add_task(task);
+        }
+        state.load_duplicates();
+        assert_eq!(state.duplicates.len(), 4);
+        let marked: Vec<_> = state
+            .duplicates
+            .iter()
+            .filter(|(_, t)| state.duplicate_delete.contains(&t.uid))
+            .map(|(_, t)| t.summary.as_str())
+            .collect();
+        assert_eq!(marked, ["Call mom"]);
+    }
 }
@@ -1,6 +1,10 @@
 // File: src/tui/view.rs
+use crate::cache::{STALE_AFTER, format_age};
 use crate::color_utils;
-use crate::store::UNCATEGORIZED_ID;
+use crate::model::completion;
+use crate::model::parser::format_mins;
+use crate::model::{humanize_date, week_label};
+use crate::store::{DueBucket, UNCATEGORIZED_ID, day_label, sum_estimated_duration};
 use crate::tui::action::SidebarMode;
 use crate::tui::state::{AppState, Focus, InputMode};
 
@@ -11,6 +15,7 @@ use ratatui::{
     text::{Line, Span},
     widgets::{Block, Borders, Clear, List, ListItem, Paragraph, Wrap},
 };
+use unicode_width::UnicodeWidthStr;
 
 pub fn draw(f: &mut Frame, state: &mut AppState) {
     let full_help_text = vec![
@@ -21,7 +26,7 @@ pub fn draw(f: &mut Frame, state: &mut AppState) {
                     .fg(Color::Cyan)
                     .add_modifier(Modifier::BOLD),
             ),
-            Span::raw(" Tab:Switch Focus  ?:Toggle Help  q:Quit"),
+            Span::raw(" Tab:Switch Focus  F:Hide sidebar  ?:Toggle Help  o:Edit config  q:Quit"),
         ]),
         Line::from(vec![
             Span::styled(
@@ -30,7 +35,9 @@ pub fn draw(f: &mut Frame, state: &mut AppState) {
                     .fg(Color::Yellow)
                     .add_modifier(Modifier::BOLD),
             ),
-            Span::raw(" j/k:Up/Down  PgUp/PgDn:Scroll"),
+            Span::raw(
+                " j/k:Up/Down  PgUp/PgDn:Scroll  gg/G:First/Last  [/]:Parent/First child  h:Fold subtasks",
+            ),
         ]),
         Line::from(vec![
             Span::styled(
@@ -39,11 +46,15 @@ pub fn draw(f: &mut Frame, state: &mut AppState) {
                     .fg(Color::Green)
                     .add_modifier(Modifier::BOLD),
             ),
-            Span::raw(" a:Add  e:Edit Title  E:Edit Desc  d:Delete  Space:Toggle Done"),
+            Span::raw(
+                " a:Add  e:Edit Title  E:Edit Desc  d:Delete  u:Undo  Space:Toggle Done  Tab:Complete #tag",
+            ),
         ]),
         Line::from(vec![
             Span::styled("       ", Style::default()), // Indent alignment
-            Span::raw("s:Start/Pause  x:Cancel  M:Move  r:Sync  X:Export(Local)"),
+            Span::raw(
+                "s:Start/Pause  x:Cancel  M:Move  r:Sync  X:Export(Local)  W:Archive done  J:Sync queue  I:Duplicates  v:Select many",
+            ),
         ]),
         Line::from(vec![
             Span::styled(
@@ -53,7 +64,13 @@ pub fn draw(f: &mut Frame, state: &mut AppState) {
                     .add_modifier(Modifier::BOLD),
             ),
             Span::raw(
-                " +/-:Priority  </>:Indent  y:Yank  b:Block(w/Yank)  c:Child(w/Yank)  C:NewChild",
+                " +/-:Priority  P0-P9:Set priority  f1-f9:Snooze  %:Progress mode  </>:Indent  y:Yank  V:Paste tree  Y:Copy  p:Duplicate  b:Block(w/Yank)  c:Child(w/Yank)  C:NewChild",
+            ),
+        ]),
+        Line::from(vec![
+            Span::styled("              ", Style::default()), // Indent alignment
+            Span::raw(
+                "O:Manual order  Alt+j/k:Move task (subtasks, or any in manual order)  z:Someday  Z:Hide someday  S:Skip occurrence  T:Template  D:Show UIDs",
             ),
         ]),
         Line::from(vec![
@@ -63,7 +80,9 @@ pub fn draw(f: &mut Frame, state: &mut AppState) {
                     .fg(Color::Blue)
                     .add_modifier(Modifier::BOLD),
             ),
-            Span::raw(" /:Search  H:Hide Completed  1:Cal View  2:Tag View"),
+            Span::raw(
+                " /:Search  H:Hide Completed  K:Hide Cancelled  B:Hide Blocked  A:Agenda  L:Review Completed  t:Today  U:Fold No Date  !1-!9:Priority filter  1:Cal View  2:Tag View  g1-g9:Go to Calendar  N:New Calendar  R:Rename Calendar",
+            ),
         ]),
         Line::from(vec![
             Span::styled(
@@ -93,13 +112,17 @@ pub fn draw(f: &mut Frame, state: &mut AppState) {
 
     let h_chunks = Layout::default()
         .direction(Direction::Horizontal)
-        .constraints([Constraint::Percentage(25), Constraint::Percentage(75)])
+        .constraints(if state.focus_mode {
+            [Constraint::Length(0), Constraint::Min(0)]
+        } else {
+            [Constraint::Percentage(25), Constraint::Percentage(75)]
+        })
         .split(v_chunks[0]);
 
     let main_chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([Constraint::Percentage(70), Constraint::Percentage(30)])
-        .split(h_chunks[1]);
+        .split(limit_width(h_chunks[1], state.max_width));
 
     // --- Sidebar ---
     let sidebar_style = if state.active_focus == Focus::Sidebar {
@@ -153,6 +176,14 @@ pub fn draw(f: &mut Frame, state: &mut AppState) {
                     };
 
                     spans.push(Span::styled(format!(" {}", c.name), text_style));
+                    let count = state.store.open_count(&c.href, state.hide_completed);
+                    spans.push(Span::styled(
+                        format!(" ({})", count),
+                        Style::default().fg(Color::DarkGray),
+                    ));
+                    if c.read_only {
+                        spans.push(Span::styled(" 🔒", Style::default().fg(Color::DarkGray)));
+                    }
 
                     ListItem::new(Line::from(spans))
                 })
@@ -165,6 +196,7 @@ pub fn draw(f: &mut Frame, state: &mut AppState) {
                 state.hide_fully_completed_tags,
                 &state.selected_categories,
                 &state.hidden_calendars,
+                state.sort_tags_by_count,
             );
             let items: Vec<ListItem> = all_cats
                 .iter()
@@ -180,7 +212,7 @@ pub fn draw(f: &mut Frame, state: &mut AppState) {
                             selected, count
                         )))
                     } else {
-                        let (r, g, b) = color_utils::generate_color(c);
+                        let (r, g, b) = color_utils::tag_color(c, &state.tag_colors);
                         let color =
                             Color::Rgb((r * 255.0) as u8, (g * 255.0) as u8, (b * 255.0) as u8);
                         let spans = vec![
@@ -213,15 +245,20 @@ pub fn draw(f: &mut Frame, state: &mut AppState) {
                 .add_modifier(Modifier::BOLD)
                 .bg(Color::Blue),
         );
-    f.render_stateful_widget(sidebar, h_chunks[0], &mut state.cal_state);
+    if !state.focus_mode {
+        f.render_stateful_widget(sidebar, h_chunks[0], &mut state.cal_state);
+    }
 
     // --- Task List ---
     let list_inner_width = main_chunks[0].width.saturating_sub(2) as usize;
+    // Flagged rows get a colored bar, the others keep their column so titles line up
+    let any_flagged = state.tasks.iter().any(|t| t.color.is_some());
 
     let task_items: Vec<ListItem> = state
         .tasks
         .iter()
-        .map(|t| {
+        .enumerate()
+        .map(|(idx, t)| {
             let is_blocked = state.store.is_blocked(t);
             let base_style = if is_blocked {
                 Style::default().fg(Color::DarkGray)
@@ -262,17 +299,70 @@ pub fn draw(f: &mut Frame, state: &mut AppState) {
             let inner_char = &full_symbol[1..2]; // e.g. "x"
 
             let due_str = t
-                .due
-                .map(|d| format!(" ({})", d.format("%d/%m")))
+                .due_date()
+                .map(|d| {
+                    let mut day = humanize_date(d, &state.date_format);
+                    if let Some(time) = t.due_time() {
+                        day.push_str(&time.format(" %H:%M").to_string());
+                    }
+                    if state.show_week_numbers {
+                        format!(" ({}, {})", day, week_label(d))
+                    } else {
+                        format!(" ({})", day)
+                    }
+                })
                 .unwrap_or_default();
             let dur_str = t.format_duration_short();
-            let show_indent = state.active_cal_href.is_some() && state.mode != InputMode::Searching;
+            let show_indent = state.shows_hierarchy();
             let indent = if show_indent {
                 "  ".repeat(t.depth)
             } else {
                 "".to_string()
             };
-            let recur_str = if t.rrule.is_some() { " (R)" } else { "" };
+            // Fold marker of parents, with the number of hidden subtasks when collapsed
+            let hidden_count = state.collapsed_counts.get(&t.uid).copied();
+            let (fold_mark, fold_str) = match hidden_count {
+                Some(n) => ("▸ ", format!(" (+{})", n)),
+                None if show_indent
+                    && state
+                        .tasks
+                        .get(idx + 1)
+                        .is_some_and(|next| next.depth > t.depth) =>
+                {
+                    ("▾ ", String::new())
+                }
+                None => ("", String::new()),
+            };
+            let recur_str = match (t.rrule.is_some(), t.deferred) {
+                (true, true) => " (R) (someday)",
+                (true, false) => " (R)",
+                (false, true) => " (someday)",
+                (false, false) => "",
+            };
+            let pick_mark = if state.selected_uids.contains(&t.uid) {
+                "* "
+            } else {
+                ""
+            };
+            let flag = t.color.as_deref().and_then(color_utils::flag_color);
+            let (flag_mark, flag_style) = match flag {
+                Some((r, g, b)) => {
+                    let color = Color::Rgb((r * 255.0) as u8, (g * 255.0) as u8, (b * 255.0) as u8);
+                    ("▌", Style::default().fg(color))
+                }
+                None if any_flagged => (" ", Style::default()),
+                None => ("", Style::default()),
+            };
+            let progress_str = state
+                .child_progress
+                .get(&t.uid)
+                .map(|(done, total)| format!(" [{}/{}]", done, total))
+                .unwrap_or_default();
+            let pct_str = if t.percent_complete > 0 && !t.status.is_done() {
+                format!(" {}%", t.percent_complete)
+            } else {
+                String::new()
+            };
 
             // Alias Hiding Logic
             let mut hidden_tags = std::collections::HashSet::new();
@@ -297,63 +387,167 @@ pub fn draw(f: &mut Frame, state: &mut AppState) {
                 .filter(|c| !hidden_tags.contains(*c))
                 .collect();
 
-            // Layout Calculation
-            let tags_str_len: usize = visible_cats.iter().map(|c| c.len() + 2).sum();
+            // Layout Calculation, in terminal columns: emoji and CJK take two
+            let tags_str_len: usize = visible_cats.iter().map(|c| c.width() + 2).sum();
 
             // Manually calc length because we are building spans manually
             let raw_text = format!(
-                "[{}] {}{}{}{}{}",
+                "[{}] {}{}{}{}{}{}{}{}",
                 inner_char,
                 if is_blocked { "[B] " } else { " " },
                 t.summary,
+                fold_str,
+                progress_str,
+                pct_str,
                 dur_str,
                 due_str,
                 recur_str
             );
 
             // "  " indent + brackets + inner + etc
-            let total_len = indent.len() + raw_text.len() + tags_str_len;
+            let total_len = flag_mark.width()
+                + pick_mark.width()
+                + indent.width()
+                + fold_mark.width()
+                + raw_text.width()
+                + tags_str_len;
             let padding_len = list_inner_width.saturating_sub(total_len);
             let padding = " ".repeat(padding_len);
 
             // Construct spans for colorful brackets
             let mut spans = vec![
+                Span::styled(flag_mark, flag_style),
+                Span::styled(
+                    pick_mark,
+                    Style::default()
+                        .fg(Color::Magenta)
+                        .add_modifier(Modifier::BOLD),
+                ),
                 Span::raw(indent),
+                Span::styled(fold_mark, Style::default().fg(Color::DarkGray)),
                 Span::styled("[", bracket_style),
                 Span::styled(inner_char, base_style),
                 Span::styled("]", bracket_style),
                 Span::raw(if is_blocked { " [B] " } else { " " }),
                 Span::styled(
-                    format!("{}{}{}{}", t.summary, dur_str, due_str, recur_str),
+                    format!(
+                        "{}{}{}{}{}{}{}",
+                        t.summary, fold_str, progress_str, pct_str, dur_str, due_str, recur_str
+                    ),
                     base_style,
                 ),
                 Span::raw(padding),
             ];
 
             for cat in visible_cats {
-                let (r, g, b) = color_utils::generate_color(cat);
+                let (r, g, b) = color_utils::tag_color(cat, &state.tag_colors);
                 let color = Color::Rgb((r * 255.0) as u8, (g * 255.0) as u8, (b * 255.0) as u8);
                 spans.push(Span::styled(
                     format!(" #{}", cat),
                     Style::default().fg(color),
                 ));
             }
+            if state.show_debug_ids {
+                let short_uid: String = t.uid.chars().take(8).collect();
+                spans.push(Span::styled(
+                    format!(" {} {}", short_uid, t.calendar_href),
+                    Style::default().fg(Color::DarkGray),
+                ));
+            }
+
+            // Agenda: prepend the section header to the first task of each bucket
+            if let Some((_, bucket)) = state
+                .agenda_sections
+                .iter()
+                .find(|(start, _)| *start == idx)
+            {
+                let label = if *bucket == DueBucket::NoDate {
+                    format!("── {} ({}) ──", bucket.label(), state.undated_count)
+                } else {
+                    format!("── {} ──", bucket.label())
+                };
+                let header = Line::from(Span::styled(
+                    label,
+                    Style::default()
+                        .fg(Color::Cyan)
+                        .add_modifier(Modifier::BOLD),
+                ));
+                return ListItem::new(vec![header, Line::from(spans)]);
+            }
+            // Review: same for the first task completed on each day
+            if let Some((_, day)) = state
+                .review_sections
+                .iter()
+                .find(|(start, _)| *start == idx)
+            {
+                let header = Line::from(Span::styled(
+                    format!(
+                        "── {} ──",
+                        day_label(*day, chrono::Local::now().date_naive())
+                    ),
+                    Style::default()
+                        .fg(Color::Cyan)
+                        .add_modifier(Modifier::BOLD),
+                ));
+                return ListItem::new(vec![header, Line::from(spans)]);
+            }
             ListItem::new(Line::from(spans))
         })
         .collect();
+    let mut task_items = if state.review_view && task_items.is_empty() && !state.loading {
+        vec![ListItem::new("Nothing completed recently")]
+    } else {
+        task_items
+    };
+    // Folded "No Date" section: its header stays below the list, past the selectable rows
+    if state.no_date_collapsed && state.undated_count > 0 {
+        task_items.push(ListItem::new(Line::from(Span::styled(
+            format!(
+                "── {} ({}) ▸ U to show ──",
+                DueBucket::NoDate.label(),
+                state.undated_count
+            ),
+            Style::default().fg(Color::DarkGray),
+        ))));
+    }
 
+    let list_name = match (state.review_view, state.agenda_view, state.today_view) {
+        (true, _, _) => "Completed Lately",
+        (false, true, true) => "Agenda: Today",
+        (false, true, false) => "Agenda",
+        (false, false, true) => "Today",
+        (false, false, false) => "Tasks",
+    };
     let mut title = if state.loading {
-        " Tasks (Loading...) ".to_string()
+        format!(" {} (Loading...) ", list_name)
     } else {
-        format!(" Tasks ({}) ", state.tasks.len())
+        format!(" {} ({}) ", list_name, state.tasks.len())
     };
-    if state.unsynced_changes {
-        title.push_str(" [UNSYNCED] ");
+    let estimated = sum_estimated_duration(&state.tasks);
+    if estimated > 0 {
+        title.push_str(&format!(" [Est: {}] ", format_mins(estimated)));
+    }
+    if state.manual_order && !state.agenda_view && !state.review_view {
+        title.push_str(" [MANUAL] ");
+    }
+    match state.priority_filter {
+        Some(1) => title.push_str(" [!1] "),
+        Some(max) => title.push_str(&format!(" [!1-{}] ", max)),
+        None => {}
+    }
+    if state.unsynced_count > 0 {
+        title.push_str(&format!(" [UNSYNCED: {}] ", state.unsynced_count));
+    }
+    if let Some(href) = &state.active_cal_href
+        && let Some(age) = state.store.sync_age(href)
+    {
+        let stale = if age > STALE_AFTER { "STALE, " } else { "" };
+        title.push_str(&format!(" [{}{}] ", stale, format_age(age)));
     }
 
     let main_style = if state.active_focus == Focus::Main {
         Style::default().fg(Color::Yellow)
-    } else if state.unsynced_changes {
+    } else if state.unsynced_count > 0 {
         Style::default().fg(Color::LightRed)
     } else {
         Style::default()
@@ -377,6 +571,18 @@ pub fn draw(f: &mut Frame, state: &mut AppState) {
     // Details
     let mut full_details = String::new();
     if let Some(task) = state.get_selected_task() {
+        if let Some(due) = task.format_due_as(&state.date_format) {
+            match task.due_date().filter(|_| state.show_week_numbers) {
+                Some(d) => full_details.push_str(&format!("Due: {} ({})\n\n", due, week_label(d))),
+                None => full_details.push_str(&format!("Due: {}\n\n", due)),
+            }
+        }
+        if !task.attendees.is_empty() {
+            full_details.push_str(&format!("Waiting on: {}\n\n", task.attendees.join(", ")));
+        }
+        if task.time_spent > 0 {
+            full_details.push_str(&format!("Time spent: {}\n\n", format_mins(task.time_spent)));
+        }
         if !task.description.is_empty() {
             full_details.push_str(&task.description);
             full_details.push_str("\n\n");
@@ -411,19 +617,43 @@ pub fn draw(f: &mut Frame, state: &mut AppState) {
         InputMode::Creating
         | InputMode::Editing
         | InputMode::Searching
+        | InputMode::CreatingCalendar
+        | InputMode::RenamingCalendar
         | InputMode::EditingDescription => {
             // ... Input Mode Rendering logic ...
             let (mut title_str, prefix, color) = match state.mode {
                 InputMode::Searching => (" Search ".to_string(), "/ ", Color::Green),
                 InputMode::Editing => (" Edit Title ".to_string(), "> ", Color::Magenta),
+                InputMode::CreatingCalendar => (" New Calendar ".to_string(), "+ ", Color::Cyan),
+                InputMode::RenamingCalendar => (" Rename Calendar ".to_string(), "> ", Color::Cyan),
                 InputMode::EditingDescription => {
                     (" Edit Description ".to_string(), "📝 ", Color::Blue)
                 }
                 InputMode::Creating => {
                     if state.creating_child_of.is_some() {
                         (" Create Child Task ".to_string(), "> ", Color::LightYellow)
+                    } else if let Some(inbox) = state.inbox_href()
+                        && state.active_cal_href.as_deref() != Some(inbox)
+                    {
+                        let name = state
+                            .calendars
+                            .iter()
+                            .find(|c| c.href == inbox)
+                            .map_or(inbox, |c| c.name.as_str());
+                        (
+                            format!(
+                                " Create Task → {} (Shift+Enter: here  Ctrl+S: start) ",
+                                name
+                            ),
+                            "> ",
+                            Color::Yellow,
+                        )
                     } else {
-                        (" Create Task ".to_string(), "> ", Color::Yellow)
+                        (
+                            " Create Task (Ctrl+S: start now) ".to_string(),
+                            "> ",
+                            Color::Yellow,
+                        )
                     }
                 }
                 _ => (" Create Task ".to_string(), "> ", Color::Yellow),
@@ -439,6 +669,25 @@ pub fn draw(f: &mut Frame, state: &mut AppState) {
                 title_str.push_str(" [Enter to jump to tag] ");
             }
 
+            if matches!(
+                state.mode,
+                InputMode::Creating | InputMode::Editing | InputMode::Searching
+            ) {
+                let suggestions = completion::suggestions(
+                    &state.input_buffer,
+                    state.cursor_position,
+                    &state.tag_candidates(),
+                );
+                if !suggestions.is_empty() {
+                    let shown: Vec<String> = suggestions
+                        .iter()
+                        .take(5)
+                        .map(|s| format!("#{}", s))
+                        .collect();
+                    title_str.push_str(&format!(" Tab: {} ", shown.join(" ")));
+                }
+            }
+
             let input_text = format!("{}{}", prefix, state.input_buffer);
             let input = Paragraph::new(input_text)
                 .style(Style::default().fg(color))
@@ -447,8 +696,12 @@ pub fn draw(f: &mut Frame, state: &mut AppState) {
             f.render_widget(input, footer_area);
 
             // Cursor rendering
-            let cursor_x =
-                footer_area.x + 1 + prefix.chars().count() as u16 + state.cursor_position as u16;
+            let typed: String = state
+                .input_buffer
+                .chars()
+                .take(state.cursor_position)
+                .collect();
+            let cursor_x = footer_area.x + 1 + (prefix.width() + typed.width()) as u16;
             f.set_cursor_position((
                 cursor_x.min(footer_area.x + footer_area.width - 2),
                 footer_area.y + 1,
@@ -461,22 +714,64 @@ pub fn draw(f: &mut Frame, state: &mut AppState) {
                     .wrap(Wrap { trim: false });
                 f.render_widget(p, footer_area);
             } else {
+                let mut status_block = Block::default()
+                    .borders(Borders::LEFT | Borders::TOP | Borders::BOTTOM)
+                    .title(if state.visual_select {
+                        format!(" VISUAL: {} selected ", state.selected_uids.len())
+                    } else {
+                        " Status ".to_string()
+                    });
+                if let Some(timer) = &state.timer {
+                    let clock = timer.display(chrono::Utc::now());
+                    status_block = status_block.title(
+                        Line::from(Span::styled(
+                            format!(" \u{23f1} {} {} ", timer.summary, clock),
+                            Style::default().fg(Color::Green),
+                        ))
+                        .right_aligned(),
+                    );
+                }
                 let status = Paragraph::new(state.message.clone())
                     .style(Style::default().fg(Color::Cyan))
-                    .block(
-                        Block::default()
-                            .borders(Borders::LEFT | Borders::TOP | Borders::BOTTOM)
-                            .title(" Status "),
-                    );
+                    .block(status_block);
                 let help_str = match state.active_focus {
-                    Focus::Sidebar => "Ret:Select Space:Vis *:All Tab:Tasks".to_string(),
+                    _ if state.mode == InputMode::ConfirmDelete => {
+                        "y:Delete n/Esc:Cancel".to_string()
+                    }
+                    _ if state.mode == InputMode::ConfirmQuit => {
+                        "y:Sync & quit n:Quit Esc:Stay".to_string()
+                    }
+                    Focus::Main if state.visual_select => {
+                        "Spc:Pick Ret:Done d:Del +/-:Prio M:Move Esc:Exit".to_string()
+                    }
+                    Focus::Sidebar => "Ret:Select Space:Vis *:All R:Rename Tab:Tasks".to_string(),
                     Focus::Main => "a:Add e:Edit Spc:Done d:Del /:Find".to_string(),
                 };
-                let help = Paragraph::new(help_str).alignment(Alignment::Right).block(
-                    Block::default()
-                        .borders(Borders::RIGHT | Borders::TOP | Borders::BOTTOM)
-                        .title(" Actions "),
-                );
+                let mut help_block = Block::default()
+                    .borders(Borders::RIGHT | Borders::TOP | Borders::BOTTOM)
+                    .title(" Actions ");
+                if state.show_clock {
+                    let now = chrono::Utc::now();
+                    let mut clock = format!(" {} ", chrono::Local::now().format("%H:%M"));
+                    if let Some(task) = state.store.next_due_within(now, chrono::Duration::hours(1))
+                        && let Some(due) = task.due
+                    {
+                        let mins = (due - now).num_minutes().max(1) as u32;
+                        clock = format!(
+                            " \u{23f0} {} in {} |{}",
+                            task.summary,
+                            format_mins(mins),
+                            clock
+                        );
+                    }
+                    help_block = help_block.title(
+                        Line::from(Span::styled(clock, Style::default().fg(Color::Yellow)))
+                            .right_aligned(),
+                    );
+                }
+                let help = Paragraph::new(help_str)
+                    .alignment(Alignment::Right)
+                    .block(help_block);
 
                 let chunks = Layout::default()
                     .direction(Direction::Horizontal)
@@ -502,6 +797,188 @@ pub fn draw(f: &mut Frame, state: &mut AppState) {
         f.render_widget(Clear, area);
         f.render_stateful_widget(popup, area, &mut state.move_selection_state);
     }
+
+    if state.mode == InputMode::Archiving {
+        let area = centered_rect(60, 50, f.area());
+        let items: Vec<ListItem> = state
+            .export_targets
+            .iter()
+            .map(|c| ListItem::new(c.name.as_str()))
+            .collect();
+        let popup = List::new(items)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(" Archive completed tasks to "),
+            )
+            .highlight_style(Style::default().bg(Color::Blue));
+        f.render_widget(Clear, area);
+        f.render_stateful_widget(popup, area, &mut state.export_selection_state);
+    }
+
+    if state.mode == InputMode::PickingTemplate {
+        let area = centered_rect(60, 50, f.area());
+        let items: Vec<ListItem> = state
+            .template_names
+            .iter()
+            .map(|name| ListItem::new(name.as_str()))
+            .collect();
+        let popup = List::new(items)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(" Create from template "),
+            )
+            .highlight_style(Style::default().bg(Color::Blue));
+        f.render_widget(Clear, area);
+        f.render_stateful_widget(popup, area, &mut state.template_state);
+    }
+
+    if state.mode == InputMode::Journal {
+        let area = centered_rect(70, 60, f.area());
+        let items: Vec<ListItem> = if state.journal_entries.is_empty() {
+            vec![ListItem::new("Nothing waiting to be synced.")]
+        } else {
+            state
+                .journal_entries
+                .iter()
+                .map(|s| ListItem::new(s.as_str()))
+                .collect()
+        };
+        let mut block = Block::default()
+            .borders(Borders::ALL)
+            .title(" Sync queue (s:Sync now  d:Drop  C:Clear all  Esc:Close) ");
+        if let Some(caps) = &state.server_capabilities {
+            block = block.title_bottom(format!(" Server: {} ", caps));
+        }
+        let popup = List::new(items)
+            .block(block)
+            .highlight_style(Style::default().bg(Color::Blue));
+        f.render_widget(Clear, area);
+        f.render_stateful_widget(popup, area, &mut state.journal_state);
+    }
+
+    // Duplicates: a header above the first task of each group, the kept one first
+    if state.mode == InputMode::Duplicates {
+        let area = centered_rect(70, 60, f.area());
+        let items: Vec<ListItem> = state
+            .duplicates
+            .iter()
+            .enumerate()
+            .map(|(i, (group, task))| {
+                let delete = state.duplicate_delete.contains(&task.uid);
+                let (mark, style) = if delete {
+                    ("[delete]", Style::default().fg(Color::Red))
+                } else {
+                    ("[keep]  ", Style::default().fg(Color::Green))
+                };
+                let mut lines = Vec::new();
+                if i == 0 || state.duplicates[i - 1].0 != *group {
+                    let cal = state
+                        .calendars
+                        .iter()
+                        .find(|c| c.href == task.calendar_href)
+                        .map_or(task.calendar_href.as_str(), |c| c.name.as_str());
+                    lines.push(Line::from(Span::styled(
+                        format!("── {} ({}) ──", task.summary, cal),
+                        Style::default().add_modifier(Modifier::BOLD),
+                    )));
+                }
+                let mut details = Vec::new();
+                if task.status.is_done() {
+                    details.push("done".to_string());
+                }
+                if let Some(due) = task.format_due_as(&state.date_format) {
+                    details.push(format!("due {}", due));
+                }
+                if let Some(modified) = task.last_modified {
+                    let modified = modified
+                        .with_timezone(&chrono::Local)
+                        .format(&state.date_format);
+                    details.push(format!("modified {}", modified));
+                }
+                if !task.description.is_empty() {
+                    details.push("has notes".to_string());
+                }
+                lines.push(Line::from(vec![
+                    Span::styled(format!("  {} ", mark), style),
+                    Span::raw(format!("{}  ", task.summary)),
+                    Span::styled(details.join(", "), Style::default().fg(Color::DarkGray)),
+                ]));
+                ListItem::new(lines)
+            })
+            .collect();
+        let title = format!(
+            " Duplicates: {} to delete (Space:Keep/Delete  Enter:Apply  Esc:Cancel) ",
+            state.duplicate_delete.len()
+        );
+        let popup = List::new(items)
+            .block(Block::default().borders(Borders::ALL).title(title))
+            .highlight_style(Style::default().bg(Color::Blue));
+        f.render_widget(Clear, area);
+        f.render_stateful_widget(popup, area, &mut state.duplicate_state);
+    }
+
+    // Sync conflict: one entry per diverged field, showing which side will win
+    if state.mode == InputMode::Resolving
+        && let Some(conflict) = state.conflicts.first()
+    {
+        let area = centered_rect(80, 60, f.area());
+        let items: Vec<ListItem> = conflict
+            .fields
+            .iter()
+            .map(|d| {
+                let keep_local = state.conflict_keep_local.contains(&d.field);
+                let (local_style, server_style) = if keep_local {
+                    (
+                        Style::default().fg(Color::Green),
+                        Style::default().fg(Color::DarkGray),
+                    )
+                } else {
+                    (
+                        Style::default().fg(Color::DarkGray),
+                        Style::default().fg(Color::Green),
+                    )
+                };
+                ListItem::new(vec![
+                    Line::from(Span::styled(
+                        format!(
+                            "{} ({})",
+                            d.field,
+                            if keep_local { "local" } else { "server" }
+                        ),
+                        Style::default().add_modifier(Modifier::BOLD),
+                    )),
+                    Line::from(Span::styled(format!("  local:  {}", d.local), local_style)),
+                    Line::from(Span::styled(
+                        format!("  server: {}", d.server),
+                        server_style,
+                    )),
+                ])
+            })
+            .collect();
+        let title = format!(
+            " Conflict: {} (Space:Pick side  Enter:Apply  Esc:Keep both) ",
+            conflict.local.summary
+        );
+        let popup = List::new(items)
+            .block(Block::default().borders(Borders::ALL).title(title))
+            .highlight_style(Style::default().bg(Color::Blue));
+        f.render_widget(Clear, area);
+        f.render_stateful_widget(popup, area, &mut state.conflict_state);
+    }
+}
+
+/// Narrows `area` to `max_width` columns, centered, when it is wider.
+fn limit_width(area: Rect, max_width: Option<u16>) -> Rect {
+    match max_width {
+        Some(width) if width > 0 && area.width > width => Rect {
+            x: area.x + (area.width - width) / 2,
+            width,
+            ..area
+        },
+        _ => area,
+    }
 }
 
 fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
@@ -0,0 +1,39 @@
+// File: ./tests/bearer_auth.rs
+use cfait::client::RustyClient;
+use cfait::client::core::Auth;
+use mockito::{Matcher, Server};
+use std::time::Duration;
+
+#[tokio::test]
+async fn test_bearer_token_is_sent() {
+    let mut server = Server::new_async().await;
+    let url = server.url();
+    let mock_propfind = server
+        .mock("PROPFIND", Matcher::Any)
+        .match_header("authorization", "Bearer secret-token")
+        .with_status(401)
+        .create_async()
+        .await;
+
+    let client = RustyClient::new_with_auth(
+        &url,
+        Auth::Bearer("secret-token"),
+        false,
+        Duration::from_secs(5),
+    )
+    .unwrap();
+    let _ = client.get_calendars().await;
+
+    mock_propfind.assert_async().await;
+}
+
+#[test]
+fn test_token_with_line_break_is_rejected() {
+    let result = RustyClient::new_with_auth(
+        "https://dav.example.com/",
+        Auth::Bearer("secret-token\n"),
+        false,
+        Duration::from_secs(5),
+    );
+    assert!(result.unwrap_err().starts_with("Invalid token"));
+}
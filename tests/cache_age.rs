@@ -0,0 +1,34 @@
+// File: ./tests/cache_age.rs
+use cfait::cache::Cache;
+use cfait::model::Task;
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+
+#[test]
+fn test_cache_sync_age() {
+    // 0. Setup Isolation
+    let temp_dir = env::temp_dir().join(format!("cfait_test_cache_age_{}", std::process::id()));
+    let _ = fs::create_dir_all(&temp_dir);
+    unsafe {
+        env::set_var("CFAIT_TEST_DIR", &temp_dir);
+    }
+
+    let tasks = vec![Task::new("Cached", &HashMap::new())];
+
+    // Files from older versions (or local-only saves) have no sync time
+    Cache::save("/cal/", &tasks, None).unwrap();
+    assert!(Cache::age("/cal/").is_none());
+
+    Cache::save_synced("/cal/", &tasks, Some("token".to_string())).unwrap();
+    let synced_at = Cache::synced_at("/cal/").expect("sync time recorded");
+    assert!(Cache::age("/cal/").unwrap().as_secs() < 60);
+
+    // Local edits keep the time of the last server sync
+    Cache::save("/cal/", &tasks, Some("token".to_string())).unwrap();
+    assert_eq!(Cache::synced_at("/cal/"), Some(synced_at));
+
+    assert!(Cache::age("/missing/").is_none());
+
+    let _ = fs::remove_dir_all(&temp_dir);
+}
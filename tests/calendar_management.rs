@@ -0,0 +1,161 @@
+// File: ./tests/calendar_management.rs
+use cfait::client::RustyClient;
+use mockito::{Matcher, Mock, ServerGuard};
+
+async fn mock_discovery(server: &mut ServerGuard) -> (Mock, Mock) {
+    let principal = server
+        .mock("PROPFIND", "/")
+        .match_body(Matcher::Regex("current-user-principal".to_string()))
+        .with_status(207)
+        .with_body(
+            r#"<?xml version="1.0"?>
+<d:multistatus xmlns:d="DAV:">
+  <d:response><d:href>/</d:href><d:propstat><d:prop>
+    <d:current-user-principal><d:href>/principals/user/</d:href></d:current-user-principal>
+  </d:prop><d:status>HTTP/1.1 200 OK</d:status></d:propstat></d:response>
+</d:multistatus>"#,
+        )
+        .create_async()
+        .await;
+    let home = server
+        .mock("PROPFIND", "/principals/user/")
+        .with_status(207)
+        .with_body(
+            r#"<?xml version="1.0"?>
+<d:multistatus xmlns:d="DAV:" xmlns:c="urn:ietf:params:xml:ns:caldav">
+  <d:response><d:href>/principals/user/</d:href><d:propstat><d:prop>
+    <c:calendar-home-set><d:href>/calendars/user/</d:href></c:calendar-home-set>
+  </d:prop><d:status>HTTP/1.1 200 OK</d:status></d:propstat></d:response>
+</d:multistatus>"#,
+        )
+        .create_async()
+        .await;
+    (principal, home)
+}
+
+#[tokio::test]
+async fn test_create_calendar_sends_mkcalendar() {
+    let mut server = mockito::Server::new_async().await;
+    let _discovery = mock_discovery(&mut server).await;
+    let mkcalendar = server
+        .mock(
+            "MKCALENDAR",
+            Matcher::Regex(r"^/calendars/user/[0-9a-f-]+/$".to_string()),
+        )
+        .match_body(Matcher::AllOf(vec![
+            Matcher::Regex("<D:displayname>Work &amp; Home</D:displayname>".to_string()),
+            Matcher::Regex(r#"<C:comp name="VTODO"/>"#.to_string()),
+        ]))
+        .with_status(201)
+        .create_async()
+        .await;
+
+    let client = RustyClient::new(&server.url(), "u", "p", false).unwrap();
+    let cal = client.create_calendar("Work & Home").await.unwrap();
+
+    mkcalendar.assert_async().await;
+    assert_eq!(cal.name, "Work & Home");
+    assert!(cal.href.starts_with("/calendars/user/"));
+    assert!(cal.href.ends_with('/'));
+}
+
+#[tokio::test]
+async fn test_create_calendar_falls_back_to_mkcol() {
+    let mut server = mockito::Server::new_async().await;
+    let _discovery = mock_discovery(&mut server).await;
+    let _mkcalendar = server
+        .mock("MKCALENDAR", Matcher::Any)
+        .with_status(405)
+        .create_async()
+        .await;
+    let mkcol = server
+        .mock("MKCOL", Matcher::Any)
+        .match_body(Matcher::Regex("VTODO".to_string()))
+        .with_status(201)
+        .create_async()
+        .await;
+
+    let client = RustyClient::new(&server.url(), "u", "p", false).unwrap();
+    assert!(client.create_calendar("Chores").await.is_ok());
+    mkcol.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_create_calendar_reports_rejection() {
+    let mut server = mockito::Server::new_async().await;
+    let _discovery = mock_discovery(&mut server).await;
+    let _mkcalendar = server
+        .mock("MKCALENDAR", Matcher::Any)
+        .with_status(405)
+        .create_async()
+        .await;
+    let _mkcol = server
+        .mock("MKCOL", Matcher::Any)
+        .with_status(405)
+        .create_async()
+        .await;
+
+    let client = RustyClient::new(&server.url(), "u", "p", false).unwrap();
+    let err = client.create_calendar("Chores").await.unwrap_err();
+    assert!(
+        err.contains("does not support creating calendars"),
+        "{}",
+        err
+    );
+
+    let demo = RustyClient::new("demo://", "", "", false).unwrap();
+    assert!(demo.create_calendar("Chores").await.is_err());
+}
+
+#[tokio::test]
+async fn test_rename_calendar_sends_proppatch() {
+    let temp_dir = std::env::temp_dir().join(format!("cfait_rename_{}", std::process::id()));
+    let _ = std::fs::create_dir_all(&temp_dir);
+    unsafe {
+        std::env::set_var("CFAIT_TEST_DIR", &temp_dir);
+    }
+
+    let mut server = mockito::Server::new_async().await;
+    let proppatch = server
+        .mock("PROPPATCH", "/calendars/user/work/")
+        .match_body(Matcher::Regex("Errands</".to_string()))
+        .with_status(207)
+        .with_body(
+            r#"<?xml version="1.0"?>
+<d:multistatus xmlns:d="DAV:">
+  <d:response><d:href>/calendars/user/work/</d:href><d:propstat>
+    <d:prop><d:displayname/></d:prop><d:status>HTTP/1.1 200 OK</d:status>
+  </d:propstat></d:response>
+</d:multistatus>"#,
+        )
+        .create_async()
+        .await;
+    let _shared = server
+        .mock("PROPPATCH", "/calendars/other/shared/")
+        .with_status(207)
+        .with_body(
+            r#"<?xml version="1.0"?>
+<d:multistatus xmlns:d="DAV:">
+  <d:response><d:href>/calendars/other/shared/</d:href><d:propstat>
+    <d:prop><d:displayname/></d:prop><d:status>HTTP/1.1 403 Forbidden</d:status>
+  </d:propstat></d:response>
+</d:multistatus>"#,
+        )
+        .create_async()
+        .await;
+
+    let client = RustyClient::new(&server.url(), "u", "p", false).unwrap();
+    client
+        .rename_calendar("/calendars/user/work/", "Errands")
+        .await
+        .unwrap();
+    proppatch.assert_async().await;
+
+    let err = client
+        .rename_calendar("/calendars/other/shared/", "Mine")
+        .await
+        .unwrap_err();
+    assert!(err.contains("Permission denied"), "{}", err);
+
+    let _ = std::fs::remove_dir_all(&temp_dir);
+}
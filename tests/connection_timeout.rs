@@ -0,0 +1,25 @@
+// File: ./tests/connection_timeout.rs
+use cfait::client::RustyClient;
+use mockito::{Matcher, Server};
+use std::time::Duration;
+
+#[tokio::test]
+async fn test_slow_server_times_out() {
+    let mut server = Server::new_async().await;
+    let url = server.url();
+    let _mock_slow = server
+        .mock("PROPFIND", Matcher::Any)
+        .with_status(207)
+        .with_body_from_request(|_| {
+            std::thread::sleep(Duration::from_secs(2));
+            Vec::new()
+        })
+        .create_async()
+        .await;
+
+    let client =
+        RustyClient::new_with_timeout(&url, "u", "p", false, Duration::from_millis(300)).unwrap();
+    let err = client.get_calendars().await.unwrap_err();
+    assert_eq!(err, "Connection timed out");
+    assert!(!err.contains("Certificate"));
+}
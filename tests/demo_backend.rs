@@ -0,0 +1,40 @@
+// File: ./tests/demo_backend.rs
+use cfait::client::RustyClient;
+use cfait::journal::Journal;
+use cfait::model::Task;
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+
+#[tokio::test]
+async fn test_demo_backend_is_in_memory() {
+    // 0. Setup Isolation
+    let temp_dir = env::temp_dir().join(format!("cfait_test_demo_{}", std::process::id()));
+    let _ = fs::create_dir_all(&temp_dir);
+    unsafe {
+        env::set_var("CFAIT_TEST_DIR", &temp_dir);
+    }
+
+    let client = RustyClient::new("demo://", "", "", false).unwrap();
+    assert!(client.is_demo());
+
+    let calendars = client.get_calendars().await.unwrap();
+    assert!(!calendars.is_empty());
+    let href = calendars[0].href.clone();
+    let seeded = client.get_tasks(&href).await.unwrap();
+    assert!(!seeded.is_empty());
+
+    let mut task = Task::new("Try the demo", &HashMap::new());
+    task.calendar_href = href.clone();
+    client.create_task(&mut task).await.unwrap();
+    let tasks = client.get_tasks(&href).await.unwrap();
+    assert_eq!(tasks.len(), seeded.len() + 1);
+
+    client.delete_task(&task).await.unwrap();
+    assert_eq!(client.get_tasks(&href).await.unwrap().len(), seeded.len());
+
+    // Nothing goes through the journal
+    assert!(Journal::load().queue.is_empty());
+
+    let _ = fs::remove_dir_all(&temp_dir);
+}
@@ -0,0 +1,160 @@
+// File: ./tests/incremental_sync.rs
+use cfait::cache::Cache;
+use cfait::client::RustyClient;
+use cfait::model::Task;
+use mockito::{Matcher, Server, ServerGuard};
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+
+fn cached(summary: &str, name: &str) -> Task {
+    let mut task = Task::new(summary, &HashMap::new());
+    task.uid = name.to_string();
+    task.href = format!("/cal/{}.ics", name);
+    task.etag = "\"1\"".to_string();
+    task.calendar_href = "/cal/".to_string();
+    task
+}
+
+fn vtodo(uid: &str, summary: &str, etag: &str) -> String {
+    format!(
+        "<d:response><d:href>/cal/{uid}.ics</d:href><d:propstat><d:prop>\
+        <d:getetag>\"{etag}\"</d:getetag><c:calendar-data>BEGIN:VCALENDAR\r\nVERSION:2.0\r\n\
+        BEGIN:VTODO\r\nUID:{uid}\r\nSUMMARY:{summary}\r\nEND:VTODO\r\nEND:VCALENDAR\r\n\
+        </c:calendar-data></d:prop><d:status>HTTP/1.1 200 OK</d:status></d:propstat>\
+        </d:response>"
+    )
+}
+
+/// A server whose calendar reports a sync-token, so the client tries the REPORT.
+async fn server_with_sync_token() -> ServerGuard {
+    let mut server = Server::new_async().await;
+    server
+        .mock("OPTIONS", "/cal/")
+        .with_status(200)
+        .with_header("Allow", "OPTIONS, GET, PUT, DELETE, PROPFIND, REPORT, MOVE")
+        .create_async()
+        .await;
+    // Both the capability probe and the sync-token lookup
+    server
+        .mock("PROPFIND", "/cal/")
+        .match_header("depth", "0")
+        .with_status(207)
+        .with_body(
+            r#"<d:multistatus xmlns:d="DAV:"><d:response><d:href>/cal/</d:href>
+            <d:propstat><d:prop><d:sync-token>tok2</d:sync-token></d:prop>
+            <d:status>HTTP/1.1 200 OK</d:status></d:propstat>
+            </d:response></d:multistatus>"#,
+        )
+        .create_async()
+        .await;
+    server
+}
+
+fn summaries(mut tasks: Vec<Task>) -> Vec<String> {
+    tasks.sort_by(|a, b| a.uid.cmp(&b.uid));
+    tasks.into_iter().map(|t| t.summary).collect()
+}
+
+#[tokio::test]
+async fn test_sync_collection_fetches_only_changes() {
+    let temp_dir = env::temp_dir().join(format!("cfait_test_incremental_{}", std::process::id()));
+    let _ = fs::create_dir_all(&temp_dir);
+    unsafe {
+        env::set_var("CFAIT_TEST_DIR", &temp_dir);
+    }
+    let cache = vec![
+        cached("Old a", "a"),
+        cached("Deleted b", "b"),
+        cached("Same d", "d"),
+    ];
+    Cache::save("/cal/", &cache, Some("tok1".to_string())).unwrap();
+
+    let mut server = server_with_sync_token().await;
+    let list = server
+        .mock("PROPFIND", "/cal/")
+        .match_header("depth", "1")
+        .expect(0)
+        .create_async()
+        .await;
+    let report = server
+        .mock("REPORT", "/cal/")
+        .match_body(Matcher::Regex("sync-collection".into()))
+        .match_body(Matcher::Regex("<D:sync-token>tok1</D:sync-token>".into()))
+        .with_status(207)
+        .with_body(
+            r#"<d:multistatus xmlns:d="DAV:">
+            <d:response><d:href>/cal/a.ics</d:href><d:propstat><d:prop>
+            <d:getetag>"2"</d:getetag></d:prop><d:status>HTTP/1.1 200 OK</d:status></d:propstat>
+            </d:response>
+            <d:response><d:href>/cal/b.ics</d:href><d:status>HTTP/1.1 404 Not Found</d:status>
+            </d:response>
+            <d:response><d:href>/cal/c.ics</d:href><d:propstat><d:prop>
+            <d:getetag>"1"</d:getetag></d:prop><d:status>HTTP/1.1 200 OK</d:status></d:propstat>
+            </d:response>
+            <d:response><d:href>/cal/d.ics</d:href><d:propstat><d:prop>
+            <d:getetag>"1"</d:getetag></d:prop><d:status>HTTP/1.1 200 OK</d:status></d:propstat>
+            </d:response>
+            <d:sync-token>tok3</d:sync-token>
+            </d:multistatus>"#,
+        )
+        .expect(1)
+        .create_async()
+        .await;
+    let multiget = server
+        .mock("REPORT", "/cal/")
+        .match_body(Matcher::Regex("calendar-multiget".into()))
+        .with_status(207)
+        .with_body(format!(
+            "<d:multistatus xmlns:d=\"DAV:\" xmlns:c=\"urn:ietf:params:xml:ns:caldav\">{}{}\
+            </d:multistatus>",
+            vtodo("a", "New a", "2"),
+            vtodo("c", "Added c", "1")
+        ))
+        .expect(1)
+        .create_async()
+        .await;
+
+    let client = RustyClient::new(&server.url(), "user", "pass", false).unwrap();
+    let tasks = client.get_tasks("/cal/").await.unwrap();
+    assert_eq!(summaries(tasks), ["New a", "Added c", "Same d"]);
+    list.assert_async().await;
+    report.assert_async().await;
+    multiget.assert_async().await;
+    assert_eq!(Cache::load("/cal/").unwrap().1.as_deref(), Some("tok3"));
+
+    // A token the server no longer accepts falls back to the full listing
+    Cache::save("/cal/", &cache, Some("expired".to_string())).unwrap();
+    let mut server = server_with_sync_token().await;
+    server
+        .mock("REPORT", "/cal/")
+        .match_body(Matcher::Regex("sync-collection".into()))
+        .with_status(403)
+        .create_async()
+        .await;
+    let list = server
+        .mock("PROPFIND", "/cal/")
+        .match_header("depth", "1")
+        .with_status(207)
+        .with_body(
+            r#"<d:multistatus xmlns:d="DAV:">
+            <d:response><d:href>/cal/d.ics</d:href><d:propstat><d:prop>
+            <d:getetag>"1"</d:getetag><d:getcontenttype>text/calendar</d:getcontenttype>
+            <d:resourcetype/></d:prop><d:status>HTTP/1.1 200 OK</d:status></d:propstat>
+            </d:response></d:multistatus>"#,
+        )
+        .expect(1)
+        .create_async()
+        .await;
+
+    let client = RustyClient::new(&server.url(), "user", "pass", false).unwrap();
+    let tasks = client.get_tasks("/cal/").await.unwrap();
+    assert_eq!(summaries(tasks), ["Same d"]);
+    list.assert_async().await;
+    assert_eq!(Cache::load("/cal/").unwrap().1.as_deref(), Some("tok2"));
+
+    unsafe {
+        env::remove_var("CFAIT_TEST_DIR");
+    }
+    let _ = fs::remove_dir_all(&temp_dir);
+}
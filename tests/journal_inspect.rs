@@ -0,0 +1,41 @@
+// File: ./tests/journal_inspect.rs
+use cfait::journal::{Action, Journal};
+use cfait::model::Task;
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+
+#[test]
+fn test_journal_inspect_and_clear() {
+    // 0. Setup Isolation
+    let temp_dir = env::temp_dir().join(format!("cfait_test_inspect_{}", std::process::id()));
+    let _ = fs::create_dir_all(&temp_dir);
+    unsafe {
+        env::set_var("CFAIT_TEST_DIR", &temp_dir);
+    }
+    let _ = Journal::clear_queue();
+
+    let aliases = HashMap::new();
+    Journal::push(Action::Create(Task::new("First", &aliases))).unwrap();
+    Journal::push(Action::Delete(Task::new("Second", &aliases))).unwrap();
+    Journal::push(Action::Move(
+        Task::new("Third", &aliases),
+        "/work/".to_string(),
+    ))
+    .unwrap();
+
+    assert_eq!(
+        Journal::queue_summary(),
+        vec!["Create: First", "Delete: Second", "Move: Third -> /work/"]
+    );
+
+    let dropped = Journal::remove_at(1).unwrap();
+    assert!(matches!(dropped, Some(Action::Delete(t)) if t.summary == "Second"));
+    assert!(Journal::remove_at(5).unwrap().is_none());
+    assert_eq!(Journal::load().queue.len(), 2);
+
+    Journal::clear_queue().unwrap();
+    assert!(Journal::load().is_empty());
+
+    let _ = fs::remove_dir_all(&temp_dir);
+}
@@ -0,0 +1,59 @@
+// File: ./tests/local_calendars.rs
+use cfait::client::RustyClient;
+use cfait::model::Task;
+use cfait::storage::{LOCAL_CALENDAR_HREF, LocalStorage};
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+
+#[tokio::test]
+async fn test_local_calendars_are_kept_apart() {
+    let temp_dir = env::temp_dir().join(format!("cfait_test_local_cals_{}", std::process::id()));
+    let _ = fs::create_dir_all(&temp_dir);
+    unsafe {
+        env::set_var("CFAIT_TEST_DIR", &temp_dir);
+    }
+
+    let client = RustyClient::new("", "", "", false).unwrap();
+    let groceries = client.create_calendar("Groceries").await.unwrap();
+    assert!(LocalStorage::is_local(&groceries.href));
+    assert_ne!(groceries.href, LOCAL_CALENDAR_HREF);
+
+    let aliases = HashMap::new();
+    let mut milk = Task::new("Milk", &aliases);
+    milk.calendar_href = groceries.href.clone();
+    client.create_task(&mut milk).await.unwrap();
+    let mut report = Task::new("Report", &aliases);
+    report.calendar_href = LOCAL_CALENDAR_HREF.to_string();
+    client.create_task(&mut report).await.unwrap();
+
+    let summaries = |tasks: Vec<Task>| tasks.into_iter().map(|t| t.summary).collect::<Vec<_>>();
+    assert_eq!(
+        summaries(LocalStorage::load(&groceries.href).unwrap()),
+        ["Milk"]
+    );
+    assert_eq!(
+        summaries(LocalStorage::load(LOCAL_CALENDAR_HREF).unwrap()),
+        ["Report"]
+    );
+
+    client
+        .rename_calendar(&groceries.href, "Shopping")
+        .await
+        .unwrap();
+    let names: Vec<_> = LocalStorage::calendars()
+        .into_iter()
+        .map(|c| c.name)
+        .collect();
+    assert_eq!(names, ["Local", "Shopping"]);
+
+    // Moving between local calendars rewrites both files
+    client.move_task(&milk, LOCAL_CALENDAR_HREF).await.unwrap();
+    assert!(LocalStorage::load(&groceries.href).unwrap().is_empty());
+    assert_eq!(LocalStorage::load(LOCAL_CALENDAR_HREF).unwrap().len(), 2);
+
+    unsafe {
+        env::remove_var("CFAIT_TEST_DIR");
+    }
+    let _ = fs::remove_dir_all(&temp_dir);
+}
@@ -0,0 +1,93 @@
+// File: ./tests/malformed_ics.rs
+use cfait::client::RustyClient;
+use cfait::model::CalendarListEntry;
+use mockito::Server;
+use std::env;
+use std::fs;
+
+#[tokio::test]
+async fn test_fetch_reports_unparsable_items() {
+    let temp_dir = env::temp_dir().join(format!("cfait_test_malformed_{}", std::process::id()));
+    let _ = fs::create_dir_all(&temp_dir);
+    unsafe {
+        env::set_var("CFAIT_TEST_DIR", &temp_dir);
+    }
+
+    let mut server = Server::new_async().await;
+    let url = server.url();
+    let _options = server
+        .mock("OPTIONS", "/cal/")
+        .with_status(200)
+        .with_header("Allow", "OPTIONS, GET, PUT, DELETE, PROPFIND, REPORT, MOVE")
+        .create_async()
+        .await;
+    // Capability probe: neither ctag nor sync-token, so the calendar is listed in full
+    let _probe = server
+        .mock("PROPFIND", "/cal/")
+        .match_header("depth", "0")
+        .with_status(207)
+        .with_body(
+            r#"<d:multistatus xmlns:d="DAV:"><d:response><d:href>/cal/</d:href>
+            <d:propstat><d:prop/><d:status>HTTP/1.1 200 OK</d:status></d:propstat>
+            </d:response></d:multistatus>"#,
+        )
+        .create_async()
+        .await;
+    let _list = server
+        .mock("PROPFIND", "/cal/")
+        .match_header("depth", "1")
+        .with_status(207)
+        .with_body(
+            r#"<d:multistatus xmlns:d="DAV:">
+            <d:response><d:href>/cal/good.ics</d:href><d:propstat><d:prop>
+            <d:getetag>"1"</d:getetag><d:getcontenttype>text/calendar</d:getcontenttype>
+            <d:resourcetype/></d:prop><d:status>HTTP/1.1 200 OK</d:status></d:propstat></d:response>
+            <d:response><d:href>/cal/bad.ics</d:href><d:propstat><d:prop>
+            <d:getetag>"2"</d:getetag><d:getcontenttype>text/calendar</d:getcontenttype>
+            <d:resourcetype/></d:prop><d:status>HTTP/1.1 200 OK</d:status></d:propstat></d:response>
+            </d:multistatus>"#,
+        )
+        .create_async()
+        .await;
+    let _multiget = server
+        .mock("REPORT", "/cal/")
+        .with_status(207)
+        .with_body(
+            "<d:multistatus xmlns:d=\"DAV:\" xmlns:c=\"urn:ietf:params:xml:ns:caldav\">\
+            <d:response><d:href>/cal/good.ics</d:href><d:propstat><d:prop>\
+            <d:getetag>\"1\"</d:getetag><c:calendar-data>BEGIN:VCALENDAR\r\nVERSION:2.0\r\n\
+            BEGIN:VTODO\r\nUID:good\r\nSUMMARY:Readable\r\nEND:VTODO\r\nEND:VCALENDAR\r\n\
+            </c:calendar-data></d:prop><d:status>HTTP/1.1 200 OK</d:status></d:propstat>\
+            </d:response>\
+            <d:response><d:href>/cal/bad.ics</d:href><d:propstat><d:prop>\
+            <d:getetag>\"2\"</d:getetag><c:calendar-data>not a calendar</c:calendar-data>\
+            </d:prop><d:status>HTTP/1.1 200 OK</d:status></d:propstat></d:response>\
+            </d:multistatus>",
+        )
+        .create_async()
+        .await;
+
+    let client = RustyClient::new(&url, "user", "pass", false).unwrap();
+    let calendars = [CalendarListEntry {
+        name: "Tasks".to_string(),
+        href: "/cal/".to_string(),
+        color: None,
+        read_only: false,
+    }];
+    let fetched = client
+        .get_all_tasks_with_progress(&calendars, |_, _| {}, |_, _| {})
+        .await
+        .unwrap();
+
+    let (_, tasks) = &fetched.calendars[0];
+    let summaries: Vec<_> = tasks.iter().map(|t| t.summary.as_str()).collect();
+    assert_eq!(summaries, ["Readable"]);
+    assert_eq!(fetched.parse_failures.len(), 1);
+    let warning = fetched.parse_warning().unwrap();
+    assert!(warning.starts_with("1 item failed to parse (/cal/bad.ics"));
+
+    unsafe {
+        env::remove_var("CFAIT_TEST_DIR");
+    }
+    let _ = fs::remove_dir_all(&temp_dir);
+}
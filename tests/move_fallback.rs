@@ -0,0 +1,81 @@
+// File: ./tests/move_fallback.rs
+use cfait::client::RustyClient;
+use cfait::journal::{Action, Journal};
+use cfait::model::Task;
+use mockito::Server;
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+
+#[tokio::test]
+async fn test_copy_and_delete_retry_finishes_the_move() {
+    let temp_dir = env::temp_dir().join(format!("cfait_test_move_{}", std::process::id()));
+    let _ = fs::create_dir_all(&temp_dir);
+    unsafe {
+        env::set_var("CFAIT_TEST_DIR", &temp_dir);
+    }
+
+    let mut server = Server::new_async().await;
+    let url = server.url();
+    let old_href = "/cal1/moved-task.ics";
+    let new_href = "/cal2/moved-task.ics";
+
+    // No MOVE on this server: the task is copied, then the original deleted
+    let _move = server
+        .mock("MOVE", old_href)
+        .with_status(405)
+        .create_async()
+        .await;
+    let copy = server
+        .mock("PUT", new_href)
+        .match_header("If-None-Match", "*")
+        .with_status(201)
+        .create_async()
+        .await;
+    let failed_delete = server
+        .mock("DELETE", old_href)
+        .with_status(500)
+        .create_async()
+        .await;
+
+    let client = RustyClient::new(&url, "user", "pass", false).unwrap();
+    let mut task = Task::new("Task to Move", &HashMap::new());
+    task.uid = "moved-task".to_string();
+    task.calendar_href = "/cal1/".to_string();
+    task.href = old_href.to_string();
+    task.etag = "\"orig-etag\"".to_string();
+    if let Some(p) = Journal::get_path() {
+        let _ = fs::remove_file(p);
+    }
+    Journal::push(Action::Move(task, "/cal2/".to_string())).unwrap();
+
+    assert!(client.sync_journal().await.is_err());
+    copy.assert();
+    failed_delete.assert();
+    assert_eq!(Journal::load().queue.len(), 1);
+
+    // The retry finds its copy already there and only has the DELETE left
+    copy.remove_async().await;
+    failed_delete.remove_async().await;
+    let existing = server
+        .mock("PUT", new_href)
+        .with_status(412)
+        .create_async()
+        .await;
+    let delete = server
+        .mock("DELETE", old_href)
+        .with_status(204)
+        .create_async()
+        .await;
+
+    let result = client.sync_journal().await;
+    assert!(result.is_ok(), "Retry should succeed: {:?}", result.err());
+    existing.assert();
+    delete.assert();
+    assert!(Journal::load().is_empty());
+
+    unsafe {
+        env::remove_var("CFAIT_TEST_DIR");
+    }
+    let _ = fs::remove_dir_all(&temp_dir);
+}
@@ -0,0 +1,111 @@
+// File: ./tests/proxy.rs
+use cfait::client::RustyClient;
+use cfait::client::core::Auth;
+use mockito::{Matcher, Server};
+use std::env;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+/// Minimal CONNECT proxy: records each request line, then pipes bytes both ways.
+async fn run_proxy(listener: TcpListener, seen: Arc<Mutex<Vec<String>>>) {
+    while let Ok((mut client, _)) = listener.accept().await {
+        let seen = seen.clone();
+        tokio::spawn(async move {
+            let mut head = Vec::new();
+            let mut buf = [0u8; 1024];
+            while !head.ends_with(b"\r\n\r\n") {
+                match client.read(&mut buf).await {
+                    Ok(0) | Err(_) => return,
+                    Ok(n) => head.extend_from_slice(&buf[..n]),
+                }
+            }
+            let head = String::from_utf8_lossy(&head).to_string();
+            let request_line = head.lines().next().unwrap_or_default().to_string();
+            let target = request_line
+                .split_whitespace()
+                .nth(1)
+                .unwrap_or_default()
+                .to_string();
+            seen.lock().unwrap().push(request_line);
+            let Ok(mut upstream) = TcpStream::connect(&target).await else {
+                return;
+            };
+            let _ = client
+                .write_all(b"HTTP/1.1 200 Connection established\r\n\r\n")
+                .await;
+            let _ = tokio::io::copy_bidirectional(&mut client, &mut upstream).await;
+        });
+    }
+}
+
+fn client_via(url: &str, proxy: &str) -> RustyClient {
+    RustyClient::new_with_proxy(
+        url,
+        Auth::Basic {
+            user: "u",
+            pass: "p",
+        },
+        false,
+        Duration::from_secs(5),
+        Some(proxy),
+    )
+    .unwrap()
+}
+
+#[tokio::test]
+async fn test_requests_go_through_proxy() {
+    unsafe {
+        env::remove_var("NO_PROXY");
+        env::remove_var("no_proxy");
+    }
+    let mut server = Server::new_async().await;
+    let url = server.url();
+    let mock_propfind = server
+        .mock("PROPFIND", Matcher::Any)
+        .with_status(401)
+        .create_async()
+        .await;
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let proxy_url = format!("http://{}", listener.local_addr().unwrap());
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    tokio::spawn(run_proxy(listener, seen.clone()));
+
+    let err = client_via(&url, &proxy_url)
+        .get_calendars()
+        .await
+        .unwrap_err();
+    assert!(
+        !err.starts_with("Could not connect through the proxy"),
+        "{}",
+        err
+    );
+    mock_propfind.assert_async().await;
+    let host = url.trim_start_matches("http://");
+    assert_eq!(
+        seen.lock().unwrap().first().map(String::as_str),
+        Some(format!("CONNECT {} HTTP/1.1", host).as_str())
+    );
+
+    // "none" ignores the proxy altogether
+    let _ = client_via(&url, "none").get_calendars().await;
+    assert_eq!(seen.lock().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_unreachable_proxy_is_reported() {
+    // Grab a free port, then close it so nothing listens there
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    drop(listener);
+
+    let client = client_via("https://caldav.invalid/", &format!("http://{}", addr));
+    let err = client.get_calendars().await.unwrap_err();
+    assert!(
+        err.starts_with(&format!("Could not connect through the proxy ({}", addr)),
+        "{}",
+        err
+    );
+}
@@ -0,0 +1,50 @@
+// File: ./tests/read_only_calendar.rs
+use cfait::cache::Cache;
+use cfait::journal::{Action, Journal};
+use cfait::model::{CalendarListEntry, Task};
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+
+#[test]
+fn test_journal_refuses_read_only_calendar() {
+    let temp_dir = env::temp_dir().join(format!("cfait_test_read_only_{}", std::process::id()));
+    let _ = fs::create_dir_all(&temp_dir);
+    unsafe {
+        env::set_var("CFAIT_TEST_DIR", &temp_dir);
+    }
+    let _ = Journal::clear_queue();
+
+    Cache::save_calendars(&[
+        CalendarListEntry {
+            name: "Holidays".to_string(),
+            href: "/cal/holidays/".to_string(),
+            color: None,
+            read_only: true,
+        },
+        CalendarListEntry {
+            name: "Work".to_string(),
+            href: "/cal/work/".to_string(),
+            color: None,
+            read_only: false,
+        },
+    ])
+    .unwrap();
+
+    let aliases = HashMap::new();
+    let mut shared = Task::new("Bank holiday", &aliases);
+    shared.calendar_href = "/cal/holidays/".to_string();
+    let mut own = Task::new("Report", &aliases);
+    own.calendar_href = "/cal/work/".to_string();
+
+    let err = Journal::push(Action::Update(shared.clone())).unwrap_err();
+    assert!(err.to_string().contains("Holidays"));
+    assert!(Journal::push(Action::Move(own.clone(), "/cal/holidays/".to_string())).is_err());
+    Journal::push(Action::Update(own)).unwrap();
+    assert_eq!(Journal::queue_summary(), vec!["Update: Report"]);
+
+    unsafe {
+        env::remove_var("CFAIT_TEST_DIR");
+    }
+    let _ = fs::remove_dir_all(&temp_dir);
+}
@@ -0,0 +1,114 @@
+// File: ./tests/recurring_promotion.rs
+use cfait::client::RustyClient;
+use cfait::model::CalendarListEntry;
+use mockito::Server;
+use std::env;
+use std::fs;
+
+#[tokio::test]
+async fn test_promoted_tasks_upload_without_a_second_fetch() {
+    let temp_dir = env::temp_dir().join(format!("cfait_test_promote_{}", std::process::id()));
+    let _ = fs::create_dir_all(&temp_dir);
+    unsafe {
+        env::set_var("CFAIT_TEST_DIR", &temp_dir);
+    }
+
+    let mut server = Server::new_async().await;
+    let url = server.url();
+    let _options = server
+        .mock("OPTIONS", "/cal/")
+        .with_status(200)
+        .with_header("Allow", "OPTIONS, GET, PUT, DELETE, PROPFIND, REPORT, MOVE")
+        .create_async()
+        .await;
+    // Capability probe: neither ctag nor sync-token, so the calendar is listed in full
+    let _probe = server
+        .mock("PROPFIND", "/cal/")
+        .match_header("depth", "0")
+        .with_status(207)
+        .with_body(
+            r#"<d:multistatus xmlns:d="DAV:"><d:response><d:href>/cal/</d:href>
+            <d:propstat><d:prop/><d:status>HTTP/1.1 200 OK</d:status></d:propstat>
+            </d:response></d:multistatus>"#,
+        )
+        .create_async()
+        .await;
+    let list = server
+        .mock("PROPFIND", "/cal/")
+        .match_header("depth", "1")
+        .with_status(207)
+        .with_body(
+            r#"<d:multistatus xmlns:d="DAV:">
+            <d:response><d:href>/cal/plants.ics</d:href><d:propstat><d:prop>
+            <d:getetag>"1"</d:getetag><d:getcontenttype>text/calendar</d:getcontenttype>
+            <d:resourcetype/></d:prop><d:status>HTTP/1.1 200 OK</d:status></d:propstat></d:response>
+            <d:response><d:href>/cal/report.ics</d:href><d:propstat><d:prop>
+            <d:getetag>"1"</d:getetag><d:getcontenttype>text/calendar</d:getcontenttype>
+            <d:resourcetype/></d:prop><d:status>HTTP/1.1 200 OK</d:status></d:propstat></d:response>
+            </d:multistatus>"#,
+        )
+        .expect(1)
+        .create_async()
+        .await;
+    // Catching up is bounded, so the stale task is only a few days overdue
+    let stale = (chrono::Local::now().date_naive() - chrono::Duration::days(10)).format("%Y%m%d");
+    let _multiget = server
+        .mock("REPORT", "/cal/")
+        .with_status(207)
+        .with_body(format!(
+            "<d:multistatus xmlns:d=\"DAV:\" xmlns:c=\"urn:ietf:params:xml:ns:caldav\">\
+            <d:response><d:href>/cal/plants.ics</d:href><d:propstat><d:prop>\
+            <d:getetag>\"1\"</d:getetag><c:calendar-data>BEGIN:VCALENDAR\r\nVERSION:2.0\r\n\
+            BEGIN:VTODO\r\nUID:plants\r\nSUMMARY:Water plants\r\nDUE;VALUE=DATE:{stale}\r\n\
+            RRULE:FREQ=DAILY\r\nEND:VTODO\r\nEND:VCALENDAR\r\n\
+            </c:calendar-data></d:prop><d:status>HTTP/1.1 200 OK</d:status></d:propstat>\
+            </d:response>\
+            <d:response><d:href>/cal/report.ics</d:href><d:propstat><d:prop>\
+            <d:getetag>\"1\"</d:getetag><c:calendar-data>BEGIN:VCALENDAR\r\nVERSION:2.0\r\n\
+            BEGIN:VTODO\r\nUID:report\r\nSUMMARY:Report\r\nDUE;VALUE=DATE:{stale}\r\n\
+            END:VTODO\r\nEND:VCALENDAR\r\n\
+            </c:calendar-data></d:prop><d:status>HTTP/1.1 200 OK</d:status></d:propstat>\
+            </d:response>\
+            </d:multistatus>"
+        ))
+        .create_async()
+        .await;
+    let upload = server
+        .mock("PUT", "/cal/plants.ics")
+        .with_status(204)
+        .with_header("ETag", "\"2\"")
+        .expect(1)
+        .create_async()
+        .await;
+
+    let client = RustyClient::new(&url, "user", "pass", false)
+        .unwrap()
+        .with_promote_recurring(true);
+    let calendars = [CalendarListEntry {
+        name: "Tasks".to_string(),
+        href: "/cal/".to_string(),
+        color: None,
+        read_only: false,
+    }];
+    let fetched = client
+        .get_all_tasks_with_progress(&calendars, |_, _| {}, |_, _| {})
+        .await
+        .unwrap();
+
+    list.assert();
+    upload.assert();
+    let (_, tasks) = &fetched.calendars[0];
+    let plants = tasks.iter().find(|t| t.uid == "plants").unwrap();
+    // Rolled to today (or tomorrow), whatever the time zone
+    assert!(plants.due.unwrap() > chrono::Utc::now() - chrono::Duration::days(2));
+    assert_eq!(plants.etag, "\"2\"");
+    // Not recurring, so left overdue
+    let report = tasks.iter().find(|t| t.uid == "report").unwrap();
+    assert_eq!(report.etag, "\"1\"");
+    assert!(cfait::journal::Journal::load().is_empty());
+
+    unsafe {
+        env::remove_var("CFAIT_TEST_DIR");
+    }
+    let _ = fs::remove_dir_all(&temp_dir);
+}
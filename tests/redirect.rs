@@ -0,0 +1,78 @@
+// File: ./tests/redirect.rs
+use cfait::client::RustyClient;
+use mockito::{Matcher, Server};
+
+#[tokio::test]
+async fn test_discovery_follows_redirect_on_same_host() {
+    let mut server = Server::new_async().await;
+    let moved = server
+        .mock("PROPFIND", "/")
+        .with_status(301)
+        .with_header("Location", "/dav/")
+        .expect_at_least(1)
+        .create_async()
+        .await;
+    let principal = server
+        .mock("PROPFIND", "/dav/")
+        .match_body(Matcher::Regex("current-user-principal".to_string()))
+        .with_status(207)
+        .with_body(
+            r#"<?xml version="1.0"?>
+<d:multistatus xmlns:d="DAV:">
+  <d:response><d:href>/dav/</d:href><d:propstat><d:prop>
+    <d:current-user-principal><d:href>/dav/principals/user/</d:href></d:current-user-principal>
+  </d:prop><d:status>HTTP/1.1 200 OK</d:status></d:propstat></d:response>
+</d:multistatus>"#,
+        )
+        .create_async()
+        .await;
+    let home = server
+        .mock("PROPFIND", "/dav/principals/user/")
+        .with_status(207)
+        .with_body(
+            r#"<?xml version="1.0"?>
+<d:multistatus xmlns:d="DAV:" xmlns:c="urn:ietf:params:xml:ns:caldav">
+  <d:response><d:href>/dav/principals/user/</d:href><d:propstat><d:prop>
+    <c:calendar-home-set><d:href>/dav/calendars/user/</d:href></c:calendar-home-set>
+  </d:prop><d:status>HTTP/1.1 200 OK</d:status></d:propstat></d:response>
+</d:multistatus>"#,
+        )
+        .create_async()
+        .await;
+    let mkcalendar = server
+        .mock(
+            "MKCALENDAR",
+            Matcher::Regex(r"^/dav/calendars/user/".to_string()),
+        )
+        .expect(1)
+        .with_status(201)
+        .create_async()
+        .await;
+
+    let client = RustyClient::new(&server.url(), "u", "p", false).unwrap();
+    let cal = client.create_calendar("Chores").await.unwrap();
+
+    assert!(cal.href.starts_with("/dav/calendars/user/"));
+    moved.assert_async().await;
+    principal.assert_async().await;
+    home.assert_async().await;
+    mkcalendar.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_redirect_to_another_host_asks_to_update_the_url() {
+    let mut server = Server::new_async().await;
+    let _moved = server
+        .mock("PROPFIND", Matcher::Any)
+        .with_status(308)
+        .with_header("Location", "https://dav.example.com/dav/")
+        .create_async()
+        .await;
+
+    let client = RustyClient::new(&server.url(), "u", "p", false).unwrap();
+    let err = client.get_calendars().await.unwrap_err();
+    assert_eq!(
+        err,
+        "Server redirected to https://dav.example.com/dav/, update your URL?"
+    );
+}
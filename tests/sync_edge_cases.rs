@@ -7,6 +7,7 @@ use std::collections::HashMap;
 use std::env;
 use std::fs;
 use std::sync::Mutex;
+use std::time::Duration;
 
 // Global lock to prevent tests from clobbering the shared ENV var
 static TEST_MUTEX: Mutex<()> = Mutex::new(());
@@ -91,11 +92,14 @@ async fn test_sync_500_keeps_item_in_queue() {
     let mock = server
         .mock("PUT", "/cal/task.ics")
         .with_status(500)
+        .expect(4) // First attempt + 3 retries
         .create_async()
         .await;
 
-    // 2. Setup Client
-    let client = RustyClient::new(&url, "u", "p", true).unwrap();
+    // 2. Setup Client (short backoff to keep the test fast)
+    let client = RustyClient::new(&url, "u", "p", true)
+        .unwrap()
+        .with_retry_delay(Duration::from_millis(1));
 
     // 3. Add Create Action
     let mut task = Task::new("T", &HashMap::new());
@@ -0,0 +1,94 @@
+// File: ./tests/sync_progress.rs
+use cfait::client::RustyClient;
+use cfait::journal::Action;
+use cfait::model::Task;
+use mockito::Server;
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+
+#[tokio::test]
+async fn test_sync_journal_reports_progress() {
+    // 0. Setup Isolation
+    let temp_dir = env::temp_dir().join(format!("cfait_test_progress_{}", std::process::id()));
+    let _ = fs::create_dir_all(&temp_dir);
+    unsafe {
+        env::set_var("CFAIT_TEST_DIR", &temp_dir);
+    }
+
+    // 1. Setup Mock Server: every create succeeds
+    let mut server = Server::new_async().await;
+    let url = server.url();
+    let mock_create = server
+        .mock(
+            "PUT",
+            mockito::Matcher::Regex(r"^/cal/.*\.ics$".to_string()),
+        )
+        .with_status(201)
+        .with_header("ETag", "\"new-etag\"")
+        .expect(3)
+        .create_async()
+        .await;
+
+    let client = RustyClient::new(&url, "user", "pass", true).unwrap();
+
+    // 2. Queue three offline creations
+    if let Some(p) = cfait::journal::Journal::get_path()
+        && p.exists()
+    {
+        let _ = fs::remove_file(p);
+    }
+    let mut uids = Vec::new();
+    for title in ["One", "Two", "Three"] {
+        let mut task = Task::new(title, &HashMap::new());
+        task.calendar_href = "/cal/".to_string();
+        uids.push(task.uid.clone());
+        cfait::journal::Journal::push(Action::Create(task)).unwrap();
+    }
+
+    // 3. Run Sync
+    let mut progress = Vec::new();
+    let result = client
+        .sync_journal_with_progress(|current, total| progress.push((current, total)))
+        .await;
+
+    // 4. Assertions
+    let sync = result.expect("Sync should succeed");
+    mock_create.assert();
+    assert_eq!(progress, vec![(1, 3), (2, 3), (3, 3)]);
+    // The store picks up the new ETags from the result
+    let mut synced: Vec<_> = sync.etags.keys().cloned().collect();
+    synced.sort();
+    uids.sort();
+    assert_eq!(synced, uids);
+    assert!(cfait::journal::Journal::load().is_empty());
+
+    // CLEANUP
+    unsafe {
+        env::remove_var("CFAIT_TEST_DIR");
+    }
+    let _ = fs::remove_dir_all(&temp_dir);
+}
+
+#[tokio::test]
+async fn test_get_all_tasks_reports_each_calendar() {
+    // The demo backend stays in memory, so this one leaves CFAIT_TEST_DIR alone
+    let client = RustyClient::new("demo://", "", "", false).unwrap();
+    let calendars = client.get_calendars().await.unwrap();
+    let total = calendars.len();
+    assert!(total > 1);
+
+    let mut progress = Vec::new();
+    let results = client
+        .get_all_tasks_with_progress(
+            &calendars,
+            |_, _| {},
+            |done, total| progress.push((done, total)),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(results.calendars.len(), total);
+    let expected: Vec<_> = (1..=total).map(|done| (done, total)).collect();
+    assert_eq!(progress, expected);
+}
@@ -0,0 +1,63 @@
+// File: ./tests/sync_retry.rs
+use cfait::client::RustyClient;
+use cfait::journal::{Action, Journal};
+use cfait::model::Task;
+use mockito::Server;
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::time::Duration;
+
+#[tokio::test]
+async fn test_sync_retries_transient_503() {
+    // 0. Setup Isolation
+    let temp_dir = env::temp_dir().join(format!("cfait_test_retry_{}", std::process::id()));
+    let _ = fs::create_dir_all(&temp_dir);
+    unsafe {
+        env::set_var("CFAIT_TEST_DIR", &temp_dir);
+    }
+    if let Some(p) = Journal::get_path()
+        && p.exists()
+    {
+        let _ = fs::remove_file(p);
+    }
+
+    // 1. Server is briefly unavailable, then accepts the request
+    let mut server = Server::new_async().await;
+    let url = server.url();
+    let mock_unavailable = server
+        .mock("PUT", "/cal/task.ics")
+        .with_status(503)
+        .expect(1)
+        .create_async()
+        .await;
+    let mock_created = server
+        .mock("PUT", "/cal/task.ics")
+        .with_status(201)
+        .expect(1)
+        .create_async()
+        .await;
+
+    // Short backoff to keep the test fast
+    let client = RustyClient::new(&url, "u", "p", true)
+        .unwrap()
+        .with_retry_delay(Duration::from_millis(1));
+
+    let mut task = Task::new("T", &HashMap::new());
+    task.uid = "task".to_string();
+    task.calendar_href = "/cal/".to_string();
+    Journal::push(Action::Create(task)).unwrap();
+
+    // 2. Sync should succeed on the retry
+    let res = client.sync_journal().await;
+    assert!(res.is_ok(), "Sync should recover after a 503");
+    mock_unavailable.assert();
+    mock_created.assert();
+    assert!(Journal::load().is_empty());
+
+    // CLEANUP
+    unsafe {
+        env::remove_var("CFAIT_TEST_DIR");
+    }
+    let _ = fs::remove_dir_all(&temp_dir);
+}
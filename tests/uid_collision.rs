@@ -0,0 +1,101 @@
+// File: ./tests/uid_collision.rs
+use cfait::client::RustyClient;
+use cfait::journal::{Action, Journal};
+use cfait::model::Task;
+use mockito::{Matcher, Server};
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+
+#[tokio::test]
+async fn test_create_picks_new_uid_on_collision() {
+    let temp_dir = env::temp_dir().join(format!("cfait_test_uid_{}", std::process::id()));
+    let _ = fs::create_dir_all(&temp_dir);
+    unsafe {
+        env::set_var("CFAIT_TEST_DIR", &temp_dir);
+    }
+
+    let mut server = Server::new_async().await;
+    let url = server.url();
+
+    // An imported task already sits at the href our UID maps to
+    let taken = server
+        .mock("PUT", "/cal/imported-1.ics")
+        .match_header("If-None-Match", "*")
+        .with_status(412)
+        .create_async()
+        .await;
+    let _existing = server
+        .mock("REPORT", "/cal/")
+        .with_status(207)
+        .with_body(
+            "<d:multistatus xmlns:d=\"DAV:\" xmlns:c=\"urn:ietf:params:xml:ns:caldav\">\
+            <d:response><d:href>/cal/imported-1.ics</d:href><d:propstat><d:prop>\
+            <d:getetag>\"9\"</d:getetag><c:calendar-data>BEGIN:VCALENDAR\r\nVERSION:2.0\r\n\
+            BEGIN:VTODO\r\nUID:imported-1\r\nSUMMARY:Someone else's\r\nEND:VTODO\r\n\
+            END:VCALENDAR\r\n</c:calendar-data></d:prop><d:status>HTTP/1.1 200 OK</d:status>\
+            </d:propstat></d:response></d:multistatus>",
+        )
+        .create_async()
+        .await;
+    // The retried create, then the queued update, both under the fresh UID
+    let fresh = server
+        .mock(
+            "PUT",
+            Matcher::Regex(r"^/cal/[0-9a-f-]{36}\.ics$".to_string()),
+        )
+        .match_body(Matcher::Regex("Water plants".to_string()))
+        .with_status(201)
+        .with_header("ETag", "\"1\"")
+        .expect(2)
+        .create_async()
+        .await;
+    // The subtask queued behind it points at the fresh UID too
+    let child_put = server
+        .mock(
+            "PUT",
+            Matcher::Regex(r"^/cal/[0-9a-f-]{36}\.ics$".to_string()),
+        )
+        .match_body(Matcher::AllOf(vec![
+            Matcher::Regex("Buy watering can".to_string()),
+            Matcher::Regex(r"RELATED-TO:[0-9a-f-]{36}\r\n".to_string()),
+        ]))
+        .with_status(201)
+        .with_header("ETag", "\"2\"")
+        .create_async()
+        .await;
+
+    let client = RustyClient::new(&url, "user", "pass", false).unwrap();
+
+    let mut task = Task::new("Water plants", &HashMap::new());
+    task.uid = "imported-1".to_string();
+    task.calendar_href = "/cal/".to_string();
+    task.href = "/cal/imported-1.ics".to_string();
+    let mut edited = task.clone();
+    edited.description = "Twice a week".to_string();
+    let mut child = Task::new("Buy watering can", &HashMap::new());
+    child.calendar_href = "/cal/".to_string();
+    child.parent_uid = Some("imported-1".to_string());
+
+    Journal::push(Action::Create(task)).unwrap();
+    Journal::push(Action::Update(edited)).unwrap();
+    Journal::push(Action::Create(child)).unwrap();
+
+    let warnings = client.sync_journal().await.unwrap();
+
+    taken.assert();
+    fresh.assert();
+    child_put.assert();
+    assert!(warnings.iter().any(|w| w.contains("already taken")));
+    let j = Journal::load();
+    assert!(
+        j.is_empty(),
+        "Journal should be empty after sync: {:?}",
+        j.queue.len()
+    );
+
+    unsafe {
+        env::remove_var("CFAIT_TEST_DIR");
+    }
+    let _ = fs::remove_dir_all(&temp_dir);
+}